@@ -7,10 +7,13 @@ import (
 	"strings"
 	"time"
 
+	"github.com/vercel/turborepo/cli/internal/cache"
+	"github.com/vercel/turborepo/cli/internal/checkversions"
 	"github.com/vercel/turborepo/cli/internal/cmd/auth"
 	"github.com/vercel/turborepo/cli/internal/cmd/info"
 	"github.com/vercel/turborepo/cli/internal/config"
 	"github.com/vercel/turborepo/cli/internal/daemon"
+	"github.com/vercel/turborepo/cli/internal/lintconfig"
 	"github.com/vercel/turborepo/cli/internal/login"
 	prune "github.com/vercel/turborepo/cli/internal/prune"
 	"github.com/vercel/turborepo/cli/internal/run"
@@ -73,9 +76,21 @@ func main() {
 			return &run.RunCommand{Config: cf, UI: ui, SignalWatcher: signalWatcher},
 				nil
 		},
+		"hash": func() (cli.Command, error) {
+			return &run.HashCommand{Config: cf, UI: ui, SignalWatcher: signalWatcher}, nil
+		},
+		"clean": func() (cli.Command, error) {
+			return &run.CleanCommand{Config: cf, UI: ui}, nil
+		},
 		"prune": func() (cli.Command, error) {
 			return &prune.PruneCommand{Config: cf, Ui: ui}, nil
 		},
+		"check-versions": func() (cli.Command, error) {
+			return &checkversions.CheckVersionsCommand{Config: cf, UI: ui}, nil
+		},
+		"lint-config": func() (cli.Command, error) {
+			return &lintconfig.LintConfigCommand{Config: cf, UI: ui}, nil
+		},
 		"link": func() (cli.Command, error) {
 			return &login.LinkCommand{Config: cf, Ui: ui}, nil
 		},
@@ -91,6 +106,18 @@ func main() {
 		"bin": func() (cli.Command, error) {
 			return &info.BinCommand{Config: cf, UI: ui}, nil
 		},
+		"doctor": func() (cli.Command, error) {
+			return &info.DoctorCommand{Config: cf, UI: ui}, nil
+		},
+		"info": func() (cli.Command, error) {
+			return &run.InfoCommand{Config: cf, UI: ui, SignalWatcher: signalWatcher}, nil
+		},
+		"cache": func() (cli.Command, error) {
+			return &cache.Command{Config: cf, UI: ui}, nil
+		},
+		"cache warm": func() (cli.Command, error) {
+			return &run.WarmCommand{Config: cf, UI: ui, SignalWatcher: signalWatcher}, nil
+		},
 		"daemon": func() (cli.Command, error) {
 			return &daemon.Command{Config: cf, UI: ui, SignalWatcher: signalWatcher}, nil
 		},
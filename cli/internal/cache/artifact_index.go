@@ -0,0 +1,288 @@
+package cache
+
+import (
+	"archive/tar"
+	"compress/gzip"
+	"encoding/json"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+// An artifact is a gzipped tar of a task's outputs. Version 2 artifacts start with a
+// regular file holding an index of every other entry, so that the index can be read
+// without extracting the artifact, and store files with the same contents as an earlier
+// file as hard links to it. Version 1 artifacts have no index, and are read the same way
+// otherwise. Earlier versions of turbo can't restore the links, but they never read
+// version 2 artifacts, since _globalCacheKey changed along with the format.
+const (
+	_artifactIndexPath    = ".turbo/artifact-index.json"
+	_artifactIndexVersion = 2
+)
+
+// ArtifactIndex lists the entries in an artifact
+type ArtifactIndex struct {
+	Version int                  `json:"version"`
+	Entries []ArtifactIndexEntry `json:"entries"`
+}
+
+// ArtifactIndexEntry describes one file, directory or link in an artifact
+type ArtifactIndexEntry struct {
+	// Path is the repo-relative, /-separated path of the entry
+	Path string `json:"path"`
+	Type string `json:"type"`
+	Mode int64  `json:"mode"`
+	// Size and Hash are the size and git blob hash of a regular file's contents. Hash is
+	// only recorded for every file when the artifact is written with verification on.
+	// Otherwise it is left out, and only the size is checked, unless another file in the
+	// artifact has the same size.
+	Size int64  `json:"size,omitempty"`
+	Hash string `json:"hash,omitempty"`
+	// Target is where a symlink points, or the earlier file that a duplicate is stored as
+	Target string `json:"target,omitempty"`
+}
+
+// Types of ArtifactIndexEntry
+const (
+	ArtifactFile      = "file"
+	ArtifactDir       = "dir"
+	ArtifactSymlink   = "symlink"
+	ArtifactDuplicate = "duplicate"
+)
+
+// newArtifactIndex describes the given repo-relative files as they will be written into
+// an artifact. Regular files with the same contents as an earlier file are duplicates.
+// Hashing a file reads it again, so unless withHashes is set, only files with the same
+// size as another file are hashed, which is all that finding duplicates needs.
+func newArtifactIndex(repoRoot fs.AbsolutePath, files []string, withHashes bool) (*ArtifactIndex, error) {
+	index := &ArtifactIndex{Version: _artifactIndexVersion}
+	sizes := make(map[int64]int)
+	for _, file := range files {
+		path := repoRoot.Join(file)
+		info, err := path.Lstat()
+		if err != nil {
+			return nil, err
+		}
+		entry := ArtifactIndexEntry{
+			Path: filepath.ToSlash(file),
+			Mode: int64(info.Mode().Perm()),
+		}
+		switch {
+		case info.IsDir():
+			entry.Type = ArtifactDir
+		case info.Mode()&os.ModeSymlink != 0:
+			entry.Type = ArtifactSymlink
+			target, err := path.Readlink()
+			if err != nil {
+				return nil, err
+			}
+			entry.Target = filepath.ToSlash(target)
+		default:
+			entry.Type = ArtifactFile
+			entry.Size = info.Size()
+			sizes[entry.Size]++
+		}
+		index.Entries = append(index.Entries, entry)
+	}
+	byHash := make(map[string]string)
+	for i := range index.Entries {
+		entry := &index.Entries[i]
+		// Empty files take no space in an artifact, so there's nothing to save
+		isCandidate := entry.Size > 0 && sizes[entry.Size] > 1
+		if entry.Type != ArtifactFile || !(withHashes || isCandidate) {
+			continue
+		}
+		hash, err := fs.GitLikeHashFile(repoRoot.Join(filepath.FromSlash(entry.Path)).ToString())
+		if err != nil {
+			return nil, err
+		}
+		entry.Hash = hash
+		if first, ok := byHash[hash]; ok && isCandidate {
+			entry.Type = ArtifactDuplicate
+			entry.Target = first
+		} else if !ok {
+			byHash[hash] = entry.Path
+		}
+	}
+	return index, nil
+}
+
+// duplicates maps each file with the same contents as an earlier file to that file
+func (index *ArtifactIndex) duplicates() map[string]string {
+	duplicates := make(map[string]string)
+	for _, entry := range index.Entries {
+		if entry.Type == ArtifactDuplicate {
+			duplicates[filepath.FromSlash(entry.Path)] = entry.Target
+		}
+	}
+	return duplicates
+}
+
+// mismatches returns the paths of the files in the index that weren't restored into
+// root with the contents the index describes. Directories and symlinks aren't checked,
+// since the local cache doesn't keep them as they were.
+func (index *ArtifactIndex) mismatches(root fs.AbsolutePath) []string {
+	var mismatched []string
+	for _, entry := range index.Entries {
+		if entry.Type != ArtifactFile && entry.Type != ArtifactDuplicate {
+			continue
+		}
+		path := root.Join(filepath.FromSlash(entry.Path))
+		info, err := path.Lstat()
+		if err != nil || !info.Mode().IsRegular() || info.Size() != entry.Size {
+			mismatched = append(mismatched, entry.Path)
+			continue
+		}
+		if entry.Hash == "" {
+			continue
+		}
+		if hash, err := fs.GitLikeHashFile(path.ToString()); err != nil || hash != entry.Hash {
+			mismatched = append(mismatched, entry.Path)
+		}
+	}
+	return mismatched
+}
+
+// CorruptArtifactError is returned by Fetch, when restores are verified, if the files
+// restored from an artifact don't match the artifact's index
+type CorruptArtifactError struct {
+	Hash string
+	// Source is the cache the artifact was restored from, LOCAL or REMOTE
+	Source string
+	Files  []string
+}
+
+func (e *CorruptArtifactError) Error() string {
+	const shown = 5
+	files := strings.Join(e.Files, ", ")
+	if len(e.Files) > shown {
+		files = fmt.Sprintf("%v and %v more", strings.Join(e.Files[:shown], ", "), len(e.Files)-shown)
+	}
+	return fmt.Sprintf("files restored from the %v cache for %v don't match the artifact: %v", strings.ToLower(e.Source), e.Hash, files)
+}
+
+// writeArtifactIndex writes the index as the first entry of an artifact
+func writeArtifactIndex(tw *tar.Writer, index *ArtifactIndex) error {
+	encoded, err := json.Marshal(index)
+	if err != nil {
+		return err
+	}
+	if err := tw.WriteHeader(&tar.Header{
+		Typeflag: tar.TypeReg,
+		Name:     _artifactIndexPath,
+		Mode:     0644,
+		Size:     int64(len(encoded)),
+		ModTime:  mtime,
+		Uid:      nobody,
+		Gid:      nobody,
+		Uname:    "nobody",
+		Gname:    "nobody",
+	}); err != nil {
+		return err
+	}
+	_, err = tw.Write(encoded)
+	return err
+}
+
+// isArtifactIndex returns true if hdr, the header of the first entry in an artifact,
+// is the artifact's index
+func isArtifactIndex(hdr *tar.Header) bool {
+	return hdr.Typeflag == tar.TypeReg && hdr.Name == _artifactIndexPath
+}
+
+// readArtifactIndex reads an artifact's index, which only requires decompressing the
+// start of the artifact. It returns nil for artifacts without an index.
+func readArtifactIndex(reader io.Reader) (*ArtifactIndex, error) {
+	gzr, err := gzip.NewReader(reader)
+	if err != nil {
+		return nil, err
+	}
+	defer func() { _ = gzr.Close() }()
+	tr := tar.NewReader(gzr)
+	hdr, err := tr.Next()
+	if err == io.EOF {
+		return nil, nil
+	} else if err != nil {
+		return nil, err
+	}
+	if !isArtifactIndex(hdr) {
+		return nil, nil
+	}
+	return decodeArtifactIndex(tr)
+}
+
+func decodeArtifactIndex(reader io.Reader) (*ArtifactIndex, error) {
+	index := &ArtifactIndex{}
+	if err := json.NewDecoder(reader).Decode(index); err != nil {
+		return nil, fmt.Errorf("invalid artifact index: %w", err)
+	}
+	return index, nil
+}
+
+// verifyArtifact checks that every file in an artifact matches the artifact's index,
+// without writing anything to disk
+func verifyArtifact(reader io.Reader) error {
+	gzr, err := gzip.NewReader(reader)
+	if err != nil {
+		return err
+	}
+	defer func() { _ = gzr.Close() }()
+	tr := tar.NewReader(gzr)
+	var expected map[string]ArtifactIndexEntry
+	seen := make(map[string]bool)
+	for first := true; ; first = false {
+		hdr, err := tr.Next()
+		if err == io.EOF {
+			break
+		} else if err != nil {
+			return err
+		}
+		if first && isArtifactIndex(hdr) {
+			index, err := decodeArtifactIndex(tr)
+			if err != nil {
+				return err
+			}
+			expected = make(map[string]ArtifactIndexEntry, len(index.Entries))
+			for _, entry := range index.Entries {
+				expected[entry.Path] = entry
+			}
+			continue
+		}
+		if expected == nil {
+			return fmt.Errorf("artifact has no index to verify against")
+		}
+		entry, ok := expected[hdr.Name]
+		if !ok {
+			return fmt.Errorf("%v is in the artifact, but not in its index", hdr.Name)
+		}
+		seen[hdr.Name] = true
+		if hdr.Typeflag != tar.TypeReg {
+			continue
+		}
+		if hdr.Size != entry.Size {
+			return fmt.Errorf("%v doesn't match the artifact's index", hdr.Name)
+		} else if entry.Hash == "" {
+			continue
+		}
+		hash, err := fs.GitLikeHashReader(tr, hdr.Size)
+		if err != nil {
+			return err
+		}
+		if hash != entry.Hash {
+			return fmt.Errorf("%v doesn't match the artifact's index", hdr.Name)
+		}
+	}
+	if expected == nil {
+		return fmt.Errorf("artifact has no index to verify against")
+	}
+	for path := range expected {
+		if !seen[path] {
+			return fmt.Errorf("%v is in the artifact's index, but not in the artifact", path)
+		}
+	}
+	return nil
+}
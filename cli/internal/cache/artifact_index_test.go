@@ -0,0 +1,127 @@
+package cache
+
+import (
+	"archive/tar"
+	"bytes"
+	"compress/gzip"
+	"io"
+	"io/ioutil"
+	"path/filepath"
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+func writeTestArtifact(t *testing.T, sources map[string]string, files []string) []byte {
+	srcRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	for name, contents := range sources {
+		file := srcRoot.Join(filepath.FromSlash(name))
+		assert.NilError(t, file.EnsureDir(), "EnsureDir")
+		assert.NilError(t, file.WriteFile([]byte(contents), 0644), "WriteFile")
+	}
+	cache := &httpCache{repoRoot: srcRoot, verify: true}
+	r, w := io.Pipe()
+	go cache.write(w, "some-hash", files)
+	artifact, err := ioutil.ReadAll(r)
+	assert.NilError(t, err, "ReadAll")
+	return artifact
+}
+
+func TestArtifactIndex(t *testing.T) {
+	artifact := writeTestArtifact(t, map[string]string{
+		"web/dist/index.js": "console.log('web');\n",
+		"web/dist/copy.js":  "console.log('web');\n",
+		"web/dist/empty.js": "",
+	}, []string{
+		filepath.FromSlash("web/dist"),
+		filepath.FromSlash("web/dist/index.js"),
+		filepath.FromSlash("web/dist/copy.js"),
+		filepath.FromSlash("web/dist/empty.js"),
+	})
+
+	index, err := readArtifactIndex(bytes.NewReader(artifact))
+	assert.NilError(t, err, "readArtifactIndex")
+	assert.Assert(t, index != nil, "expected an index")
+	assert.Equal(t, index.Version, _artifactIndexVersion)
+	var types []string
+	for _, entry := range index.Entries {
+		types = append(types, entry.Type)
+	}
+	assert.DeepEqual(t, types, []string{ArtifactDir, ArtifactFile, ArtifactDuplicate, ArtifactFile})
+	assert.Equal(t, index.Entries[2].Target, "web/dist/index.js")
+	assert.Equal(t, index.Entries[1].Size, int64(len("console.log('web');\n")))
+
+	assert.NilError(t, verifyArtifact(bytes.NewReader(artifact)), "verifyArtifact")
+
+	// The index is a regular file, so that older versions of turbo can read the artifact,
+	// but it isn't restored as one of the task's outputs
+	gzr, err := gzip.NewReader(bytes.NewReader(artifact))
+	assert.NilError(t, err, "NewReader")
+	hdr, err := tar.NewReader(gzr).Next()
+	assert.NilError(t, err, "Next")
+	assert.Equal(t, hdr.Typeflag, byte(tar.TypeReg))
+	assert.Equal(t, hdr.Name, _artifactIndexPath)
+	files, err := restoreTar(fs.AbsolutePathFromUpstream(t.TempDir()), bytes.NewReader(artifact))
+	assert.NilError(t, err, "restoreTar")
+	assert.DeepEqual(t, files, []string{"web/dist", "web/dist/index.js", "web/dist/copy.js", "web/dist/empty.js"})
+}
+
+func TestArtifactIndexWithoutHashes(t *testing.T) {
+	root := fs.AbsolutePathFromUpstream(t.TempDir())
+	sources := map[string]string{
+		"web/dist/index.js": "console.log('web');\n",
+		"web/dist/copy.js":  "console.log('web');\n",
+		"web/dist/other.js": "console.log('other');\n",
+	}
+	for name, contents := range sources {
+		file := root.Join(filepath.FromSlash(name))
+		assert.NilError(t, file.EnsureDir(), "EnsureDir")
+		assert.NilError(t, file.WriteFile([]byte(contents), 0644), "WriteFile")
+	}
+	files := []string{
+		filepath.FromSlash("web/dist/index.js"),
+		filepath.FromSlash("web/dist/copy.js"),
+		filepath.FromSlash("web/dist/other.js"),
+	}
+
+	// Only files that could be duplicates are hashed
+	index, err := newArtifactIndex(root, files, false)
+	assert.NilError(t, err, "newArtifactIndex")
+	assert.Equal(t, index.Entries[1].Type, ArtifactDuplicate)
+	assert.Assert(t, index.Entries[0].Hash != "", "expected files of the same size to be hashed")
+	assert.Equal(t, index.Entries[2].Hash, "")
+	assert.Equal(t, index.Entries[2].Size, int64(len("console.log('other');\n")))
+	assert.Equal(t, len(index.mismatches(root)), 0)
+
+	index, err = newArtifactIndex(root, files, true)
+	assert.NilError(t, err, "newArtifactIndex")
+	assert.Assert(t, index.Entries[2].Hash != "", "expected every file to be hashed")
+}
+
+func TestVerifyArtifactDetectsChanges(t *testing.T) {
+	artifact := writeTestArtifact(t, map[string]string{"web/dist/index.js": "original"}, []string{filepath.FromSlash("web/dist/index.js")})
+	index, err := readArtifactIndex(bytes.NewReader(artifact))
+	assert.NilError(t, err, "readArtifactIndex")
+
+	// Rewrite the artifact with the same index, but different contents
+	var tampered bytes.Buffer
+	gzw := gzip.NewWriter(&tampered)
+	tw := tar.NewWriter(gzw)
+	assert.NilError(t, writeArtifactIndex(tw, index), "writeArtifactIndex")
+	contents := []byte("modified")
+	assert.NilError(t, tw.WriteHeader(&tar.Header{Typeflag: tar.TypeReg, Name: "web/dist/index.js", Mode: 0644, Size: int64(len(contents))}), "WriteHeader")
+	_, err = tw.Write(contents)
+	assert.NilError(t, err, "Write")
+	assert.NilError(t, tw.Close(), "Close")
+	assert.NilError(t, gzw.Close(), "Close")
+
+	err = verifyArtifact(bytes.NewReader(tampered.Bytes()))
+	assert.ErrorContains(t, err, "web/dist/index.js doesn't match")
+}
+
+func TestReadArtifactIndexWithoutIndex(t *testing.T) {
+	index, err := readArtifactIndex(makeValidTar(t))
+	assert.NilError(t, err, "readArtifactIndex")
+	assert.Assert(t, index == nil, "artifacts from older versions have no index")
+}
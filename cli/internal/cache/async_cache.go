@@ -24,6 +24,7 @@ type cacheRequest struct {
 	key      string
 	duration int
 	files    []string
+	opts     putOptions
 }
 
 func newAsyncCache(realCache Cache, opts Opts) Cache {
@@ -70,7 +71,7 @@ func (c *asyncCache) Shutdown() {
 // run implements the actual async logic.
 func (c *asyncCache) run() {
 	for r := range c.requests {
-		c.realCache.Put(r.target, r.key, r.duration, r.files)
+		putWithOptions(c.realCache, r.target, r.key, r.duration, r.files, r.opts)
 	}
 	c.wg.Done()
 }
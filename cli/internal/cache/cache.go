@@ -62,16 +62,32 @@ type Opts struct {
 	SkipFilesystem  bool
 	Workers         int
 	RemoteCacheOpts fs.RemoteCacheOptions
+	QueueUploads    bool
+	// CompressionWorkers is the number of artifacts that the remote cache compresses or
+	// decompresses at the same time, independent of Workers and task concurrency
+	CompressionWorkers int
+	// Verify checks that the files restored from an artifact match the artifact
+	Verify bool
 }
 
 var _remoteOnlyHelp = `Ignore the local filesystem cache for all tasks. Only
 allow reading and caching artifacts using the remote cache.`
 
+var _queueUploadsHelp = `If the remote cache can't be reached, keep artifacts in a
+local queue instead of dropping them. Upload them later with
+"turbo cache flush".`
+
+var _cacheVerifyHelp = `After restoring outputs from the cache, check them against
+the artifact they were restored from, and fail the task if
+they don't match.`
+
 // AddFlags adds cache-related flags to the given FlagSet
 func AddFlags(opts *Opts, flags *pflag.FlagSet, repoRoot fs.AbsolutePath) {
 	// skipping remote caching not currently a flag
 	flags.BoolVar(&opts.SkipFilesystem, "remote-only", false, _remoteOnlyHelp)
 	fs.AbsolutePathVar(flags, &opts.Dir, "cache-dir", repoRoot, "Specify local filesystem cache directory.", "./node_modules/.cache/turbo")
+	flags.BoolVar(&opts.QueueUploads, "queue-uploads", false, _queueUploadsHelp)
+	flags.BoolVar(&opts.Verify, "cache-verify", false, _cacheVerifyHelp)
 }
 
 // New creates a new cache
@@ -158,7 +174,7 @@ type cacheMultiplexer struct {
 }
 
 func (mplex *cacheMultiplexer) Put(target string, key string, duration int, files []string) error {
-	return mplex.storeUntil(target, key, duration, files, len(mplex.caches))
+	return mplex.storeUntil(target, key, duration, files, len(mplex.caches), putOptions{})
 }
 
 type cacheRemoval struct {
@@ -169,7 +185,7 @@ type cacheRemoval struct {
 // storeUntil stores artifacts into higher priority caches than the given one.
 // Used after artifact retrieval to ensure we have them in eg. the directory cache after
 // downloading from the RPC cache.
-func (mplex *cacheMultiplexer) storeUntil(target string, key string, duration int, outputGlobs []string, stopAt int) error {
+func (mplex *cacheMultiplexer) storeUntil(target string, key string, duration int, outputGlobs []string, stopAt int, opts putOptions) error {
 	// Attempt to store on all caches simultaneously.
 	toRemove := make([]*cacheRemoval, stopAt)
 	g := &errgroup.Group{}
@@ -178,10 +194,13 @@ func (mplex *cacheMultiplexer) storeUntil(target string, key string, duration in
 		if i == stopAt {
 			break
 		}
+		if _, isRemote := cache.(*httpCache); isRemote && opts.localOnly {
+			continue
+		}
 		c := cache
 		i := i
 		g.Go(func() error {
-			err := c.Put(target, key, duration, outputGlobs)
+			err := putWithOptions(c, target, key, duration, outputGlobs, opts)
 			if err != nil {
 				cd := &util.CacheDisabledError{}
 				if errors.As(err, &cd) {
@@ -227,6 +246,12 @@ func (mplex *cacheMultiplexer) removeCache(removal *cacheRemoval) {
 }
 
 func (mplex *cacheMultiplexer) Fetch(target string, key string, files []string) (bool, []string, int, error) {
+	return mplex.fetch(target, key, files, false)
+}
+
+// fetch retrieves an artifact from the first cache that has it, skipping the remote
+// cache if localOnly is set
+func (mplex *cacheMultiplexer) fetch(target string, key string, files []string, localOnly bool) (bool, []string, int, error) {
 	// Make a shallow copy of the caches, since storeUntil can call removeCache
 	mplex.mu.RLock()
 	caches := make([]Cache, len(mplex.caches))
@@ -236,7 +261,16 @@ func (mplex *cacheMultiplexer) Fetch(target string, key string, files []string)
 	// Retrieve from caches sequentially; if we did them simultaneously we could
 	// easily write the same file from two goroutines at once.
 	for i, cache := range caches {
+		if _, isRemote := cache.(*httpCache); isRemote && localOnly {
+			continue
+		}
 		ok, actualFiles, duration, err := cache.Fetch(target, key, files)
+		// Files may have been partly restored from a corrupt artifact, so don't restore
+		// over them from another cache as if nothing happened
+		corrupt := &CorruptArtifactError{}
+		if errors.As(err, &corrupt) {
+			return false, files, 0, err
+		}
 		if err != nil {
 			cd := &util.CacheDisabledError{}
 			if errors.As(err, &cd) {
@@ -254,7 +288,7 @@ func (mplex *cacheMultiplexer) Fetch(target string, key string, files []string)
 			// Store this into other caches. We can ignore errors here because we know
 			// we have previously successfully stored in a higher-priority cache, and so the overall
 			// result is a success at fetching. Storing in lower-priority caches is an optimization.
-			_ = mplex.storeUntil(target, key, duration, actualFiles, i)
+			_ = mplex.storeUntil(target, key, duration, actualFiles, i, putOptions{localOnly: localOnly})
 			return ok, actualFiles, duration, err
 		}
 	}
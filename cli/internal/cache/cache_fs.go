@@ -7,8 +7,11 @@ import (
 	"encoding/json"
 	"fmt"
 	"io/ioutil"
+	"log"
+	"os"
 	"path/filepath"
 	"runtime"
+	"sync/atomic"
 
 	"github.com/vercel/turborepo/cli/internal/analytics"
 	"github.com/vercel/turborepo/cli/internal/fs"
@@ -20,6 +23,8 @@ type fsCache struct {
 	cacheDirectory string
 	recorder       analytics.Recorder
 	repoRoot       fs.AbsolutePath
+	// verify checks restored files against the artifact's index
+	verify bool
 }
 
 // newFsCache creates a new filesystem cache
@@ -31,6 +36,7 @@ func newFsCache(opts Opts, recorder analytics.Recorder, repoRoot fs.AbsolutePath
 		cacheDirectory: opts.Dir.ToStringDuringMigration(),
 		recorder:       recorder,
 		repoRoot:       repoRoot,
+		verify:         opts.Verify,
 	}, nil
 }
 
@@ -45,7 +51,7 @@ func (f *fsCache) Fetch(target, hash string, _unusedOutputGlobs []string) (bool,
 	}
 
 	// Otherwise, copy it into position
-	err := fs.RecursiveCopyOrLinkFile(cachedFolder, target, false, false)
+	err := fs.RecursiveCopyParallel(cachedFolder, target, runtime.NumCPU())
 	if err != nil {
 		// TODO: what event to log here?
 		return false, nil, 0, fmt.Errorf("error moving artifact from cache into %v: %w", target, err)
@@ -55,6 +61,15 @@ func (f *fsCache) Fetch(target, hash string, _unusedOutputGlobs []string) (bool,
 	if err != nil {
 		return false, nil, 0, fmt.Errorf("error reading cache metadata: %w", err)
 	}
+	// Artifacts cached by older versions of turbo have no index, and can't be verified
+	if f.verify && meta.Index != nil {
+		if mismatched := meta.Index.mismatches(f.repoRoot); len(mismatched) > 0 {
+			// Drop the entry, so that the next run rebuilds or fetches a good copy
+			_ = os.RemoveAll(cachedFolder)
+			_ = os.Remove(filepath.Join(f.cacheDirectory, hash+"-meta.json"))
+			return false, nil, 0, &CorruptArtifactError{Hash: hash, Source: "LOCAL", Files: mismatched}
+		}
+	}
 	f.logFetch(true, hash, meta.Duration)
 	return true, nil, meta.Duration, nil
 }
@@ -80,6 +95,7 @@ func (f *fsCache) Put(target, hash string, duration int, files []string) error {
 
 	numDigesters := runtime.NumCPU()
 	fileQueue := make(chan string, numDigesters)
+	var size int64
 
 	for i := 0; i < numDigesters; i++ {
 		g.Go(func() error {
@@ -94,9 +110,13 @@ func (f *fsCache) Put(target, hash string, duration int, files []string) error {
 						return fmt.Errorf("error ensuring directory file from cache: %w", err)
 					}
 
-					if err := fs.CopyOrLinkFile(&statedFile, filepath.Join(f.cacheDirectory, hash, file), false, false); err != nil {
+					cachedFile := filepath.Join(f.cacheDirectory, hash, file)
+					if err := fs.CopyOrLinkFile(&statedFile, cachedFile, false, false); err != nil {
 						return fmt.Errorf("error copying file from cache: %w", err)
 					}
+					if info, err := os.Lstat(cachedFile); err == nil {
+						atomic.AddInt64(&size, info.Size())
+					}
 				}
 			}
 			return nil
@@ -112,9 +132,21 @@ func (f *fsCache) Put(target, hash string, duration int, files []string) error {
 		return err
 	}
 
+	// The index is only used to verify restores, and hashing every file reads it again
+	var index *ArtifactIndex
+	if f.verify {
+		var err error
+		index, err = newArtifactIndex(f.repoRoot, files, true)
+		if err != nil {
+			log.Printf("[WARNING] Could not index artifact %s: %s", hash, err)
+		}
+	}
 	WriteCacheMetaFile(filepath.Join(f.cacheDirectory, hash+"-meta.json"), &CacheMetadata{
 		Duration: duration,
 		Hash:     hash,
+		TaskID:   artifactTaskID(f.repoRoot, files),
+		Size:     size,
+		Index:    index,
 	})
 
 	return nil
@@ -135,6 +167,12 @@ func (cache *fsCache) Shutdown() {}
 type CacheMetadata struct {
 	Hash     string `json:"hash"`
 	Duration int    `json:"duration"`
+	// TaskID is the task that produced the artifact, if it could be told from the artifact
+	TaskID string `json:"taskId,omitempty"`
+	// Size is the number of bytes that the artifact's files take up in the local cache
+	Size int64 `json:"size,omitempty"`
+	// Index describes the artifact's files, so that restores can be verified
+	Index *ArtifactIndex `json:"index,omitempty"`
 }
 
 // WriteCacheMetaFile writes cache metadata file at a path
@@ -1,6 +1,9 @@
 package cache
 
 import (
+	"bytes"
+	"errors"
+	"fmt"
 	"io/ioutil"
 	"os"
 	"path/filepath"
@@ -246,3 +249,69 @@ func TestFetch(t *testing.T) {
 	_, err = os.Readlink(dstBrokenLinkPath)
 	assert.ErrorIs(t, err, os.ErrNotExist)
 }
+
+func TestFetchVerify(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	cacheDir := t.TempDir()
+	dist := repoRoot.Join("some-package", "dist")
+	assert.NilError(t, dist.MkdirAll(), "MkdirAll")
+	assert.NilError(t, dist.Join("a.js").WriteFile([]byte("a"), 0644), "WriteFile")
+	assert.NilError(t, dist.Join("b.js").WriteFile([]byte("b"), 0644), "WriteFile")
+	files := []string{
+		filepath.Join("some-package", "dist", "a.js"),
+		filepath.Join("some-package", "dist", "b.js"),
+	}
+
+	cache := &fsCache{
+		cacheDirectory: cacheDir,
+		recorder:       &dummyRecorder{},
+		repoRoot:       repoRoot,
+		verify:         true,
+	}
+	assert.NilError(t, cache.Put("unused", "the-hash", 0, files), "Put")
+	hit, _, _, err := cache.Fetch(repoRoot.ToString(), "the-hash", nil)
+	assert.NilError(t, err, "Fetch")
+	assert.Assert(t, hit, "expected a verified cache hit")
+
+	// Corrupt the cached copy of one of the files
+	cachedB := filepath.Join(cacheDir, "the-hash", "some-package", "dist", "b.js")
+	assert.NilError(t, ioutil.WriteFile(cachedB, []byte("not b"), 0644), "WriteFile")
+	hit, _, _, err = cache.Fetch(repoRoot.ToString(), "the-hash", nil)
+	assert.Assert(t, !hit, "expected a corrupt artifact not to be a hit")
+	corrupt := &CorruptArtifactError{}
+	assert.Assert(t, errors.As(err, &corrupt), "expected a CorruptArtifactError, got %v", err)
+	assert.DeepEqual(t, corrupt.Files, []string{"some-package/dist/b.js"})
+	assert.Assert(t, !fs.PathExists(filepath.Join(cacheDir, "the-hash")), "expected the corrupt entry to be removed")
+}
+
+// BenchmarkFetch restores an artifact of many files from the filesystem cache.
+// Point TMPDIR at a btrfs, XFS or APFS volume to measure restoring with copy-on-write
+// clones, rather than copies.
+func BenchmarkFetch(b *testing.B) {
+	const fileCount = 200
+	for _, size := range []int{1 << 10, 1 << 20} {
+		b.Run(fmt.Sprintf("%v files of %v bytes", fileCount, size), func(b *testing.B) {
+			cacheDir := b.TempDir()
+			contents := bytes.Repeat([]byte("a"), size)
+			for i := 0; i < fileCount; i++ {
+				path := filepath.Join(cacheDir, "the-hash", "some-package", "dist", fmt.Sprintf("chunk-%v", i/20), fmt.Sprintf("file-%v.js", i))
+				assert.NilError(b, os.MkdirAll(filepath.Dir(path), 0755), "MkdirAll")
+				assert.NilError(b, ioutil.WriteFile(path, contents, 0644), "WriteFile")
+			}
+			metadataPath := filepath.Join(cacheDir, "the-hash-meta.json")
+			assert.NilError(b, ioutil.WriteFile(metadataPath, []byte(`{"hash":"the-hash","duration":0}`), 0644), "WriteFile")
+			cache := &fsCache{
+				cacheDirectory: cacheDir,
+				recorder:       &dummyRecorder{},
+			}
+			target := b.TempDir()
+			b.SetBytes(int64(fileCount * size))
+			b.ResetTimer()
+			for i := 0; i < b.N; i++ {
+				if _, _, _, err := cache.Fetch(target, "the-hash", nil); err != nil {
+					b.Fatalf("Fetch: %v", err)
+				}
+			}
+		})
+	}
+}
@@ -15,16 +15,19 @@ import (
 	"net/http"
 	"os"
 	"path/filepath"
+	"runtime"
 	"strconv"
+	"sync"
 	"time"
 
 	"github.com/vercel/turborepo/cli/internal/analytics"
 	"github.com/vercel/turborepo/cli/internal/config"
 	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/ui"
 )
 
 type client interface {
-	PutArtifact(hash string, body []byte, duration int, tag string) error
+	PutArtifact(hash string, body []byte, duration int, tag string, ttl time.Duration) error
 	FetchArtifact(hash string) (*http.Response, error)
 }
 
@@ -35,6 +38,13 @@ type httpCache struct {
 	recorder       analytics.Recorder
 	signerVerifier *ArtifactSignatureAuthentication
 	repoRoot       fs.AbsolutePath
+	// uploadQueue holds artifacts that failed to upload, if --queue-uploads was passed
+	uploadQueue *uploadQueue
+	queuedOnce  sync.Once
+	// compressionLimiter bounds how many artifacts are compressed or decompressed at once
+	compressionLimiter limiter
+	// verify checks restored files against the artifact's index
+	verify bool
 }
 
 type limiter chan struct{}
@@ -54,17 +64,16 @@ var mtime = time.Date(2000, time.January, 1, 0, 0, 0, 0, time.UTC)
 const nobody = 65534
 
 func (cache *httpCache) Put(target, hash string, duration int, files []string) error {
+	return cache.put(target, hash, duration, files, 0)
+}
+
+// put uploads an artifact, asking the remote cache to keep it for ttl if it is positive.
+func (cache *httpCache) put(target, hash string, duration int, files []string, ttl time.Duration) error {
 	// if cache.writable {
 	cache.requestLimiter.acquire()
 	defer cache.requestLimiter.release()
 
-	r, w := io.Pipe()
-	go cache.write(w, hash, files)
-
-	// Read the entire artifact tar into memory so we can easily compute the signature.
-	// Note: retryablehttp.NewRequest reads the files into memory anyways so there's no
-	// additional overhead by doing the ioutil.ReadAll here instead.
-	artifactBody, err := ioutil.ReadAll(r)
+	artifactBody, err := cache.compress(hash, files)
 	if err != nil {
 		return fmt.Errorf("failed to store files in HTTP cache: %w", err)
 	}
@@ -75,7 +84,37 @@ func (cache *httpCache) Put(target, hash string, duration int, files []string) e
 			return fmt.Errorf("failed to store files in HTTP cache: %w", err)
 		}
 	}
-	return cache.client.PutArtifact(hash, artifactBody, duration, tag)
+	err = cache.client.PutArtifact(hash, artifactBody, duration, tag, ttl)
+	if err != nil && cache.uploadQueue != nil && shouldQueue(err) {
+		if queueErr := cache.uploadQueue.add(hash, artifactBody, duration, tag, ttl); queueErr != nil {
+			return fmt.Errorf("failed to queue upload after %v: %w", err, queueErr)
+		}
+		cache.queuedOnce.Do(func() {
+			fmt.Println(ui.Dim("• Remote cache unavailable, queueing uploads. Run \"turbo cache flush\" to upload them later"))
+		})
+		return nil
+	}
+	return err
+}
+
+// compress writes the files into an artifact, once a compression worker is free.
+func (cache *httpCache) compress(hash string, files []string) ([]byte, error) {
+	cache.compressionLimiter.acquire()
+	defer cache.compressionLimiter.release()
+	r, w := io.Pipe()
+	go cache.write(w, hash, files)
+
+	// Read the entire artifact tar into memory so we can easily compute the signature.
+	// Note: retryablehttp.NewRequest reads the files into memory anyways so there's no
+	// additional overhead by doing the ioutil.ReadAll here instead.
+	return ioutil.ReadAll(r)
+}
+
+// decompress restores an artifact into root, once a compression worker is free.
+func (cache *httpCache) decompress(root fs.AbsolutePath, artifact io.Reader) ([]string, error) {
+	cache.compressionLimiter.acquire()
+	defer cache.compressionLimiter.release()
+	return restoreTar(root, artifact)
 }
 
 // write writes a series of files into the given Writer.
@@ -85,23 +124,37 @@ func (cache *httpCache) write(w io.WriteCloser, hash string, files []string) {
 	defer gzw.Close()
 	tw := tar.NewWriter(gzw)
 	defer tw.Close()
+	// Files with the same contents as an earlier file are stored as a hard link to it
+	var duplicates map[string]string
+	if index, err := newArtifactIndex(cache.repoRoot, files, cache.verify); err != nil {
+		log.Printf("[WARNING] Could not index artifact %s: %s", hash, err)
+	} else if err := writeArtifactIndex(tw, index); err != nil {
+		log.Printf("[ERROR] Error writing the index of artifact %s: %s", hash, err)
+	} else {
+		duplicates = index.duplicates()
+	}
 	for _, file := range files {
 		// log.Printf("caching file %v", file)
-		if err := cache.storeFile(tw, file); err != nil {
+		if err := cache.storeFile(tw, file, duplicates[file]); err != nil {
 			log.Printf("[ERROR] Error uploading artifact %s to HTTP cache due to: %s", file, err)
 			// TODO(jaredpalmer): How can we cancel the request at this point?
 		}
 	}
 }
 
-func (cache *httpCache) storeFile(tw *tar.Writer, repoRelativePath string) error {
-	info, err := os.Lstat(repoRelativePath)
+// storeFile writes a file into the artifact. If duplicateOf is set, the file has the same
+// contents as that earlier file, and is written as a hard link to it.
+func (cache *httpCache) storeFile(tw *tar.Writer, repoRelativePath string, duplicateOf string) error {
+	// Use the absolute path, since paths relative to the working directory can't
+	// exceed MAX_PATH on Windows
+	sourcePath := cache.repoRoot.Join(repoRelativePath)
+	info, err := sourcePath.Lstat()
 	if err != nil {
 		return err
 	}
 	target := ""
 	if info.Mode()&os.ModeSymlink != 0 {
-		target, err = os.Readlink(repoRelativePath)
+		target, err = sourcePath.Readlink()
 		if err != nil {
 			return err
 		}
@@ -121,12 +174,18 @@ func (cache *httpCache) storeFile(tw *tar.Writer, repoRelativePath string) error
 	hdr.Gid = nobody
 	hdr.Uname = "nobody"
 	hdr.Gname = "nobody"
+	if duplicateOf != "" {
+		hdr.Typeflag = tar.TypeLink
+		hdr.Linkname = filepath.ToSlash(duplicateOf)
+		hdr.Size = 0
+		return tw.WriteHeader(hdr)
+	}
 	if err := tw.WriteHeader(hdr); err != nil {
 		return err
 	} else if info.IsDir() || target != "" {
 		return nil // nothing to write
 	}
-	f, err := os.Open(repoRelativePath)
+	f, err := sourcePath.Open()
 	if err != nil {
 		return err
 	}
@@ -167,57 +226,117 @@ func (cache *httpCache) logFetch(hit bool, hash string, duration int) {
 }
 
 func (cache *httpCache) retrieve(hash string) (bool, []string, int, error) {
-	resp, err := cache.client.FetchArtifact(hash)
+	artifact, meta, err := cache.fetchArtifact(hash)
+	if err != nil {
+		return false, nil, 0, err
+	} else if artifact == nil {
+		return false, nil, 0, nil // doesn't exist - not an error
+	}
+	defer func() { _ = artifact.Close() }()
+	if !cache.verify {
+		files, err := cache.decompress(cache.repoRoot, artifact)
+		if err != nil {
+			return false, nil, 0, err
+		}
+		return true, files, meta.duration, nil
+	}
+
+	// The index is read from the start of the artifact before it is restored, so keep
+	// the artifact in memory
+	body, err := ioutil.ReadAll(artifact)
+	if err != nil {
+		return false, nil, 0, err
+	}
+	index, err := readArtifactIndex(bytes.NewReader(body))
+	if err != nil {
+		return false, nil, 0, err
+	}
+	files, err := cache.decompress(cache.repoRoot, bytes.NewReader(body))
 	if err != nil {
 		return false, nil, 0, err
 	}
-	defer resp.Body.Close()
+	// Artifacts written by older versions of turbo have no index, and can't be verified
+	if index != nil {
+		if mismatched := index.mismatches(cache.repoRoot); len(mismatched) > 0 {
+			return false, nil, 0, &CorruptArtifactError{Hash: hash, Source: "REMOTE", Files: mismatched}
+		}
+	}
+	return true, files, meta.duration, nil
+}
+
+// artifactMetadata is what the remote cache says about an artifact it returns
+type artifactMetadata struct {
+	// duration is how long the task that produced the artifact took, in milliseconds
+	duration int
+	// expires is when the remote cache plans to drop the artifact, if it says
+	expires time.Time
+}
+
+// fetchArtifact downloads an artifact and verifies its signature, if signatures are enabled.
+// It returns a nil artifact if the remote cache doesn't have it.
+func (cache *httpCache) fetchArtifact(hash string) (io.ReadCloser, artifactMetadata, error) {
+	meta := artifactMetadata{}
+	resp, err := cache.client.FetchArtifact(hash)
+	if err != nil {
+		return nil, meta, err
+	}
 	if resp.StatusCode == http.StatusNotFound {
-		return false, nil, 0, nil // doesn't exist - not an error
+		_ = resp.Body.Close()
+		return nil, meta, nil
 	} else if resp.StatusCode != http.StatusOK {
 		b, _ := ioutil.ReadAll(resp.Body)
-		return false, nil, 0, fmt.Errorf("%s", string(b))
+		_ = resp.Body.Close()
+		return nil, meta, fmt.Errorf("%s", string(b))
 	}
 	// If present, extract the duration from the response.
-	duration := 0
 	if resp.Header.Get("x-artifact-duration") != "" {
 		intVar, err := strconv.Atoi(resp.Header.Get("x-artifact-duration"))
 		if err != nil {
-			return false, nil, 0, fmt.Errorf("invalid x-artifact-duration header: %w", err)
+			_ = resp.Body.Close()
+			return nil, meta, fmt.Errorf("invalid x-artifact-duration header: %w", err)
 		}
-		duration = intVar
+		meta.duration = intVar
+	}
+	// The expiry is informational, so a value we can't parse is ignored
+	meta.expires = parseExpires(resp.Header.Get("x-artifact-expires"))
+	if !cache.signerVerifier.isEnabled() {
+		return resp.Body, meta, nil
 	}
-	var tarReader io.Reader
 
 	defer func() { _ = resp.Body.Close() }()
-	if cache.signerVerifier.isEnabled() {
-		expectedTag := resp.Header.Get("x-artifact-tag")
-		if expectedTag == "" {
-			// If the verifier is enabled all incoming artifact downloads must have a signature
-			return false, nil, 0, errors.New("artifact verification failed: Downloaded artifact is missing required x-artifact-tag header")
-		}
-		b, err := ioutil.ReadAll(resp.Body)
-		if err != nil {
-			return false, nil, 0, fmt.Errorf("artifact verification failed: %w", err)
-		}
-		isValid, err := cache.signerVerifier.validate(hash, b, expectedTag)
-		if err != nil {
-			return false, nil, 0, fmt.Errorf("artifact verification failed: %w", err)
-		}
-		if !isValid {
-			err = fmt.Errorf("artifact verification failed: artifact tag does not match expected tag %s", expectedTag)
-			return false, nil, 0, err
-		}
-		// The artifact has been verified and the body can be read and untarred
-		tarReader = bytes.NewReader(b)
-	} else {
-		tarReader = resp.Body
+	expectedTag := resp.Header.Get("x-artifact-tag")
+	if expectedTag == "" {
+		// If the verifier is enabled all incoming artifact downloads must have a signature
+		return nil, meta, errors.New("artifact verification failed: Downloaded artifact is missing required x-artifact-tag header")
 	}
-	files, err := restoreTar(cache.repoRoot, tarReader)
+	b, err := ioutil.ReadAll(resp.Body)
 	if err != nil {
-		return false, nil, 0, err
+		return nil, meta, fmt.Errorf("artifact verification failed: %w", err)
+	}
+	isValid, err := cache.signerVerifier.validate(hash, b, expectedTag)
+	if err != nil {
+		return nil, meta, fmt.Errorf("artifact verification failed: %w", err)
+	}
+	if !isValid {
+		return nil, meta, fmt.Errorf("artifact verification failed: artifact tag does not match expected tag %s", expectedTag)
 	}
-	return true, files, duration, nil
+	// The artifact has been verified and the body can be read and untarred
+	return ioutil.NopCloser(bytes.NewReader(b)), meta, nil
+}
+
+// parseExpires reads an x-artifact-expires header, which is either an RFC 3339 timestamp
+// or an HTTP date. It returns the zero time if the header is missing or invalid.
+func parseExpires(header string) time.Time {
+	if header == "" {
+		return time.Time{}
+	}
+	if expires, err := time.Parse(time.RFC3339, header); err == nil {
+		return expires
+	}
+	if expires, err := http.ParseTime(header); err == nil {
+		return expires
+	}
+	return time.Time{}
 }
 
 // restoreTar returns posix-style repo-relative paths of the files it
@@ -233,7 +352,7 @@ func restoreTar(root fs.AbsolutePath, reader io.Reader) ([]string, error) {
 	}
 	defer func() { _ = gzr.Close() }()
 	tr := tar.NewReader(gzr)
-	for {
+	for first := true; ; first = false {
 		hdr, err := tr.Next()
 		if err != nil {
 			if err == io.EOF {
@@ -248,6 +367,10 @@ func restoreTar(root fs.AbsolutePath, reader io.Reader) ([]string, error) {
 			}
 			return nil, err
 		}
+		// The artifact's index isn't one of the task's outputs
+		if first && isArtifactIndex(hdr) {
+			continue
+		}
 		// hdr.Name is always a posix-style path
 		// TODO: files should eventually be repo-relative system paths
 		files = append(files, hdr.Name)
@@ -275,6 +398,10 @@ func restoreTar(root fs.AbsolutePath, reader io.Reader) ([]string, error) {
 			} else if err := f.Close(); err != nil {
 				return nil, err
 			}
+		case tar.TypeLink:
+			if err := restoreDuplicate(root, hdr); err != nil {
+				return nil, err
+			}
 		case tar.TypeSymlink:
 			if err := restoreSymlink(root, hdr, false); errors.Is(err, errNonexistentLinkTarget) {
 				missingLinks = append(missingLinks, hdr)
@@ -330,7 +457,7 @@ func (cache *httpCache) CleanAll() {
 func (cache *httpCache) Shutdown() {}
 
 func newHTTPCache(opts Opts, config *config.Config, client client, recorder analytics.Recorder, repoRoot fs.AbsolutePath) *httpCache {
-	return &httpCache{
+	cache := &httpCache{
 		writable:       true,
 		client:         client,
 		requestLimiter: make(limiter, 20),
@@ -342,5 +469,20 @@ func newHTTPCache(opts Opts, config *config.Config, client client, recorder anal
 			enabled: opts.RemoteCacheOpts.Signature,
 		},
 		repoRoot: repoRoot,
+		// Without a configured number of workers, e.g. for turbo cache commands,
+		// compression isn't competing with running tasks
+		compressionLimiter: make(limiter, compressionWorkers(opts)),
+		verify:             opts.Verify,
+	}
+	if opts.QueueUploads {
+		cache.uploadQueue = newUploadQueue(opts.Dir)
+	}
+	return cache
+}
+
+func compressionWorkers(opts Opts) int {
+	if opts.CompressionWorkers > 0 {
+		return opts.CompressionWorkers
 	}
+	return runtime.NumCPU()
 }
@@ -5,8 +5,13 @@ import (
 	"bytes"
 	"compress/gzip"
 	"errors"
+	"io"
+	"io/ioutil"
 	"net/http"
+	"path/filepath"
+	"strings"
 	"testing"
+	"time"
 
 	"github.com/vercel/turborepo/cli/internal/fs"
 	"github.com/vercel/turborepo/cli/internal/util"
@@ -17,7 +22,7 @@ type errorResp struct {
 	err error
 }
 
-func (sr *errorResp) PutArtifact(hash string, body []byte, duration int, tag string) error {
+func (sr *errorResp) PutArtifact(hash string, body []byte, duration int, tag string, ttl time.Duration) error {
 	return sr.err
 }
 
@@ -224,6 +229,85 @@ func TestRestoreInvalidTar(t *testing.T) {
 	assert.Equal(t, string(contents), string(expectedContents), "expected to not overwrite file")
 }
 
+func TestRestoreTarLongPaths(t *testing.T) {
+	// Deeply nested node_modules in outputs easily exceed MAX_PATH (260 characters) on Windows
+	nestedFile := filepath.Join(strings.Repeat(filepath.Join("node_modules", "nested-package")+string(filepath.Separator), 12), "package.json")
+	srcRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	srcFile := srcRoot.Join(nestedFile)
+	assert.Assert(t, len(srcFile.ToString()) > 260)
+	err := srcFile.EnsureDir()
+	assert.NilError(t, err, "EnsureDir")
+	err = srcFile.WriteFile([]byte("{}"), 0644)
+	assert.NilError(t, err, "WriteFile")
+
+	cache := &httpCache{repoRoot: srcRoot}
+	r, w := io.Pipe()
+	go cache.write(w, "some-hash", []string{nestedFile})
+	artifact, err := ioutil.ReadAll(r)
+	assert.NilError(t, err, "ReadAll")
+
+	dstRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	files, err := restoreTar(dstRoot, bytes.NewReader(artifact))
+	assert.NilError(t, err, "restoreTar")
+	assert.DeepEqual(t, files, []string{filepath.ToSlash(nestedFile)})
+	contents, err := dstRoot.Join(nestedFile).ReadFile()
+	assert.NilError(t, err, "ReadFile")
+	assert.Equal(t, string(contents), "{}")
+}
+
+func TestWriteDeduplicatesFiles(t *testing.T) {
+	srcRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	sources := map[string]string{
+		"packages/a/dist/types.d.ts": "export type A = string;\n",
+		"packages/b/dist/types.d.ts": "export type A = string;\n",
+		"packages/b/dist/index.js":   "export const b = 'b';\n",
+	}
+	for name, contents := range sources {
+		file := srcRoot.Join(filepath.FromSlash(name))
+		assert.NilError(t, file.EnsureDir(), "EnsureDir")
+		assert.NilError(t, file.WriteFile([]byte(contents), 0644), "WriteFile")
+	}
+	files := []string{
+		filepath.FromSlash("packages/a/dist/types.d.ts"),
+		filepath.FromSlash("packages/b/dist/index.js"),
+		filepath.FromSlash("packages/b/dist/types.d.ts"),
+	}
+
+	cache := &httpCache{repoRoot: srcRoot}
+	r, w := io.Pipe()
+	go cache.write(w, "some-hash", files)
+	artifact, err := ioutil.ReadAll(r)
+	assert.NilError(t, err, "ReadAll")
+
+	gzr, err := gzip.NewReader(bytes.NewReader(artifact))
+	assert.NilError(t, err, "NewReader")
+	tr := tar.NewReader(gzr)
+	links := map[string]string{}
+	for {
+		hdr, err := tr.Next()
+		if err == io.EOF {
+			break
+		}
+		assert.NilError(t, err, "Next")
+		if hdr.Typeflag == tar.TypeLink {
+			links[hdr.Name] = hdr.Linkname
+		}
+	}
+	assert.DeepEqual(t, links, map[string]string{"packages/b/dist/types.d.ts": "packages/a/dist/types.d.ts"})
+
+	dstRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	_, err = restoreTar(dstRoot, bytes.NewReader(artifact))
+	assert.NilError(t, err, "restoreTar")
+	for name, expected := range sources {
+		contents, err := dstRoot.Join(filepath.FromSlash(name)).ReadFile()
+		assert.NilError(t, err, "ReadFile")
+		assert.Equal(t, string(contents), expected)
+	}
+	same, err := fs.SameFile(dstRoot.Join("packages", "a", "dist", "types.d.ts").ToString(), dstRoot.Join("packages", "b", "dist", "types.d.ts").ToString())
+	assert.NilError(t, err, "SameFile")
+	assert.Assert(t, !same, "duplicates should be restored as copies")
+}
+
 // Note that testing Put will require mocking the filesystem and is not currently the most
 // interesting test. The current implementation directly returns the error from PutArtifact.
 // We should still add the test once feasible to avoid future breakage.
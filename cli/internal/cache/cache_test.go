@@ -267,3 +267,28 @@ func TestNew(t *testing.T) {
 		})
 	}
 }
+
+func TestLocalOnly(t *testing.T) {
+	localCache := newEnabledCache()
+	// A remote cache is never called through the view, so it doesn't need a client
+	remoteCache := &httpCache{}
+	mplex := &cacheMultiplexer{
+		caches: []Cache{localCache, remoteCache},
+	}
+	view := LocalOnly(mplex)
+
+	if err := view.Put("unused-target", "some-hash", 5, []string{"a-file"}); err != nil {
+		t.Errorf("Put got error %v, want <nil>", err)
+	}
+	if !reflect.DeepEqual(localCache.entries["some-hash"], []string{"a-file"}) {
+		t.Errorf("Put didn't store the artifact in the local cache")
+	}
+	hit, _, _, err := view.Fetch("unused-target", "some-hash", nil)
+	if err != nil || !hit {
+		t.Errorf("Fetch got %v, %v, want a hit from the local cache", hit, err)
+	}
+	hit, _, _, err = view.Fetch("unused-target", "other-hash", nil)
+	if err != nil || hit {
+		t.Errorf("Fetch got %v, %v, want a miss without asking the remote cache", hit, err)
+	}
+}
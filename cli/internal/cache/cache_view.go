@@ -0,0 +1,87 @@
+package cache
+
+import "time"
+
+// putOptions are settings for storing one artifact, from the task that produced it
+type putOptions struct {
+	// localOnly skips the remote cache
+	localOnly bool
+	// ttl, if positive, is sent to the remote cache as a hint of how long to keep the
+	// artifact
+	ttl time.Duration
+}
+
+// LocalOnly returns a view of the cache that neither reads from nor writes to the remote
+// cache, for tasks whose role doesn't allow it. The view shares the caches, and the
+// upload workers, of the cache it wraps.
+func LocalOnly(c Cache) Cache {
+	return &cacheView{cache: c, opts: putOptions{localOnly: true}}
+}
+
+// WithTTL returns a view of the cache that asks the remote cache to keep the artifacts
+// stored through it for the given time. It is only a hint: the remote cache decides how
+// long it keeps artifacts.
+func WithTTL(c Cache, ttl time.Duration) Cache {
+	return &cacheView{cache: c, opts: putOptions{ttl: ttl}}
+}
+
+type cacheView struct {
+	cache Cache
+	opts  putOptions
+}
+
+func (v *cacheView) Fetch(target string, key string, files []string) (bool, []string, int, error) {
+	if v.opts.localOnly {
+		return fetchLocal(v.cache, target, key, files)
+	}
+	return v.cache.Fetch(target, key, files)
+}
+
+func (v *cacheView) Put(target string, key string, duration int, files []string) error {
+	return putWithOptions(v.cache, target, key, duration, files, v.opts)
+}
+
+func (v *cacheView) Clean(target string) {
+	v.cache.Clean(target)
+}
+
+func (v *cacheView) CleanAll() {
+	v.cache.CleanAll()
+}
+
+// Shutdown does nothing, since the wrapped cache is shut down by its owner
+func (v *cacheView) Shutdown() {}
+
+func fetchLocal(c Cache, target string, key string, files []string) (bool, []string, int, error) {
+	switch c := c.(type) {
+	case *asyncCache:
+		return fetchLocal(c.realCache, target, key, files)
+	case *cacheMultiplexer:
+		return c.fetch(target, key, files, true)
+	case *httpCache:
+		return false, files, 0, nil
+	}
+	return c.Fetch(target, key, files)
+}
+
+func putWithOptions(c Cache, target string, key string, duration int, files []string, opts putOptions) error {
+	switch c := c.(type) {
+	case *asyncCache:
+		c.requests <- cacheRequest{
+			target:   target,
+			key:      key,
+			files:    files,
+			duration: duration,
+			opts:     opts,
+		}
+		return nil
+	case *cacheMultiplexer:
+		return c.storeUntil(target, key, duration, files, len(c.caches), opts)
+	case *httpCache:
+		if opts.localOnly {
+			return nil
+		}
+		return c.put(target, key, duration, files, opts.ttl)
+	}
+	return c.Put(target, key, duration, files)
+}
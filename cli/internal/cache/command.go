@@ -0,0 +1,50 @@
+package cache
+
+import (
+	"github.com/mitchellh/cli"
+	"github.com/spf13/cobra"
+	"github.com/vercel/turborepo/cli/internal/config"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// Command is the wrapper around the cache command until we port fully to cobra
+type Command struct {
+	Config *config.Config
+	UI     cli.Ui
+}
+
+// Run runs the cache command
+func (c *Command) Run(args []string) int {
+	cmd := getCmd(c.Config, c.UI)
+	cmd.SetArgs(args)
+	err := cmd.Execute()
+	if err != nil {
+		return 1
+	}
+	return 0
+}
+
+// Help returns information about the `cache` command
+func (c *Command) Help() string {
+	cmd := getCmd(c.Config, c.UI)
+	return util.HelpForCobraCmd(cmd)
+}
+
+// Synopsis of cache command
+func (c *Command) Synopsis() string {
+	cmd := getCmd(c.Config, c.UI)
+	return cmd.Short
+}
+
+func getCmd(config *config.Config, output cli.Ui) *cobra.Command {
+	cmd := &cobra.Command{
+		Use:           "turbo cache",
+		Short:         "Manage the local and remote caches",
+		SilenceUsage:  true,
+		SilenceErrors: true,
+	}
+	addFlushCmd(cmd, config, output)
+	addInspectCmd(cmd, config, output)
+	addDuCmd(cmd, config, output)
+	return cmd
+}
@@ -0,0 +1,40 @@
+package cache
+
+import (
+	"archive/tar"
+	"fmt"
+	"io"
+	"os"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+// restoreDuplicate restores a file that was stored as a hard link to an earlier file
+// with the same contents. It is restored as a copy, so that editing one output doesn't
+// change another.
+func restoreDuplicate(root fs.AbsolutePath, hdr *tar.Header) error {
+	source := root.Join(hdr.Linkname)
+	if isChild, err := root.ContainsPath(source); err != nil {
+		return err
+	} else if !isChild {
+		return fmt.Errorf("cannot copy %v from %v, which is outside of %v", hdr.Name, hdr.Linkname, root)
+	}
+	from, err := source.Open()
+	if err != nil {
+		return err
+	}
+	defer func() { _ = from.Close() }()
+	filename := root.Join(hdr.Name)
+	if err := filename.EnsureDir(); err != nil {
+		return err
+	}
+	to, err := filename.OpenFile(os.O_WRONLY|os.O_TRUNC|os.O_CREATE, os.FileMode(hdr.Mode))
+	if err != nil {
+		return err
+	}
+	if _, err := io.Copy(to, from); err != nil {
+		_ = to.Close()
+		return err
+	}
+	return to.Close()
+}
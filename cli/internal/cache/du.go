@@ -0,0 +1,287 @@
+package cache
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	iofs "io/fs"
+	"os"
+	"path"
+	"path/filepath"
+	"sort"
+	"strings"
+	"text/tabwriter"
+
+	"github.com/fatih/color"
+	"github.com/mitchellh/cli"
+	"github.com/spf13/cobra"
+	"github.com/vercel/turborepo/cli/internal/config"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/ui"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// _unknownTask groups artifacts whose task can't be told, e.g. because the package
+// that produced them no longer exists
+const _unknownTask = "<unknown>"
+
+func addDuCmd(root *cobra.Command, config *config.Config, output cli.Ui) {
+	cacheDir := DefaultLocation(config.Cwd)
+	var byTask bool
+	var byPackage bool
+	var top int
+	var outputJSON bool
+	cmd := &cobra.Command{
+		Use:           "du",
+		Short:         "Shows how much disk space the local cache uses, and which artifacts use it",
+		SilenceUsage:  true,
+		SilenceErrors: true,
+		RunE: func(cmd *cobra.Command, args []string) error {
+			if err := printDiskUsage(config, output, cacheDir, byTask, byPackage, top, outputJSON); err != nil {
+				output.Error(fmt.Sprintf("%s%s", ui.ERROR_PREFIX, color.RedString(" %v", err)))
+				return err
+			}
+			return nil
+		},
+	}
+	cmd.Flags().BoolVar(&byTask, "by-task", false, "Add up the artifacts of each task")
+	cmd.Flags().BoolVar(&byPackage, "by-package", false, "Add up the artifacts of each package")
+	cmd.Flags().IntVar(&top, "top", 10, "Only list this many of the largest entries. Use 0 to list all of them")
+	cmd.Flags().BoolVar(&outputJSON, "json", false, "Print the disk usage as JSON")
+	fs.AbsolutePathVar(cmd.Flags(), &cacheDir, "cache-dir", config.Cwd, "Specify local filesystem cache directory.", "./node_modules/.cache/turbo")
+	root.AddCommand(cmd)
+}
+
+// artifactUsage is the disk space used by an artifact in the local cache
+type artifactUsage struct {
+	Hash   string `json:"hash"`
+	TaskID string `json:"taskId"`
+	Size   int64  `json:"size"`
+}
+
+// diskUsageEntry is the disk space used by an artifact, or a group of them
+type diskUsageEntry struct {
+	Name      string `json:"name"`
+	Artifacts int    `json:"artifacts"`
+	Size      int64  `json:"size"`
+}
+
+// diskUsage is what turbo cache du reports
+type diskUsage struct {
+	Entries []diskUsageEntry `json:"entries"`
+	// Artifacts and Size cover every artifact, including the ones not listed in Entries
+	Artifacts int   `json:"artifacts"`
+	Size      int64 `json:"size"`
+	// QueuedUploads are the artifacts waiting to be uploaded, and QueuedSize their
+	// compressed size
+	QueuedUploads int   `json:"queuedUploads"`
+	QueuedSize    int64 `json:"queuedSize"`
+}
+
+func printDiskUsage(config *config.Config, output cli.Ui, cacheDir fs.AbsolutePath, byTask bool, byPackage bool, top int, outputJSON bool) error {
+	if byTask && byPackage {
+		return errors.New("--by-task and --by-package can't be used together")
+	}
+	artifacts, err := readArtifactUsage(cacheDir, config.Cwd)
+	if err != nil {
+		return err
+	}
+	groupBy := func(artifact artifactUsage) string { return artifact.Hash }
+	if byTask {
+		groupBy = func(artifact artifactUsage) string { return artifact.TaskID }
+	} else if byPackage {
+		groupBy = packageOf
+	}
+	usage := summarizeDiskUsage(artifacts, groupBy, top)
+	usage.QueuedUploads, usage.QueuedSize, err = uploadQueueUsage(cacheDir)
+	if err != nil {
+		return err
+	}
+
+	if outputJSON {
+		encoded, err := json.MarshalIndent(usage, "", "  ")
+		if err != nil {
+			return err
+		}
+		output.Output(string(encoded))
+		return nil
+	}
+	w := tabwriter.NewWriter(os.Stdout, 0, 0, 2, ' ', 0)
+	for _, entry := range usage.Entries {
+		if byTask || byPackage {
+			fmt.Fprintf(w, "%v\t%v artifacts\t%v\n", util.FormatBytes(uint64(entry.Size)), entry.Artifacts, entry.Name)
+		} else {
+			fmt.Fprintf(w, "%v\t%v\t%v\n", util.FormatBytes(uint64(entry.Size)), entry.Name, taskOf(artifacts, entry.Name))
+		}
+	}
+	_ = w.Flush()
+	if len(usage.Entries) > 0 {
+		output.Output("")
+	}
+	output.Output(fmt.Sprintf("Total: %v in %v artifacts", util.FormatBytes(uint64(usage.Size)), usage.Artifacts))
+	if usage.QueuedUploads > 0 {
+		output.Output(fmt.Sprintf("Queued for upload: %v in %v artifacts", util.FormatBytes(uint64(usage.QueuedSize)), usage.QueuedUploads))
+	}
+	return nil
+}
+
+// taskOf returns the task of the artifact with the given hash
+func taskOf(artifacts []artifactUsage, hash string) string {
+	for _, artifact := range artifacts {
+		if artifact.Hash == hash {
+			return artifact.TaskID
+		}
+	}
+	return _unknownTask
+}
+
+// packageOf returns the package whose task produced the artifact
+func packageOf(artifact artifactUsage) string {
+	if artifact.TaskID == _unknownTask {
+		return _unknownTask
+	}
+	pkg, _ := util.GetPackageTaskFromId(artifact.TaskID)
+	return pkg
+}
+
+// summarizeDiskUsage groups artifacts by the given key and returns the top groups, largest first
+func summarizeDiskUsage(artifacts []artifactUsage, groupBy func(artifactUsage) string, top int) *diskUsage {
+	usage := &diskUsage{Entries: []diskUsageEntry{}}
+	groups := make(map[string]*diskUsageEntry)
+	for _, artifact := range artifacts {
+		usage.Artifacts++
+		usage.Size += artifact.Size
+		name := groupBy(artifact)
+		entry, ok := groups[name]
+		if !ok {
+			entry = &diskUsageEntry{Name: name}
+			groups[name] = entry
+		}
+		entry.Artifacts++
+		entry.Size += artifact.Size
+	}
+	for _, entry := range groups {
+		usage.Entries = append(usage.Entries, *entry)
+	}
+	sort.Slice(usage.Entries, func(i, j int) bool {
+		if usage.Entries[i].Size != usage.Entries[j].Size {
+			return usage.Entries[i].Size > usage.Entries[j].Size
+		}
+		return usage.Entries[i].Name < usage.Entries[j].Name
+	})
+	if top > 0 && len(usage.Entries) > top {
+		usage.Entries = usage.Entries[:top]
+	}
+	return usage
+}
+
+// readArtifactUsage returns the disk space used by each artifact in the local cache.
+// Artifacts cached by older versions of turbo don't have their size and task in their
+// metadata, so those are worked out from the artifact's files.
+func readArtifactUsage(cacheDir fs.AbsolutePath, repoRoot fs.AbsolutePath) ([]artifactUsage, error) {
+	entries, err := os.ReadDir(cacheDir.ToString())
+	if errors.Is(err, os.ErrNotExist) {
+		return nil, nil
+	} else if err != nil {
+		return nil, err
+	}
+	var artifacts []artifactUsage
+	for _, entry := range entries {
+		hash := entry.Name()
+		// Skip the upload queue and artifacts that turbo cache warm is still downloading
+		if !entry.IsDir() || hash == _uploadQueueDir || strings.Contains(hash, "-warm-") {
+			continue
+		}
+		artifact := artifactUsage{Hash: hash}
+		metaFile := cacheDir.Join(hash + "-meta.json")
+		if meta, err := ReadCacheMetaFile(metaFile.ToString()); err == nil {
+			artifact.TaskID = meta.TaskID
+			artifact.Size = meta.Size
+		}
+		if artifact.Size == 0 {
+			artifact.Size = artifactSize(cacheDir.Join(hash))
+		}
+		if info, err := metaFile.Lstat(); err == nil {
+			artifact.Size += info.Size()
+		}
+		if artifact.TaskID == "" {
+			artifact.TaskID = artifactTaskID(repoRoot, artifactFiles(cacheDir.Join(hash)))
+		}
+		if artifact.TaskID == "" {
+			artifact.TaskID = _unknownTask
+		}
+		artifacts = append(artifacts, artifact)
+	}
+	return artifacts, nil
+}
+
+// uploadQueueUsage returns the number of queued uploads and the bytes they take up
+func uploadQueueUsage(cacheDir fs.AbsolutePath) (int, int64, error) {
+	uploads, err := newUploadQueue(cacheDir).list()
+	if err != nil {
+		return 0, 0, err
+	}
+	var size int64
+	for _, upload := range uploads {
+		if info, err := cacheDir.Join(_uploadQueueDir, upload.Hash+".tar.gz").Lstat(); err == nil {
+			size += info.Size()
+		}
+	}
+	return len(uploads), size, nil
+}
+
+// artifactSize adds up the sizes of the files in a directory of the local cache
+func artifactSize(dir fs.AbsolutePath) int64 {
+	var size int64
+	_ = filepath.WalkDir(dir.ToString(), func(_ string, entry iofs.DirEntry, err error) error {
+		if err != nil || entry.IsDir() {
+			return nil
+		}
+		if info, err := entry.Info(); err == nil {
+			size += info.Size()
+		}
+		return nil
+	})
+	return size
+}
+
+// artifactFiles lists the files in a directory of the local cache, relative to it
+func artifactFiles(dir fs.AbsolutePath) []string {
+	var files []string
+	_ = filepath.WalkDir(dir.ToString(), func(name string, entry iofs.DirEntry, err error) error {
+		if err != nil || entry.IsDir() {
+			return nil
+		}
+		if relativePath, err := filepath.Rel(dir.ToString(), name); err == nil {
+			files = append(files, relativePath)
+		}
+		return nil
+	})
+	return files
+}
+
+// artifactTaskID tells which task produced an artifact from its files. Every artifact
+// holds the task's log, at <package dir>/.turbo/turbo-<task>.log(.gz), and the package's name
+// is read from the manifest in that directory. It returns "" if there is no log, or the
+// package no longer exists.
+func artifactTaskID(repoRoot fs.AbsolutePath, files []string) string {
+	for _, file := range files {
+		file = filepath.ToSlash(file)
+		logName := strings.TrimSuffix(path.Base(file), ".gz")
+		if path.Base(path.Dir(file)) != ".turbo" || !strings.HasPrefix(logName, "turbo-") || !strings.HasSuffix(logName, ".log") {
+			continue
+		}
+		task := strings.TrimSuffix(strings.TrimPrefix(logName, "turbo-"), ".log")
+		pkgDir := path.Dir(path.Dir(file))
+		if pkgDir == "." {
+			return util.RootTaskID(task)
+		}
+		for _, manifest := range []string{"package.json", "turbo-package.json"} {
+			if pkg, err := fs.ReadPackageJSON(repoRoot.Join(filepath.FromSlash(pkgDir), manifest).ToString()); err == nil && pkg.Name != "" {
+				return util.GetTaskId(pkg.Name, task)
+			}
+		}
+		return ""
+	}
+	return ""
+}
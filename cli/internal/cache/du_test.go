@@ -0,0 +1,54 @@
+package cache
+
+import (
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+func TestArtifactUsage(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	cacheDir := repoRoot.Join("node_modules", ".cache", "turbo")
+	assert.NilError(t, repoRoot.Join("apps", "web").MkdirAll(), "MkdirAll")
+	assert.NilError(t, repoRoot.Join("apps", "web", "package.json").WriteFile([]byte(`{"name": "web"}`), 0644), "WriteFile")
+
+	// An artifact cached by this version of turbo, with its task and size in its metadata
+	assert.NilError(t, cacheDir.Join("web-build-hash").MkdirAll(), "MkdirAll")
+	assert.NilError(t, WriteCacheMetaFile(cacheDir.Join("web-build-hash-meta.json").ToString(), &CacheMetadata{
+		Hash:   "web-build-hash",
+		TaskID: "web#build",
+		Size:   1000,
+	}), "WriteCacheMetaFile")
+	// An artifact cached by an older version of turbo, without them
+	oldArtifact := cacheDir.Join("web-test-hash", "apps", "web", ".turbo")
+	assert.NilError(t, oldArtifact.MkdirAll(), "MkdirAll")
+	assert.NilError(t, oldArtifact.Join("turbo-test.log").WriteFile([]byte("0123456789"), 0644), "WriteFile")
+	assert.NilError(t, WriteCacheMetaFile(cacheDir.Join("web-test-hash-meta.json").ToString(), &CacheMetadata{
+		Hash: "web-test-hash",
+	}), "WriteCacheMetaFile")
+	// An artifact of the root package
+	rootArtifact := cacheDir.Join("root-hash", ".turbo")
+	assert.NilError(t, rootArtifact.MkdirAll(), "MkdirAll")
+	assert.NilError(t, rootArtifact.Join("turbo-lint.log").WriteFile([]byte("01234"), 0644), "WriteFile")
+
+	artifacts, err := readArtifactUsage(cacheDir, repoRoot)
+	assert.NilError(t, err, "readArtifactUsage")
+	assert.Equal(t, len(artifacts), 3)
+	tasks := make(map[string]string)
+	for _, artifact := range artifacts {
+		tasks[artifact.Hash] = artifact.TaskID
+	}
+	assert.DeepEqual(t, tasks, map[string]string{
+		"web-build-hash": "web#build",
+		"web-test-hash":  "web#test",
+		"root-hash":      "//#lint",
+	})
+
+	byPackage := summarizeDiskUsage(artifacts, packageOf, 1)
+	assert.Equal(t, byPackage.Artifacts, 3)
+	assert.Equal(t, len(byPackage.Entries), 1)
+	assert.Equal(t, byPackage.Entries[0].Name, "web")
+	assert.Equal(t, byPackage.Entries[0].Artifacts, 2)
+	assert.Assert(t, byPackage.Entries[0].Size > 1010, "the web artifacts include their metadata")
+}
@@ -0,0 +1,45 @@
+package cache
+
+import (
+	"errors"
+	"fmt"
+
+	"github.com/fatih/color"
+	"github.com/mitchellh/cli"
+	"github.com/spf13/cobra"
+	"github.com/vercel/turborepo/cli/internal/config"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/ui"
+)
+
+func addFlushCmd(root *cobra.Command, config *config.Config, output cli.Ui) {
+	cacheDir := DefaultLocation(config.Cwd)
+	cmd := &cobra.Command{
+		Use:           "flush",
+		Short:         "Uploads artifacts that were queued while the remote cache was unavailable",
+		SilenceUsage:  true,
+		SilenceErrors: true,
+		RunE: func(cmd *cobra.Command, args []string) error {
+			if !config.IsLoggedIn() {
+				err := errors.New("not linked to a remote cache. Run \"turbo login\" and \"turbo link\" first")
+				output.Error(fmt.Sprintf("%s%s", ui.ERROR_PREFIX, color.RedString(" %v", err)))
+				return err
+			}
+			uploaded, remaining, err := FlushUploadQueue(cacheDir, config.NewClient())
+			if uploaded > 0 {
+				output.Output(fmt.Sprintf("Uploaded %v queued artifacts", uploaded))
+			}
+			if err != nil {
+				output.Error(fmt.Sprintf("%s%s", ui.ERROR_PREFIX, color.RedString(" %v", err)))
+				output.Output(fmt.Sprintf("%v artifacts are still queued", remaining))
+				return err
+			}
+			if uploaded == 0 {
+				output.Output("No queued artifacts to upload")
+			}
+			return nil
+		},
+	}
+	fs.AbsolutePathVar(cmd.Flags(), &cacheDir, "cache-dir", config.Cwd, "Specify local filesystem cache directory.", "./node_modules/.cache/turbo")
+	root.AddCommand(cmd)
+}
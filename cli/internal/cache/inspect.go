@@ -0,0 +1,115 @@
+package cache
+
+import (
+	"bytes"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"io/ioutil"
+	"os"
+	"text/tabwriter"
+	"time"
+
+	"github.com/fatih/color"
+	"github.com/mitchellh/cli"
+	"github.com/spf13/cobra"
+	"github.com/vercel/turborepo/cli/internal/config"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/ui"
+)
+
+func addInspectCmd(root *cobra.Command, config *config.Config, output cli.Ui) {
+	var verify bool
+	var outputJSON bool
+	cmd := &cobra.Command{
+		Use:           "inspect <hash>",
+		Short:         "Lists the files in an artifact in the remote cache, without restoring it",
+		Args:          cobra.ExactArgs(1),
+		SilenceUsage:  true,
+		SilenceErrors: true,
+		RunE: func(cmd *cobra.Command, args []string) error {
+			if err := inspectArtifact(config, output, args[0], verify, outputJSON); err != nil {
+				output.Error(fmt.Sprintf("%s%s", ui.ERROR_PREFIX, color.RedString(" %v", err)))
+				return err
+			}
+			return nil
+		},
+	}
+	cmd.Flags().BoolVar(&verify, "verify", false, "Also check that every file in the artifact matches its index")
+	cmd.Flags().BoolVar(&outputJSON, "json", false, "Print the artifact's index as JSON")
+	root.AddCommand(cmd)
+}
+
+// inspectOutput is what inspect --json prints: the artifact's index, and when the remote
+// cache plans to drop the artifact, if it says
+type inspectOutput struct {
+	*ArtifactIndex
+	Expires *time.Time `json:"expires,omitempty"`
+}
+
+func inspectArtifact(config *config.Config, output cli.Ui, hash string, verify bool, outputJSON bool) error {
+	if !config.IsLoggedIn() {
+		return errors.New("not linked to a remote cache. Run \"turbo login\" and \"turbo link\" first")
+	}
+	opts := Opts{}
+	if turboJSON, err := fs.ReadTurboConfig(config.Cwd, config.RootPackageJSON); err == nil {
+		opts.RemoteCacheOpts = turboJSON.RemoteCacheOptions
+	}
+	cache := newHTTPCache(opts, config, config.NewClient(), nil, config.Cwd)
+	artifact, meta, err := cache.fetchArtifact(hash)
+	if err != nil {
+		return err
+	} else if artifact == nil {
+		return fmt.Errorf("artifact %v is not in the remote cache", hash)
+	}
+	defer func() { _ = artifact.Close() }()
+	var reader io.Reader = artifact
+	var body []byte
+	if verify {
+		// Verifying reads the artifact a second time, so keep it in memory
+		if body, err = ioutil.ReadAll(artifact); err != nil {
+			return err
+		}
+		reader = bytes.NewReader(body)
+	}
+	// Only the start of the artifact is read for its index
+	index, err := readArtifactIndex(reader)
+	if err != nil {
+		return err
+	} else if index == nil {
+		return fmt.Errorf("artifact %v was written by an older version of turbo, and has no index", hash)
+	}
+
+	if outputJSON {
+		inspected := &inspectOutput{ArtifactIndex: index}
+		if !meta.expires.IsZero() {
+			inspected.Expires = &meta.expires
+		}
+		encoded, err := json.MarshalIndent(inspected, "", "  ")
+		if err != nil {
+			return err
+		}
+		output.Output(string(encoded))
+	} else {
+		if !meta.expires.IsZero() {
+			output.Output(fmt.Sprintf("Expires %v (in %v)", meta.expires.Local().Format(time.RFC1123), time.Until(meta.expires).Round(time.Minute)))
+		}
+		w := tabwriter.NewWriter(os.Stdout, 0, 0, 2, ' ', 0)
+		for _, entry := range index.Entries {
+			path := entry.Path
+			if entry.Target != "" {
+				path = fmt.Sprintf("%v -> %v", entry.Path, entry.Target)
+			}
+			fmt.Fprintf(w, "%v\t%#o\t%v\t%v\n", entry.Type, entry.Mode, entry.Size, path)
+		}
+		_ = w.Flush()
+	}
+	if verify {
+		if err := verifyArtifact(bytes.NewReader(body)); err != nil {
+			return fmt.Errorf("artifact %v is corrupt: %w", hash, err)
+		}
+		output.Output(fmt.Sprintf("Verified %v entries", len(index.Entries)))
+	}
+	return nil
+}
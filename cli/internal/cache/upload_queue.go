@@ -0,0 +1,125 @@
+package cache
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	"os"
+	"sort"
+	"strings"
+	"time"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// _uploadQueueDir is the folder inside the local cache directory that holds
+// artifacts waiting to be uploaded to the remote cache
+const _uploadQueueDir = "upload-queue"
+
+// queuedUpload is the metadata saved alongside a queued artifact, holding
+// everything needed to upload it later
+type queuedUpload struct {
+	Hash     string `json:"hash"`
+	Duration int    `json:"duration"`
+	Tag      string `json:"tag,omitempty"`
+	// TTL is the retention hint to send with the artifact, in seconds
+	TTL int `json:"ttl,omitempty"`
+}
+
+// uploadQueue stores artifacts that could not be uploaded to the remote cache, so
+// that `turbo cache flush` can upload them once the remote cache is reachable again.
+// Each artifact is stored as <hash>.tar.gz, the same bytes that would have been
+// uploaded, next to a <hash>.json metadata file.
+type uploadQueue struct {
+	dir fs.AbsolutePath
+}
+
+func newUploadQueue(cacheDir fs.AbsolutePath) *uploadQueue {
+	return &uploadQueue{dir: cacheDir.Join(_uploadQueueDir)}
+}
+
+func (q *uploadQueue) add(hash string, artifactBody []byte, duration int, tag string, ttl time.Duration) error {
+	if err := q.dir.MkdirAll(); err != nil {
+		return err
+	}
+	metadata, err := json.Marshal(&queuedUpload{Hash: hash, Duration: duration, Tag: tag, TTL: int(ttl / time.Second)})
+	if err != nil {
+		return err
+	}
+	if err := q.dir.Join(hash+".tar.gz").WriteFile(artifactBody, 0644); err != nil {
+		return err
+	}
+	// The metadata is written last, so only complete artifacts are picked up by list
+	return q.dir.Join(hash+".json").WriteFile(metadata, 0644)
+}
+
+// list returns the queued uploads, sorted by hash
+func (q *uploadQueue) list() ([]queuedUpload, error) {
+	entries, err := os.ReadDir(q.dir.ToString())
+	if errors.Is(err, os.ErrNotExist) {
+		return nil, nil
+	} else if err != nil {
+		return nil, err
+	}
+	var uploads []queuedUpload
+	for _, entry := range entries {
+		if entry.IsDir() || !strings.HasSuffix(entry.Name(), ".json") {
+			continue
+		}
+		contents, err := q.dir.Join(entry.Name()).ReadFile()
+		if err != nil {
+			return nil, err
+		}
+		upload := queuedUpload{}
+		if err := json.Unmarshal(contents, &upload); err != nil {
+			return nil, fmt.Errorf("invalid queued upload %v: %w", entry.Name(), err)
+		}
+		uploads = append(uploads, upload)
+	}
+	sort.Slice(uploads, func(i, j int) bool {
+		return uploads[i].Hash < uploads[j].Hash
+	})
+	return uploads, nil
+}
+
+func (q *uploadQueue) remove(hash string) error {
+	if err := q.dir.Join(hash + ".json").Remove(); err != nil {
+		return err
+	}
+	return q.dir.Join(hash + ".tar.gz").Remove()
+}
+
+// FlushUploadQueue uploads the artifacts that were queued in the given cache directory
+// while the remote cache was unreachable. Artifacts are removed from the queue once
+// they have been uploaded. It stops at the first failure, leaving the rest queued,
+// and returns the number of artifacts uploaded along with the number still queued.
+func FlushUploadQueue(cacheDir fs.AbsolutePath, client client) (int, int, error) {
+	queue := newUploadQueue(cacheDir)
+	uploads, err := queue.list()
+	if err != nil {
+		return 0, 0, err
+	}
+	for i, upload := range uploads {
+		artifactBody, err := queue.dir.Join(upload.Hash + ".tar.gz").ReadFile()
+		if err != nil {
+			return i, len(uploads) - i, err
+		}
+		if err := client.PutArtifact(upload.Hash, artifactBody, upload.Duration, upload.Tag, time.Duration(upload.TTL)*time.Second); err != nil {
+			return i, len(uploads) - i, fmt.Errorf("failed to upload %v: %w", upload.Hash, err)
+		}
+		if err := queue.remove(upload.Hash); err != nil {
+			return i + 1, len(uploads) - i - 1, err
+		}
+	}
+	return len(uploads), 0, nil
+}
+
+// shouldQueue returns true if a failed upload should be retried later, which is only
+// when the remote cache couldn't be reached or answered with a 5xx or 429 status.
+// Other rejections, like a 403 because remote caching is disabled for the team, will
+// fail the same way again, so aren't queued.
+func shouldQueue(err error) bool {
+	ru := &util.RemoteUnavailableError{}
+	return errors.As(err, &ru)
+}
@@ -0,0 +1,129 @@
+package cache
+
+import (
+	"errors"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+	"time"
+
+	"github.com/hashicorp/go-hclog"
+	"github.com/vercel/turborepo/cli/internal/client"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/util"
+	"gotest.tools/v3/assert"
+)
+
+type recordingClient struct {
+	err      error
+	uploaded map[string][]byte
+	lastTTL  time.Duration
+}
+
+func (rc *recordingClient) PutArtifact(hash string, body []byte, duration int, tag string, ttl time.Duration) error {
+	if rc.err != nil {
+		return rc.err
+	}
+	rc.uploaded[hash] = body
+	rc.lastTTL = ttl
+	return nil
+}
+
+func (rc *recordingClient) FetchArtifact(hash string) (*http.Response, error) {
+	return nil, rc.err
+}
+
+func TestQueueUploadsWhenOffline(t *testing.T) {
+	cacheDir := fs.AbsolutePathFromUpstream(t.TempDir())
+	client := &recordingClient{err: &util.RemoteUnavailableError{Err: errors.New("dial tcp: no route to host")}, uploaded: make(map[string][]byte)}
+	cache := &httpCache{
+		client:             client,
+		requestLimiter:     make(limiter, 20),
+		compressionLimiter: make(limiter, 1),
+		signerVerifier:     &ArtifactSignatureAuthentication{},
+		uploadQueue:        newUploadQueue(cacheDir),
+	}
+
+	err := cache.Put("unused-target", "some-hash", 100, []string{})
+	assert.NilError(t, err, "Put")
+	uploads, err := cache.uploadQueue.list()
+	assert.NilError(t, err, "list")
+	assert.DeepEqual(t, uploads, []queuedUpload{{Hash: "some-hash", Duration: 100}})
+
+	client.err = nil
+	uploaded, remaining, err := FlushUploadQueue(cacheDir, client)
+	assert.NilError(t, err, "FlushUploadQueue")
+	assert.Equal(t, uploaded, 1)
+	assert.Equal(t, remaining, 0)
+	assert.Assert(t, len(client.uploaded["some-hash"]) > 0, "expected the queued artifact to be uploaded")
+
+	uploads, err = cache.uploadQueue.list()
+	assert.NilError(t, err, "list")
+	assert.Equal(t, len(uploads), 0)
+}
+
+func TestDoesNotQueueWhenCachingDisabled(t *testing.T) {
+	cacheDir := fs.AbsolutePathFromUpstream(t.TempDir())
+	client := &recordingClient{err: &util.CacheDisabledError{Status: util.CachingStatusDisabled}}
+	cache := &httpCache{
+		client:             client,
+		requestLimiter:     make(limiter, 20),
+		compressionLimiter: make(limiter, 1),
+		signerVerifier:     &ArtifactSignatureAuthentication{},
+		uploadQueue:        newUploadQueue(cacheDir),
+	}
+
+	err := cache.Put("unused-target", "some-hash", 100, []string{})
+	assert.Assert(t, err != nil, "expected the upload to fail")
+	uploads, err := cache.uploadQueue.list()
+	assert.NilError(t, err, "list")
+	assert.Equal(t, len(uploads), 0)
+}
+
+func TestDoesNotQueueForbiddenUploads(t *testing.T) {
+	ts := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, req *http.Request) {
+		defer func() { _ = req.Body.Close() }()
+		w.WriteHeader(http.StatusForbidden)
+		_, _ = w.Write([]byte(`{"code": "forbidden", "message": "Not authorized"}`))
+	}))
+	defer ts.Close()
+	apiClient := client.NewClient(ts.URL, hclog.Default(), "v1", "", "my-team-slug", 1, false)
+	apiClient.SetToken("my-token")
+	cacheDir := fs.AbsolutePathFromUpstream(t.TempDir())
+	cache := &httpCache{
+		client:             apiClient,
+		requestLimiter:     make(limiter, 20),
+		compressionLimiter: make(limiter, 1),
+		signerVerifier:     &ArtifactSignatureAuthentication{},
+		uploadQueue:        newUploadQueue(cacheDir),
+	}
+
+	err := cache.Put("unused-target", "some-hash", 100, []string{})
+	assert.ErrorContains(t, err, "Not authorized")
+	uploads, err := cache.uploadQueue.list()
+	assert.NilError(t, err, "list")
+	assert.Equal(t, len(uploads), 0)
+}
+
+func TestQueuedUploadKeepsTTL(t *testing.T) {
+	cacheDir := fs.AbsolutePathFromUpstream(t.TempDir())
+	client := &recordingClient{err: &util.RemoteUnavailableError{Err: errors.New("dial tcp: no route to host")}, uploaded: make(map[string][]byte)}
+	cache := &httpCache{
+		client:             client,
+		requestLimiter:     make(limiter, 20),
+		compressionLimiter: make(limiter, 1),
+		signerVerifier:     &ArtifactSignatureAuthentication{},
+		uploadQueue:        newUploadQueue(cacheDir),
+	}
+
+	err := WithTTL(cache, 7*24*time.Hour).Put("unused-target", "some-hash", 100, []string{})
+	assert.NilError(t, err, "Put")
+	uploads, err := cache.uploadQueue.list()
+	assert.NilError(t, err, "list")
+	assert.DeepEqual(t, uploads, []queuedUpload{{Hash: "some-hash", Duration: 100, TTL: 604800}})
+
+	client.err = nil
+	_, _, err = FlushUploadQueue(cacheDir, client)
+	assert.NilError(t, err, "FlushUploadQueue")
+	assert.Equal(t, client.lastTTL, 7*24*time.Hour)
+}
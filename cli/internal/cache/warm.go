@@ -0,0 +1,99 @@
+package cache
+
+import (
+	"errors"
+	"fmt"
+	"io/ioutil"
+	"os"
+
+	"github.com/vercel/turborepo/cli/internal/config"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+// WarmResult is what happened to an artifact when warming the local cache
+type WarmResult int
+
+const (
+	// WarmDownloaded means the artifact was downloaded into the local cache
+	WarmDownloaded WarmResult = iota
+	// WarmAlreadyLocal means the local cache already had the artifact
+	WarmAlreadyLocal
+	// WarmMissing means the remote cache doesn't have the artifact
+	WarmMissing
+)
+
+// Warmer downloads artifacts from the remote cache into the local cache, without
+// restoring them into the repository
+type Warmer struct {
+	remote   *httpCache
+	cacheDir fs.AbsolutePath
+	repoRoot fs.AbsolutePath
+}
+
+// NewWarmer creates a Warmer for the remote cache the repository is linked to, and the
+// local cache in opts.Dir
+func NewWarmer(opts Opts, config *config.Config) (*Warmer, error) {
+	if !config.IsLoggedIn() {
+		return nil, errors.New("not linked to a remote cache. Run \"turbo login\" and \"turbo link\" first")
+	}
+	if err := opts.Dir.MkdirAll(); err != nil {
+		return nil, err
+	}
+	return &Warmer{
+		remote:   newHTTPCache(opts, config, config.NewClient(), nil, config.Cwd),
+		cacheDir: opts.Dir,
+		repoRoot: config.Cwd,
+	}, nil
+}
+
+// Warm downloads the artifact with the given hash into the local cache, unless the local
+// cache already has it. It is safe to call from multiple goroutines.
+func (w *Warmer) Warm(hash string) (WarmResult, error) {
+	target := w.cacheDir.Join(hash)
+	if fs.PathExists(target.ToString()) {
+		return WarmAlreadyLocal, nil
+	}
+	w.remote.requestLimiter.acquire()
+	defer w.remote.requestLimiter.release()
+	artifact, meta, err := w.remote.fetchArtifact(hash)
+	if err != nil {
+		return WarmMissing, fmt.Errorf("failed to download %v: %w", hash, err)
+	} else if artifact == nil {
+		return WarmMissing, nil
+	}
+	defer func() { _ = artifact.Close() }()
+
+	// The local cache stores an artifact's files under <cache dir>/<hash>, laid out as they
+	// are in the repository. The artifact is restored next to that directory and renamed
+	// into place, so that an interrupted download never leaves a partial entry.
+	tmp, err := ioutil.TempDir(w.cacheDir.ToString(), hash+"-warm-")
+	if err != nil {
+		return WarmMissing, err
+	}
+	tmpDir := fs.AbsolutePathFromUpstream(tmp)
+	files, err := w.remote.decompress(tmpDir, artifact)
+	if err != nil {
+		_ = tmpDir.RemoveAll()
+		return WarmMissing, fmt.Errorf("failed to restore %v: %w", hash, err)
+	}
+	// Without an index, restores from this entry just aren't verified
+	index, _ := newArtifactIndex(tmpDir, files, w.remote.verify)
+	if err := os.Rename(tmpDir.ToString(), target.ToString()); err != nil {
+		_ = tmpDir.RemoveAll()
+		// Another process may have cached the same artifact in the meantime
+		if fs.PathExists(target.ToString()) {
+			return WarmAlreadyLocal, nil
+		}
+		return WarmMissing, err
+	}
+	if err := WriteCacheMetaFile(w.cacheDir.Join(hash+"-meta.json").ToString(), &CacheMetadata{
+		Duration: meta.duration,
+		Hash:     hash,
+		TaskID:   artifactTaskID(w.repoRoot, files),
+		Size:     artifactSize(target),
+		Index:    index,
+	}); err != nil {
+		return WarmMissing, err
+	}
+	return WarmDownloaded, nil
+}
@@ -0,0 +1,68 @@
+package cache
+
+import (
+	"bytes"
+	"io/ioutil"
+	"net/http"
+	"path/filepath"
+	"testing"
+	"time"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+type artifactClient struct {
+	artifacts map[string][]byte
+}
+
+func (ac *artifactClient) PutArtifact(hash string, body []byte, duration int, tag string, ttl time.Duration) error {
+	return nil
+}
+
+func (ac *artifactClient) FetchArtifact(hash string) (*http.Response, error) {
+	artifact, ok := ac.artifacts[hash]
+	if !ok {
+		return &http.Response{StatusCode: http.StatusNotFound, Body: ioutil.NopCloser(&bytes.Buffer{})}, nil
+	}
+	return &http.Response{
+		StatusCode: http.StatusOK,
+		Header:     http.Header{"X-Artifact-Duration": []string{"42"}},
+		Body:       ioutil.NopCloser(bytes.NewReader(artifact)),
+	}, nil
+}
+
+func TestWarm(t *testing.T) {
+	cacheDir := fs.AbsolutePathFromUpstream(t.TempDir())
+	warmer := &Warmer{
+		remote: &httpCache{
+			client:             &artifactClient{artifacts: map[string][]byte{"remote-hash": makeValidTar(t).Bytes()}},
+			requestLimiter:     make(limiter, 20),
+			compressionLimiter: make(limiter, 1),
+			signerVerifier:     &ArtifactSignatureAuthentication{},
+		},
+		cacheDir: cacheDir,
+	}
+
+	result, err := warmer.Warm("remote-hash")
+	assert.NilError(t, err, "Warm")
+	assert.Equal(t, result, WarmDownloaded)
+	contents, err := cacheDir.Join("remote-hash", "my-pkg", "some-file").ReadFile()
+	assert.NilError(t, err, "ReadFile")
+	assert.Equal(t, string(contents), "some-file-contents")
+	meta, err := ReadCacheMetaFile(cacheDir.Join("remote-hash-meta.json").ToString())
+	assert.NilError(t, err, "ReadCacheMetaFile")
+	assert.Equal(t, meta.Duration, 42)
+
+	result, err = warmer.Warm("remote-hash")
+	assert.NilError(t, err, "Warm")
+	assert.Equal(t, result, WarmAlreadyLocal)
+
+	result, err = warmer.Warm("missing-hash")
+	assert.NilError(t, err, "Warm")
+	assert.Equal(t, result, WarmMissing)
+
+	leftovers, err := filepath.Glob(cacheDir.Join("*-warm-*").ToString())
+	assert.NilError(t, err, "Glob")
+	assert.Equal(t, len(leftovers), 0)
+}
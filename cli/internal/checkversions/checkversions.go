@@ -0,0 +1,337 @@
+package checkversions
+
+import (
+	"encoding/json"
+	"fmt"
+	"sort"
+	"strings"
+
+	"github.com/Masterminds/semver"
+	"github.com/fatih/color"
+	"github.com/hashicorp/go-hclog"
+	"github.com/mitchellh/cli"
+	"github.com/pkg/errors"
+	"github.com/spf13/cobra"
+	"github.com/spf13/pflag"
+	"github.com/vercel/turborepo/cli/internal/cache"
+	"github.com/vercel/turborepo/cli/internal/config"
+	"github.com/vercel/turborepo/cli/internal/context"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// CheckVersionsCommand is a Command implementation that reports dependency version skew
+// across the workspace
+type CheckVersionsCommand struct {
+	Config *config.Config
+	UI     *cli.ColoredUi
+}
+
+// Synopsis of check-versions command
+func (c *CheckVersionsCommand) Synopsis() string {
+	return getCmd(c.Config, c.UI).Short
+}
+
+// Help returns information about the `check-versions` command
+func (c *CheckVersionsCommand) Help() string {
+	cmd := getCmd(c.Config, c.UI)
+	return util.HelpForCobraCmd(cmd)
+}
+
+// Run implements cli.Command.Run
+func (c *CheckVersionsCommand) Run(args []string) int {
+	cmd := getCmd(c.Config, c.UI)
+	cmd.SetArgs(args)
+	if err := cmd.Execute(); err != nil {
+		return 1
+	}
+	return 0
+}
+
+type opts struct {
+	json bool
+}
+
+func addFlags(opts *opts, flags *pflag.FlagSet) {
+	flags.BoolVar(&opts.json, "json", false, "Print the problems found as JSON")
+	// No-op the cwd flag while the root level command is not yet cobra
+	_ = flags.String("cwd", "", "")
+	if err := flags.MarkHidden("cwd"); err != nil {
+		// Fail fast if we have misconfigured our flags
+		panic(err)
+	}
+}
+
+// errProblemsFound makes the command exit with a non-zero code once the problems are printed
+var errProblemsFound = errors.New("dependency versions are out of sync")
+
+func getCmd(config *config.Config, ui cli.Ui) *cobra.Command {
+	opts := &opts{}
+	cmd := &cobra.Command{
+		Use:                   "turbo check-versions [<flags>]",
+		Short:                 "Find dependencies used at mismatched versions across the monorepo.",
+		SilenceUsage:          true,
+		SilenceErrors:         true,
+		DisableFlagsInUseLine: true,
+		RunE: func(cmd *cobra.Command, args []string) error {
+			logger := config.Logger.Named("check-versions")
+			report, err := readReport(config)
+			if err != nil {
+				logError(logger, ui, err)
+				return err
+			}
+			if opts.json {
+				encoded, err := json.MarshalIndent(report, "", "  ")
+				if err != nil {
+					logError(logger, ui, err)
+					return err
+				}
+				ui.Output(string(encoded))
+			} else {
+				printReport(ui, report)
+			}
+			if report.problems() > 0 {
+				return errProblemsFound
+			}
+			return nil
+		},
+	}
+	addFlags(opts, cmd.Flags())
+	return cmd
+}
+
+func logError(logger hclog.Logger, ui cli.Ui, err error) {
+	logger.Error("error", err)
+	pref := color.New(color.Bold, color.FgRed, color.ReverseVideo).Sprint(" ERROR ")
+	ui.Error(fmt.Sprintf("%s%s", pref, color.RedString(" %v", err)))
+}
+
+// internalSkew is a dependency on a workspace package that resolves to another copy of
+// it, usually from the registry, rather than to the workspace package
+type internalSkew struct {
+	Package          string `json:"package"`
+	Dependency       string `json:"dependency"`
+	Range            string `json:"range"`
+	WorkspaceVersion string `json:"workspaceVersion"`
+	// Resolved is the version the lockfile resolves the range to, if it is known
+	Resolved string `json:"resolved,omitempty"`
+}
+
+// majorUsage is the packages that depend on one major version of a dependency
+type majorUsage struct {
+	Major    int64    `json:"major"`
+	Packages []string `json:"packages"`
+}
+
+// duplicateMajor is an external dependency that packages depend on at different major versions
+type duplicateMajor struct {
+	Dependency string       `json:"dependency"`
+	Majors     []majorUsage `json:"majors"`
+}
+
+// peerConflict is a package whose version of a dependency doesn't satisfy the peer
+// dependency range of a workspace package it depends on
+type peerConflict struct {
+	Package    string `json:"package"`
+	Dependency string `json:"dependency"`
+	Peer       string `json:"peer"`
+	PeerRange  string `json:"peerRange"`
+	Version    string `json:"version"`
+}
+
+// report is what turbo check-versions finds
+type report struct {
+	InternalSkew    []internalSkew   `json:"internalSkew"`
+	DuplicateMajors []duplicateMajor `json:"duplicateMajors"`
+	PeerConflicts   []peerConflict   `json:"peerConflicts"`
+}
+
+func (r *report) problems() int {
+	return len(r.InternalSkew) + len(r.DuplicateMajors) + len(r.PeerConflicts)
+}
+
+func readReport(config *config.Config) (*report, error) {
+	turboJSON, err := fs.ReadTurboConfig(config.Cwd, config.RootPackageJSON)
+	if err != nil {
+		return nil, err
+	}
+	ctx, err := context.New(context.WithGraph(config, turboJSON, cache.DefaultLocation(config.Cwd)))
+	if err != nil {
+		return nil, errors.Wrap(err, "could not construct graph")
+	}
+	return checkVersions(ctx.PackageInfos, ctx.Lockfile), nil
+}
+
+func printReport(ui cli.Ui, r *report) {
+	if r.problems() == 0 {
+		ui.Output("No dependency version problems found")
+		return
+	}
+	if len(r.InternalSkew) > 0 {
+		ui.Output(util.Sprintf("${BOLD}Workspace packages not used from the workspace:${RESET}"))
+		for _, skew := range r.InternalSkew {
+			resolved := ""
+			if skew.Resolved != "" {
+				resolved = fmt.Sprintf(", resolved to %v", skew.Resolved)
+			}
+			ui.Output(fmt.Sprintf("  %v depends on %v@%v%v, but the workspace has %v", skew.Package, skew.Dependency, skew.Range, resolved, skew.WorkspaceVersion))
+		}
+		ui.Output("")
+	}
+	if len(r.DuplicateMajors) > 0 {
+		ui.Output(util.Sprintf("${BOLD}Dependencies used at more than one major version:${RESET}"))
+		for _, duplicate := range r.DuplicateMajors {
+			ui.Output(fmt.Sprintf("  %v", duplicate.Dependency))
+			for _, usage := range duplicate.Majors {
+				ui.Output(util.Sprintf("    %v ${GRAY}%v${RESET}", usage.Major, strings.Join(usage.Packages, ", ")))
+			}
+		}
+		ui.Output("")
+	}
+	if len(r.PeerConflicts) > 0 {
+		ui.Output(util.Sprintf("${BOLD}Peer dependency conflicts:${RESET}"))
+		for _, conflict := range r.PeerConflicts {
+			ui.Output(fmt.Sprintf("  %v uses %v@%v, but %v needs %v", conflict.Package, conflict.Peer, conflict.Version, conflict.Dependency, conflict.PeerRange))
+		}
+		ui.Output("")
+	}
+	ui.Output(fmt.Sprintf("Found %v problems", r.problems()))
+}
+
+// checkVersions looks for dependency version problems among the direct dependencies of
+// the workspace packages. The lockfile, which is only read for yarn, gives the versions
+// that ranges resolve to. Without it, a range is taken to resolve to the lowest version
+// it allows.
+func checkVersions(packageInfos map[interface{}]*fs.PackageJSON, lockfile *fs.YarnLockfile) *report {
+	r := &report{
+		InternalSkew:    []internalSkew{},
+		DuplicateMajors: []duplicateMajor{},
+		PeerConflicts:   []peerConflict{},
+	}
+	names := make([]string, 0, len(packageInfos))
+	for name := range packageInfos {
+		names = append(names, name.(string))
+	}
+	sort.Strings(names)
+
+	// dependency -> major -> packages
+	majors := make(map[string]map[int64][]string)
+	for _, name := range names {
+		pkg := packageInfos[name]
+		internal := make(map[string]bool, len(pkg.InternalDeps))
+		for _, dep := range pkg.InternalDeps {
+			internal[dep] = true
+		}
+		deps := directDependencies(pkg)
+		for _, dep := range sortedKeys(deps) {
+			versionRange := deps[dep]
+			if internal[dep] {
+				for _, peer := range sortedKeys(packageInfos[dep].PeerDependencies) {
+					peerRange := packageInfos[dep].PeerDependencies[peer]
+					spec, ok := deps[peer]
+					if !ok {
+						continue
+					}
+					version, ok := resolveVersion(lockfile, peer, spec)
+					if !ok {
+						continue
+					}
+					if constraint, err := semver.NewConstraint(peerRange); err == nil && !constraint.Check(version) {
+						r.PeerConflicts = append(r.PeerConflicts, peerConflict{
+							Package:    name,
+							Dependency: dep,
+							Peer:       peer,
+							PeerRange:  peerRange,
+							Version:    version.String(),
+						})
+					}
+				}
+				continue
+			}
+			if workspacePkg, ok := packageInfos[dep]; ok {
+				skew := internalSkew{
+					Package:          name,
+					Dependency:       dep,
+					Range:            versionRange,
+					WorkspaceVersion: workspacePkg.Version,
+				}
+				if lockfile != nil {
+					if version, ok := resolveVersion(lockfile, dep, versionRange); ok {
+						skew.Resolved = version.String()
+					}
+				}
+				r.InternalSkew = append(r.InternalSkew, skew)
+				continue
+			}
+			version, ok := resolveVersion(lockfile, dep, versionRange)
+			if !ok {
+				continue
+			}
+			if majors[dep] == nil {
+				majors[dep] = make(map[int64][]string)
+			}
+			majors[dep][version.Major()] = append(majors[dep][version.Major()], name)
+		}
+	}
+
+	duplicated := make([]string, 0, len(majors))
+	for dep := range majors {
+		duplicated = append(duplicated, dep)
+	}
+	sort.Strings(duplicated)
+	for _, dep := range duplicated {
+		if len(majors[dep]) < 2 {
+			continue
+		}
+		duplicate := duplicateMajor{Dependency: dep}
+		for major, packages := range majors[dep] {
+			duplicate.Majors = append(duplicate.Majors, majorUsage{Major: major, Packages: packages})
+		}
+		sort.Slice(duplicate.Majors, func(i, j int) bool {
+			return duplicate.Majors[i].Major < duplicate.Majors[j].Major
+		})
+		r.DuplicateMajors = append(r.DuplicateMajors, duplicate)
+	}
+	return r
+}
+
+// directDependencies merges a package's dependencies, with the same precedence that
+// building the package graph uses
+func directDependencies(pkg *fs.PackageJSON) map[string]string {
+	deps := make(map[string]string)
+	for _, field := range []map[string]string{pkg.DevDependencies, pkg.OptionalDependencies, pkg.Dependencies} {
+		for dep, version := range field {
+			deps[dep] = version
+		}
+	}
+	return deps
+}
+
+// resolveVersion returns the version that a dependency's range resolves to, from the
+// lockfile if there is one, or else the lowest version the range allows. Ranges that
+// aren't semver, such as git URLs or workspace: ranges, can't be resolved.
+func resolveVersion(lockfile *fs.YarnLockfile, name string, versionRange string) (*semver.Version, bool) {
+	if lockfile != nil {
+		for _, key := range []string{fmt.Sprintf("%v@%v", name, versionRange), fmt.Sprintf("%v@npm:%v", name, versionRange)} {
+			if entry, ok := (*lockfile)[key]; ok {
+				version, err := semver.NewVersion(entry.Version)
+				return version, err == nil
+			}
+		}
+	}
+	lowest := strings.Fields(versionRange)
+	if len(lowest) == 0 {
+		return nil, false
+	}
+	version, err := semver.NewVersion(strings.TrimLeft(lowest[0], "^~>=v"))
+	return version, err == nil
+}
+
+func sortedKeys(m map[string]string) []string {
+	keys := make([]string, 0, len(m))
+	for key := range m {
+		keys = append(keys, key)
+	}
+	sort.Strings(keys)
+	return keys
+}
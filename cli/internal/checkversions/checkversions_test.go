@@ -0,0 +1,72 @@
+package checkversions
+
+import (
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+func TestCheckVersions(t *testing.T) {
+	packageInfos := map[interface{}]*fs.PackageJSON{
+		"ui": {
+			Name:             "ui",
+			Version:          "2.0.0",
+			PeerDependencies: map[string]string{"react": "^18.0.0"},
+			Dependencies:     map[string]string{"lodash": "^4.17.21"},
+		},
+		"web": {
+			Name:         "web",
+			Dependencies: map[string]string{"ui": "*", "react": "^18.2.0", "lodash": "^4.17.0"},
+			InternalDeps: []string{"ui"},
+		},
+		"docs": {
+			Name:         "docs",
+			Dependencies: map[string]string{"ui": "^2.0.0", "react": "^17.0.2", "lodash": "^3.10.1"},
+			InternalDeps: []string{"ui"},
+		},
+		"admin": {
+			Name:            "admin",
+			DevDependencies: map[string]string{"ui": "^1.4.0", "typescript": "github:microsoft/TypeScript"},
+		},
+	}
+	lockfile := &fs.YarnLockfile{
+		"ui@^1.4.0":       {Version: "1.4.2"},
+		"lodash@^4.17.0":  {Version: "4.17.21"},
+		"lodash@^4.17.21": {Version: "4.17.21"},
+		"lodash@^3.10.1":  {Version: "3.10.1"},
+	}
+
+	r := checkVersions(packageInfos, lockfile)
+	assert.DeepEqual(t, r.InternalSkew, []internalSkew{{
+		Package:          "admin",
+		Dependency:       "ui",
+		Range:            "^1.4.0",
+		WorkspaceVersion: "2.0.0",
+		Resolved:         "1.4.2",
+	}})
+	assert.DeepEqual(t, r.DuplicateMajors, []duplicateMajor{
+		{
+			Dependency: "lodash",
+			Majors: []majorUsage{
+				{Major: 3, Packages: []string{"docs"}},
+				{Major: 4, Packages: []string{"ui", "web"}},
+			},
+		},
+		{
+			Dependency: "react",
+			Majors: []majorUsage{
+				{Major: 17, Packages: []string{"docs"}},
+				{Major: 18, Packages: []string{"web"}},
+			},
+		},
+	})
+	assert.DeepEqual(t, r.PeerConflicts, []peerConflict{{
+		Package:    "docs",
+		Dependency: "ui",
+		Peer:       "react",
+		PeerRange:  "^18.0.0",
+		Version:    "17.0.2",
+	}})
+	assert.Equal(t, r.problems(), 4)
+}
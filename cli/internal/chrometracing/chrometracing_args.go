@@ -0,0 +1,69 @@
+package chrometracing
+
+import (
+	"time"
+
+	"github.com/google/chrometracing/traceinternal"
+)
+
+// Args are the details shown for an event when it is selected in chrome://tracing or
+// Perfetto. Like Close, these helpers live outside of the upstream code.
+type Args map[string]interface{}
+
+const instant = "i"
+
+// EventWithArgs is like Event, but records args on the begin event
+func EventWithArgs(name string, args Args) *PendingEvent {
+	if trace.file == nil {
+		return &PendingEvent{}
+	}
+	tid := tid()
+	writeEvent(&traceinternal.ViewerEvent{
+		Name:  name,
+		Phase: begin,
+		Pid:   trace.pid,
+		Tid:   tid,
+		Time:  float64(time.Since(trace.start).Microseconds()),
+		Arg:   args,
+	})
+	return &PendingEvent{
+		name: name,
+		tid:  tid,
+	}
+}
+
+// DoneWithArgs is like Done, but records args that are only known once the unit of
+// work is finished, such as whether a cache lookup was a hit. They are merged with
+// the args of the begin event.
+func (pe *PendingEvent) DoneWithArgs(args Args) {
+	if pe == nil || pe.name == "" || trace.file == nil {
+		return
+	}
+	writeEvent(&traceinternal.ViewerEvent{
+		Name:  pe.name,
+		Phase: end,
+		Pid:   trace.pid,
+		Tid:   pe.tid,
+		Time:  float64(time.Since(trace.start).Microseconds()),
+		Arg:   args,
+	})
+	releaseTid(pe.tid)
+}
+
+// Instant logs something that happened at a single point in time, rather than a unit
+// of work, such as a decision made by the scheduler
+func Instant(name string, args Args) {
+	if trace.file == nil {
+		return
+	}
+	tid := tid()
+	defer releaseTid(tid)
+	writeEvent(&traceinternal.ViewerEvent{
+		Name:  name,
+		Phase: instant,
+		Pid:   trace.pid,
+		Tid:   tid,
+		Time:  float64(time.Since(trace.start).Microseconds()),
+		Arg:   args,
+	})
+}
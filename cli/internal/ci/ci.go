@@ -0,0 +1,46 @@
+// Package ci detects the continuous integration provider that turbo is running under
+package ci
+
+import "os"
+
+// Vendor describes a continuous integration provider
+type Vendor struct {
+	// Name is the human-readable name of the provider
+	Name string
+	// Constant is a stable identifier for the provider
+	Constant string
+	// Env is an environment variable that is only set when running under this provider
+	Env string
+}
+
+// Vendors is the list of providers that we know how to detect
+var Vendors = []Vendor{
+	{Name: "GitHub Actions", Constant: "GITHUB_ACTIONS", Env: "GITHUB_ACTIONS"},
+	{Name: "GitLab CI", Constant: "GITLAB", Env: "GITLAB_CI"},
+	{Name: "CircleCI", Constant: "CIRCLE", Env: "CIRCLECI"},
+	{Name: "Travis CI", Constant: "TRAVIS", Env: "TRAVIS"},
+	{Name: "Buildkite", Constant: "BUILDKITE", Env: "BUILDKITE"},
+	{Name: "Jenkins", Constant: "JENKINS", Env: "JENKINS_URL"},
+	{Name: "Azure Pipelines", Constant: "AZURE_PIPELINES", Env: "TF_BUILD"},
+	{Name: "Vercel", Constant: "VERCEL", Env: "NOW_BUILDER"},
+}
+
+// Info returns the provider that we are currently running under, or nil
+// if we aren't running under a known provider
+func Info() *Vendor {
+	for i := range Vendors {
+		if os.Getenv(Vendors[i].Env) != "" {
+			return &Vendors[i]
+		}
+	}
+	return nil
+}
+
+// Constant returns the stable identifier of the provider that we are currently
+// running under, or an empty string if we aren't running under a known provider
+func Constant() string {
+	if vendor := Info(); vendor != nil {
+		return vendor.Constant
+	}
+	return ""
+}
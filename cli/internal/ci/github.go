@@ -0,0 +1,25 @@
+package ci
+
+import (
+	"fmt"
+	"strings"
+)
+
+// GitHubStepSummaryEnv is set by GitHub Actions to the file that Markdown can be
+// appended to for the job summary
+const GitHubStepSummaryEnv = "GITHUB_STEP_SUMMARY"
+
+var _githubDataEscaper = strings.NewReplacer("%", "%25", "\r", "%0D", "\n", "%0A")
+var _githubPropertyEscaper = strings.NewReplacer("%", "%25", "\r", "%0D", "\n", "%0A", ":", "%3A", ",", "%2C")
+
+// GitHubErrorAnnotation returns a GitHub Actions workflow command that shows the
+// message as an error on the given file, relative to the repository root
+func GitHubErrorAnnotation(file string, message string) string {
+	return fmt.Sprintf("::error file=%v::%v\n", _githubPropertyEscaper.Replace(file), _githubDataEscaper.Replace(message))
+}
+
+// GitHubWarningAnnotation returns a GitHub Actions workflow command that shows the
+// message as a warning on the given file, relative to the repository root
+func GitHubWarningAnnotation(file string, message string) string {
+	return fmt.Sprintf("::warning file=%v::%v\n", _githubPropertyEscaper.Replace(file), _githubDataEscaper.Replace(message))
+}
@@ -0,0 +1,17 @@
+package ci
+
+import (
+	"testing"
+
+	"gotest.tools/v3/assert"
+)
+
+func TestGitHubErrorAnnotation(t *testing.T) {
+	annotation := GitHubErrorAnnotation("turbo.json", "invalid pipeline:\n100% wrong")
+	assert.Equal(t, annotation, "::error file=turbo.json::invalid pipeline:%0A100%25 wrong\n")
+}
+
+func TestGitHubWarningAnnotation(t *testing.T) {
+	annotation := GitHubWarningAnnotation("turbo.json", `outputs "build/**" matched no files`)
+	assert.Equal(t, annotation, "::warning file=turbo.json::outputs \"build/**\" matched no files\n")
+}
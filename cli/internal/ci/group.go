@@ -0,0 +1,66 @@
+package ci
+
+import (
+	"fmt"
+	"regexp"
+	"time"
+)
+
+// Grouper produces the markers that a provider uses to render a
+// collapsible group of log lines
+type Grouper interface {
+	// Start returns the line that opens a group with the given title
+	Start(title string) string
+	// End returns the line that closes the group with the given title
+	End(title string) string
+}
+
+type githubGrouper struct{}
+
+// Start implements Grouper.Start using GitHub Actions workflow commands
+func (githubGrouper) Start(title string) string {
+	return fmt.Sprintf("::group::%v\n", title)
+}
+
+// End implements Grouper.End using GitHub Actions workflow commands
+func (githubGrouper) End(title string) string {
+	return "::endgroup::\n"
+}
+
+// GitLab section names may only contain letters, numbers, and _ . -
+var _gitlabSectionInvalidChars = regexp.MustCompile(`[^a-zA-Z0-9_.-]`)
+
+type gitlabGrouper struct{}
+
+func gitlabSectionName(title string) string {
+	return _gitlabSectionInvalidChars.ReplaceAllString(title, "_")
+}
+
+// Start implements Grouper.Start using GitLab collapsible sections
+func (gitlabGrouper) Start(title string) string {
+	return fmt.Sprintf("\x1b[0Ksection_start:%v:%v[collapsed=true]\r\x1b[0K%v\n", time.Now().Unix(), gitlabSectionName(title), title)
+}
+
+// End implements Grouper.End using GitLab collapsible sections
+func (gitlabGrouper) End(title string) string {
+	return fmt.Sprintf("\x1b[0Ksection_end:%v:%v\r\x1b[0K\n", time.Now().Unix(), gitlabSectionName(title))
+}
+
+var (
+	// GitHubGrouper renders groups for GitHub Actions
+	GitHubGrouper Grouper = githubGrouper{}
+	// GitLabGrouper renders groups for GitLab CI
+	GitLabGrouper Grouper = gitlabGrouper{}
+)
+
+// GrouperFor returns the Grouper for the provider with the given constant,
+// or nil if that provider doesn't support collapsible groups
+func GrouperFor(constant string) Grouper {
+	switch constant {
+	case "GITHUB_ACTIONS":
+		return GitHubGrouper
+	case "GITLAB":
+		return GitLabGrouper
+	}
+	return nil
+}
@@ -0,0 +1,41 @@
+package ci
+
+import (
+	"strings"
+	"testing"
+
+	"gotest.tools/v3/assert"
+)
+
+func TestGrouperFor(t *testing.T) {
+	assert.Equal(t, GrouperFor("GITHUB_ACTIONS"), GitHubGrouper)
+	assert.Equal(t, GrouperFor("GITLAB"), GitLabGrouper)
+	assert.Equal(t, GrouperFor("CIRCLE"), nil)
+	assert.Equal(t, GrouperFor(""), nil)
+}
+
+func TestGitHubGrouper(t *testing.T) {
+	assert.Equal(t, GitHubGrouper.Start("web:build"), "::group::web:build\n")
+	assert.Equal(t, GitHubGrouper.End("web:build"), "::endgroup::\n")
+}
+
+func TestGitLabGrouper(t *testing.T) {
+	start := GitLabGrouper.Start("@acme/web:build")
+	assert.Assert(t, strings.Contains(start, ":_acme_web_build[collapsed=true]\r"), start)
+	assert.Assert(t, strings.HasSuffix(start, "@acme/web:build\n"), start)
+	end := GitLabGrouper.End("@acme/web:build")
+	assert.Assert(t, strings.Contains(end, "section_end:"), end)
+	assert.Assert(t, strings.Contains(end, ":_acme_web_build\r"), end)
+}
+
+func TestInfo(t *testing.T) {
+	for _, vendor := range Vendors {
+		t.Setenv(vendor.Env, "")
+	}
+	assert.Assert(t, Info() == nil)
+	assert.Equal(t, Constant(), "")
+
+	t.Setenv("GITLAB_CI", "true")
+	assert.Equal(t, Info().Name, "GitLab CI")
+	assert.Equal(t, Constant(), "GITLAB")
+}
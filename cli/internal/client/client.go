@@ -29,6 +29,8 @@ type ApiClient struct {
 	maxRemoteFailCount uint64
 	// Must be used via atomic package
 	currentFailCount uint64
+	// Number of requests that have been retried. Must be used via atomic package
+	retryCount uint64
 	// An http client
 	HttpClient *retryablehttp.Client
 	teamID     string
@@ -58,7 +60,7 @@ func NewClient(baseURL string, logger hclog.Logger, turboVersion string, teamID
 			RetryWaitMin: 2 * time.Second,
 			RetryWaitMax: 10 * time.Second,
 			RetryMax:     2,
-			Backoff:      retryablehttp.DefaultBackoff,
+			Backoff:      backoff,
 			Logger:       logger,
 		},
 		teamID:       teamID,
@@ -74,6 +76,26 @@ func (c *ApiClient) IsLoggedIn() bool {
 	return c.Token != ""
 }
 
+// SetMaxRetries sets how many times a failed request is retried before giving up
+func (c *ApiClient) SetMaxRetries(maxRetries int) {
+	c.HttpClient.RetryMax = maxRetries
+}
+
+// RecordHAR records every request made by this client, and its response, to a HAR
+// file at the given path, with credentials redacted
+func (c *ApiClient) RecordHAR(path string, logger hclog.Logger) {
+	transport := c.HttpClient.HTTPClient.Transport
+	if transport == nil {
+		transport = http.DefaultTransport
+	}
+	c.HttpClient.HTTPClient.Transport = newHARRecorder(path, c.turboVersion, transport, logger)
+}
+
+// RetryCount returns the number of times requests made by this client have been retried
+func (c *ApiClient) RetryCount() uint64 {
+	return atomic.LoadUint64(&c.retryCount)
+}
+
 // SetTeamID sets the team parameter used on all requests by this client
 func (c *ApiClient) SetTeamID(teamID string) {
 	c.teamID = teamID
@@ -122,10 +144,15 @@ func (c *ApiClient) checkRetry(ctx context.Context, resp *http.Response, err err
 	// from our retry policy.
 	shouldRetry, err := c.retryCachePolicy(resp, err)
 	if shouldRetry {
+		// A request that may have reached the server is only sent again if doing so is harmless
+		if isNonIdempotent(ctx) && !wasRejected(resp) {
+			return false, err
+		}
 		// Our policy says it's ok to retry, but we need to check the failure count
 		if retryErr := c.okToRequest(); retryErr != nil {
 			return false, retryErr
 		}
+		atomic.AddUint64(&c.retryCount, 1)
 	}
 	return shouldRetry, err
 }
@@ -218,9 +245,11 @@ func (c *ApiClient) handle403(body io.Reader) error {
 	return disabledErr
 }
 
-func (c *ApiClient) PutArtifact(hash string, artifactBody []byte, duration int, tag string) error {
+// PutArtifact uploads an artifact to the Remote Caching server. If ttl is positive, it is
+// sent as a hint of how long the server should keep the artifact.
+func (c *ApiClient) PutArtifact(hash string, artifactBody []byte, duration int, tag string, ttl time.Duration) error {
 	if err := c.okToRequest(); err != nil {
-		return err
+		return &util.RemoteUnavailableError{Err: err}
 	}
 	params := url.Values{}
 	c.addTeamParam(&params)
@@ -233,9 +262,9 @@ func (c *ApiClient) PutArtifact(hash string, artifactBody []byte, duration int,
 	requestURL := c.makeUrl("/v8/artifacts/" + hash + encoded)
 	allowAuth := true
 	if c.usePreflight {
-		resp, latestRequestURL, err := c.doPreflight(requestURL, http.MethodPut, "Content-Type, x-artifact-duration, Authorization, User-Agent, x-artifact-tag")
+		resp, latestRequestURL, err := c.doPreflight(requestURL, http.MethodPut, "Content-Type, x-artifact-duration, Authorization, User-Agent, x-artifact-tag, x-artifact-ttl")
 		if err != nil {
-			return fmt.Errorf("pre-flight request failed before trying to store in HTTP cache: %w", err)
+			return &util.RemoteUnavailableError{Err: fmt.Errorf("pre-flight request failed before trying to store in HTTP cache: %w", err)}
 		}
 		requestURL = latestRequestURL
 		headers := resp.Header.Get("Access-Control-Allow-Headers")
@@ -252,17 +281,26 @@ func (c *ApiClient) PutArtifact(hash string, artifactBody []byte, duration int,
 	if tag != "" {
 		req.Header.Set("x-artifact-tag", tag)
 	}
+	if ttl > 0 {
+		req.Header.Set("x-artifact-ttl", fmt.Sprintf("%v", int64(ttl/time.Second)))
+	}
 	if err != nil {
 		return fmt.Errorf("[WARNING] Invalid cache URL: %w", err)
 	}
 
+	// Requests that can't connect, or keep getting a 5xx or 429 status until they
+	// run out of retries, fail here
 	resp, err := c.HttpClient.Do(req)
 	if err != nil {
-		return fmt.Errorf("failed to store files in HTTP cache: %w", err)
+		return &util.RemoteUnavailableError{Err: fmt.Errorf("failed to store files in HTTP cache: %w", err)}
 	}
 	defer func() { _ = resp.Body.Close() }()
 	if resp.StatusCode == http.StatusForbidden {
 		return c.handle403(resp.Body)
+	} else if resp.StatusCode >= 500 || resp.StatusCode == http.StatusTooManyRequests {
+		return &util.RemoteUnavailableError{Err: fmt.Errorf("failed to store files in HTTP cache: unexpected HTTP status %s", resp.Status)}
+	} else if resp.StatusCode >= 300 {
+		return fmt.Errorf("failed to store files in HTTP cache: unexpected HTTP status %s", resp.Status)
 	}
 	return nil
 }
@@ -344,6 +382,7 @@ func (c *ApiClient) RecordAnalyticsEvents(events []map[string]interface{}) error
 	if err != nil {
 		return err
 	}
+	req = req.WithContext(withNonIdempotent(req.Context()))
 	req.Header.Set("Content-Type", "application/json")
 	if allowAuth {
 		req.Header.Set("Authorization", "Bearer "+c.Token)
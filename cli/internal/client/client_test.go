@@ -9,6 +9,7 @@ import (
 	"net/http/httptest"
 	"reflect"
 	"testing"
+	"time"
 
 	"github.com/google/uuid"
 	"github.com/hashicorp/go-hclog"
@@ -86,7 +87,7 @@ func Test_PutArtifact(t *testing.T) {
 	expectedArtifactBody := []byte("My string artifact")
 
 	// Test Put Artifact
-	apiClient.PutArtifact("hash", expectedArtifactBody, 500, "")
+	apiClient.PutArtifact("hash", expectedArtifactBody, 500, "", 0)
 	testBody := <-ch
 	if !bytes.Equal(expectedArtifactBody, testBody) {
 		t.Errorf("Handler read '%v', wants '%v'", testBody, expectedArtifactBody)
@@ -94,6 +95,25 @@ func Test_PutArtifact(t *testing.T) {
 
 }
 
+func Test_PutArtifactTTL(t *testing.T) {
+	ch := make(chan string, 1)
+	ts := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, req *http.Request) {
+		defer func() { _ = req.Body.Close() }()
+		ch <- req.Header.Get("x-artifact-ttl")
+		w.WriteHeader(200)
+	}))
+	defer ts.Close()
+
+	apiClient := NewClient(ts.URL+"/hash", hclog.Default(), "v1", "", "my-team-slug", 1, false)
+	apiClient.SetToken("my-token")
+	if err := apiClient.PutArtifact("hash", []byte("artifact"), 500, "", 36*time.Hour); err != nil {
+		t.Fatalf("PutArtifact: %v", err)
+	}
+	if ttl := <-ch; ttl != "129600" {
+		t.Errorf("x-artifact-ttl: expected 129600, got %v", ttl)
+	}
+}
+
 func Test_PutWhenCachingDisabled(t *testing.T) {
 	ts := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, req *http.Request) {
 		defer func() { _ = req.Body.Close() }()
@@ -107,7 +127,7 @@ func Test_PutWhenCachingDisabled(t *testing.T) {
 	apiClient.SetToken("my-token")
 	expectedArtifactBody := []byte("My string artifact")
 	// Test Put Artifact
-	err := apiClient.PutArtifact("hash", expectedArtifactBody, 500, "")
+	err := apiClient.PutArtifact("hash", expectedArtifactBody, 500, "", 0)
 	cd := &util.CacheDisabledError{}
 	if !errors.As(err, &cd) {
 		t.Errorf("expected cache disabled error, got %v", err)
@@ -117,6 +137,40 @@ func Test_PutWhenCachingDisabled(t *testing.T) {
 	}
 }
 
+func Test_PutArtifactUnavailable(t *testing.T) {
+	testCases := []struct {
+		status      int
+		unavailable bool
+	}{
+		{http.StatusServiceUnavailable, true},
+		{http.StatusInternalServerError, true},
+		{http.StatusTooManyRequests, true},
+		{http.StatusForbidden, false},
+		{http.StatusNotFound, false},
+	}
+	for _, tc := range testCases {
+		ts := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, req *http.Request) {
+			defer func() { _ = req.Body.Close() }()
+			w.WriteHeader(tc.status)
+			_, _ = w.Write([]byte("{\"code\": \"forbidden\",\"message\":\"Not authorized\"}"))
+		}))
+
+		apiClient := NewClient(ts.URL+"/hash", hclog.Default(), "v1", "", "my-team-slug", 1, false)
+		apiClient.SetToken("my-token")
+		apiClient.SetMaxRetries(0)
+		err := apiClient.PutArtifact("hash", []byte("artifact"), 500, "", 0)
+		ts.Close()
+		if err == nil {
+			t.Errorf("status %v: expected an error", tc.status)
+			continue
+		}
+		ru := &util.RemoteUnavailableError{}
+		if errors.As(err, &ru) != tc.unavailable {
+			t.Errorf("status %v: expected unavailable to be %v, got %v", tc.status, tc.unavailable, err)
+		}
+	}
+}
+
 func Test_FetchWhenCachingDisabled(t *testing.T) {
 	ts := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, req *http.Request) {
 		defer func() { _ = req.Body.Close() }()
@@ -0,0 +1,350 @@
+package client
+
+import (
+	"bytes"
+	"encoding/json"
+	"io"
+	"io/ioutil"
+	"mime"
+	"net/http"
+	"net/url"
+	"os"
+	"strings"
+	"sync"
+	"time"
+
+	"github.com/hashicorp/go-hclog"
+)
+
+// _maxHARBodySize is the largest request or response body that is copied into a HAR
+// file. Larger bodies, and binary ones such as artifacts, are recorded by size only.
+const _maxHARBodySize = 64 * 1024
+
+const _redacted = "<redacted>"
+
+// _harEnd closes the entries array and the objects around it, and always ends a HAR file
+const _harEnd = "\n]}}\n"
+
+// The subset of the HAR 1.2 format that we write. See http://www.softwareishard.com/blog/har-12-spec/
+type harFile struct {
+	Log harLog `json:"log"`
+}
+
+type harLog struct {
+	Version string     `json:"version"`
+	Creator harCreator `json:"creator"`
+	Entries []harEntry `json:"entries"`
+}
+
+type harCreator struct {
+	Name    string `json:"name"`
+	Version string `json:"version"`
+}
+
+type harEntry struct {
+	StartedDateTime string      `json:"startedDateTime"`
+	Time            int64       `json:"time"`
+	Request         harRequest  `json:"request"`
+	Response        harResponse `json:"response"`
+	Cache           struct{}    `json:"cache"`
+	Timings         harTimings  `json:"timings"`
+}
+
+type harNameValue struct {
+	Name  string `json:"name"`
+	Value string `json:"value"`
+}
+
+type harRequest struct {
+	Method      string         `json:"method"`
+	URL         string         `json:"url"`
+	HTTPVersion string         `json:"httpVersion"`
+	Headers     []harNameValue `json:"headers"`
+	QueryString []harNameValue `json:"queryString"`
+	Cookies     []harNameValue `json:"cookies"`
+	HeadersSize int64          `json:"headersSize"`
+	BodySize    int64          `json:"bodySize"`
+	PostData    *harPostData   `json:"postData,omitempty"`
+}
+
+type harPostData struct {
+	MimeType string `json:"mimeType"`
+	Text     string `json:"text"`
+}
+
+type harResponse struct {
+	Status      int            `json:"status"`
+	StatusText  string         `json:"statusText"`
+	HTTPVersion string         `json:"httpVersion"`
+	Headers     []harNameValue `json:"headers"`
+	Cookies     []harNameValue `json:"cookies"`
+	Content     harContent     `json:"content"`
+	RedirectURL string         `json:"redirectURL"`
+	HeadersSize int64          `json:"headersSize"`
+	BodySize    int64          `json:"bodySize"`
+	// Not part of the spec, but useful when a request never got a response
+	Error string `json:"_error,omitempty"`
+}
+
+type harContent struct {
+	Size     int64  `json:"size"`
+	MimeType string `json:"mimeType"`
+	Text     string `json:"text,omitempty"`
+}
+
+type harTimings struct {
+	Send    int64 `json:"send"`
+	Wait    int64 `json:"wait"`
+	Receive int64 `json:"receive"`
+}
+
+// harRecorder is an http.RoundTripper that records every request it sends, and the
+// response to it, to a HAR file. Credentials are redacted before anything is written.
+// Each entry is written over the end of the file, which is then written again after it,
+// so that the file is complete even if turbo exits abruptly.
+type harRecorder struct {
+	transport    http.RoundTripper
+	path         string
+	turboVersion string
+	logger       hclog.Logger
+
+	mu sync.Mutex
+	// end is the offset of _harEnd in the file, or 0 if the file hasn't been created yet
+	end     int64
+	entries int
+}
+
+func newHARRecorder(path string, turboVersion string, transport http.RoundTripper, logger hclog.Logger) *harRecorder {
+	return &harRecorder{
+		transport:    transport,
+		path:         path,
+		turboVersion: turboVersion,
+		logger:       logger,
+	}
+}
+
+// RoundTrip implements http.RoundTripper
+func (h *harRecorder) RoundTrip(req *http.Request) (*http.Response, error) {
+	start := time.Now()
+	entry := harEntry{
+		StartedDateTime: start.Format(time.RFC3339Nano),
+		Request:         h.recordRequest(req),
+	}
+	resp, err := h.transport.RoundTrip(req)
+	elapsed := time.Since(start).Milliseconds()
+	entry.Time = elapsed
+	entry.Timings = harTimings{Wait: elapsed}
+	if err != nil {
+		entry.Response = harResponse{
+			Headers:     []harNameValue{},
+			Cookies:     []harNameValue{},
+			HeadersSize: -1,
+			BodySize:    -1,
+			Error:       err.Error(),
+		}
+	} else {
+		entry.Response = h.recordResponse(resp)
+	}
+	h.add(entry)
+	return resp, err
+}
+
+func (h *harRecorder) add(entry harEntry) {
+	h.mu.Lock()
+	defer h.mu.Unlock()
+	if err := h.append(entry); err != nil {
+		h.logger.Warn("failed to write HTTP trace", "path", h.path, "error", err)
+	}
+}
+
+// append writes entry over the end of the file, followed by the end of the file.
+// The file is created, with no entries, the first time.
+func (h *harRecorder) append(entry harEntry) error {
+	if h.end == 0 {
+		if err := h.create(); err != nil {
+			return err
+		}
+	}
+	encoded, err := json.Marshal(entry)
+	if err != nil {
+		return err
+	}
+	separator := "\n"
+	if h.entries > 0 {
+		separator = ",\n"
+	}
+	written := append([]byte(separator), encoded...)
+	f, err := os.OpenFile(h.path, os.O_WRONLY, 0644)
+	if err != nil {
+		return err
+	}
+	defer func() { _ = f.Close() }()
+	if _, err := f.WriteAt(append(written, _harEnd...), h.end); err != nil {
+		return err
+	}
+	h.end += int64(len(written))
+	h.entries++
+	return nil
+}
+
+// create writes a HAR file without any entries
+func (h *harRecorder) create() error {
+	empty, err := json.Marshal(harFile{
+		Log: harLog{
+			Version: "1.2",
+			Creator: harCreator{Name: "turbo", Version: h.turboVersion},
+			Entries: []harEntry{},
+		},
+	})
+	if err != nil {
+		return err
+	}
+	// Leave the entries array open, and end the file as it will always end
+	start := bytes.TrimSuffix(empty, []byte("]}}"))
+	if err := os.WriteFile(h.path, append(start, _harEnd...), 0644); err != nil {
+		return err
+	}
+	h.end = int64(len(start))
+	return nil
+}
+
+func (h *harRecorder) recordRequest(req *http.Request) harRequest {
+	recorded := harRequest{
+		Method:      req.Method,
+		URL:         redactURL(req.URL),
+		HTTPVersion: req.Proto,
+		Headers:     redactHeaders(req.Header),
+		QueryString: []harNameValue{},
+		Cookies:     []harNameValue{},
+		HeadersSize: -1,
+		BodySize:    req.ContentLength,
+	}
+	for name, values := range req.URL.Query() {
+		for _, value := range values {
+			recorded.QueryString = append(recorded.QueryString, harNameValue{Name: name, Value: redactValue(name, value)})
+		}
+	}
+	// Read a copy of the body so that the one being sent is left untouched
+	if req.GetBody != nil && req.ContentLength > 0 {
+		mimeType := req.Header.Get("Content-Type")
+		if isTextual(mimeType) && req.ContentLength <= _maxHARBodySize {
+			if body, err := req.GetBody(); err == nil {
+				contents, err := ioutil.ReadAll(body)
+				_ = body.Close()
+				if err == nil {
+					recorded.PostData = &harPostData{MimeType: mimeType, Text: redactBody(contents)}
+				}
+			}
+		}
+	}
+	return recorded
+}
+
+func (h *harRecorder) recordResponse(resp *http.Response) harResponse {
+	mimeType := resp.Header.Get("Content-Type")
+	recorded := harResponse{
+		Status:      resp.StatusCode,
+		StatusText:  http.StatusText(resp.StatusCode),
+		HTTPVersion: resp.Proto,
+		Headers:     redactHeaders(resp.Header),
+		Cookies:     []harNameValue{},
+		Content:     harContent{Size: resp.ContentLength, MimeType: mimeType},
+		RedirectURL: resp.Header.Get("Location"),
+		HeadersSize: -1,
+		BodySize:    resp.ContentLength,
+	}
+	if resp.Body == nil || !isTextual(mimeType) || resp.ContentLength > _maxHARBodySize {
+		return recorded
+	}
+	// Buffer the body so that we can record it and still hand it back to the caller
+	contents, err := ioutil.ReadAll(io.LimitReader(resp.Body, _maxHARBodySize+1))
+	if err != nil {
+		return recorded
+	}
+	resp.Body = struct {
+		io.Reader
+		io.Closer
+	}{io.MultiReader(bytes.NewReader(contents), resp.Body), resp.Body}
+	if len(contents) <= _maxHARBodySize {
+		recorded.Content.Size = int64(len(contents))
+		recorded.Content.Text = redactBody(contents)
+	}
+	return recorded
+}
+
+// isTextual returns true for content types that are worth copying into a HAR file
+func isTextual(contentType string) bool {
+	mediaType, _, err := mime.ParseMediaType(contentType)
+	if err != nil {
+		return false
+	}
+	return strings.HasPrefix(mediaType, "text/") || mediaType == "application/json" || strings.HasSuffix(mediaType, "+json")
+}
+
+// isSensitive returns true for header, query and JSON field names that may hold credentials
+func isSensitive(name string) bool {
+	name = strings.ToLower(name)
+	return name == "authorization" || name == "cookie" || name == "set-cookie" || strings.Contains(name, "token")
+}
+
+func redactValue(name string, value string) string {
+	if isSensitive(name) {
+		return _redacted
+	}
+	return value
+}
+
+func redactHeaders(header http.Header) []harNameValue {
+	headers := []harNameValue{}
+	for name, values := range header {
+		for _, value := range values {
+			headers = append(headers, harNameValue{Name: name, Value: redactValue(name, value)})
+		}
+	}
+	return headers
+}
+
+func redactURL(u *url.URL) string {
+	redacted := *u
+	redacted.User = nil
+	query := redacted.Query()
+	for name := range query {
+		if isSensitive(name) {
+			query.Set(name, _redacted)
+		}
+	}
+	redacted.RawQuery = query.Encode()
+	return redacted.String()
+}
+
+// redactBody replaces the values of any sensitive fields in a JSON body. Bodies
+// that aren't JSON are returned as-is.
+func redactBody(body []byte) string {
+	var parsed interface{}
+	if err := json.Unmarshal(body, &parsed); err != nil {
+		return string(body)
+	}
+	redacted, err := json.Marshal(redactJSON(parsed))
+	if err != nil {
+		return string(body)
+	}
+	return string(redacted)
+}
+
+func redactJSON(value interface{}) interface{} {
+	switch v := value.(type) {
+	case map[string]interface{}:
+		for key, field := range v {
+			if isSensitive(key) {
+				v[key] = _redacted
+			} else {
+				v[key] = redactJSON(field)
+			}
+		}
+	case []interface{}:
+		for i, item := range v {
+			v[i] = redactJSON(item)
+		}
+	}
+	return value
+}
@@ -0,0 +1,62 @@
+package client
+
+import (
+	"encoding/json"
+	"io/ioutil"
+	"net/http"
+	"net/http/httptest"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/hashicorp/go-hclog"
+)
+
+func Test_RecordHAR(t *testing.T) {
+	ts := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, req *http.Request) {
+		w.Header().Set("Content-Type", "application/json")
+		w.WriteHeader(http.StatusOK)
+		_, _ = w.Write([]byte(`{"user":{"username":"my-user"},"token":"response-secret"}`))
+	}))
+	defer ts.Close()
+
+	harPath := filepath.Join(t.TempDir(), "trace.har")
+	apiClient := NewClient(ts.URL, hclog.Default(), "v1", "", "my-team-slug", 1, false)
+	apiClient.SetToken("my-secret-token")
+	apiClient.RecordHAR(harPath, hclog.Default())
+
+	for i := 0; i < 2; i++ {
+		user, err := apiClient.GetUser()
+		if err != nil {
+			t.Fatalf("GetUser: %v", err)
+		}
+		if user.User.Username != "my-user" {
+			t.Errorf("expected the response body to still be readable, got username %q", user.User.Username)
+		}
+	}
+
+	contents, err := ioutil.ReadFile(harPath)
+	if err != nil {
+		t.Fatalf("reading HAR file: %v", err)
+	}
+	if strings.Contains(string(contents), "my-secret-token") || strings.Contains(string(contents), "response-secret") {
+		t.Errorf("expected credentials to be redacted, got %v", string(contents))
+	}
+	har := &harFile{}
+	if err := json.Unmarshal(contents, har); err != nil {
+		t.Fatalf("parsing HAR file: %v", err)
+	}
+	if har.Log.Version != "1.2" || har.Log.Creator.Name != "turbo" {
+		t.Errorf("unexpected log %v %v", har.Log.Version, har.Log.Creator)
+	}
+	if len(har.Log.Entries) != 2 {
+		t.Fatalf("expected 2 entries, got %v", len(har.Log.Entries))
+	}
+	entry := har.Log.Entries[0]
+	if entry.Request.Method != http.MethodGet || !strings.HasSuffix(entry.Request.URL, "/v2/user") {
+		t.Errorf("unexpected request %v %v", entry.Request.Method, entry.Request.URL)
+	}
+	if entry.Response.Status != http.StatusOK || !strings.Contains(entry.Response.Content.Text, "my-user") {
+		t.Errorf("unexpected response %v %v", entry.Response.Status, entry.Response.Content.Text)
+	}
+}
@@ -0,0 +1,76 @@
+package client
+
+import (
+	"context"
+	"math"
+	"math/rand"
+	"net/http"
+	"strconv"
+	"time"
+)
+
+// maxRetryAfter caps how long we will wait when a server asks us to come back later
+const maxRetryAfter = 60 * time.Second
+
+type nonIdempotentKey struct{}
+
+// withNonIdempotent marks a request context as belonging to a request that is not
+// safe to send twice. Artifact uploads are keyed by their hash, so repeating one is
+// harmless, but repeating e.g. an analytics POST would record its events twice.
+func withNonIdempotent(ctx context.Context) context.Context {
+	return context.WithValue(ctx, nonIdempotentKey{}, true)
+}
+
+func isNonIdempotent(ctx context.Context) bool {
+	nonIdempotent, _ := ctx.Value(nonIdempotentKey{}).(bool)
+	return nonIdempotent
+}
+
+// wasRejected returns true if the server explicitly declined to handle a request,
+// in which case it is safe to retry even if the request isn't idempotent
+func wasRejected(resp *http.Response) bool {
+	return resp != nil && (resp.StatusCode == http.StatusTooManyRequests || resp.StatusCode == http.StatusServiceUnavailable)
+}
+
+// retryAfter returns the delay requested by the Retry-After header of a 429 or 503
+// response. The header can hold either a number of seconds or an HTTP date.
+func retryAfter(resp *http.Response, now time.Time) (time.Duration, bool) {
+	if !wasRejected(resp) {
+		return 0, false
+	}
+	header := resp.Header.Get("Retry-After")
+	if header == "" {
+		return 0, false
+	}
+	if seconds, err := strconv.Atoi(header); err == nil {
+		if seconds < 0 {
+			return 0, false
+		}
+		return time.Duration(seconds) * time.Second, true
+	}
+	if date, err := http.ParseTime(header); err == nil {
+		if wait := date.Sub(now); wait > 0 {
+			return wait, true
+		}
+		return 0, true
+	}
+	return 0, false
+}
+
+// backoff is a retryablehttp.Backoff that honors Retry-After, and otherwise waits
+// exponentially longer between attempts. Half of each wait is random so that many
+// turbo processes that hit the same failure don't all retry at the same moment.
+func backoff(min, max time.Duration, attemptNum int, resp *http.Response) time.Duration {
+	if wait, ok := retryAfter(resp, time.Now()); ok {
+		if wait > maxRetryAfter {
+			return maxRetryAfter
+		}
+		return wait
+	}
+	wait := max
+	if exponential := math.Pow(2, float64(attemptNum)) * float64(min); exponential < float64(max) {
+		wait = time.Duration(exponential)
+	}
+	half := int64(wait / 2)
+	return time.Duration(half + rand.Int63n(half+1))
+}
@@ -0,0 +1,105 @@
+package client
+
+import (
+	"net/http"
+	"net/http/httptest"
+	"sync/atomic"
+	"testing"
+	"time"
+
+	"github.com/hashicorp/go-hclog"
+)
+
+func Test_retryAfter(t *testing.T) {
+	now := time.Date(2022, time.July, 1, 12, 0, 0, 0, time.UTC)
+	testCases := []struct {
+		status int
+		header string
+		want   time.Duration
+		ok     bool
+	}{
+		{http.StatusTooManyRequests, "3", 3 * time.Second, true},
+		{http.StatusServiceUnavailable, "Fri, 01 Jul 2022 12:00:10 GMT", 10 * time.Second, true},
+		{http.StatusTooManyRequests, "Fri, 01 Jul 2022 11:59:00 GMT", 0, true},
+		{http.StatusTooManyRequests, "soon", 0, false},
+		{http.StatusTooManyRequests, "", 0, false},
+		{http.StatusBadGateway, "3", 0, false},
+	}
+	for _, tc := range testCases {
+		resp := &http.Response{StatusCode: tc.status, Header: http.Header{}}
+		resp.Header.Set("Retry-After", tc.header)
+		got, ok := retryAfter(resp, now)
+		if got != tc.want || ok != tc.ok {
+			t.Errorf("retryAfter(%v, %q) got %v, %v, want %v, %v", tc.status, tc.header, got, ok, tc.want, tc.ok)
+		}
+	}
+}
+
+func Test_backoff(t *testing.T) {
+	min := 100 * time.Millisecond
+	max := 1 * time.Second
+	for attempt := 0; attempt < 6; attempt++ {
+		wait := backoff(min, max, attempt, nil)
+		if wait > max {
+			t.Errorf("attempt %v waited %v, longer than the maximum %v", attempt, wait, max)
+		}
+		if attempt == 0 && wait < min/2 {
+			t.Errorf("attempt %v waited %v, expected at least %v", attempt, wait, min/2)
+		}
+	}
+
+	resp := &http.Response{StatusCode: http.StatusTooManyRequests, Header: http.Header{}}
+	resp.Header.Set("Retry-After", "3600")
+	if wait := backoff(min, max, 0, resp); wait != maxRetryAfter {
+		t.Errorf("backoff with a long Retry-After got %v, want %v", wait, maxRetryAfter)
+	}
+}
+
+func Test_retriesTransientFailures(t *testing.T) {
+	var requests int32
+	ts := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, req *http.Request) {
+		if atomic.AddInt32(&requests, 1) == 1 {
+			w.WriteHeader(http.StatusBadGateway)
+			return
+		}
+		w.WriteHeader(http.StatusOK)
+	}))
+	defer ts.Close()
+
+	apiClient := NewClient(ts.URL, hclog.Default(), "v1", "", "my-team-slug", 3, false)
+	apiClient.HttpClient.RetryWaitMin = time.Millisecond
+	apiClient.HttpClient.RetryWaitMax = time.Millisecond
+	apiClient.SetToken("my-token")
+
+	if err := apiClient.PutArtifact("hash", []byte("artifact"), 500, "", 0); err != nil {
+		t.Errorf("expected upload to succeed after a retry, got %v", err)
+	}
+	if requests := atomic.LoadInt32(&requests); requests != 2 {
+		t.Errorf("expected 2 requests, got %v", requests)
+	}
+	if apiClient.RetryCount() != 1 {
+		t.Errorf("expected 1 retry, got %v", apiClient.RetryCount())
+	}
+}
+
+func Test_doesNotRetryNonIdempotentRequests(t *testing.T) {
+	var requests int32
+	ts := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, req *http.Request) {
+		atomic.AddInt32(&requests, 1)
+		w.WriteHeader(http.StatusBadGateway)
+	}))
+	defer ts.Close()
+
+	apiClient := NewClient(ts.URL, hclog.Default(), "v1", "", "my-team-slug", 3, false)
+	apiClient.HttpClient.RetryWaitMin = time.Millisecond
+	apiClient.HttpClient.RetryWaitMax = time.Millisecond
+	apiClient.SetToken("my-token")
+
+	_ = apiClient.RecordAnalyticsEvents([]map[string]interface{}{{"event": "HIT"}})
+	if requests := atomic.LoadInt32(&requests); requests != 1 {
+		t.Errorf("expected analytics events to be sent once, got %v requests", requests)
+	}
+	if apiClient.RetryCount() != 0 {
+		t.Errorf("expected no retries, got %v", apiClient.RetryCount())
+	}
+}
@@ -0,0 +1,342 @@
+package info
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"net/http"
+	"net/url"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"strings"
+	"time"
+
+	"github.com/fatih/color"
+	"github.com/vercel/turborepo/cli/internal/config"
+	"github.com/vercel/turborepo/cli/internal/daemon"
+	"github.com/vercel/turborepo/cli/internal/daemon/connector"
+	"github.com/vercel/turborepo/cli/internal/daemonclient"
+	"github.com/vercel/turborepo/cli/internal/ui"
+	"github.com/vercel/turborepo/cli/internal/util"
+
+	"github.com/mitchellh/cli"
+	"github.com/spf13/cobra"
+)
+
+// checkStatus is the outcome of a single doctor check
+type checkStatus int
+
+const (
+	checkOK checkStatus = iota
+	checkWarning
+	checkFailed
+)
+
+// checkResult describes the outcome of a single doctor check, along with a
+// suggested fix when there is something the user can do about it
+type checkResult struct {
+	name   string
+	status checkStatus
+	detail string
+	fix    string
+}
+
+// DoctorCommand is the structure for the doctor command
+type DoctorCommand struct {
+	Config *config.Config
+	UI     *cli.ColoredUi
+}
+
+// Synopsis of the doctor command
+func (c *DoctorCommand) Synopsis() string {
+	return DoctorCmd(c).Short
+}
+
+// Help returns information about the doctor command
+func (c *DoctorCommand) Help() string {
+	return util.HelpForCobraCmd(DoctorCmd(c))
+}
+
+// Run setups the command and runs it
+func (c *DoctorCommand) Run(args []string) int {
+	cmd := DoctorCmd(c)
+
+	cmd.SilenceErrors = true
+	cmd.CompletionOptions.DisableDefaultCmd = true
+
+	cmd.SetArgs(args)
+
+	err := cmd.Execute()
+	if err == nil {
+		return 0
+	}
+
+	var cmdErr *util.ExitCodeError
+	if errors.As(err, &cmdErr) {
+		return cmdErr.ExitCode
+	}
+
+	return 1
+}
+
+// DoctorCmd returns the Cobra doctor command
+func DoctorCmd(ch *DoctorCommand) *cobra.Command {
+	cmd := &cobra.Command{
+		Use:   "doctor",
+		Short: "Check for common problems with your environment",
+		RunE: func(cmd *cobra.Command, args []string) error {
+			results := []checkResult{
+				checkGitVersion(),
+			}
+			results = append(results, checkGitConfig(ch.Config.Cwd.ToString())...)
+			results = append(results,
+				checkCaseSensitivity(ch.Config),
+				checkDaemon(ch.Config),
+			)
+			results = append(results, checkProxy(ch.Config)...)
+			results = append(results, checkRemoteCache(ch.Config)...)
+
+			failed := false
+			for _, result := range results {
+				ch.printResult(result)
+				if result.status == checkFailed {
+					failed = true
+				}
+			}
+			if failed {
+				return &util.ExitCodeError{ExitCode: 1}
+			}
+			return nil
+		},
+	}
+
+	return cmd
+}
+
+func (c *DoctorCommand) printResult(result checkResult) {
+	var status string
+	switch result.status {
+	case checkOK:
+		status = color.GreenString("✓")
+	case checkWarning:
+		status = color.YellowString("!")
+	case checkFailed:
+		status = color.RedString("✗")
+	}
+	c.UI.Output(fmt.Sprintf("%v %v: %v", status, ui.Bold(result.name), result.detail))
+	if result.fix != "" {
+		c.UI.Output(ui.Dim(fmt.Sprintf("  %v", result.fix)))
+	}
+}
+
+// parseGitVersion extracts the version number from the output of `git --version`,
+// which looks like "git version 2.37.1" or "git version 2.37.1 (Apple Git-137.1)"
+func parseGitVersion(output string) (string, bool) {
+	fields := strings.Fields(strings.TrimPrefix(strings.TrimSpace(output), "git version"))
+	if len(fields) == 0 {
+		return "", false
+	}
+	return fields[0], true
+}
+
+func checkGitVersion() checkResult {
+	result := checkResult{name: "git"}
+	out, err := exec.Command("git", "--version").Output()
+	if err != nil {
+		result.status = checkFailed
+		result.detail = fmt.Sprintf("could not run git: %v", err)
+		result.fix = "turbo uses git to hash files quickly. Install git and make sure it is on your PATH."
+		return result
+	}
+	version, ok := parseGitVersion(string(out))
+	if !ok {
+		result.status = checkWarning
+		result.detail = fmt.Sprintf("unrecognized version output %q", strings.TrimSpace(string(out)))
+		return result
+	}
+	result.detail = fmt.Sprintf("version %v", version)
+	return result
+}
+
+// gitConfigValue returns the value of the given git config key in the
+// repository at repoRoot, or an empty string if it is not set
+func gitConfigValue(repoRoot string, key string) string {
+	cmd := exec.Command("git", "config", "--get", key)
+	cmd.Dir = repoRoot
+	out, err := cmd.Output()
+	if err != nil {
+		return ""
+	}
+	return strings.TrimSpace(string(out))
+}
+
+func checkGitConfig(repoRoot string) []checkResult {
+	fsmonitor := checkResult{name: "git core.fsmonitor"}
+	if value := gitConfigValue(repoRoot, "core.fsmonitor"); value == "" || value == "false" {
+		fsmonitor.detail = "not set"
+	} else {
+		fsmonitor.status = checkWarning
+		fsmonitor.detail = fmt.Sprintf("set to %q", value)
+		fsmonitor.fix = "turbo asks git which files changed. If hashes look stale, check that your fsmonitor hook is working or run `git config --unset core.fsmonitor`."
+	}
+
+	untrackedCache := checkResult{name: "git core.untrackedCache"}
+	if value := gitConfigValue(repoRoot, "core.untrackedCache"); value == "true" {
+		untrackedCache.detail = "enabled"
+	} else {
+		untrackedCache.detail = "not enabled"
+		untrackedCache.fix = "Finding untracked files is faster in large repositories after `git config core.untrackedCache true`."
+	}
+
+	return []checkResult{fsmonitor, untrackedCache}
+}
+
+func checkCaseSensitivity(config *config.Config) checkResult {
+	result := checkResult{name: "filesystem"}
+	cacheDir := config.Cwd.Join("node_modules", ".cache", "turbo")
+	if err := os.MkdirAll(cacheDir.ToString(), 0755); err != nil {
+		result.status = checkWarning
+		result.detail = fmt.Sprintf("could not create %v: %v", cacheDir, err)
+		return result
+	}
+	dir, err := os.MkdirTemp(cacheDir.ToString(), "doctor-")
+	if err != nil {
+		result.status = checkWarning
+		result.detail = fmt.Sprintf("could not write to %v: %v", cacheDir, err)
+		result.fix = "turbo writes cached outputs here. Check the permissions of this folder."
+		return result
+	}
+	defer func() { _ = os.RemoveAll(dir) }()
+
+	if err := os.WriteFile(filepath.Join(dir, "Turbo"), nil, 0644); err != nil {
+		result.status = checkWarning
+		result.detail = fmt.Sprintf("could not write to %v: %v", cacheDir, err)
+		return result
+	}
+	if _, err := os.Stat(filepath.Join(dir, "turbo")); err == nil {
+		result.status = checkWarning
+		result.detail = "case-insensitive"
+		result.fix = "Files whose names differ only by case will overwrite each other when outputs are restored, and may hash differently on case-sensitive machines."
+		return result
+	}
+	result.detail = "case-sensitive"
+	return result
+}
+
+func checkDaemon(config *config.Config) checkResult {
+	result := checkResult{name: "daemon"}
+	ctx := context.Background()
+	client, err := daemon.GetClient(ctx, config.Cwd, config.Logger, config.TurboVersion, daemon.ClientOpts{
+		// Checking on the daemon shouldn't start it
+		DontStart: true,
+	})
+	if err != nil {
+		if errors.Is(err, connector.ErrDaemonNotRunning) {
+			result.detail = "not running"
+			result.fix = "The daemon starts automatically when needed. Run `turbo daemon start` to start it now."
+			return result
+		}
+		result.status = checkFailed
+		result.detail = fmt.Sprintf("could not connect: %v", err)
+		result.fix = "Run `turbo daemon restart`. If this keeps happening, see the daemon logs reported by `turbo daemon status`."
+		return result
+	}
+	status, err := daemonclient.New(client).Status(ctx)
+	if err != nil {
+		result.status = checkFailed
+		result.detail = fmt.Sprintf("not responding: %v", err)
+		result.fix = "Run `turbo daemon restart`."
+		return result
+	}
+	uptime := time.Duration(int64(status.UptimeMs * 1000 * 1000))
+	result.detail = fmt.Sprintf("running for %v", uptime.Round(time.Second))
+	if len(status.RecentErrors) > 0 {
+		result.status = checkWarning
+		result.detail = fmt.Sprintf("%v, with %v recent errors", result.detail, len(status.RecentErrors))
+		result.fix = fmt.Sprintf("See %v or run `turbo daemon status` for details.", status.LogFile)
+	}
+	return result
+}
+
+// proxyFor describes how a request to rawURL is routed, given a function that picks
+// the proxy for a request such as http.ProxyFromEnvironment
+func proxyFor(rawURL string, proxy func(*http.Request) (*url.URL, error)) (string, error) {
+	req, err := http.NewRequest(http.MethodGet, rawURL, nil)
+	if err != nil {
+		return "", err
+	}
+	proxyURL, err := proxy(req)
+	if err != nil {
+		return "", err
+	}
+	if proxyURL == nil {
+		return "direct", nil
+	}
+	// Don't print proxy credentials
+	redacted := *proxyURL
+	redacted.User = nil
+	return fmt.Sprintf("via proxy %v", redacted.String()), nil
+}
+
+func checkProxy(config *config.Config) []checkResult {
+	var results []checkResult
+	for _, target := range []string{config.ApiUrl, config.LoginUrl} {
+		result := checkResult{name: fmt.Sprintf("network route to %v", target)}
+		route, err := proxyFor(target, http.ProxyFromEnvironment)
+		if err != nil {
+			result.status = checkFailed
+			result.detail = fmt.Sprintf("invalid proxy configuration: %v", err)
+			result.fix = "Check the HTTPS_PROXY, HTTP_PROXY and NO_PROXY environment variables."
+		} else {
+			result.detail = route
+		}
+		results = append(results, result)
+	}
+	return results
+}
+
+func checkRemoteCache(config *config.Config) []checkResult {
+	const remoteCacheDocs = "https://turborepo.org/docs/core-concepts/remote-caching"
+	auth := checkResult{name: "remote cache credentials"}
+	if !config.IsLoggedIn() {
+		auth.status = checkWarning
+		auth.detail = "not linked to a remote cache"
+		auth.fix = fmt.Sprintf("Run `turbo login` and `turbo link` to share your cache. See %v", remoteCacheDocs)
+		return []checkResult{auth}
+	}
+
+	apiClient := config.NewClient()
+	user, err := apiClient.GetUser()
+	if err != nil {
+		auth.status = checkFailed
+		auth.detail = fmt.Sprintf("token rejected: %v", err)
+		auth.fix = "Run `turbo login` to get a new token, or check the TURBO_TOKEN environment variable."
+		return []checkResult{auth}
+	}
+	auth.detail = fmt.Sprintf("logged in as %v", user.User.Username)
+
+	reachability := checkResult{name: "remote cache"}
+	start := time.Now()
+	cachingStatus, err := apiClient.GetCachingStatus()
+	latency := time.Since(start).Round(time.Millisecond)
+	if err != nil {
+		reachability.status = checkFailed
+		reachability.detail = fmt.Sprintf("could not reach %v: %v", config.ApiUrl, err)
+		reachability.fix = fmt.Sprintf("Check your network connection and the --api flag. See %v", remoteCacheDocs)
+		return []checkResult{auth, reachability}
+	}
+	switch cachingStatus {
+	case util.CachingStatusEnabled:
+		reachability.detail = fmt.Sprintf("enabled, responded in %v", latency)
+	case util.CachingStatusOverLimit:
+		reachability.status = checkWarning
+		reachability.detail = fmt.Sprintf("over usage limit, responded in %v", latency)
+		reachability.fix = "Remote caching is paused until your usage resets. Check your team's usage in the Vercel dashboard."
+	default:
+		reachability.status = checkWarning
+		reachability.detail = fmt.Sprintf("disabled, responded in %v", latency)
+		reachability.fix = "Enable remote caching in your team settings on the Vercel dashboard."
+	}
+	return []checkResult{auth, reachability}
+}
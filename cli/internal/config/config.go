@@ -8,6 +8,7 @@ import (
 	"os"
 	"path/filepath"
 	"runtime"
+	"strconv"
 	"strings"
 
 	"github.com/vercel/turborepo/cli/internal/client"
@@ -22,6 +23,15 @@ import (
 const (
 	// EnvLogLevel is the environment log level
 	EnvLogLevel = "TURBO_LOG_LEVEL"
+	// EnvApiMaxRetries is the number of times a failed API request is retried
+	EnvApiMaxRetries = "TURBO_API_MAX_RETRIES"
+	// EnvApiMaxFailures is the number of failed API requests after which the remote cache is skipped
+	EnvApiMaxFailures = "TURBO_API_MAX_FAILURES"
+	// EnvHTTPTrace records API requests for debugging, e.g. "har:/path/to/trace.har"
+	EnvHTTPTrace = "TURBO_HTTP_TRACE"
+	// EnvCacheCompressionWorkers is the number of artifacts that can be compressed or
+	// decompressed at the same time
+	EnvCacheCompressionWorkers = "TURBO_CACHE_COMPRESSION_WORKERS"
 )
 
 // IsCI returns true if running in a CI/CD environment
@@ -52,9 +62,15 @@ type Config struct {
 	RootPackageJSON *fs.PackageJSON
 	// Current Working Directory
 	Cwd fs.AbsolutePath
+	// InvocationDir is the directory turbo was run from, if that is inside the
+	// repository, such as a package directory, rather than its root
+	InvocationDir fs.AbsolutePath
 
 	UsePreflight      bool
 	MaxClientFailures uint64
+	MaxClientRetries  int
+	// Path to record API requests to as a HAR file, if any
+	HTTPTraceFile string
 }
 
 // IsLoggedIn returns true if we have a token and either a team id or team slug
@@ -66,6 +82,8 @@ func (c *Config) IsLoggedIn() bool {
 type CacheConfig struct {
 	// Number of async workers
 	Workers int
+	// Number of artifacts that can be compressed or decompressed at the same time
+	CompressionWorkers int
 }
 
 // ParseAndValidate parses the cmd line flags / env vars, and verifies that all required
@@ -90,6 +108,7 @@ func ParseAndValidate(args []string, ui cli.Ui, turboVersion string) (c *Config,
 	if err != nil {
 		return nil, err
 	}
+	cwd, invocationDir := findRepoRoot(cwd)
 	// Precedence is flags > env > config > default
 	packageJSONPath := cwd.Join("package.json")
 	rootPackageJSON, err := fs.ReadPackageJSON(packageJSONPath.ToStringDuringMigration())
@@ -199,6 +218,37 @@ func ParseAndValidate(args []string, ui cli.Ui, turboVersion string) (c *Config,
 	})
 
 	maxRemoteFailCount := uint64(3)
+	if v := os.Getenv(EnvApiMaxFailures); v != "" {
+		maxRemoteFailCount, err = strconv.ParseUint(v, 10, 64)
+		if err != nil {
+			return nil, fmt.Errorf("%s value %q is not a valid number", EnvApiMaxFailures, v)
+		}
+	}
+	maxRemoteRetries := 2
+	if v := os.Getenv(EnvApiMaxRetries); v != "" {
+		maxRemoteRetries, err = strconv.Atoi(v)
+		if err != nil || maxRemoteRetries < 0 {
+			return nil, fmt.Errorf("%s value %q is not a valid number", EnvApiMaxRetries, v)
+		}
+	}
+	// Leave most cores to the tasks that are running while artifacts are uploaded
+	compressionWorkers := runtime.NumCPU() / 2
+	if compressionWorkers < 1 {
+		compressionWorkers = 1
+	}
+	if v := os.Getenv(EnvCacheCompressionWorkers); v != "" {
+		compressionWorkers, err = strconv.Atoi(v)
+		if err != nil || compressionWorkers < 1 {
+			return nil, fmt.Errorf("%s value %q is not a valid number", EnvCacheCompressionWorkers, v)
+		}
+	}
+	httpTraceFile := ""
+	if v := os.Getenv(EnvHTTPTrace); v != "" {
+		if !strings.HasPrefix(v, "har:") || len(v) == len("har:") {
+			return nil, fmt.Errorf("%s value %q is not valid, expected har:<path>", EnvHTTPTrace, v)
+		}
+		httpTraceFile = v[len("har:"):]
+	}
 
 	c = &Config{
 		Logger:       logger,
@@ -209,13 +259,17 @@ func ParseAndValidate(args []string, ui cli.Ui, turboVersion string) (c *Config,
 		LoginUrl:     partialConfig.LoginUrl,
 		TurboVersion: turboVersion,
 		Cache: &CacheConfig{
-			Workers: runtime.NumCPU() + 2,
+			Workers:            runtime.NumCPU() + 2,
+			CompressionWorkers: compressionWorkers,
 		},
 		RootPackageJSON: rootPackageJSON,
 		Cwd:             cwd,
+		InvocationDir:   invocationDir,
 
 		UsePreflight:      usePreflight,
 		MaxClientFailures: maxRemoteFailCount,
+		MaxClientRetries:  maxRemoteRetries,
+		HTTPTraceFile:     httpTraceFile,
 	}
 	return c, nil
 }
@@ -233,9 +287,31 @@ func (c *Config) NewClient() *client.ApiClient {
 		c.UsePreflight,
 	)
 	apiClient.SetToken(c.Token)
+	apiClient.SetMaxRetries(c.MaxClientRetries)
+	if c.HTTPTraceFile != "" {
+		apiClient.RecordHAR(c.HTTPTraceFile, c.Logger)
+	}
 	return apiClient
 }
 
+// findRepoRoot returns the nearest directory, starting at cwd, with a turbo.json, along
+// with cwd if that is a directory inside the repository rather than its root. If there
+// is no turbo.json, or cwd has the legacy "turbo" key in its package.json, cwd is the root.
+func findRepoRoot(cwd fs.AbsolutePath) (fs.AbsolutePath, fs.AbsolutePath) {
+	if cwd.Join("turbo.json").FileExists() {
+		return cwd, ""
+	}
+	if pkg, err := fs.ReadPackageJSON(cwd.Join("package.json").ToStringDuringMigration()); err == nil && pkg.LegacyTurboConfig != nil {
+		return cwd, ""
+	}
+	for dir := cwd.Dir(); dir != dir.Dir(); dir = dir.Dir() {
+		if dir.Join("turbo.json").FileExists() {
+			return dir, cwd
+		}
+	}
+	return cwd, ""
+}
+
 // Selects the current working directory from OS
 // and overrides with the `--cwd=` input argument
 // The various package managers we support resolve symlinks at this stage,
@@ -63,3 +63,28 @@ func TestSelectCwd(t *testing.T) {
 	}
 
 }
+
+func TestFindRepoRoot(t *testing.T) {
+	tempDir, err := os.MkdirTemp("", "turbo-test")
+	if err != nil {
+		t.Fatalf("MkdirTemp %v", err)
+	}
+	defer func() { _ = os.RemoveAll(tempDir) }()
+	repoRoot := fs.AbsolutePathFromUpstream(tempDir)
+	pkgDir := repoRoot.Join("apps", "web")
+	assert.NoError(t, pkgDir.Join("src").MkdirAll())
+	assert.NoError(t, repoRoot.Join("turbo.json").WriteFile([]byte("{}"), 0644))
+
+	root, invocationDir := findRepoRoot(repoRoot)
+	assert.Equal(t, repoRoot, root)
+	assert.Equal(t, fs.AbsolutePath(""), invocationDir, "the root isn't inside the repository")
+
+	root, invocationDir = findRepoRoot(pkgDir.Join("src"))
+	assert.Equal(t, repoRoot, root)
+	assert.Equal(t, pkgDir.Join("src"), invocationDir)
+
+	assert.NoError(t, pkgDir.Join("package.json").WriteFile([]byte(`{"turbo": {"pipeline": {}}}`), 0644))
+	root, invocationDir = findRepoRoot(pkgDir)
+	assert.Equal(t, pkgDir, root, "a legacy turbo key makes a directory the root")
+	assert.Equal(t, fs.AbsolutePath(""), invocationDir)
+}
@@ -9,6 +9,7 @@ import (
 	"sync"
 
 	"github.com/hashicorp/go-hclog"
+	"github.com/vercel/turborepo/cli/internal/chrometracing"
 	"github.com/vercel/turborepo/cli/internal/config"
 	"github.com/vercel/turborepo/cli/internal/core"
 	"github.com/vercel/turborepo/cli/internal/fs"
@@ -23,7 +24,10 @@ import (
 	"golang.org/x/sync/errgroup"
 )
 
-const _globalCacheKey = "Real G's move in silence like lasagna"
+// _globalCacheKey is part of every task's hash. Changing it keeps versions of turbo that
+// write artifacts differently from sharing them: artifacts may contain hard links to
+// duplicate files, which earlier versions would skip when restoring.
+const _globalCacheKey = "Real G's move in silence like lasagna, twice"
 
 // Context of the CLI
 type Context struct {
@@ -33,8 +37,12 @@ type Context struct {
 	TopologicalGraph dag.AcyclicGraph
 	RootNode         string
 	GlobalHash       string
+	GlobalHashInputs *GlobalHashInputs
 	Lockfile         *fs.YarnLockfile
 	PackageManager   *packagemanager.PackageManager
+	// Yarn "resolutions" from the root package.json, which override the
+	// version range requested by any package in the monorepo
+	resolutions map[string]string
 	// Used to arbitrate access to the graph. We parallelise most build operations
 	// and Go maps aren't natively threadsafe so this is needed.
 	mutex sync.Mutex
@@ -143,6 +151,7 @@ func WithGraph(config *config.Config, turboJSON *fs.TurboJSON, cacheDir fs.Absol
 				return fmt.Errorf("yarn.lock: %w", err)
 			}
 			c.Lockfile = lockfile
+			c.resolutions = config.RootPackageJSON.Resolutions
 		}
 
 		if err := c.resolveWorkspaceRootDeps(config.RootPackageJSON); err != nil {
@@ -152,45 +161,61 @@ func WithGraph(config *config.Config, turboJSON *fs.TurboJSON, cacheDir fs.Absol
 
 		// TODO: it seems like calculating the global hash could be separate from
 		// construction of the package-dependency graph
-		globalHash, err := calculateGlobalHash(
+		globalHashEvent := chrometracing.Event("calculate global hash")
+		globalHash, globalHashInputs, err := calculateGlobalHash(
 			config.Cwd,
 			config.RootPackageJSON,
 			turboJSON.Pipeline,
 			turboJSON.GlobalDependencies,
+			turboJSON.Hooks,
+			turboJSON.CacheKeySuffix,
 			c.PackageManager,
 			config.Logger,
 			os.Environ(),
 		)
+		globalHashEvent.Done()
 		if err != nil {
 			return fmt.Errorf("failed to calculate global hash: %v", err)
 		}
 
 		c.GlobalHash = globalHash
-
-		// Get the workspaces from the package manager.
-		workspaces, err := c.PackageManager.GetWorkspaces(config.Cwd)
-
-		if err != nil {
-			return fmt.Errorf("workspace configuration error: %w", err)
-		}
+		c.GlobalHashInputs = globalHashInputs
 
 		// We will parse all package.json's simultaneously. We use a
 		// wait group because we cannot fully populate the graph (the next step)
 		// until all parsing is complete
 		parseJSONWaitGroup := &errgroup.Group{}
-		for _, workspace := range workspaces {
-			relativePkgPath, err := filepath.Rel(rootpath, workspace)
+		// The manifest that made each workspace directory a package
+		packageDirs := make(map[string]string)
+		for _, provider := range workspaceProviders {
+			provider := provider
+			// Get the workspaces from the package manager.
+			workspaces, err := c.PackageManager.GetWorkspaceManifests(config.Cwd, provider.Manifest, turboJSON.Workspaces.Exclude...)
 			if err != nil {
-				return fmt.Errorf("non-nested package.json path %w", err)
+				return fmt.Errorf("workspace configuration error: %w", err)
+			}
+			for _, workspace := range workspaces {
+				relativePkgPath, err := filepath.Rel(rootpath, workspace)
+				if err != nil {
+					return fmt.Errorf("non-nested %v path %w", provider.Manifest, err)
+				}
+				dir := filepath.Dir(relativePkgPath)
+				if other, ok := packageDirs[dir]; ok {
+					return fmt.Errorf("%v has both a %v and a %v. A workspace directory can only have one of them", dir, other, provider.Manifest)
+				}
+				packageDirs[dir] = provider.Manifest
+				parseJSONWaitGroup.Go(func() error {
+					return c.parsePackageJSON(relativePkgPath, provider.read)
+				})
 			}
-			parseJSONWaitGroup.Go(func() error {
-				return c.parsePackageJSON(relativePkgPath)
-			})
 		}
 
 		if err := parseJSONWaitGroup.Wait(); err != nil {
 			return err
 		}
+		if err := validateWorkspaceDependencies(c.PackageInfos); err != nil {
+			return err
+		}
 		populateGraphWaitGroup := &errgroup.Group{}
 		for _, pkg := range c.PackageInfos {
 			pkg := pkg
@@ -330,17 +355,23 @@ func (c *Context) populateTopologicGraphForPackageJSON(pkg *fs.PackageJSON, root
 	return nil
 }
 
-func (c *Context) parsePackageJSON(buildFilePath string) error {
+func (c *Context) parsePackageJSON(buildFilePath string, read func(string) (*fs.PackageJSON, error)) error {
 	c.mutex.Lock()
 	defer c.mutex.Unlock()
 
 	// log.Printf("[TRACE] reading package.json : %+v", buildFilePath)
 	if fs.FileExists(buildFilePath) {
-		pkg, err := fs.ReadPackageJSON(buildFilePath)
+		pkg, err := read(buildFilePath)
 		if err != nil {
 			return fmt.Errorf("parsing %s: %w", buildFilePath, err)
 		}
 
+		if existing, ok := c.PackageInfos[pkg.Name]; ok {
+			paths := []string{existing.PackageJSONPath, buildFilePath}
+			sort.Strings(paths)
+			return fmt.Errorf("duplicate package name %q: found in both %v and %v. Package names must be unique across workspaces", pkg.Name, paths[0], paths[1])
+		}
+
 		// log.Printf("[TRACE] adding %+v to graph", pkg.Name)
 		c.TopologicalGraph.Add(pkg.Name)
 		pkg.PackageJSONPath = buildFilePath
@@ -351,6 +382,56 @@ func (c *Context) parsePackageJSON(buildFilePath string) error {
 	return nil
 }
 
+// workspaceDependencyTarget returns the name of the workspace package that a "workspace:"
+// dependency refers to. pnpm allows aliasing a workspace package, e.g. "workspace:other@*".
+// Dependencies that point to a path rather than a package name return false.
+func workspaceDependencyTarget(depName string, version string) (string, bool) {
+	protocol, version := parseDependencyProtocol(version)
+	if protocol != "workspace" {
+		return "", false
+	}
+	if strings.HasPrefix(version, ".") || strings.HasPrefix(version, "/") {
+		return "", false
+	}
+	if at := strings.LastIndex(version, "@"); at > 0 {
+		return version[:at], true
+	}
+	return depName, true
+}
+
+// validateWorkspaceDependencies checks that every "workspace:" dependency resolves to a package
+// in the workspace. All unresolved dependencies are reported together, along with the
+// package.json that declares them.
+func validateWorkspaceDependencies(packageInfos map[interface{}]*fs.PackageJSON) error {
+	var problems []string
+	for _, pkg := range packageInfos {
+		fields := []struct {
+			name string
+			deps map[string]string
+		}{
+			{"dependencies", pkg.Dependencies},
+			{"devDependencies", pkg.DevDependencies},
+			{"optionalDependencies", pkg.OptionalDependencies},
+		}
+		for _, field := range fields {
+			for depName, version := range field.deps {
+				target, ok := workspaceDependencyTarget(depName, version)
+				if !ok {
+					continue
+				}
+				if _, ok := packageInfos[target]; !ok {
+					problems = append(problems, fmt.Sprintf("%v: %v.%v is %q, but there is no workspace package named %q", pkg.PackageJSONPath, field.name, depName, version, target))
+				}
+			}
+		}
+	}
+	if len(problems) == 0 {
+		return nil
+	}
+	sort.Strings(problems)
+	return fmt.Errorf("unresolved workspace dependencies. Check that each package is matched by a workspace glob:\n  %v", strings.Join(problems, "\n  "))
+}
+
 func (c *Context) resolveDepGraph(wg *sync.WaitGroup, unresolvedDirectDeps map[string]string, resolvedDepsSet mapset.Set, seen mapset.Set, pkg *fs.PackageJSON) {
 	if !util.IsYarn(c.PackageManager.Name) {
 		return
@@ -373,7 +454,24 @@ func (c *Context) resolveDepGraph(wg *sync.WaitGroup, unresolvedDirectDeps map[s
 			entry1, ok1 := (*c.Lockfile)[lockfileKey1]
 			entry2, ok2 := (*c.Lockfile)[lockfileKey2]
 			if !ok1 && !ok2 {
-				return
+				// Yarn 2+ records a dependency that is overridden by a root "resolutions"
+				// entry under the overriding range, rather than the range that was requested.
+				resolution, ok := c.resolutionFor(directDepName)
+				if !ok {
+					return
+				}
+				lockfileKey1 = fmt.Sprintf("%v@%v", directDepName, resolution)
+				lockfileKey2 = fmt.Sprintf("%v@npm:%v", directDepName, resolution)
+				if seen.Contains(lockfileKey1) || seen.Contains(lockfileKey2) {
+					return
+				}
+				seen.Add(lockfileKey1)
+				seen.Add(lockfileKey2)
+				entry1, ok1 = (*c.Lockfile)[lockfileKey1]
+				entry2, ok2 = (*c.Lockfile)[lockfileKey2]
+				if !ok1 && !ok2 {
+					return
+				}
 			}
 			if ok1 {
 				lockfileKey = lockfileKey1
@@ -399,6 +497,12 @@ func (c *Context) resolveDepGraph(wg *sync.WaitGroup, unresolvedDirectDeps map[s
 	}
 }
 
+// resolutionFor returns the root "resolutions" range for the given dependency. See
+// fs.ResolutionFor.
+func (c *Context) resolutionFor(depName string) (string, bool) {
+	return fs.ResolutionFor(c.resolutions, depName)
+}
+
 // getHashableTurboEnvVarsFromOs returns a list of environment variables names and
 // that are safe to include in the global hash
 func getHashableTurboEnvVarsFromOs(env []string) ([]string, []string) {
@@ -419,7 +523,31 @@ var _defaultEnvVars = []string{
 	"VERCEL_ANALYTICS_ID",
 }
 
-func calculateGlobalHash(rootpath fs.AbsolutePath, rootPackageJSON *fs.PackageJSON, pipeline fs.Pipeline, externalGlobalDependencies []string, packageManager *packagemanager.PackageManager, logger hclog.Logger, env []string) (string, error) {
+// GlobalHashInputs lists everything that contributes to the global hash, so that
+// users can see why it changed. Environment variable values are hashed rather than
+// shown, since they may be secrets.
+type GlobalHashInputs struct {
+	// Hashes of the files matched by globalDependencies, and of the package manager's
+	// lockfile and specfile outside of yarn, keyed by repo-relative path
+	Files map[turbopath.AnchoredUnixPath]string `json:"files"`
+	// Hashes of the values of the environment variables that are included, keyed by name
+	EnvVars map[string]string `json:"envVars"`
+	// Hash of the resolved versions of the root package's external dependencies
+	RootExternalDepsHash string `json:"rootExternalDepsHash"`
+	// Hash of the pipeline configuration from turbo.json
+	PipelineHash string `json:"pipelineHash"`
+	// Static key, changed when turbo changes how hashes are calculated
+	GlobalCacheKey string `json:"globalCacheKey"`
+	// Environment variables pinned to a value by globalDependencies entries of the form
+	// $<name>=<value>. They are set for every task.
+	PinnedEnvVars map[string]string `json:"pinnedEnvVars,omitempty"`
+	// Hash of the hook commands from turbo.json, when hooks.hash is set
+	HooksHash string `json:"hooksHash,omitempty"`
+	// CacheKeySuffix separates the cache of runs in different environments
+	CacheKeySuffix string `json:"cacheKeySuffix,omitempty"`
+}
+
+func calculateGlobalHash(rootpath fs.AbsolutePath, rootPackageJSON *fs.PackageJSON, pipeline fs.Pipeline, externalGlobalDependencies []string, hooks fs.Hooks, cacheKeySuffix string, packageManager *packagemanager.PackageManager, logger hclog.Logger, env []string) (string, *GlobalHashInputs, error) {
 	// Calculate the global hash
 	globalDeps := make(util.Set)
 
@@ -430,27 +558,45 @@ func calculateGlobalHash(rootpath fs.AbsolutePath, rootPackageJSON *fs.PackageJS
 		globalHashableEnvNames = append(globalHashableEnvNames, builtinEnvVar)
 		globalHashableEnvPairs = append(globalHashableEnvPairs, fmt.Sprintf("%v=%v", builtinEnvVar, os.Getenv(builtinEnvVar)))
 	}
+	var pinnedEnvVars map[string]string
 	if len(externalGlobalDependencies) > 0 {
 		var globs []string
+		var envVarNames []string
+		var envVarExclusions []string
 		for _, v := range externalGlobalDependencies {
-			if strings.HasPrefix(v, "$") {
+			if strings.HasPrefix(v, "!$") {
+				envVarExclusions = append(envVarExclusions, strings.TrimPrefix(v, "!$"))
+			} else if strings.HasPrefix(v, "$") {
 				trimmed := strings.TrimPrefix(v, "$")
-				globalHashableEnvNames = append(globalHashableEnvNames, trimmed)
-				globalHashableEnvPairs = append(globalHashableEnvPairs, fmt.Sprintf("%v=%v", trimmed, os.Getenv(trimmed)))
+				if name, value, ok := util.ParsePinnedEnvVar(trimmed); ok {
+					if strings.Contains(name, util.TaskWildcard) {
+						return "", nil, fmt.Errorf("invalid globalDependencies entry %q: environment variables with a wildcard can't be pinned to a value", v)
+					}
+					if pinnedEnvVars == nil {
+						pinnedEnvVars = make(map[string]string)
+					}
+					pinnedEnvVars[name] = value
+				} else {
+					envVarNames = append(envVarNames, trimmed)
+				}
 			} else {
 				globs = append(globs, v)
 			}
 		}
+		for name, value := range util.ResolveEnvVars(env, envVarNames, envVarExclusions, pinnedEnvVars) {
+			globalHashableEnvNames = append(globalHashableEnvNames, name)
+			globalHashableEnvPairs = append(globalHashableEnvPairs, fmt.Sprintf("%v=%v", name, value))
+		}
 
 		if len(globs) > 0 {
 			ignores, err := packageManager.GetWorkspaceIgnores(rootpath)
 			if err != nil {
-				return "", err
+				return "", nil, err
 			}
 
 			f, err := globby.GlobFiles(rootpath.ToStringDuringMigration(), globs, ignores)
 			if err != nil {
-				return "", err
+				return "", nil, err
 			}
 
 			for _, val := range f {
@@ -484,7 +630,15 @@ func calculateGlobalHash(rootpath fs.AbsolutePath, rootPackageJSON *fs.PackageJS
 
 	globalFileHashMap, err := fs.GetHashableDeps(rootpath, globalDepsPaths)
 	if err != nil {
-		return "", fmt.Errorf("error hashing files. make sure that git has been initialized %w", err)
+		return "", nil, fmt.Errorf("error hashing files. make sure that git has been initialized %w", err)
+	}
+	// Hooks don't affect any hashes unless they opt in
+	hooksHash := ""
+	if hooks.Hash {
+		hooksHash, err = fs.HashObject(hooks)
+		if err != nil {
+			return "", nil, err
+		}
 	}
 	globalHashable := struct {
 		globalFileHashMap    map[turbopath.AnchoredUnixPath]string
@@ -492,16 +646,42 @@ func calculateGlobalHash(rootpath fs.AbsolutePath, rootPackageJSON *fs.PackageJS
 		hashedSortedEnvPairs []string
 		globalCacheKey       string
 		pipeline             fs.Pipeline
+		hooksHash            string
+		cacheKeySuffix       string
 	}{
 		globalFileHashMap:    globalFileHashMap,
 		rootExternalDepsHash: rootPackageJSON.ExternalDepsHash,
 		hashedSortedEnvPairs: globalHashableEnvPairs,
 		globalCacheKey:       _globalCacheKey,
 		pipeline:             pipeline,
+		hooksHash:            hooksHash,
+		cacheKeySuffix:       cacheKeySuffix,
 	}
 	globalHash, err := fs.HashObject(globalHashable)
 	if err != nil {
-		return "", fmt.Errorf("error hashing global dependencies %w", err)
+		return "", nil, fmt.Errorf("error hashing global dependencies %w", err)
+	}
+
+	inputs := &GlobalHashInputs{
+		Files:                globalFileHashMap,
+		EnvVars:              make(map[string]string, len(globalHashableEnvPairs)),
+		RootExternalDepsHash: rootPackageJSON.ExternalDepsHash,
+		GlobalCacheKey:       _globalCacheKey,
+		PinnedEnvVars:        pinnedEnvVars,
+		HooksHash:            hooksHash,
+		CacheKeySuffix:       cacheKeySuffix,
+	}
+	for _, pair := range globalHashableEnvPairs {
+		kv := strings.SplitN(pair, "=", 2)
+		valueHash, err := fs.HashObject(kv[1])
+		if err != nil {
+			return "", nil, err
+		}
+		inputs.EnvVars[kv[0]] = valueHash
+	}
+	inputs.PipelineHash, err = fs.HashObject(pipeline)
+	if err != nil {
+		return "", nil, err
 	}
-	return globalHash, nil
+	return globalHash, inputs, nil
 }
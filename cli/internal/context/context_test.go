@@ -3,7 +3,10 @@ package context
 import (
 	"path/filepath"
 	"reflect"
+	"strings"
 	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
 )
 
 func Test_getHashableTurboEnvVarsFromOs(t *testing.T) {
@@ -142,3 +145,79 @@ func Test_isWorkspaceReference(t *testing.T) {
 		})
 	}
 }
+
+func Test_resolutionFor(t *testing.T) {
+	c := &Context{
+		resolutions: map[string]string{
+			"lodash":          "4.17.20",
+			"**/@babel/core":  "npm:7.18.0",
+			"parent/left-pad": "1.3.0",
+		},
+	}
+	tests := []struct {
+		depName string
+		want    string
+		wantOk  bool
+	}{
+		{depName: "lodash", want: "4.17.20", wantOk: true},
+		{depName: "@babel/core", want: "npm:7.18.0", wantOk: true},
+		{depName: "left-pad", want: "", wantOk: false},
+		{depName: "react", want: "", wantOk: false},
+	}
+	for _, tt := range tests {
+		got, gotOk := c.resolutionFor(tt.depName)
+		if got != tt.want || gotOk != tt.wantOk {
+			t.Errorf("resolutionFor(%v) got = %v, %v, want %v, %v", tt.depName, got, gotOk, tt.want, tt.wantOk)
+		}
+	}
+}
+
+func Test_workspaceDependencyTarget(t *testing.T) {
+	tests := []struct {
+		depName string
+		version string
+		want    string
+		wantOk  bool
+	}{
+		{"a", "workspace:*", "a", true},
+		{"a", "workspace:^1.0.0", "a", true},
+		{"a", "workspace:b@*", "b", true},
+		{"a", "workspace:@scope/b@^", "@scope/b", true},
+		{"a", "workspace:../b", "", false},
+		{"a", "^1.0.0", "", false},
+		{"a", "npm:b@1.0.0", "", false},
+	}
+	for _, tt := range tests {
+		got, ok := workspaceDependencyTarget(tt.depName, tt.version)
+		if got != tt.want || ok != tt.wantOk {
+			t.Errorf("workspaceDependencyTarget(%v, %v) got = (%v, %v), want (%v, %v)", tt.depName, tt.version, got, ok, tt.want, tt.wantOk)
+		}
+	}
+}
+
+func Test_validateWorkspaceDependencies(t *testing.T) {
+	packageInfos := map[interface{}]*fs.PackageJSON{
+		"a": {
+			Name:            "a",
+			PackageJSONPath: "packages/a/package.json",
+			Dependencies:    map[string]string{"b": "workspace:*", "react": "^18.0.0"},
+		},
+		"b": {
+			Name:            "b",
+			PackageJSONPath: "packages/b/package.json",
+			DevDependencies: map[string]string{"missing": "workspace:*"},
+		},
+	}
+	err := validateWorkspaceDependencies(packageInfos)
+	if err == nil {
+		t.Fatal("expected an error for an unresolved workspace dependency")
+	}
+	if !strings.Contains(err.Error(), `packages/b/package.json: devDependencies.missing is "workspace:*"`) {
+		t.Errorf("expected error to point at the dependency, got %v", err)
+	}
+
+	delete(packageInfos["b"].DevDependencies, "missing")
+	if err := validateWorkspaceDependencies(packageInfos); err != nil {
+		t.Errorf("expected no error, got %v", err)
+	}
+}
@@ -0,0 +1,33 @@
+package context
+
+import "github.com/vercel/turborepo/cli/internal/fs"
+
+// workspaceProvider finds workspace packages by a manifest file in the package manager's
+// workspace directories, and reads them
+type workspaceProvider struct {
+	// Manifest is the name of the file that makes a workspace directory a package
+	Manifest string
+	// read parses the manifest at the given path
+	read func(manifestPath string) (*fs.PackageJSON, error)
+}
+
+// workspaceProviders are tried in order. A directory can only be one kind of package.
+var workspaceProviders = []workspaceProvider{
+	{
+		Manifest: "package.json",
+		read:     fs.ReadPackageJSON,
+	},
+	// turbo-package.json describes a package that isn't a JavaScript package, like a Go
+	// or Rust service, in the same format as package.json. Its scripts run in a shell.
+	{
+		Manifest: "turbo-package.json",
+		read: func(manifestPath string) (*fs.PackageJSON, error) {
+			pkg, err := fs.ReadPackageJSON(manifestPath)
+			if err != nil {
+				return nil, err
+			}
+			pkg.ShellScripts = true
+			return pkg, nil
+		},
+	},
+}
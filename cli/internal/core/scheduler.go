@@ -3,8 +3,11 @@ package core
 import (
 	"errors"
 	"fmt"
+	"sort"
 	"strings"
+	"sync"
 
+	"github.com/vercel/turborepo/cli/internal/chrometracing"
 	"github.com/vercel/turborepo/cli/internal/util"
 
 	"github.com/pyr-sh/dag"
@@ -79,35 +82,172 @@ type ExecOpts struct {
 	Parallel bool
 	// Concurrency is the number of concurrent tasks that can be executed
 	Concurrency int
+	// Mutexes are the names of the mutexes that each task holds while it runs, keyed by
+	// task ID. Tasks that share a mutex never run at the same time, even when Parallel.
+	Mutexes map[string][]string
+	// Priority are task IDs that, along with every task they depend on, take a free
+	// concurrency slot ahead of other tasks that are waiting for one
+	Priority []string
 }
 
 // Execute executes the pipeline, constructing an internal task graph and walking it accordingly.
 func (p *Scheduler) Execute(visitor Visitor, opts ExecOpts) []error {
-	var sema = util.NewSemaphore(opts.Concurrency)
+	var sema = util.NewPrioritySemaphore(opts.Concurrency)
+	mutexes := newNamedMutexes(opts.Mutexes)
+	prioritized, err := p.prioritizedTasks(opts.Priority)
+	if err != nil {
+		return []error{err}
+	}
 	return p.TaskGraph.Walk(func(v dag.Vertex) error {
 		// Always return if it is the root node
 		if strings.Contains(dag.VertexName(v), ROOT_NODE_NAME) {
 			return nil
 		}
+		taskID := dag.VertexName(v)
+		// Take the task's mutexes before the semaphore, so that tasks waiting
+		// for a mutex don't hold up unrelated tasks
+		if names := opts.Mutexes[taskID]; len(names) > 0 {
+			waiting := chrometracing.EventWithArgs(taskID+" waiting for mutexes", chrometracing.Args{"mutexes": names})
+			unlock := mutexes.lock(names)
+			waiting.Done()
+			defer unlock()
+		}
 		// Acquire the semaphore unless parallel
 		if !opts.Parallel {
-			sema.Acquire()
+			isPrioritized := prioritized.Includes(taskID)
+			waiting := chrometracing.EventWithArgs(taskID+" waiting for a slot", chrometracing.Args{"prioritized": isPrioritized})
+			sema.Acquire(isPrioritized)
+			waiting.Done()
 			defer sema.Release()
 		}
-		return visitor(dag.VertexName(v))
+		chrometracing.Instant(taskID+" scheduled", chrometracing.Args{"parallel": opts.Parallel})
+		return visitor(taskID)
 	})
 }
 
+// prioritizedTasks returns the given tasks, along with every task they depend on
+func (p *Scheduler) prioritizedTasks(taskIDs []string) (util.Set, error) {
+	prioritized := make(util.Set)
+	for _, taskID := range taskIDs {
+		if !p.TaskGraph.HasVertex(taskID) {
+			return nil, fmt.Errorf("cannot prioritize %v: it isn't one of the tasks to run", taskID)
+		}
+		prioritized.Add(taskID)
+		dependencies, err := p.TaskGraph.Ancestors(taskID)
+		if err != nil {
+			return nil, err
+		}
+		for _, dependency := range dependencies.List() {
+			prioritized.Add(dag.VertexName(dependency))
+		}
+	}
+	return prioritized, nil
+}
+
+// PruneToNeeded removes every task that the given tasks don't depend on, and returns the
+// given tasks along with the tasks whose outputs they consume, transitively. A dependency's
+// outputs are consumed if hasOutputs says it has any. Dependencies that are kept but not
+// returned, such as lint tasks that only order the run, still have to be hashed, since the
+// hashes of the tasks that depend on them include theirs.
+func (p *Scheduler) PruneToNeeded(taskIDs []string, hasOutputs func(taskID string) bool) (util.Set, error) {
+	keep := make(util.Set)
+	needed := make(util.Set)
+	queue := []string{}
+	for _, taskID := range taskIDs {
+		if !p.TaskGraph.HasVertex(taskID) {
+			return nil, fmt.Errorf("cannot find the tasks needed for %v: it isn't one of the tasks to run", taskID)
+		}
+		keep.Add(taskID)
+		dependencies, err := p.TaskGraph.Ancestors(taskID)
+		if err != nil {
+			return nil, err
+		}
+		for _, dependency := range dependencies.List() {
+			keep.Add(dag.VertexName(dependency))
+		}
+		needed.Add(taskID)
+		queue = append(queue, taskID)
+	}
+	for len(queue) > 0 {
+		taskID := queue[0]
+		queue = queue[1:]
+		for _, dependency := range p.TaskGraph.DownEdges(taskID).List() {
+			dependencyID := dag.VertexName(dependency)
+			if strings.Contains(dependencyID, ROOT_NODE_NAME) || needed.Includes(dependencyID) || !hasOutputs(dependencyID) {
+				continue
+			}
+			needed.Add(dependencyID)
+			queue = append(queue, dependencyID)
+		}
+	}
+	for _, v := range p.TaskGraph.Vertices() {
+		taskID := dag.VertexName(v)
+		if !keep.Includes(taskID) && !strings.Contains(taskID, ROOT_NODE_NAME) {
+			p.TaskGraph.Remove(v)
+		}
+	}
+	return needed, nil
+}
+
+// namedMutexes are the mutexes that tasks declare, by name
+type namedMutexes map[string]*sync.Mutex
+
+func newNamedMutexes(taskMutexes map[string][]string) namedMutexes {
+	mutexes := make(namedMutexes)
+	for _, names := range taskMutexes {
+		for _, name := range names {
+			if _, ok := mutexes[name]; !ok {
+				mutexes[name] = &sync.Mutex{}
+			}
+		}
+	}
+	return mutexes
+}
+
+// lock locks the named mutexes, always in the same order so that tasks sharing more
+// than one mutex can't deadlock, and returns a function that unlocks them
+func (m namedMutexes) lock(names []string) func() {
+	set := make(util.Set)
+	for _, name := range names {
+		set.Add(name)
+	}
+	sorted := set.UnsafeListOfStrings()
+	sort.Strings(sorted)
+	for _, name := range sorted {
+		m[name].Lock()
+	}
+	return func() {
+		for i := len(sorted) - 1; i >= 0; i-- {
+			m[sorted[i]].Unlock()
+		}
+	}
+}
+
 func (p *Scheduler) getTaskDefinition(pkg string, taskName string, taskID string) (*Task, error) {
 	if task, ok := p.Tasks[taskID]; ok {
 		return task, nil
 	}
-	if task, ok := p.Tasks[taskName]; ok {
-		return task, nil
+	keys := make([]string, 0, len(p.Tasks))
+	for key := range p.Tasks {
+		keys = append(keys, key)
+	}
+	if key, ok := util.ResolveTaskKey(keys, taskID); ok {
+		return p.Tasks[key], nil
 	}
 	return nil, errNoTask
 }
 
+// isRootEnabled returns true if the task is defined for the root package, either
+// exactly or by a key with a wildcard
+func (p *Scheduler) isRootEnabled(taskName string) bool {
+	for _, pattern := range p.rootEnabledTasks.UnsafeListOfStrings() {
+		if util.MatchWildcard(pattern, taskName) {
+			return true
+		}
+	}
+	return false
+}
+
 func (p *Scheduler) generateTaskGraph(pkgs []string, taskNames []string, tasksOnly bool) error {
 	if p.PackageTaskDeps == nil {
 		p.PackageTaskDeps = [][]string{}
@@ -120,7 +260,7 @@ func (p *Scheduler) generateTaskGraph(pkgs []string, taskNames []string, tasksOn
 	for _, pkg := range pkgs {
 		isRootPkg := pkg == util.RootPkgName
 		for _, taskName := range taskNames {
-			if !isRootPkg || p.rootEnabledTasks.Includes(taskName) {
+			if !isRootPkg || p.isRootEnabled(taskName) {
 				taskID := util.GetTaskId(pkg, taskName)
 				if _, err := p.getTaskDefinition(pkg, taskName, taskID); err != nil {
 					// Initial, non-package tasks are not required to exist, as long as some
@@ -139,7 +279,7 @@ func (p *Scheduler) generateTaskGraph(pkgs []string, taskNames []string, tasksOn
 		taskId := traversalQueue[0]
 		traversalQueue = traversalQueue[1:]
 		pkg, taskName := util.GetPackageTaskFromId(taskId)
-		if pkg == util.RootPkgName && !p.rootEnabledTasks.Includes(taskName) {
+		if pkg == util.RootPkgName && !p.isRootEnabled(taskName) {
 			return fmt.Errorf("%v needs an entry in turbo.json before it can be depended on because it is a task run from the root package", taskId)
 		}
 		task, err := p.getTaskDefinition(pkg, taskName, taskId)
@@ -166,18 +306,19 @@ func (p *Scheduler) generateTaskGraph(pkgs []string, taskNames []string, tasksOn
 			}
 
 			toTaskId := taskId
-			hasTopoDeps := task.TopoDeps.Len() > 0 && p.TopologicGraph.DownEdges(pkg).Len() > 0
+			depPkgs := p.topologicalDependencies(pkg)
+			hasTopoDeps := task.TopoDeps.Len() > 0 && len(depPkgs) > 0
 			hasDeps := deps.Len() > 0
 			hasPackageTaskDeps := false
-			if _, ok := packageTasksDepsMap[toTaskId]; ok {
+			// Package task dependencies are keyed by the task's definition, which may be a wildcard
+			if _, ok := packageTasksDepsMap[task.Name]; ok {
 				hasPackageTaskDeps = true
 			}
 
 			if hasTopoDeps {
-				depPkgs := p.TopologicGraph.DownEdges(pkg)
 				for _, from := range task.TopoDeps.UnsafeListOfStrings() {
 					// add task dep from all the package deps within repo
-					for depPkg := range depPkgs {
+					for _, depPkg := range depPkgs {
 						fromTaskId := util.GetTaskId(depPkg, from)
 						p.TaskGraph.Add(fromTaskId)
 						p.TaskGraph.Add(toTaskId)
@@ -189,17 +330,14 @@ func (p *Scheduler) generateTaskGraph(pkgs []string, taskNames []string, tasksOn
 
 			if hasDeps {
 				for _, from := range deps.UnsafeListOfStrings() {
-					fromTaskId := util.GetTaskId(pkg, from)
-					p.TaskGraph.Add(fromTaskId)
-					p.TaskGraph.Add(toTaskId)
-					p.TaskGraph.Connect(dag.BasicEdge(toTaskId, fromTaskId))
-					traversalQueue = append(traversalQueue, fromTaskId)
-				}
-			}
-
-			if hasPackageTaskDeps {
-				if pkgTaskDeps, ok := packageTasksDepsMap[toTaskId]; ok {
-					for _, fromTaskId := range pkgTaskDeps {
+					if pkg == util.RootPkgName && !util.IsPackageTask(from) && !p.isRootEnabled(from) {
+						return fmt.Errorf("%v depends on %v, but %v isn't a task in the root package. Add %v to turbo.json to run it from the root, or depend on \"^%v\" to run %v in every workspace package first", taskId, from, from, util.RootTaskID(from), from, from)
+					}
+					fromTaskIds, err := p.expandDependency(util.GetTaskId(pkg, from), toTaskId)
+					if err != nil {
+						return err
+					}
+					for _, fromTaskId := range fromTaskIds {
 						p.TaskGraph.Add(fromTaskId)
 						p.TaskGraph.Add(toTaskId)
 						p.TaskGraph.Connect(dag.BasicEdge(toTaskId, fromTaskId))
@@ -208,6 +346,23 @@ func (p *Scheduler) generateTaskGraph(pkgs []string, taskNames []string, tasksOn
 				}
 			}
 
+			if hasPackageTaskDeps {
+				if pkgTaskDeps, ok := packageTasksDepsMap[task.Name]; ok {
+					for _, from := range pkgTaskDeps {
+						fromTaskIds, err := p.expandDependency(from, toTaskId)
+						if err != nil {
+							return err
+						}
+						for _, fromTaskId := range fromTaskIds {
+							p.TaskGraph.Add(fromTaskId)
+							p.TaskGraph.Add(toTaskId)
+							p.TaskGraph.Connect(dag.BasicEdge(toTaskId, fromTaskId))
+							traversalQueue = append(traversalQueue, fromTaskId)
+						}
+					}
+				}
+			}
+
 			if !hasDeps && !hasTopoDeps && !hasPackageTaskDeps {
 				p.TaskGraph.Add(ROOT_NODE_NAME)
 				p.TaskGraph.Add(toTaskId)
@@ -218,6 +373,58 @@ func (p *Scheduler) generateTaskGraph(pkgs []string, taskNames []string, tasksOn
 	return nil
 }
 
+// topologicalDependencies returns the packages whose tasks a "^" dependency of a task in
+// the given package refers to. The root package has no place in the package graph, so
+// for its tasks these are all of the workspace packages, letting root tasks fan in on
+// tasks across the whole monorepo.
+func (p *Scheduler) topologicalDependencies(pkg string) []string {
+	if pkg != util.RootPkgName {
+		var depPkgs []string
+		for depPkg := range p.TopologicGraph.DownEdges(pkg) {
+			depPkgs = append(depPkgs, dag.VertexName(depPkg))
+		}
+		return depPkgs
+	}
+	var depPkgs []string
+	for _, v := range p.TopologicGraph.Vertices() {
+		depPkg := dag.VertexName(v)
+		if depPkg != util.RootPkgName && !strings.Contains(depPkg, ROOT_NODE_NAME) {
+			depPkgs = append(depPkgs, depPkg)
+		}
+	}
+	sort.Strings(depPkgs)
+	return depPkgs
+}
+
+// expandDependency returns the package tasks that a dependency of the given task refers to.
+// A dependency with a wildcard in its package name, like "@acme/*#build", refers to that
+// task in every matching workspace package other than the dependent task itself. The root
+// package is never matched by a wildcard.
+func (p *Scheduler) expandDependency(from string, toTaskId string) ([]string, error) {
+	if !util.IsPackagePattern(from) {
+		return []string{from}, nil
+	}
+	pattern, taskName := util.GetPackageTaskFromId(from)
+	var pkgs []string
+	for _, v := range p.TopologicGraph.Vertices() {
+		pkg := dag.VertexName(v)
+		if pkg != util.RootPkgName && !strings.Contains(pkg, ROOT_NODE_NAME) && util.MatchWildcard(pattern, pkg) {
+			pkgs = append(pkgs, pkg)
+		}
+	}
+	if len(pkgs) == 0 {
+		return nil, fmt.Errorf("%v depends on %v, but no packages match %v", toTaskId, from, pattern)
+	}
+	sort.Strings(pkgs)
+	fromTaskIds := make([]string, 0, len(pkgs))
+	for _, pkg := range pkgs {
+		if fromTaskId := util.GetTaskId(pkg, taskName); fromTaskId != toTaskId {
+			fromTaskIds = append(fromTaskIds, fromTaskId)
+		}
+	}
+	return fromTaskIds, nil
+}
+
 func getPackageTaskDepsMap(packageTaskDeps [][]string) map[string][]string {
 	depMap := make(map[string][]string)
 	for _, packageTaskDep := range packageTaskDeps {
@@ -246,7 +453,8 @@ func (p *Scheduler) AddTask(task *Task) *Scheduler {
 
 func (p *Scheduler) AddDep(fromTaskId string, toTaskId string) error {
 	fromPkg, _ := util.GetPackageTaskFromId(fromTaskId)
-	if fromPkg != ROOT_NODE_NAME && fromPkg != util.RootPkgName && !p.TopologicGraph.HasVertex(fromPkg) {
+	// Wildcards are matched against the packages in the graph when it is generated
+	if !util.IsPackagePattern(fromTaskId) && fromPkg != ROOT_NODE_NAME && fromPkg != util.RootPkgName && !p.TopologicGraph.HasVertex(fromPkg) {
 		return fmt.Errorf("found reference to unknown package: %v in task %v", fromPkg, fromTaskId)
 	}
 	p.PackageTaskDeps = append(p.PackageTaskDeps, []string{fromTaskId, toTaskId})
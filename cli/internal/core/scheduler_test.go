@@ -3,7 +3,9 @@ package core
 import (
 	"fmt"
 	"strings"
+	"sync"
 	"testing"
+	"time"
 
 	"github.com/vercel/turborepo/cli/internal/util"
 	"gotest.tools/v3/assert"
@@ -388,6 +390,71 @@ func TestDependOnUnenabledRootTask(t *testing.T) {
 	}
 }
 
+func TestRootTaskDependsOnEveryPackage(t *testing.T) {
+	graph := &dag.AcyclicGraph{}
+	graph.Add(util.RootPkgName)
+	graph.Add("app1")
+	graph.Add("libA")
+	graph.Add(ROOT_NODE_NAME)
+	graph.Connect(dag.BasicEdge("app1", "libA"))
+	graph.Connect(dag.BasicEdge("libA", ROOT_NODE_NAME))
+	graph.Connect(dag.BasicEdge(util.RootPkgName, ROOT_NODE_NAME))
+
+	p := NewScheduler(graph)
+	dependOnBuild := make(util.Set)
+	dependOnBuild.Add("build")
+	p.AddTask(&Task{
+		Name:     "build",
+		TopoDeps: make(util.Set),
+		Deps:     make(util.Set),
+	})
+	p.AddTask(&Task{
+		Name:     util.RootTaskID("release"),
+		TopoDeps: dependOnBuild,
+		Deps:     make(util.Set),
+	})
+	err := p.Prepare(&SchedulerExecutionOptions{
+		Packages:  []string{util.RootPkgName},
+		TaskNames: []string{"release"},
+	})
+	assert.NilError(t, err, "Prepare")
+	actual := strings.TrimSpace(p.TaskGraph.String())
+	expected := fmt.Sprintf(`%v#release
+  app1#build
+  libA#build
+___ROOT___
+app1#build
+  ___ROOT___
+libA#build
+  ___ROOT___`, util.RootPkgName)
+	assert.Equal(t, expected, actual)
+}
+
+func TestRootTaskDependsOnUndefinedRootTask(t *testing.T) {
+	graph := &dag.AcyclicGraph{}
+	graph.Add(util.RootPkgName)
+	graph.Add("app1")
+
+	p := NewScheduler(graph)
+	dependOnBuild := make(util.Set)
+	dependOnBuild.Add("build")
+	p.AddTask(&Task{
+		Name:     "build",
+		TopoDeps: make(util.Set),
+		Deps:     make(util.Set),
+	})
+	p.AddTask(&Task{
+		Name:     util.RootTaskID("release"),
+		TopoDeps: make(util.Set),
+		Deps:     dependOnBuild,
+	})
+	err := p.Prepare(&SchedulerExecutionOptions{
+		Packages:  []string{util.RootPkgName},
+		TaskNames: []string{"release"},
+	})
+	assert.ErrorContains(t, err, `depend on "^build"`)
+}
+
 func TestSchedulerTasksOnly(t *testing.T) {
 	var g dag.AcyclicGraph
 	g.Add("a")
@@ -479,3 +546,130 @@ b#test
 c#test
   ___ROOT___
 `
+
+func TestSchedulerMutexes(t *testing.T) {
+	var g dag.AcyclicGraph
+	g.Add("a")
+	g.Add("b")
+	g.Add("c")
+
+	p := NewScheduler(&g)
+	p.AddTask(&Task{
+		Name: "test",
+	})
+	err := p.Prepare(&SchedulerExecutionOptions{
+		Packages:  []string{"a", "b", "c"},
+		TaskNames: []string{"test"},
+	})
+	assert.NilError(t, err, "Prepare")
+
+	var mu sync.Mutex
+	running := make(map[string]bool)
+	overlapped := make(map[string]bool)
+	visitor := func(taskID string) error {
+		mu.Lock()
+		for other := range running {
+			overlapped[taskID] = true
+			overlapped[other] = true
+		}
+		running[taskID] = true
+		mu.Unlock()
+		time.Sleep(20 * time.Millisecond)
+		mu.Lock()
+		delete(running, taskID)
+		mu.Unlock()
+		return nil
+	}
+	errs := p.Execute(visitor, ExecOpts{
+		Parallel: true,
+		Mutexes: map[string][]string{
+			"a#test": {"db"},
+			"b#test": {"db", "port"},
+			"c#test": {"port", "db"},
+		},
+	})
+	assert.Equal(t, len(errs), 0)
+	assert.DeepEqual(t, overlapped, map[string]bool{})
+}
+
+func TestSchedulerPrioritizedTasks(t *testing.T) {
+	var g dag.AcyclicGraph
+	g.Add("a")
+	g.Add("b")
+	g.Add("c")
+	g.Connect(dag.BasicEdge("c", "b"))
+
+	p := NewScheduler(&g)
+	topoDeps := make(util.Set)
+	topoDeps.Add("build")
+	p.AddTask(&Task{
+		Name:     "build",
+		TopoDeps: topoDeps,
+	})
+	err := p.Prepare(&SchedulerExecutionOptions{
+		Packages:  []string{"a", "b", "c"},
+		TaskNames: []string{"build"},
+	})
+	assert.NilError(t, err, "Prepare")
+
+	prioritized, err := p.prioritizedTasks([]string{"c#build"})
+	assert.NilError(t, err, "prioritizedTasks")
+	assert.Assert(t, prioritized.Includes("c#build"))
+	assert.Assert(t, prioritized.Includes("b#build"), "dependencies of prioritized tasks should be prioritized")
+	assert.Assert(t, !prioritized.Includes("a#build"))
+
+	_, err = p.prioritizedTasks([]string{"d#build"})
+	assert.ErrorContains(t, err, "cannot prioritize d#build")
+}
+
+func TestSchedulerPruneToNeeded(t *testing.T) {
+	var g dag.AcyclicGraph
+	g.Add("a")
+	g.Add("b")
+	g.Add("c")
+	g.Connect(dag.BasicEdge("c", "b"))
+
+	p := NewScheduler(&g)
+	buildDeps := make(util.Set)
+	buildDeps.Add("lint")
+	buildTopoDeps := make(util.Set)
+	buildTopoDeps.Add("build")
+	p.AddTask(&Task{
+		Name:     "build",
+		Deps:     buildDeps,
+		TopoDeps: buildTopoDeps,
+	})
+	lintTopoDeps := make(util.Set)
+	lintTopoDeps.Add("codegen")
+	p.AddTask(&Task{
+		Name:     "lint",
+		TopoDeps: lintTopoDeps,
+	})
+	p.AddTask(&Task{
+		Name: "codegen",
+	})
+	p.AddTask(&Task{
+		Name: "test",
+	})
+	err := p.Prepare(&SchedulerExecutionOptions{
+		Packages:  []string{"a", "b", "c"},
+		TaskNames: []string{"build", "test"},
+	})
+	assert.NilError(t, err, "Prepare")
+
+	needed, err := p.PruneToNeeded([]string{"c#build"}, func(taskID string) bool {
+		return !strings.HasSuffix(taskID, "#lint")
+	})
+	assert.NilError(t, err, "PruneToNeeded")
+	assert.Equal(t, needed.Len(), 2)
+	assert.Assert(t, needed.Includes("c#build"))
+	assert.Assert(t, needed.Includes("b#build"), "dependencies with outputs should be needed")
+	assert.Assert(t, !needed.Includes("b#codegen"), "tasks only reached through lint tasks shouldn't be needed")
+	assert.Assert(t, p.TaskGraph.HasVertex("c#lint"), "dependencies that aren't needed should be kept to be hashed")
+	assert.Assert(t, p.TaskGraph.HasVertex("b#codegen"))
+	assert.Assert(t, !p.TaskGraph.HasVertex("c#test"), "tasks the target doesn't depend on should be removed")
+	assert.Assert(t, !p.TaskGraph.HasVertex("a#build"))
+
+	_, err = p.PruneToNeeded([]string{"a#build"}, func(taskID string) bool { return true })
+	assert.ErrorContains(t, err, "cannot find the tasks needed for a#build")
+}
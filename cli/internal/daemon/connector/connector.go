@@ -16,6 +16,7 @@ import (
 	"google.golang.org/grpc"
 	"google.golang.org/grpc/codes"
 	"google.golang.org/grpc/credentials/insecure"
+	"google.golang.org/grpc/metadata"
 	"google.golang.org/grpc/status"
 )
 
@@ -31,6 +32,10 @@ var (
 	ErrDaemonNotRunning = errors.New("the daemon is not running")
 )
 
+// RepoRootMetadataKey is the grpc metadata key used to tell the daemon which
+// repository a request is for, since a single daemon serves every repository.
+const RepoRootMetadataKey = "turbo-repo-root"
+
 // Opts is the set of configurable options for the client connection,
 // including some options to be passed through to the daemon process if
 // it needs to be started.
@@ -54,6 +59,7 @@ type Connector struct {
 	Logger       hclog.Logger
 	Bin          string
 	Opts         Opts
+	RepoRoot     fs.AbsolutePath
 	SockPath     fs.AbsolutePath
 	PidPath      fs.AbsolutePath
 	LogPath      fs.AbsolutePath
@@ -284,9 +290,15 @@ func (c *Connector) getOrStartDaemon() (int, error) {
 	}
 }
 
+// addRepoRoot attaches the repository root to every outgoing request
+func (c *Connector) addRepoRoot(ctx context.Context, method string, req, reply interface{}, cc *grpc.ClientConn, invoker grpc.UnaryInvoker, opts ...grpc.CallOption) error {
+	ctx = metadata.AppendToOutgoingContext(ctx, RepoRootMetadataKey, c.RepoRoot.ToString())
+	return invoker(ctx, method, req, reply, cc, opts...)
+}
+
 func (c *Connector) getClientConn() (*Client, error) {
 	creds := insecure.NewCredentials()
-	conn, err := grpc.Dial(c.addr(), grpc.WithTransportCredentials(creds))
+	conn, err := grpc.Dial(c.addr(), grpc.WithTransportCredentials(creds), grpc.WithUnaryInterceptor(c.addRepoRoot))
 	if err != nil {
 		return nil, err
 	}
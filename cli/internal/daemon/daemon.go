@@ -2,8 +2,6 @@ package daemon
 
 import (
 	"context"
-	"crypto/sha256"
-	"encoding/hex"
 	"fmt"
 	"io"
 	"net"
@@ -59,42 +57,34 @@ func (c *Command) Synopsis() string {
 
 type daemon struct {
 	logger     hclog.Logger
-	repoRoot   fs.AbsolutePath
+	fileRoot   fs.AbsolutePath
 	timeout    time.Duration
 	reqCh      chan struct{}
 	timedOutCh chan struct{}
 }
 
-func getRepoHash(repoRoot fs.AbsolutePath) string {
-	pathHash := sha256.Sum256([]byte(repoRoot.ToString()))
-	// We grab a substring of the hash because there is a 108-character limit on the length
-	// of a filepath for unix domain socket.
-	return hex.EncodeToString(pathHash[:])[:16]
-}
-
-func getDaemonFileRoot(repoRoot fs.AbsolutePath) fs.AbsolutePath {
-	tempDir := fs.TempDir("turbod")
-	hexHash := getRepoHash(repoRoot)
-	return tempDir.Join(hexHash)
+// getDaemonFileRoot returns the directory holding the socket and pid file.
+// A single daemon process serves every repository for the current user and
+// version of turbo, so this location is not specific to any one repository.
+func getDaemonFileRoot(turboVersion string) fs.AbsolutePath {
+	if runtimeDir := os.Getenv("XDG_RUNTIME_DIR"); runtimeDir != "" {
+		return fs.AbsolutePathFromUpstream(runtimeDir).Join("turbod", turboVersion)
+	}
+	// The temp directory is often shared between users, so include the uid
+	return fs.TempDir(fmt.Sprintf("turbod-%v", os.Getuid())).Join(turboVersion)
 }
 
-func getLogFilePath(repoRoot fs.AbsolutePath) (fs.AbsolutePath, error) {
-	hexHash := getRepoHash(repoRoot)
-	base := repoRoot.Base()
-	logFilename := fmt.Sprintf("%v-%v.log", hexHash, base)
-
+func getLogFilePath() (fs.AbsolutePath, error) {
 	logsDir := fs.GetTurboDataDir().Join("logs")
-	return logsDir.Join(logFilename), nil
+	return logsDir.Join("turbod.log"), nil
 }
 
-func getUnixSocket(repoRoot fs.AbsolutePath) fs.AbsolutePath {
-	root := getDaemonFileRoot(repoRoot)
-	return root.Join("turbod.sock")
+func getUnixSocket(fileRoot fs.AbsolutePath) fs.AbsolutePath {
+	return fileRoot.Join("turbod.sock")
 }
 
-func getPidFile(repoRoot fs.AbsolutePath) fs.AbsolutePath {
-	root := getDaemonFileRoot(repoRoot)
-	return root.Join("turbod.pid")
+func getPidFile(fileRoot fs.AbsolutePath) fs.AbsolutePath {
+	return fileRoot.Join("turbod.pid")
 }
 
 // logError logs an error and outputs it to the UI.
@@ -108,13 +98,14 @@ var _logFileFlags = os.O_WRONLY | os.O_APPEND | os.O_CREATE
 
 func getCmd(config *config.Config, output cli.Ui, signalWatcher *signals.Watcher) *cobra.Command {
 	var idleTimeout time.Duration
+	var watchIgnores []string
 	cmd := &cobra.Command{
 		Use:           "turbo daemon",
 		Short:         "Runs turbod",
 		SilenceUsage:  true,
 		SilenceErrors: true,
 		RunE: func(cmd *cobra.Command, args []string) error {
-			logFilePath, err := getLogFilePath(config.Cwd)
+			logFilePath, err := getLogFilePath()
 			if err != nil {
 				return err
 			}
@@ -135,17 +126,13 @@ func getCmd(config *config.Config, output cli.Ui, signalWatcher *signals.Watcher
 			ctx := cmd.Context()
 			d := &daemon{
 				logger:     logger,
-				repoRoot:   config.Cwd,
+				fileRoot:   getDaemonFileRoot(config.TurboVersion),
 				timeout:    idleTimeout,
 				reqCh:      make(chan struct{}),
 				timedOutCh: make(chan struct{}),
 			}
-			serverName := getRepoHash(config.Cwd)
-			turboServer, err := server.New(serverName, d.logger.Named("rpc server"), config.Cwd, config.TurboVersion, logFilePath)
-			if err != nil {
-				d.logError(err)
-				return err
-			}
+			// Repositories are served on demand, as requests for them arrive.
+			turboServer := server.NewMultiplexer(d.logger.Named("rpc server"), config.TurboVersion, logFilePath, watchIgnores...)
 			defer func() { _ = turboServer.Close() }()
 			err = d.runTurboServer(ctx, turboServer, signalWatcher)
 			if err != nil {
@@ -156,6 +143,7 @@ func getCmd(config *config.Config, output cli.Ui, signalWatcher *signals.Watcher
 		},
 	}
 	cmd.Flags().DurationVar(&idleTimeout, "idle-time", 4*time.Hour, "Set the idle timeout for turbod")
+	cmd.Flags().StringSliceVar(&watchIgnores, "watch-ignore", nil, "Repo-relative directories or globs that turbod should never watch, in addition to watchOptions.ignore in turbo.json")
 	addDaemonSubcommands(cmd, config, output)
 	return cmd
 }
@@ -195,7 +183,7 @@ type rpcServer interface {
 func (d *daemon) runTurboServer(parentContext context.Context, rpcServer rpcServer, signalWatcher *signals.Watcher) error {
 	ctx, cancel := context.WithCancel(parentContext)
 	defer cancel()
-	pidPath := getPidFile(d.repoRoot)
+	pidPath := getPidFile(d.fileRoot)
 	lock, err := tryAcquirePidfileLock(pidPath)
 	if err != nil {
 		return errors.Wrapf(err, "failed to lock the pid file at %v. Is another turbo daemon running?", lock)
@@ -219,7 +207,7 @@ func (d *daemon) runTurboServer(parentContext context.Context, rpcServer rpcServ
 
 	// If we have the lock, assume that we are the owners of the socket file,
 	// whether it already exists or not. That means we are free to remove it.
-	sockPath := getUnixSocket(d.repoRoot)
+	sockPath := getUnixSocket(d.fileRoot)
 	if err := sockPath.Remove(); err != nil && !errors.Is(err, os.ErrNotExist) {
 		return err
 	}
@@ -308,9 +296,10 @@ type Client = connector.Client
 
 // GetClient returns a client that can be used to interact with the daemon
 func GetClient(ctx context.Context, repoRoot fs.AbsolutePath, logger hclog.Logger, turboVersion string, opts ClientOpts) (*Client, error) {
-	sockPath := getUnixSocket(repoRoot)
-	pidPath := getPidFile(repoRoot)
-	logPath, err := getLogFilePath(repoRoot)
+	fileRoot := getDaemonFileRoot(turboVersion)
+	sockPath := getUnixSocket(fileRoot)
+	pidPath := getPidFile(fileRoot)
+	logPath, err := getLogFilePath()
 	if err != nil {
 		return nil, err
 	}
@@ -322,6 +311,7 @@ func GetClient(ctx context.Context, repoRoot fs.AbsolutePath, logger hclog.Logge
 		Logger:       logger.Named("TurbodClient"),
 		Bin:          bin,
 		Opts:         opts,
+		RepoRoot:     repoRoot,
 		SockPath:     sockPath,
 		PidPath:      pidPath,
 		LogPath:      logPath,
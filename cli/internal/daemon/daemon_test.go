@@ -34,9 +34,9 @@ func testBin() string {
 }
 
 func TestPidFileLock(t *testing.T) {
-	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	fileRoot := fs.AbsolutePathFromUpstream(t.TempDir())
 
-	pidPath := getPidFile(repoRoot)
+	pidPath := getPidFile(fileRoot)
 	// the lockfile library handles removing pids from dead owners
 	_, err := tryAcquirePidfileLock(pidPath)
 	assert.NilError(t, err, "acquirePidLock")
@@ -112,7 +112,7 @@ outer:
 func TestDaemonLifecycle(t *testing.T) {
 	logger := hclog.Default()
 	logger.SetLevel(hclog.Debug)
-	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	fileRoot := fs.AbsolutePathFromUpstream(t.TempDir())
 
 	ts := newTestRPCServer()
 	watcher := signals.NewWatcher()
@@ -120,7 +120,7 @@ func TestDaemonLifecycle(t *testing.T) {
 
 	d := &daemon{
 		logger:     logger,
-		repoRoot:   repoRoot,
+		fileRoot:   fileRoot,
 		timeout:    10 * time.Second,
 		reqCh:      make(chan struct{}),
 		timedOutCh: make(chan struct{}),
@@ -134,9 +134,9 @@ func TestDaemonLifecycle(t *testing.T) {
 		wg.Done()
 	}()
 
-	sockPath := getUnixSocket(repoRoot)
+	sockPath := getUnixSocket(fileRoot)
 	waitForFile(t, sockPath, 30*time.Second)
-	pidPath := getPidFile(repoRoot)
+	pidPath := getPidFile(fileRoot)
 	waitForFile(t, pidPath, 1*time.Second)
 	cancel()
 	wg.Wait()
@@ -152,7 +152,7 @@ func TestDaemonLifecycle(t *testing.T) {
 func TestTimeout(t *testing.T) {
 	logger := hclog.Default()
 	logger.SetLevel(hclog.Debug)
-	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	fileRoot := fs.AbsolutePathFromUpstream(t.TempDir())
 
 	ts := newTestRPCServer()
 	watcher := signals.NewWatcher()
@@ -160,7 +160,7 @@ func TestTimeout(t *testing.T) {
 
 	d := &daemon{
 		logger:     logger,
-		repoRoot:   repoRoot,
+		fileRoot:   fileRoot,
 		timeout:    5 * time.Millisecond,
 		reqCh:      make(chan struct{}),
 		timedOutCh: make(chan struct{}),
@@ -174,7 +174,7 @@ func TestTimeout(t *testing.T) {
 func TestCaughtSignal(t *testing.T) {
 	logger := hclog.Default()
 	logger.SetLevel(hclog.Debug)
-	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	fileRoot := fs.AbsolutePathFromUpstream(t.TempDir())
 
 	ts := newTestRPCServer()
 	watcher := signals.NewWatcher()
@@ -182,7 +182,7 @@ func TestCaughtSignal(t *testing.T) {
 
 	d := &daemon{
 		logger:     logger,
-		repoRoot:   repoRoot,
+		fileRoot:   fileRoot,
 		timeout:    5 * time.Second,
 		reqCh:      make(chan struct{}),
 		timedOutCh: make(chan struct{}),
@@ -206,7 +206,7 @@ func TestCaughtSignal(t *testing.T) {
 	watcher.Close()
 
 	err := <-errCh
-	pidPath := getPidFile(repoRoot)
+	pidPath := getPidFile(fileRoot)
 	if pidPath.FileExists() {
 		t.Errorf("expected to clean up %v, but it still exists", pidPath)
 	}
@@ -221,7 +221,7 @@ func TestCaughtSignal(t *testing.T) {
 func TestCleanupOnPanic(t *testing.T) {
 	logger := hclog.Default()
 	logger.SetLevel(hclog.Debug)
-	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	fileRoot := fs.AbsolutePathFromUpstream(t.TempDir())
 
 	ts := newTestRPCServer()
 	watcher := signals.NewWatcher()
@@ -229,7 +229,7 @@ func TestCleanupOnPanic(t *testing.T) {
 
 	d := &daemon{
 		logger:     logger,
-		repoRoot:   repoRoot,
+		fileRoot:   fileRoot,
 		timeout:    5 * time.Second,
 		reqCh:      make(chan struct{}),
 		timedOutCh: make(chan struct{}),
@@ -242,7 +242,7 @@ func TestCleanupOnPanic(t *testing.T) {
 	<-ts.registered
 
 	creds := insecure.NewCredentials()
-	sockFile := getUnixSocket(repoRoot)
+	sockFile := getUnixSocket(fileRoot)
 	conn, err := grpc.Dial("unix://"+sockFile.ToString(), grpc.WithTransportCredentials(creds))
 	assert.NilError(t, err, "Dial")
 
@@ -254,7 +254,7 @@ func TestCleanupOnPanic(t *testing.T) {
 	// wait for the server to finish
 	<-errCh
 
-	pidPath := getPidFile(repoRoot)
+	pidPath := getPidFile(fileRoot)
 	if pidPath.FileExists() {
 		t.Errorf("expected to clean up %v, but it still exists", pidPath)
 	}
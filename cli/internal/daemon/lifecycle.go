@@ -2,9 +2,11 @@ package daemon
 
 import (
 	"context"
+	"fmt"
 
 	"github.com/hashicorp/go-hclog"
 	"github.com/mitchellh/cli"
+	"github.com/nightlyone/lockfile"
 	"github.com/pkg/errors"
 	"github.com/spf13/cobra"
 	"github.com/vercel/turborepo/cli/internal/config"
@@ -39,7 +41,7 @@ func addStartCmd(root *cobra.Command, config *config.Config, output cli.Ui) {
 func addStopCmd(root *cobra.Command, config *config.Config, output cli.Ui) {
 	cmd := &cobra.Command{
 		Use:           "stop",
-		Short:         "Stop the turbo daemon",
+		Short:         "Stop the turbo daemon serving this repository",
 		SilenceUsage:  true,
 		SilenceErrors: true,
 		RunE: func(cmd *cobra.Command, args []string) error {
@@ -86,6 +88,33 @@ func addRestartCmd(root *cobra.Command, config *config.Config, output cli.Ui) {
 	root.AddCommand(cmd)
 }
 
+// Stop asks the turbo daemon to stop serving this repository, if it is running.
+// The daemon exits once it isn't serving any repositories.
+func Stop(config *config.Config, output cli.Ui) error {
+	l := &lifecycle{
+		repoRoot:     config.Cwd,
+		logger:       config.Logger,
+		output:       output,
+		turboVersion: config.TurboVersion,
+	}
+	return l.ensureStopped()
+}
+
+// StatePaths returns the files and directories that hold the daemon's state: its
+// socket and pid file, and its log file. The socket and pid file are left out while
+// the daemon is still running, since it may be serving other repositories.
+func StatePaths(turboVersion string) []fs.AbsolutePath {
+	logFile, _ := getLogFilePath()
+	fileRoot := getDaemonFileRoot(turboVersion)
+	lockFile, err := lockfile.New(getPidFile(fileRoot).ToString())
+	if err == nil {
+		if _, err := lockFile.GetOwner(); err == nil {
+			return []fs.AbsolutePath{logFile}
+		}
+	}
+	return []fs.AbsolutePath{fileRoot, logFile}
+}
+
 type lifecycle struct {
 	repoRoot     fs.AbsolutePath
 	logger       hclog.Logger
@@ -129,6 +158,6 @@ func (l *lifecycle) ensureStopped() error {
 	if err != nil {
 		return err
 	}
-	l.output.Output("Successfully requested that turbo daemon shut down")
+	l.output.Output(fmt.Sprintf("Successfully requested that turbo daemon stop serving %v", l.repoRoot))
 	return nil
 }
@@ -12,6 +12,7 @@ import (
 	"github.com/vercel/turborepo/cli/internal/config"
 	"github.com/vercel/turborepo/cli/internal/daemon/connector"
 	"github.com/vercel/turborepo/cli/internal/daemonclient"
+	"github.com/vercel/turborepo/cli/internal/util"
 )
 
 func addStatusCmd(root *cobra.Command, config *config.Config, output cli.Ui) {
@@ -66,6 +67,24 @@ func (l *lifecycle) status(outputJSON bool) error {
 		l.output.Output(fmt.Sprintf("Daemon uptime: %v", uptime.String()))
 		l.output.Output(fmt.Sprintf("Daemon pid file: %v", client.PidPath))
 		l.output.Output(fmt.Sprintf("Daemon socket file: %v", client.SockPath))
+		l.output.Output("Repositories served by this daemon:")
+		for _, repoRoot := range status.RepoRoots {
+			l.output.Output(fmt.Sprintf("  %v", repoRoot))
+		}
+		l.output.Output(fmt.Sprintf("Watched roots: %v", status.WatchedRoots))
+		l.output.Output(fmt.Sprintf("Pending file events: %v", status.WatchBacklog))
+		cookieLatency := time.Duration(int64(status.CookieLatencyUs * 1000))
+		l.output.Output(fmt.Sprintf("Last cookie latency: %v", cookieLatency.String()))
+		l.output.Output(fmt.Sprintf("Heap memory: %v", util.FormatBytes(status.HeapAllocBytes)))
+		l.output.Output(fmt.Sprintf("Memory obtained from OS: %v", util.FormatBytes(status.SysMemoryBytes)))
+		if len(status.RecentErrors) == 0 {
+			l.output.Output("Recent errors: none")
+		} else {
+			l.output.Output("Recent errors:")
+			for _, recent := range status.RecentErrors {
+				l.output.Output(fmt.Sprintf("  %v: %v", recent.Time.Format(time.RFC3339), recent.Message))
+			}
+		}
 	}
 	return nil
 }
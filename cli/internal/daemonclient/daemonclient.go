@@ -4,8 +4,10 @@ package daemonclient
 
 import (
 	"context"
+	"time"
 
 	"github.com/vercel/turborepo/cli/internal/daemon/connector"
+	"github.com/vercel/turborepo/cli/internal/filequery"
 	"github.com/vercel/turborepo/cli/internal/fs"
 	"github.com/vercel/turborepo/cli/internal/turbodprotocol"
 )
@@ -17,10 +19,23 @@ type DaemonClient struct {
 
 // Status provides details about the daemon's status
 type Status struct {
-	UptimeMs uint64          `json:"uptimeMs"`
-	LogFile  fs.AbsolutePath `json:"logFile"`
-	PidFile  fs.AbsolutePath `json:"pidFile"`
-	SockFile fs.AbsolutePath `json:"sockFile"`
+	UptimeMs        uint64          `json:"uptimeMs"`
+	LogFile         fs.AbsolutePath `json:"logFile"`
+	PidFile         fs.AbsolutePath `json:"pidFile"`
+	SockFile        fs.AbsolutePath `json:"sockFile"`
+	WatchBacklog    uint64          `json:"watchBacklog"`
+	WatchedRoots    uint32          `json:"watchedRoots"`
+	HeapAllocBytes  uint64          `json:"heapAllocBytes"`
+	SysMemoryBytes  uint64          `json:"sysMemoryBytes"`
+	CookieLatencyUs uint64          `json:"cookieLatencyUs"`
+	RecentErrors    []Error         `json:"recentErrors"`
+	RepoRoots       []string        `json:"repoRoots"`
+}
+
+// Error is an error encountered by the daemon, along with when it happened
+type Error struct {
+	Message string    `json:"message"`
+	Time    time.Time `json:"time"`
 }
 
 // New creates a new instance of a DaemonClient.
@@ -51,6 +66,43 @@ func (d *DaemonClient) NotifyOutputsWritten(ctx context.Context, hash string, re
 	return err
 }
 
+// GetFileHashes returns content hashes for the given repo-relative, unix-style
+// file paths, as computed and cached by the daemon.
+func (d *DaemonClient) GetFileHashes(ctx context.Context, repoRelativeFiles []string) (map[string]string, error) {
+	resp, err := d.client.GetFileHashes(ctx, &turbodprotocol.GetFileHashesRequest{
+		Files: repoRelativeFiles,
+	})
+	if err != nil {
+		return nil, err
+	}
+	return resp.FileHashes, nil
+}
+
+// QueryFiles returns the files under root matching any of the given globs. If since
+// is a clock from a previous call, only files changed after that clock are returned.
+func (d *DaemonClient) QueryFiles(ctx context.Context, root string, globs []string, since string) (*filequery.Result, error) {
+	resp, err := d.client.QueryFiles(ctx, &turbodprotocol.QueryFilesRequest{
+		Root:  root,
+		Globs: globs,
+		Since: since,
+	})
+	if err != nil {
+		return nil, err
+	}
+	files := make([]filequery.File, len(resp.Files))
+	for i, file := range resp.Files {
+		files[i] = filequery.File{
+			Name:   file.Name,
+			Exists: file.Exists,
+		}
+	}
+	return &filequery.Result{
+		Clock:           resp.Clock,
+		IsFreshInstance: resp.IsFreshInstance,
+		Files:           files,
+	}, nil
+}
+
 // Status returns the DaemonStatus from the daemon
 func (d *DaemonClient) Status(ctx context.Context) (*Status, error) {
 	resp, err := d.client.Status(ctx, &turbodprotocol.StatusRequest{})
@@ -58,10 +110,24 @@ func (d *DaemonClient) Status(ctx context.Context) (*Status, error) {
 		return nil, err
 	}
 	daemonStatus := resp.DaemonStatus
+	recentErrors := make([]Error, len(daemonStatus.RecentErrors))
+	for i, recent := range daemonStatus.RecentErrors {
+		recentErrors[i] = Error{
+			Message: recent.Message,
+			Time:    time.UnixMilli(recent.TimestampMsec),
+		}
+	}
 	return &Status{
-		UptimeMs: daemonStatus.UptimeMsec,
-		LogFile:  d.client.LogPath,
-		PidFile:  d.client.PidPath,
-		SockFile: d.client.SockPath,
+		UptimeMs:        daemonStatus.UptimeMsec,
+		LogFile:         d.client.LogPath,
+		PidFile:         d.client.PidPath,
+		SockFile:        d.client.SockPath,
+		WatchBacklog:    daemonStatus.WatchBacklog,
+		WatchedRoots:    daemonStatus.WatchedRoots,
+		HeapAllocBytes:  daemonStatus.HeapAllocBytes,
+		SysMemoryBytes:  daemonStatus.SysMemoryBytes,
+		CookieLatencyUs: daemonStatus.CookieLatencyUsec,
+		RecentErrors:    recentErrors,
+		RepoRoots:       daemonStatus.RepoRoots,
 	}, nil
 }
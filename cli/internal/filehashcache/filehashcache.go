@@ -0,0 +1,201 @@
+// Package filehashcache maintains a persistent mapping of file metadata to
+// content hashes so that unchanged files don't need to be re-hashed across runs.
+package filehashcache
+
+import (
+	"encoding/json"
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+	"sync"
+
+	"github.com/hashicorp/go-hclog"
+	"github.com/pkg/errors"
+	"github.com/vercel/turborepo/cli/internal/filewatcher"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+// _cacheVersion is bumped whenever the on-disk format changes. Caches with
+// a different version are discarded on load.
+const _cacheVersion = 1
+
+// entry records the metadata observed when a file was hashed. If any of
+// these fields differ from the current state of the file, the hash is stale.
+type entry struct {
+	Mtime int64  `json:"mtime"`
+	Size  int64  `json:"size"`
+	Inode uint64 `json:"inode"`
+	Hash  string `json:"hash"`
+}
+
+type persistedCache struct {
+	Version int              `json:"version"`
+	Entries map[string]entry `json:"entries"`
+}
+
+// FileHashCache maps repo-relative file paths to git-like content hashes.
+// Entries are keyed by (path, mtime, size, inode) and are dropped whenever a
+// file watching event is received for the path, or any of its parents. The
+// metadata check means entries persisted by a previous daemon remain safe to
+// use even though we missed any events while it wasn't running.
+type FileHashCache struct {
+	logger    hclog.Logger
+	repoRoot  fs.AbsolutePath
+	cachePath fs.AbsolutePath
+
+	mu      sync.RWMutex // protects the fields below
+	entries map[string]entry
+	dirty   bool
+}
+
+// New returns a FileHashCache for the given repository, loading any previously
+// persisted entries from cachePath. A missing or unreadable cache file is not
+// an error; we simply start with an empty cache.
+func New(logger hclog.Logger, repoRoot fs.AbsolutePath, cachePath fs.AbsolutePath) *FileHashCache {
+	c := &FileHashCache{
+		logger:    logger,
+		repoRoot:  repoRoot,
+		cachePath: cachePath,
+		entries:   make(map[string]entry),
+	}
+	if err := c.load(); err != nil {
+		logger.Warn(fmt.Sprintf("discarding file hash cache at %v: %v", cachePath, err))
+	}
+	return c
+}
+
+func (c *FileHashCache) load() error {
+	contents, err := c.cachePath.ReadFile()
+	if os.IsNotExist(err) {
+		return nil
+	} else if err != nil {
+		return err
+	}
+	var persisted persistedCache
+	if err := json.Unmarshal(contents, &persisted); err != nil {
+		return err
+	}
+	if persisted.Version != _cacheVersion {
+		return fmt.Errorf("unsupported cache version %v", persisted.Version)
+	}
+	if persisted.Entries != nil {
+		c.entries = persisted.Entries
+	}
+	return nil
+}
+
+// Save writes the current set of entries to disk if anything has changed
+// since the last save.
+func (c *FileHashCache) Save() error {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	if !c.dirty {
+		return nil
+	}
+	contents, err := json.Marshal(&persistedCache{
+		Version: _cacheVersion,
+		Entries: c.entries,
+	})
+	if err != nil {
+		return errors.Wrap(err, "serializing file hash cache")
+	}
+	if err := c.cachePath.EnsureDir(); err != nil {
+		return errors.Wrapf(err, "creating directory for %v", c.cachePath)
+	}
+	// Write to a temporary file and rename so that a crash mid-write
+	// doesn't leave a truncated cache behind.
+	tmpPath := fs.UnsafeToAbsolutePath(c.cachePath.ToString() + ".tmp")
+	if err := tmpPath.WriteFile(contents, 0644); err != nil {
+		return errors.Wrapf(err, "writing %v", tmpPath)
+	}
+	if err := tmpPath.Rename(c.cachePath); err != nil {
+		return errors.Wrapf(err, "renaming %v", tmpPath)
+	}
+	c.dirty = false
+	return nil
+}
+
+// GetFileHashes returns the content hash for each of the given repo-relative
+// paths, hashing and recording any files that aren't already cached.
+func (c *FileHashCache) GetFileHashes(repoRelativePaths []string) (map[string]string, error) {
+	hashes := make(map[string]string, len(repoRelativePaths))
+	for _, relativePath := range repoRelativePaths {
+		hash, err := c.getFileHash(filepath.FromSlash(relativePath))
+		if err != nil {
+			return nil, err
+		}
+		hashes[relativePath] = hash
+	}
+	return hashes, nil
+}
+
+func (c *FileHashCache) getFileHash(relativePath string) (string, error) {
+	absolutePath := c.repoRoot.Join(relativePath)
+	info, err := absolutePath.Lstat()
+	if err != nil {
+		return "", errors.Wrapf(err, "failed to stat %v", relativePath)
+	}
+	current := entry{
+		Mtime: info.ModTime().UnixNano(),
+		Size:  info.Size(),
+		Inode: inodeOf(info),
+	}
+	c.mu.RLock()
+	existing, ok := c.entries[relativePath]
+	c.mu.RUnlock()
+	if ok && existing.Mtime == current.Mtime && existing.Size == current.Size && existing.Inode == current.Inode {
+		return existing.Hash, nil
+	}
+	hash, err := fs.GitLikeHashFile(absolutePath.ToString())
+	if err != nil {
+		return "", errors.Wrapf(err, "failed to hash %v", relativePath)
+	}
+	current.Hash = hash
+	c.mu.Lock()
+	c.entries[relativePath] = current
+	c.dirty = true
+	c.mu.Unlock()
+	return hash, nil
+}
+
+// OnFileWatchEvent implements FileWatchClient.OnFileWatchEvent
+// Any event for a path invalidates the cached hash for that path and,
+// in case the path is a directory, everything underneath it.
+func (c *FileHashCache) OnFileWatchEvent(ev filewatcher.Event) {
+	relativePath, err := c.repoRoot.RelativePathString(ev.Path.ToStringDuringMigration())
+	if err != nil {
+		c.logger.Error(fmt.Sprintf("could not get relative path from %v to %v: %v", c.repoRoot, ev.Path, err))
+		return
+	}
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	if relativePath == "." {
+		c.entries = make(map[string]entry)
+		c.dirty = true
+		return
+	}
+	if _, ok := c.entries[relativePath]; ok {
+		delete(c.entries, relativePath)
+		c.dirty = true
+	}
+	if ev.EventType == filewatcher.FileDeleted || ev.EventType == filewatcher.FileRenamed {
+		prefix := relativePath + string(filepath.Separator)
+		for path := range c.entries {
+			if strings.HasPrefix(path, prefix) {
+				delete(c.entries, path)
+				c.dirty = true
+			}
+		}
+	}
+}
+
+// OnFileWatchError implements FileWatchClient.OnFileWatchError
+func (c *FileHashCache) OnFileWatchError(err error) {
+	c.logger.Error(fmt.Sprintf("file watching received an error: %v", err))
+}
+
+// OnFileWatchClosed implements FileWatchClient.OnFileWatchClosed
+// Entries remain usable after file watching stops, since each lookup
+// re-validates the file's metadata before trusting the cached hash.
+func (c *FileHashCache) OnFileWatchClosed() {}
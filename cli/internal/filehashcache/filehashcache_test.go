@@ -0,0 +1,91 @@
+package filehashcache
+
+import (
+	"path/filepath"
+	"testing"
+
+	"github.com/hashicorp/go-hclog"
+	"github.com/vercel/turborepo/cli/internal/filewatcher"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+func writeFile(t *testing.T, path fs.AbsolutePath, contents string) {
+	err := path.EnsureDir()
+	assert.NilError(t, err, "EnsureDir")
+	err = path.WriteFile([]byte(contents), 0644)
+	assert.NilError(t, err, "WriteFile")
+}
+
+func TestGetFileHashes(t *testing.T) {
+	logger := hclog.Default()
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	cachePath := fs.AbsolutePathFromUpstream(t.TempDir()).Join("hashes.json")
+	writeFile(t, repoRoot.Join("my-pkg", "src", "index.js"), "console.log('hi')")
+
+	c := New(logger, repoRoot, cachePath)
+	hashes, err := c.GetFileHashes([]string{"my-pkg/src/index.js"})
+	assert.NilError(t, err, "GetFileHashes")
+	expected, err := fs.GitLikeHashFile(repoRoot.Join("my-pkg", "src", "index.js").ToString())
+	assert.NilError(t, err, "GitLikeHashFile")
+	assert.Equal(t, hashes["my-pkg/src/index.js"], expected)
+
+	_, err = c.GetFileHashes([]string{"my-pkg/missing.js"})
+	assert.ErrorContains(t, err, "failed to stat")
+}
+
+func TestInvalidation(t *testing.T) {
+	logger := hclog.Default()
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	cachePath := fs.AbsolutePathFromUpstream(t.TempDir()).Join("hashes.json")
+	filePath := repoRoot.Join("my-pkg", "src", "index.js")
+	writeFile(t, filePath, "console.log('hi')")
+	otherPath := repoRoot.Join("other-pkg", "index.js")
+	writeFile(t, otherPath, "other")
+
+	c := New(logger, repoRoot, cachePath)
+	_, err := c.GetFileHashes([]string{"my-pkg/src/index.js", "other-pkg/index.js"})
+	assert.NilError(t, err, "GetFileHashes")
+	assert.Equal(t, len(c.entries), 2)
+
+	c.OnFileWatchEvent(filewatcher.Event{
+		Path:      repoRoot.Join("my-pkg"),
+		EventType: filewatcher.FileDeleted,
+	})
+	_, ok := c.entries[filepath.Join("my-pkg", "src", "index.js")]
+	assert.Assert(t, !ok, "expected deleting a parent directory to invalidate the file")
+	_, ok = c.entries[filepath.Join("other-pkg", "index.js")]
+	assert.Assert(t, ok, "expected unrelated files to remain cached")
+
+	c.OnFileWatchEvent(filewatcher.Event{
+		Path:      otherPath,
+		EventType: filewatcher.FileModified,
+	})
+	assert.Equal(t, len(c.entries), 0)
+}
+
+func TestPersistence(t *testing.T) {
+	logger := hclog.Default()
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	cachePath := fs.AbsolutePathFromUpstream(t.TempDir()).Join("hashes.json")
+	filePath := repoRoot.Join("index.js")
+	writeFile(t, filePath, "original")
+
+	c := New(logger, repoRoot, cachePath)
+	original, err := c.GetFileHashes([]string{"index.js"})
+	assert.NilError(t, err, "GetFileHashes")
+	err = c.Save()
+	assert.NilError(t, err, "Save")
+
+	reloaded := New(logger, repoRoot, cachePath)
+	assert.Equal(t, len(reloaded.entries), 1)
+	hashes, err := reloaded.GetFileHashes([]string{"index.js"})
+	assert.NilError(t, err, "GetFileHashes")
+	assert.Equal(t, hashes["index.js"], original["index.js"])
+
+	// Changes made while no daemon was watching are caught by the metadata check
+	writeFile(t, filePath, "changed contents")
+	hashes, err = reloaded.GetFileHashes([]string{"index.js"})
+	assert.NilError(t, err, "GetFileHashes")
+	assert.Assert(t, hashes["index.js"] != original["index.js"], "expected a new hash for modified file")
+}
@@ -0,0 +1,12 @@
+//go:build windows
+// +build windows
+
+package filehashcache
+
+import "os"
+
+// Windows doesn't expose a stable inode through os.FileInfo, so we
+// rely on mtime and size alone.
+func inodeOf(info os.FileInfo) uint64 {
+	return 0
+}
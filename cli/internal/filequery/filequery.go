@@ -0,0 +1,244 @@
+// Package filequery answers watchman-style "which files matching these globs
+// have changed since clock C" queries using file watching events.
+package filequery
+
+import (
+	"fmt"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"sync"
+	"time"
+
+	"github.com/hashicorp/go-hclog"
+	"github.com/pkg/errors"
+	"github.com/vercel/turborepo/cli/internal/doublestar"
+	"github.com/vercel/turborepo/cli/internal/filewatcher"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/globby"
+)
+
+// _clockPrefix mirrors the "c:" prefix used by watchman clock tokens
+const _clockPrefix = "c:"
+
+// File is a single result from a query. Paths are unix-style and relative to
+// the root of the query.
+type File struct {
+	Name   string
+	Exists bool
+}
+
+// Result is the response to a query. If IsFreshInstance is true, the provided
+// since clock could not be honored and Files contains every matching file
+// that currently exists, rather than only the files that changed.
+type Result struct {
+	Clock           string
+	IsFreshInstance bool
+	Files           []File
+}
+
+type change struct {
+	tick   uint64
+	exists bool
+}
+
+// FileQuery tracks the most recent change to every path that it has seen an event for.
+// Each event advances a monotonic tick, which is handed out to callers as an opaque
+// clock token. Clock tokens embed an instance id, so tokens from a previous daemon,
+// which may have missed events, are treated as a fresh instance.
+type FileQuery struct {
+	logger       hclog.Logger
+	repoRoot     fs.AbsolutePath
+	cookieWaiter filewatcher.CookieWaiter
+	instanceID   string
+
+	mu      sync.Mutex // protects the fields below
+	tick    uint64
+	changes map[string]change // repo-relative unix path -> most recent change
+	closed  bool
+}
+
+// New returns a new FileQuery instance
+func New(logger hclog.Logger, repoRoot fs.AbsolutePath, cookieWaiter filewatcher.CookieWaiter) *FileQuery {
+	return &FileQuery{
+		logger:       logger,
+		repoRoot:     repoRoot,
+		cookieWaiter: cookieWaiter,
+		instanceID:   strconv.FormatInt(time.Now().UnixNano(), 36),
+		changes:      make(map[string]change),
+	}
+}
+
+func (q *FileQuery) formatClock(tick uint64) string {
+	return fmt.Sprintf("%v%v:%v", _clockPrefix, q.instanceID, tick)
+}
+
+// parseClock returns the tick encoded in the given clock, and whether or not the
+// clock was issued by this instance.
+func (q *FileQuery) parseClock(clock string) (uint64, bool) {
+	if !strings.HasPrefix(clock, _clockPrefix) {
+		return 0, false
+	}
+	parts := strings.Split(strings.TrimPrefix(clock, _clockPrefix), ":")
+	if len(parts) != 2 || parts[0] != q.instanceID {
+		return 0, false
+	}
+	tick, err := strconv.ParseUint(parts[1], 10, 64)
+	if err != nil {
+		return 0, false
+	}
+	return tick, true
+}
+
+// Query returns the files under the repo-relative root directory that match any of
+// the given globs. If since is a clock previously returned from this instance, only
+// files that have changed after that clock are returned, including deleted files.
+// Otherwise, the filesystem is walked and all currently-existing matches are returned.
+func (q *FileQuery) Query(root string, globs []string, since string) (*Result, error) {
+	absoluteRoot := q.repoRoot.Join(filepath.FromSlash(root))
+	if contains, err := q.repoRoot.ContainsPath(absoluteRoot); err != nil {
+		return nil, err
+	} else if !contains {
+		return nil, fmt.Errorf("query root %v is outside of the repository", root)
+	}
+	sinceTick, ok := q.parseClock(since)
+	q.mu.Lock()
+	closed := q.closed
+	q.mu.Unlock()
+	if !closed {
+		// Wait for a cookie so that we have seen every filesystem write
+		// made by the caller before answering.
+		if err := q.cookieWaiter.WaitForCookie(); err != nil {
+			return nil, err
+		}
+	}
+	q.mu.Lock()
+	clock := q.formatClock(q.tick)
+	if !ok || q.closed {
+		q.mu.Unlock()
+		return q.freshInstance(absoluteRoot, globs, clock)
+	}
+	relativeRoot := filepath.ToSlash(filepath.Clean(root))
+	var files []File
+	for path, c := range q.changes {
+		if c.tick <= sinceTick {
+			continue
+		}
+		name, ok := relativeTo(relativeRoot, path)
+		if !ok {
+			continue
+		}
+		matches, err := matchesAny(globs, name)
+		if err != nil {
+			q.mu.Unlock()
+			return nil, err
+		}
+		if matches {
+			files = append(files, File{Name: name, Exists: c.exists})
+		}
+	}
+	q.mu.Unlock()
+	return &Result{
+		Clock: clock,
+		Files: files,
+	}, nil
+}
+
+func (q *FileQuery) freshInstance(absoluteRoot fs.AbsolutePath, globs []string, clock string) (*Result, error) {
+	if !absoluteRoot.DirExists() {
+		return &Result{Clock: clock, IsFreshInstance: true}, nil
+	}
+	matches, err := globby.GlobFiles(absoluteRoot.ToStringDuringMigration(), globs, nil)
+	if err != nil {
+		return nil, errors.Wrapf(err, "walking %v", absoluteRoot)
+	}
+	files := make([]File, 0, len(matches))
+	for _, match := range matches {
+		name, err := absoluteRoot.RelativePathString(match)
+		if err != nil {
+			return nil, err
+		}
+		files = append(files, File{Name: filepath.ToSlash(name), Exists: true})
+	}
+	return &Result{
+		Clock:           clock,
+		IsFreshInstance: true,
+		Files:           files,
+	}, nil
+}
+
+// relativeTo returns path relative to root, if path is contained within root.
+// Both arguments are unix-style and repo-relative.
+func relativeTo(root string, path string) (string, bool) {
+	if root == "." {
+		return path, true
+	}
+	if strings.HasPrefix(path, root+"/") {
+		return path[len(root)+1:], true
+	}
+	return "", false
+}
+
+func matchesAny(globs []string, name string) (bool, error) {
+	for _, glob := range globs {
+		matches, err := doublestar.Match(glob, name)
+		if err != nil {
+			return false, err
+		}
+		if matches {
+			return true, nil
+		}
+	}
+	return false, nil
+}
+
+// OnFileWatchEvent implements FileWatchClient.OnFileWatchEvent
+// Every event advances the clock. Directories are not reported as results,
+// but deleting one marks every file we know of underneath it as deleted.
+func (q *FileQuery) OnFileWatchEvent(ev filewatcher.Event) {
+	repoRelativePath, err := q.repoRoot.RelativePathString(ev.Path.ToStringDuringMigration())
+	if err != nil {
+		q.logger.Error(fmt.Sprintf("could not get relative path from %v to %v: %v", q.repoRoot, ev.Path, err))
+		return
+	}
+	path := filepath.ToSlash(repoRelativePath)
+	exists := false
+	isDir := false
+	if ev.EventType != filewatcher.FileDeleted {
+		if info, err := ev.Path.Lstat(); err == nil {
+			exists = true
+			isDir = info.IsDir()
+		}
+	}
+	q.mu.Lock()
+	defer q.mu.Unlock()
+	q.tick++
+	if isDir {
+		return
+	}
+	if !exists {
+		prefix := path + "/"
+		for other, c := range q.changes {
+			if c.exists && strings.HasPrefix(other, prefix) {
+				q.changes[other] = change{tick: q.tick, exists: false}
+			}
+		}
+	}
+	q.changes[path] = change{tick: q.tick, exists: exists}
+}
+
+// OnFileWatchError implements FileWatchClient.OnFileWatchError
+func (q *FileQuery) OnFileWatchError(err error) {
+	q.logger.Error(fmt.Sprintf("file watching received an error: %v", err))
+}
+
+// OnFileWatchClosed implements FileWatchClient.OnFileWatchClosed
+// Without events we can no longer compute incremental results, so every
+// subsequent query is answered as a fresh instance.
+func (q *FileQuery) OnFileWatchClosed() {
+	q.mu.Lock()
+	q.closed = true
+	q.changes = make(map[string]change)
+	q.mu.Unlock()
+	q.logger.Warn("file queries are falling back to walking the filesystem due to file watching closing")
+}
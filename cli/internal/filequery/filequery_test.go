@@ -0,0 +1,133 @@
+package filequery
+
+import (
+	"sort"
+	"testing"
+
+	"github.com/hashicorp/go-hclog"
+	"github.com/vercel/turborepo/cli/internal/filewatcher"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+type noopCookieWaiter struct{}
+
+func (*noopCookieWaiter) WaitForCookie() error {
+	return nil
+}
+
+var _noopCookieWaiter = &noopCookieWaiter{}
+
+func createFile(t *testing.T, path fs.AbsolutePath) {
+	err := path.EnsureDir()
+	assert.NilError(t, err, "EnsureDir")
+	f, err := path.Create()
+	assert.NilError(t, err, "Create")
+	err = f.Close()
+	assert.NilError(t, err, "Close")
+}
+
+func sortedFiles(files []File) []File {
+	sort.Slice(files, func(i, j int) bool {
+		return files[i].Name < files[j].Name
+	})
+	return files
+}
+
+func TestQueryFreshInstance(t *testing.T) {
+	logger := hclog.Default()
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	createFile(t, repoRoot.Join("my-pkg", "src", "index.ts"))
+	createFile(t, repoRoot.Join("my-pkg", "src", "util.ts"))
+	createFile(t, repoRoot.Join("my-pkg", "README.md"))
+
+	q := New(logger, repoRoot, _noopCookieWaiter)
+	result, err := q.Query("my-pkg", []string{"**/*.ts"}, "")
+	assert.NilError(t, err, "Query")
+	assert.Assert(t, result.IsFreshInstance)
+	assert.DeepEqual(t, sortedFiles(result.Files), []File{
+		{Name: "src/index.ts", Exists: true},
+		{Name: "src/util.ts", Exists: true},
+	})
+
+	// A clock from another instance can't be trusted
+	result, err = q.Query("my-pkg", []string{"**/*.ts"}, "c:someoneelse:4")
+	assert.NilError(t, err, "Query")
+	assert.Assert(t, result.IsFreshInstance)
+
+	_, err = q.Query("../elsewhere", []string{"**"}, "")
+	assert.ErrorContains(t, err, "outside of the repository")
+}
+
+func TestQuerySinceClock(t *testing.T) {
+	logger := hclog.Default()
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	indexPath := repoRoot.Join("my-pkg", "src", "index.ts")
+	utilPath := repoRoot.Join("my-pkg", "src", "util.ts")
+	createFile(t, indexPath)
+	createFile(t, utilPath)
+
+	q := New(logger, repoRoot, _noopCookieWaiter)
+	result, err := q.Query("my-pkg", []string{"**/*.ts"}, "")
+	assert.NilError(t, err, "Query")
+	clock := result.Clock
+
+	// Nothing has happened yet
+	result, err = q.Query("my-pkg", []string{"**/*.ts"}, clock)
+	assert.NilError(t, err, "Query")
+	assert.Assert(t, !result.IsFreshInstance)
+	assert.Equal(t, len(result.Files), 0)
+	assert.Equal(t, result.Clock, clock)
+
+	q.OnFileWatchEvent(filewatcher.Event{Path: indexPath, EventType: filewatcher.FileModified})
+	err = utilPath.Remove()
+	assert.NilError(t, err, "Remove")
+	q.OnFileWatchEvent(filewatcher.Event{Path: utilPath, EventType: filewatcher.FileDeleted})
+	readmePath := repoRoot.Join("my-pkg", "README.md")
+	createFile(t, readmePath)
+	q.OnFileWatchEvent(filewatcher.Event{Path: readmePath, EventType: filewatcher.FileAdded})
+
+	result, err = q.Query("my-pkg", []string{"**/*.ts"}, clock)
+	assert.NilError(t, err, "Query")
+	assert.Assert(t, !result.IsFreshInstance)
+	assert.DeepEqual(t, sortedFiles(result.Files), []File{
+		{Name: "src/index.ts", Exists: true},
+		{Name: "src/util.ts", Exists: false},
+	})
+	nextClock := result.Clock
+	assert.Assert(t, nextClock != clock)
+
+	result, err = q.Query("my-pkg", []string{"**/*.ts"}, nextClock)
+	assert.NilError(t, err, "Query")
+	assert.Equal(t, len(result.Files), 0)
+}
+
+func TestQueryDirectoryDeleted(t *testing.T) {
+	logger := hclog.Default()
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	indexPath := repoRoot.Join("my-pkg", "src", "index.ts")
+	createFile(t, indexPath)
+
+	q := New(logger, repoRoot, _noopCookieWaiter)
+	q.OnFileWatchEvent(filewatcher.Event{Path: indexPath, EventType: filewatcher.FileAdded})
+	result, err := q.Query(".", []string{"**"}, "")
+	assert.NilError(t, err, "Query")
+	clock := result.Clock
+
+	srcDir := repoRoot.Join("my-pkg", "src")
+	err = srcDir.RemoveAll()
+	assert.NilError(t, err, "RemoveAll")
+	q.OnFileWatchEvent(filewatcher.Event{Path: srcDir, EventType: filewatcher.FileDeleted})
+
+	result, err = q.Query(".", []string{"**/*.ts"}, clock)
+	assert.NilError(t, err, "Query")
+	assert.DeepEqual(t, result.Files, []File{
+		{Name: "my-pkg/src/index.ts", Exists: false},
+	})
+
+	q.OnFileWatchClosed()
+	result, err = q.Query(".", []string{"**/*.ts"}, clock)
+	assert.NilError(t, err, "Query")
+	assert.Assert(t, result.IsFreshInstance)
+	assert.Equal(t, len(result.Files), 0)
+}
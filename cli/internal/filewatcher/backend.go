@@ -74,8 +74,12 @@ func (f *fsNotifyBackend) onFileAdded(name fs.AbsolutePath) error {
 		return errors.Wrapf(err, "error checking lstat of new file %v", name)
 	}
 	if info.IsDir() {
-		// If a directory has been added, we need to synthesize events for everything it contains
-		if err := f.watchRecursively(name, []string{}, synthesizeEvents); err != nil {
+		// If a directory has been added, we need to synthesize events for everything it contains.
+		// Newly-created directories are subject to the same exclusions as the initial roots.
+		f.mu.Lock()
+		excludes := append([]string{}, f.allExcludes...)
+		f.mu.Unlock()
+		if err := f.watchRecursively(name, excludes, synthesizeEvents); err != nil {
 			return errors.Wrapf(err, "failed recursive watch of %v", name)
 		}
 	} else {
@@ -116,8 +120,6 @@ func (f *fsNotifyBackend) watchRecursively(root fs.AbsolutePath, excludePatterns
 	if err != nil {
 		return err
 	}
-	f.allExcludes = append(f.allExcludes, excludePatterns...)
-
 	return nil
 }
 
@@ -188,6 +190,9 @@ func (f *fsNotifyBackend) Start() error {
 }
 
 func (f *fsNotifyBackend) AddRoot(root fs.AbsolutePath, excludePatterns ...string) error {
+	f.mu.Lock()
+	f.allExcludes = append(f.allExcludes, excludePatterns...)
+	f.mu.Unlock()
 	// We don't synthesize events for the initial watch
 	return f.watchRecursively(root, excludePatterns, dontSynthesizeEvents)
 }
@@ -201,7 +206,7 @@ func GetPlatformSpecificBackend(logger hclog.Logger) (Backend, error) {
 	}
 	return &fsNotifyBackend{
 		watcher: watcher,
-		events:  make(chan Event),
+		events:  make(chan Event, _eventBufferSize),
 		errors:  make(chan error),
 		logger:  logger.Named("fsnotify"),
 	}, nil
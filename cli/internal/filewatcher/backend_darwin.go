@@ -206,7 +206,7 @@ func toFileEvent(flags fsevents.EventFlags) FileEvent {
 // running on.
 func GetPlatformSpecificBackend(logger hclog.Logger) (Backend, error) {
 	return &fseventsBackend{
-		events: make(chan Event),
+		events: make(chan Event, _eventBufferSize),
 		errors: make(chan error),
 		logger: logger.Named("fsevents"),
 	}, nil
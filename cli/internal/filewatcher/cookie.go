@@ -32,6 +32,9 @@ type CookieJar struct {
 	mu      sync.Mutex
 	cookies map[fs.AbsolutePath]chan error
 	closed  bool
+	// lastLatency is the duration in nanoseconds of the most recent
+	// successful cookie roundtrip. Accessed atomically.
+	lastLatency int64
 }
 
 // NewCookieJar returns a new instance of a CookieJar. There should only ever be a single
@@ -114,6 +117,7 @@ func (cj *CookieJar) WaitForCookie() error {
 	}
 	cj.cookies[cookiePath] = ch
 	cj.mu.Unlock()
+	start := time.Now()
 	if err := touchCookieFile(cookiePath); err != nil {
 		cj.notifyCookie(cookiePath, err)
 		return err
@@ -124,6 +128,7 @@ func (cj *CookieJar) WaitForCookie() error {
 	case err, ok := <-ch:
 		if !ok {
 			// the channel closed without an error, we're all set
+			atomic.StoreInt64(&cj.lastLatency, int64(time.Since(start)))
 			return nil
 		}
 		// the channel didn't close, meaning we got some error.
@@ -133,6 +138,12 @@ func (cj *CookieJar) WaitForCookie() error {
 	}
 }
 
+// LastLatency returns the duration of the most recent successful cookie roundtrip,
+// or 0 if no cookie has completed yet.
+func (cj *CookieJar) LastLatency() time.Duration {
+	return time.Duration(atomic.LoadInt64(&cj.lastLatency))
+}
+
 func (cj *CookieJar) notifyCookie(cookie fs.AbsolutePath, err error) {
 	cj.mu.Lock()
 	ch, ok := cj.cookies[cookie]
@@ -14,6 +14,10 @@ import (
 // _ignores is the set of paths we exempt from file-watching
 var _ignores = []string{".git", "node_modules"}
 
+// _eventBufferSize is the number of events a backend can queue up while
+// clients are busy handling earlier events.
+const _eventBufferSize = 1024
+
 // FileWatchClient defines the callbacks used by the file watching loop.
 // All methods are called from the same goroutine so they:
 // 1) do not need synchronization
@@ -65,8 +69,8 @@ type Backend interface {
 }
 
 // FileWatcher handles watching all of the files in the monorepo.
-// We currently ignore .git and top-level node_modules. We can revisit
-// if necessary.
+// We always ignore .git and top-level node_modules, along with any
+// additional repo-relative paths or globs provided by the caller.
 type FileWatcher struct {
 	backend Backend
 
@@ -77,13 +81,27 @@ type FileWatcher struct {
 	clientsMu sync.RWMutex
 	clients   []FileWatchClient
 	closed    bool
+
+	rootsMu sync.Mutex
+	roots   int
+}
+
+// Stats is a snapshot of the state of file watching
+type Stats struct {
+	// Backlog is the number of events that have been received from the
+	// backend, but not yet delivered to clients
+	Backlog int
+	// WatchedRoots is the number of directory hierarchies being watched
+	WatchedRoots int
 }
 
-// New returns a new FileWatcher instance
-func New(logger hclog.Logger, repoRoot fs.AbsolutePath, backend Backend) *FileWatcher {
-	excludes := make([]string, len(_ignores))
-	for i, ignore := range _ignores {
-		excludes[i] = filepath.ToSlash(repoRoot.Join(ignore).ToString() + "/**")
+// New returns a new FileWatcher instance. ignores are repo-relative directories,
+// or globs matching directories, that should never be watched.
+func New(logger hclog.Logger, repoRoot fs.AbsolutePath, backend Backend, ignores ...string) *FileWatcher {
+	allIgnores := append(append([]string{}, _ignores...), ignores...)
+	excludes := make([]string, len(allIgnores))
+	for i, ignore := range allIgnores {
+		excludes[i] = filepath.ToSlash(repoRoot.ToString()) + "/" + strings.Trim(filepath.ToSlash(ignore), "/") + "/**"
 	}
 	excludePattern := "{" + strings.Join(excludes, ",") + "}"
 	return &FileWatcher{
@@ -105,6 +123,7 @@ func (fw *FileWatcher) Start() error {
 	if err := fw.backend.AddRoot(fw.repoRoot, fw.excludePattern); err != nil {
 		return err
 	}
+	fw.addedRoot()
 	if err := fw.backend.Start(); err != nil {
 		return err
 	}
@@ -112,12 +131,32 @@ func (fw *FileWatcher) Start() error {
 	return nil
 }
 
+func (fw *FileWatcher) addedRoot() {
+	fw.rootsMu.Lock()
+	fw.roots++
+	fw.rootsMu.Unlock()
+}
+
+// Stats returns the current backlog of events and number of watched roots
+func (fw *FileWatcher) Stats() Stats {
+	fw.rootsMu.Lock()
+	defer fw.rootsMu.Unlock()
+	return Stats{
+		Backlog:      len(fw.backend.Events()),
+		WatchedRoots: fw.roots,
+	}
+}
+
 // AddRoot registers the root a filesystem hierarchy to be watched for changes. Events are *not*
 // fired for existing files when AddRoot is called, only for subsequent changes.
 // NOTE: if it appears helpful, we could change this behavior so that we provide a stream of initial
 // events.
 func (fw *FileWatcher) AddRoot(root fs.AbsolutePath, excludePatterns ...string) error {
-	return fw.backend.AddRoot(root, excludePatterns...)
+	if err := fw.backend.AddRoot(root, excludePatterns...); err != nil {
+		return err
+	}
+	fw.addedRoot()
+	return nil
 }
 
 // watch is the main file-watching loop. Watching is not recursive,
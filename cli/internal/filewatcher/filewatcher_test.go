@@ -149,3 +149,46 @@ func TestFileWatching(t *testing.T) {
 	assert.NilError(t, err, "WriteFile")
 	expectNoFilesystemEvent(t, ch)
 }
+
+func TestFileWatchingIgnores(t *testing.T) {
+	logger := hclog.Default()
+	logger.SetLevel(hclog.Debug)
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	err := repoRoot.Join("bazel-out", "bin").MkdirAll()
+	assert.NilError(t, err, "MkdirAll")
+	err = repoRoot.Join("packages", "rust-pkg", "target").MkdirAll()
+	assert.NilError(t, err, "MkdirAll")
+
+	// Directory layout:
+	// <repoRoot>/
+	//   bazel-out/
+	//     bin/
+	//   packages/
+	//     rust-pkg/
+	//       target/
+
+	watcher, err := GetPlatformSpecificBackend(logger)
+	assert.NilError(t, err, "GetPlatformSpecificBackend")
+	fw := New(logger, repoRoot, watcher, "bazel-out", "**/target")
+	err = fw.Start()
+	assert.NilError(t, err, "fw.Start")
+
+	ch := make(chan Event, 1)
+	c := &testClient{
+		notify: ch,
+	}
+	fw.AddClient(c)
+	expectWatching(t, c, []fs.AbsolutePath{
+		repoRoot,
+		repoRoot.Join("packages"),
+		repoRoot.Join("packages", "rust-pkg"),
+	})
+
+	err = repoRoot.Join("bazel-out", "bin", "output").WriteFile([]byte("nope"), 0644)
+	assert.NilError(t, err, "WriteFile")
+	expectNoFilesystemEvent(t, ch)
+
+	err = repoRoot.Join("packages", "rust-pkg", "target", "output").WriteFile([]byte("nope"), 0644)
+	assert.NilError(t, err, "WriteFile")
+	expectNoFilesystemEvent(t, ch)
+}
@@ -0,0 +1,13 @@
+//go:build darwin
+// +build darwin
+
+package fs
+
+import "golang.org/x/sys/unix"
+
+// cloneFile makes to a copy-on-write clone of from using clonefile(2), which shares
+// storage with from until either is modified. It fails on filesystems other than APFS,
+// and across volumes. to must not exist.
+func cloneFile(from string, to string) error {
+	return unix.Clonefile(from, to, 0)
+}
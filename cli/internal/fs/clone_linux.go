@@ -0,0 +1,31 @@
+//go:build linux
+// +build linux
+
+package fs
+
+import (
+	"os"
+
+	"golang.org/x/sys/unix"
+)
+
+// cloneFile makes to a copy-on-write clone of from, which shares storage with from
+// until either is modified. It fails on filesystems without reflinks, such as ext4,
+// and across filesystems. to must not exist.
+func cloneFile(from string, to string) error {
+	src, err := os.Open(from)
+	if err != nil {
+		return err
+	}
+	defer func() { _ = src.Close() }()
+	dst, err := os.OpenFile(to, os.O_WRONLY|os.O_CREATE|os.O_EXCL, 0600)
+	if err != nil {
+		return err
+	}
+	if err := unix.IoctlFileClone(int(dst.Fd()), int(src.Fd())); err != nil {
+		_ = dst.Close()
+		_ = os.Remove(to)
+		return err
+	}
+	return dst.Close()
+}
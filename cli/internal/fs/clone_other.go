@@ -0,0 +1,13 @@
+//go:build !linux && !darwin
+// +build !linux,!darwin
+
+package fs
+
+import "errors"
+
+var errCloneUnsupported = errors.New("copy-on-write clones are not supported on this platform")
+
+// cloneFile always fails on platforms without copy-on-write clones, so files are copied instead
+func cloneFile(from string, to string) error {
+	return errCloneUnsupported
+}
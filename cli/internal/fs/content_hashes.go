@@ -0,0 +1,150 @@
+package fs
+
+import (
+	"path/filepath"
+	"strings"
+	"sync"
+
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+// ContentHashes remembers the git hashes of files that were read from the working tree,
+// so that files which are inputs to many tasks, like shared config files, are only read
+// and hashed once. It is only valid while the files don't change, e.g. for the hashing
+// phase of a run. It is safe for concurrent use.
+type ContentHashes struct {
+	mu       sync.Mutex
+	hashes   map[turbopath.AbsoluteSystemPath]string
+	repoRoot turbopath.AbsoluteSystemPath
+	source   FileHashSource
+}
+
+// FileHashSource looks up the git hashes of files given as repo-relative, unix-style
+// paths, e.g. from the daemon's file hash cache. Files it doesn't know about are left
+// out of the result.
+type FileHashSource func(repoRelativeFiles []string) (map[string]string, error)
+
+// NewContentHashes creates an empty ContentHashes
+func NewContentHashes() *ContentHashes {
+	return &ContentHashes{
+		hashes: make(map[turbopath.AbsoluteSystemPath]string),
+	}
+}
+
+// UseSource asks source for the hashes of files under repoRoot before reading them.
+// If source fails, files are hashed locally instead.
+func (c *ContentHashes) UseSource(repoRoot turbopath.AbsoluteSystemPath, source FileHashSource) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	c.repoRoot = repoRoot
+	c.source = source
+}
+
+// fromSource fills in hashes from the source for as many of paths as it knows about,
+// and returns the paths it didn't know about
+func (c *ContentHashes) fromSource(paths []turbopath.AbsoluteSystemPath, hashes map[turbopath.AbsoluteSystemPath]string) []turbopath.AbsoluteSystemPath {
+	c.mu.Lock()
+	repoRoot, source := c.repoRoot, c.source
+	c.mu.Unlock()
+	if source == nil || len(paths) == 0 {
+		return paths
+	}
+	repoRelativeFiles := make([]string, 0, len(paths))
+	byRepoRelativeFile := make(map[string]turbopath.AbsoluteSystemPath, len(paths))
+	var unknown []turbopath.AbsoluteSystemPath
+	for _, path := range paths {
+		relativePath, err := filepath.Rel(repoRoot.ToString(), path.ToString())
+		if err != nil || strings.HasPrefix(relativePath, "..") {
+			unknown = append(unknown, path)
+			continue
+		}
+		repoRelativeFile := filepath.ToSlash(relativePath)
+		repoRelativeFiles = append(repoRelativeFiles, repoRelativeFile)
+		byRepoRelativeFile[repoRelativeFile] = path
+	}
+	sourceHashes, err := source(repoRelativeFiles)
+	if err != nil {
+		return paths
+	}
+	for _, repoRelativeFile := range repoRelativeFiles {
+		path := byRepoRelativeFile[repoRelativeFile]
+		if hash, ok := sourceHashes[repoRelativeFile]; ok && hash != "" {
+			hashes[path] = hash
+		} else {
+			unknown = append(unknown, path)
+		}
+	}
+	return unknown
+}
+
+// gitHashObject is like the package-level gitHashObject, but only passes files that
+// haven't been hashed yet to `git hash-object`, in a single batch
+func (c *ContentHashes) gitHashObject(anchor turbopath.AbsoluteSystemPath, filesToHash []turbopath.AnchoredSystemPath) (map[turbopath.AnchoredUnixPath]string, error) {
+	output := make(map[turbopath.AnchoredUnixPath]string, len(filesToHash))
+	var missing []turbopath.AnchoredSystemPath
+	c.mu.Lock()
+	for _, file := range filesToHash {
+		if hash, ok := c.hashes[file.RestoreAnchor(anchor)]; ok {
+			output[file.ToUnixPath()] = hash
+		} else {
+			missing = append(missing, file)
+		}
+	}
+	c.mu.Unlock()
+
+	missingPaths := make([]turbopath.AbsoluteSystemPath, len(missing))
+	for i, file := range missing {
+		missingPaths[i] = file.RestoreAnchor(anchor)
+	}
+	sourceHashes := make(map[turbopath.AbsoluteSystemPath]string)
+	unknown := make(map[turbopath.AbsoluteSystemPath]bool)
+	for _, path := range c.fromSource(missingPaths, sourceHashes) {
+		unknown[path] = true
+	}
+	var toHash []turbopath.AnchoredSystemPath
+	for _, file := range missing {
+		if unknown[file.RestoreAnchor(anchor)] {
+			toHash = append(toHash, file)
+		}
+	}
+
+	hashes, err := gitHashObject(anchor, toHash)
+	if err != nil {
+		return nil, err
+	}
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	for _, file := range missing {
+		hash, ok := sourceHashes[file.RestoreAnchor(anchor)]
+		if !ok {
+			hash = hashes[file.ToUnixPath()]
+		}
+		c.hashes[file.RestoreAnchor(anchor)] = hash
+		output[file.ToUnixPath()] = hash
+	}
+	return output, nil
+}
+
+// HashFile returns the git hash of the file, reading it only the first time it is asked for
+func (c *ContentHashes) HashFile(path turbopath.AbsoluteSystemPath) (string, error) {
+	c.mu.Lock()
+	hash, ok := c.hashes[path]
+	c.mu.Unlock()
+	if ok {
+		return hash, nil
+	}
+	sourceHashes := make(map[turbopath.AbsoluteSystemPath]string, 1)
+	if len(c.fromSource([]turbopath.AbsoluteSystemPath{path}, sourceHashes)) == 0 {
+		hash = sourceHashes[path]
+	} else {
+		var err error
+		hash, err = GitLikeHashFile(path.ToString())
+		if err != nil {
+			return "", err
+		}
+	}
+	c.mu.Lock()
+	c.hashes[path] = hash
+	c.mu.Unlock()
+	return hash, nil
+}
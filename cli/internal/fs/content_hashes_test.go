@@ -0,0 +1,88 @@
+package fs
+
+import (
+	"os"
+	"path/filepath"
+	"reflect"
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+func TestContentHashesHashFile(t *testing.T) {
+	path := turbopath.AbsoluteSystemPath(filepath.Join(t.TempDir(), "tsconfig.json"))
+	if err := os.WriteFile(path.ToString(), []byte("{}"), 0644); err != nil {
+		t.Fatalf("failed to write file: %v", err)
+	}
+	contentHashes := NewContentHashes()
+	first, err := contentHashes.HashFile(path)
+	if err != nil {
+		t.Fatalf("HashFile: %v", err)
+	}
+	if err := os.WriteFile(path.ToString(), []byte(`{"changed": true}`), 0644); err != nil {
+		t.Fatalf("failed to write file: %v", err)
+	}
+	second, err := contentHashes.HashFile(path)
+	if err != nil {
+		t.Fatalf("HashFile: %v", err)
+	}
+	if first != second {
+		t.Errorf("HashFile got %v the second time, want %v, since the file should only be read once", second, first)
+	}
+}
+
+func TestContentHashesGitHashObject(t *testing.T) {
+	fixturePath := getFixture(1)
+	contentHashes := NewContentHashes()
+	// A file that doesn't exist would fail `git hash-object`, so it can only be hashed if
+	// its hash is already known
+	contentHashes.hashes[fixturePath.Join("child", "remembered.json")] = "remembered-hash"
+
+	got, err := contentHashes.gitHashObject(fixturePath.Join("child"), []turbopath.AnchoredSystemPath{
+		turbopath.AnchoredSystemPath(filepath.Join("..", "root.json")),
+		turbopath.AnchoredSystemPath("remembered.json"),
+	})
+	if err != nil {
+		t.Fatalf("gitHashObject: %v", err)
+	}
+	want := map[turbopath.AnchoredUnixPath]string{
+		"../root.json":    "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391",
+		"remembered.json": "remembered-hash",
+	}
+	if !reflect.DeepEqual(got, want) {
+		t.Errorf("gitHashObject() = %v, want %v", got, want)
+	}
+	if hash := contentHashes.hashes[fixturePath.Join("root.json")]; hash != want["../root.json"] {
+		t.Errorf("hash of root.json got %q, want it to be remembered as %v", hash, want["../root.json"])
+	}
+}
+
+func TestContentHashesUseSource(t *testing.T) {
+	fixturePath := getFixture(1)
+	contentHashes := NewContentHashes()
+	var asked []string
+	contentHashes.UseSource(fixturePath, func(repoRelativeFiles []string) (map[string]string, error) {
+		asked = append(asked, repoRelativeFiles...)
+		return map[string]string{"child/cached.json": "cached-hash"}, nil
+	})
+
+	// cached.json doesn't exist, so it can only be hashed by the source, while root.json
+	// is unknown to the source and falls back to `git hash-object`
+	got, err := contentHashes.gitHashObject(fixturePath.Join("child"), []turbopath.AnchoredSystemPath{
+		turbopath.AnchoredSystemPath(filepath.Join("..", "root.json")),
+		turbopath.AnchoredSystemPath("cached.json"),
+	})
+	if err != nil {
+		t.Fatalf("gitHashObject: %v", err)
+	}
+	want := map[turbopath.AnchoredUnixPath]string{
+		"../root.json": "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391",
+		"cached.json":  "cached-hash",
+	}
+	if !reflect.DeepEqual(got, want) {
+		t.Errorf("gitHashObject() = %v, want %v", got, want)
+	}
+	if wantAsked := []string{"root.json", "child/cached.json"}; !reflect.DeepEqual(asked, wantAsked) {
+		t.Errorf("source was asked for %v, want %v", asked, wantAsked)
+	}
+}
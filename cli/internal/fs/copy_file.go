@@ -4,11 +4,13 @@
 package fs
 
 import (
+	"context"
 	"errors"
 	"os"
 	"path/filepath"
 
 	"github.com/karrick/godirwalk"
+	"golang.org/x/sync/errgroup"
 )
 
 // CopyOrLinkFile either copies or hardlinks a file based on the link argument.
@@ -27,10 +29,10 @@ func CopyOrLinkFile(from *LstatCachedFile, to string, link bool, fallback bool)
 				return err
 			}
 			// Make sure the link we're about to create doesn't already exist
-			if err := os.Remove(to); err != nil && !errors.Is(err, os.ErrNotExist) {
+			if err := os.Remove(longPath(to)); err != nil && !errors.Is(err, os.ErrNotExist) {
 				return err
 			}
-			return os.Symlink(dest, to)
+			return os.Symlink(dest, longPath(to))
 		}
 		if err := from.Path.Link(to); err == nil || !fallback {
 			return err
@@ -60,7 +62,7 @@ func RecursiveCopyOrLinkFile(from string, to string, link bool, fallback bool) e
 		return WalkMode(statedFrom.Path.ToStringDuringMigration(), func(name string, isDir bool, fileType os.FileMode) error {
 			dest := filepath.Join(to, name[len(statedFrom.Path.ToString()):])
 			if isDir {
-				return os.MkdirAll(dest, DirPermissions)
+				return os.MkdirAll(longPath(dest), DirPermissions)
 			}
 			if isSame, err := SameFile(statedFrom.Path.ToStringDuringMigration(), name); err != nil {
 				return err
@@ -74,6 +76,60 @@ func RecursiveCopyOrLinkFile(from string, to string, link bool, fallback bool) e
 	return CopyOrLinkFile(&statedFrom, to, link, fallback)
 }
 
+// RecursiveCopyParallel is like RecursiveCopy, but copies the files in a directory using
+// the given number of workers. Each directory is created before any of its files are copied.
+func RecursiveCopyParallel(from string, to string, workers int) error {
+	statedFrom := LstatCachedFile{Path: UnsafeToAbsolutePath(from)}
+	fromType, err := statedFrom.GetType()
+	if err != nil {
+		return err
+	}
+	if !fromType.IsDir() || workers < 2 {
+		return RecursiveCopy(from, to)
+	}
+
+	type copyJob struct {
+		from *LstatCachedFile
+		to   string
+	}
+	g, ctx := errgroup.WithContext(context.Background())
+	jobs := make(chan copyJob, workers)
+	for i := 0; i < workers; i++ {
+		g.Go(func() error {
+			for job := range jobs {
+				if err := CopyOrLinkFile(job.from, job.to, false, false); err != nil {
+					return err
+				}
+			}
+			return nil
+		})
+	}
+	walkErr := WalkMode(statedFrom.Path.ToStringDuringMigration(), func(name string, isDir bool, fileType os.FileMode) error {
+		dest := filepath.Join(to, name[len(statedFrom.Path.ToString()):])
+		if isDir {
+			return os.MkdirAll(longPath(dest), DirPermissions)
+		}
+		if isSame, err := SameFile(statedFrom.Path.ToStringDuringMigration(), name); err != nil {
+			return err
+		} else if isSame {
+			return nil
+		}
+		fileType := fileType
+		select {
+		case jobs <- copyJob{from: &LstatCachedFile{Path: UnsafeToAbsolutePath(name), fileType: &fileType}, to: dest}:
+			return nil
+		case <-ctx.Done():
+			return ctx.Err()
+		}
+	})
+	close(jobs)
+	// A failed copy cancels the walk, so its error is the one to report
+	if err := g.Wait(); err != nil {
+		return err
+	}
+	return walkErr
+}
+
 // Walk implements an equivalent to filepath.Walk.
 // It's implemented over github.com/karrick/godirwalk but the provided interface doesn't use that
 // to make it a little easier to handle.
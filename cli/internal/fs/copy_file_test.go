@@ -203,6 +203,36 @@ func TestRecursiveCopyOrLinkFile(t *testing.T) {
 	}
 }
 
+func TestRecursiveCopyParallel(t *testing.T) {
+	src := fs.NewDir(t, "recursive-copy-parallel")
+	dst := fs.NewDir(t, "recursive-copy-parallel-dist")
+	files := map[string]string{
+		"b":             "bFile",
+		"child/a":       "hello",
+		"child/c/d":     "deep",
+		"other/e":       "e",
+		"other/f/g/h/i": "deeper",
+	}
+	for name, contents := range files {
+		path := AbsolutePath(filepath.Join(src.Path(), filepath.FromSlash(name)))
+		assert.NilError(t, path.EnsureDir(), "EnsureDir")
+		assert.NilError(t, path.WriteFile([]byte(contents), 0644), "WriteFile")
+	}
+	assert.NilError(t, os.Symlink("missing", filepath.Join(src.Path(), "child", "broken")), "Symlink")
+
+	err := RecursiveCopyParallel(src.Path(), dst.Path(), 4)
+	assert.NilError(t, err, "RecursiveCopyParallel")
+
+	for name, contents := range files {
+		got, err := os.ReadFile(filepath.Join(dst.Path(), filepath.FromSlash(name)))
+		assert.NilError(t, err, "ReadFile")
+		assert.Equal(t, string(got), contents)
+	}
+	if PathExists(filepath.Join(dst.Path(), "child", "broken")) {
+		t.Error("We copied a broken link")
+	}
+}
+
 func TestSameFile(t *testing.T) {
 	a := fs.NewFile(t, "a")
 	b := filepath.Join(filepath.Dir(a.Path()), "b")
@@ -1,12 +1,14 @@
 package fs
 
 import (
+	"fmt"
 	"io"
 	"io/ioutil"
 	"log"
 	"os"
 	"path/filepath"
 	"strings"
+	"sync/atomic"
 )
 
 // https://github.com/thought-machine/please/blob/master/src/fs/fs.go
@@ -16,7 +18,7 @@ const DirPermissions = os.ModeDir | 0775
 
 // EnsureDir ensures that the directory of the given file has been created.
 func EnsureDir(filename string) error {
-	dir := filepath.Dir(filename)
+	dir := filepath.Dir(longPath(filename))
 	err := os.MkdirAll(dir, DirPermissions)
 	if err != nil && FileExists(dir) {
 		// It looks like this is a file and not a directory. Attempt to remove it; this can
@@ -79,12 +81,47 @@ func CopyFile(from *LstatCachedFile, to string) error {
 		return err
 	}
 	defer fromFile.Close()
+	// Where the filesystem supports it, a copy-on-write clone avoids copying the contents
+	if err := cloneFileIntoPlace(from.Path.ToString(), to, fromMode); err == nil {
+		return nil
+	}
 	return writeFileFromStream(fromFile, to, fromMode)
 }
 
+var _cloneCount uint64
+
+// cloneFileIntoPlace clones from to a temporary file next to 'to', and then renames it
+// into place, the same way writeFileFromStream does for copies.
+func cloneFileIntoPlace(from string, to string, mode os.FileMode) error {
+	to = longPath(to)
+	dir, file := filepath.Split(to)
+	if dir != "" {
+		if err := os.MkdirAll(dir, DirPermissions); err != nil {
+			return err
+		}
+	}
+	tempFile := filepath.Join(dir, fmt.Sprintf(".%v.%v-%v.clone", file, os.Getpid(), atomic.AddUint64(&_cloneCount, 1)))
+	if err := cloneFile(longPath(from), tempFile); err != nil {
+		return err
+	}
+	if mode == 0 {
+		mode = 0664
+	}
+	if err := os.Chmod(tempFile, mode); err != nil {
+		_ = os.Remove(tempFile)
+		return err
+	}
+	if err := os.Rename(tempFile, to); err != nil {
+		_ = os.Remove(tempFile)
+		return err
+	}
+	return nil
+}
+
 // writeFileFromStream writes data from a reader to the file named 'to', with an attempt to perform
 // a copy & rename to avoid chaos if anything goes wrong partway.
 func writeFileFromStream(fromFile io.Reader, to string, mode os.FileMode) error {
+	to = longPath(to)
 	dir, file := filepath.Split(to)
 	if dir != "" {
 		if err := os.MkdirAll(dir, DirPermissions); err != nil {
@@ -26,7 +26,6 @@ var a = regexp.MustCompile(`(\w|\")\s(\"|\w)`)
 
 // ReadLockfile will read `yarn.lock` into memory (either from the cache or fresh)
 func ReadLockfile(rootpath string, backendName string, cacheDir AbsolutePath) (*YarnLockfile, error) {
-	var lockfile YarnLockfile
 	var prettyLockFile = YarnLockfile{}
 	hash, err := HashFile(filepath.Join(rootpath, "yarn.lock"))
 	if err != nil {
@@ -39,63 +38,12 @@ func ReadLockfile(rootpath string, backendName string, cacheDir AbsolutePath) (*
 		if err != nil {
 			return nil, fmt.Errorf("reading yarn.lock: %w", err)
 		}
-
-		var next []byte
-		if backendName == "nodejs-yarn" {
-			var lines []string
-			var l *regexp.Regexp
-			var output string
-
-			hasLF := !bytes.HasSuffix(contentsB, []byte("\r\n"))
-			if hasLF {
-				lines = strings.Split(string(contentsB), "\n")
-				l = nLineEnding
-			} else {
-				lines = strings.Split(strings.TrimRight(string(contentsB), "\r\n"), "\r\n")
-				l = rnLineEnding
-			}
-
-			for i, line := range lines {
-				if r.MatchString(line) {
-					first := fmt.Sprintf("\"%v\":", l.ReplaceAllString(line, ""))
-					lines[i] = double.ReplaceAllString(first, "\":")
-				}
-			}
-
-			if hasLF {
-				output = o.ReplaceAllString(strings.Join(lines, "\n"), "\": \"")
-			} else {
-				output = o.ReplaceAllString(strings.Join(lines, "\r\n"), "\": \"")
-			}
-
-			next = []byte(a.ReplaceAllStringFunc(output, func(m string) string {
-				parts := a.FindStringSubmatch(m)
-				return fmt.Sprintf("%s: %s", parts[1], parts[2])
-			}))
-		} else {
-			next = contentsB
-		}
-
-		err = yaml.Unmarshal(next, &lockfile)
+		parsed, err := ParseLockfile(contentsB, backendName)
 		if err != nil {
-			return &YarnLockfile{}, fmt.Errorf("could not unmarshal lockfile: %w", err)
-		}
-		// This final step is important, it splits any deps with multiple-resolutions
-		// (e.g. "@babel/generator@^7.13.0, @babel/generator@^7.13.9":) into separate
-		// entries in our map
-		// TODO: make concurrent
-		for key, val := range lockfile {
-			if strings.Contains(key, ",") {
-				for _, v := range strings.Split(key, ", ") {
-					prettyLockFile[strings.TrimSpace(v)] = val
-				}
-
-			} else {
-				prettyLockFile[key] = val
-			}
+			return &YarnLockfile{}, err
 		}
 
-		better, err := yaml.Marshal(&prettyLockFile)
+		better, err := yaml.Marshal(parsed)
 		if err != nil {
 			return nil, err
 		}
@@ -105,6 +53,7 @@ func ReadLockfile(rootpath string, backendName string, cacheDir AbsolutePath) (*
 		if err = turboLockFile.WriteFile([]byte(better), 0644); err != nil {
 			return nil, err
 		}
+		return parsed, nil
 	} else {
 		if contentsOfLock != nil {
 			err = yaml.Unmarshal(contentsOfLock, &prettyLockFile)
@@ -116,3 +65,64 @@ func ReadLockfile(rootpath string, backendName string, cacheDir AbsolutePath) (*
 
 	return &prettyLockFile, nil
 }
+
+// ParseLockfile parses the contents of a `yarn.lock`, such as one from an earlier commit
+func ParseLockfile(contentsB []byte, backendName string) (*YarnLockfile, error) {
+	var lockfile YarnLockfile
+	var prettyLockFile = YarnLockfile{}
+	var next []byte
+	if backendName == "nodejs-yarn" {
+		var lines []string
+		var l *regexp.Regexp
+		var output string
+
+		hasLF := !bytes.HasSuffix(contentsB, []byte("\r\n"))
+		if hasLF {
+			lines = strings.Split(string(contentsB), "\n")
+			l = nLineEnding
+		} else {
+			lines = strings.Split(strings.TrimRight(string(contentsB), "\r\n"), "\r\n")
+			l = rnLineEnding
+		}
+
+		for i, line := range lines {
+			if r.MatchString(line) {
+				first := fmt.Sprintf("\"%v\":", l.ReplaceAllString(line, ""))
+				lines[i] = double.ReplaceAllString(first, "\":")
+			}
+		}
+
+		if hasLF {
+			output = o.ReplaceAllString(strings.Join(lines, "\n"), "\": \"")
+		} else {
+			output = o.ReplaceAllString(strings.Join(lines, "\r\n"), "\": \"")
+		}
+
+		next = []byte(a.ReplaceAllStringFunc(output, func(m string) string {
+			parts := a.FindStringSubmatch(m)
+			return fmt.Sprintf("%s: %s", parts[1], parts[2])
+		}))
+	} else {
+		next = contentsB
+	}
+
+	err := yaml.Unmarshal(next, &lockfile)
+	if err != nil {
+		return nil, fmt.Errorf("could not unmarshal lockfile: %w", err)
+	}
+	// This final step is important, it splits any deps with multiple-resolutions
+	// (e.g. "@babel/generator@^7.13.0, @babel/generator@^7.13.9":) into separate
+	// entries in our map
+	// TODO: make concurrent
+	for key, val := range lockfile {
+		if strings.Contains(key, ",") {
+			for _, v := range strings.Split(key, ", ") {
+				prettyLockFile[strings.TrimSpace(v)] = val
+			}
+
+		} else {
+			prettyLockFile[key] = val
+		}
+	}
+	return &prettyLockFile, nil
+}
@@ -0,0 +1,9 @@
+//go:build !windows
+// +build !windows
+
+package fs
+
+// longPath is a no-op outside of Windows, where path length is only limited by the filesystem
+func longPath(path string) string {
+	return path
+}
@@ -0,0 +1,31 @@
+//go:build windows
+// +build windows
+
+package fs
+
+import (
+	"path/filepath"
+	"strings"
+)
+
+// _maxShortPath is the longest path that every Windows file API accepts without the
+// extended-length \\?\ prefix. MAX_PATH is 260, but creating a directory leaves room
+// for an 8.3 file name, so the limit for directories is 248.
+const _maxShortPath = 248
+
+// longPath converts a long absolute path to its extended-length form, so that it
+// isn't rejected with ERROR_PATH_NOT_FOUND once it grows past MAX_PATH. Go does this
+// itself for drive-letter paths made only of plain components, but not for UNC paths
+// like \\server\share\dir, or for paths that contain . or .. components.
+func longPath(path string) string {
+	if len(path) < _maxShortPath || strings.HasPrefix(path, `\\?\`) || !filepath.IsAbs(path) {
+		return path
+	}
+	// Extended-length paths are passed to the filesystem as-is, so they must not contain
+	// forward slashes, or . and .. components.
+	cleaned := filepath.Clean(path)
+	if strings.HasPrefix(cleaned, `\\`) {
+		return `\\?\UNC\` + cleaned[2:]
+	}
+	return `\\?\` + cleaned
+}
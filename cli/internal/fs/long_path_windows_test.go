@@ -0,0 +1,45 @@
+//go:build windows
+// +build windows
+
+package fs
+
+import (
+	"strings"
+	"testing"
+
+	"gotest.tools/v3/assert"
+)
+
+func Test_longPath(t *testing.T) {
+	longDir := strings.Repeat(`a-very-long-directory-name\`, 10)
+	testCases := []struct {
+		name     string
+		path     string
+		expected string
+	}{
+		{"short path", `C:\repo\dist\index.js`, `C:\repo\dist\index.js`},
+		{"long drive path", `C:\repo\` + longDir + `index.js`, `\\?\C:\repo\` + longDir + `index.js`},
+		{"long path with dot segments", `C:\repo\.\other\..\` + longDir + `index.js`, `\\?\C:\repo\` + longDir + `index.js`},
+		{"long path with forward slashes", `C:/repo/` + strings.ReplaceAll(longDir, `\`, `/`) + `index.js`, `\\?\C:\repo\` + longDir + `index.js`},
+		{"long UNC path", `\\server\share\` + longDir + `index.js`, `\\?\UNC\server\share\` + longDir + `index.js`},
+		{"already extended", `\\?\C:\repo\` + longDir + `index.js`, `\\?\C:\repo\` + longDir + `index.js`},
+		{"relative path", longDir + `index.js`, longDir + `index.js`},
+	}
+	for _, tc := range testCases {
+		assert.Equal(t, longPath(tc.path), tc.expected, tc.name)
+	}
+}
+
+func TestAbsolutePathBeyondMaxPath(t *testing.T) {
+	root := AbsolutePathFromUpstream(t.TempDir())
+	// Deeply nested node_modules, as found in restored outputs
+	file := root.Join(strings.Repeat(`node_modules\nested-package\`, 12), "package.json")
+	assert.Assert(t, len(file.ToString()) > 260)
+
+	assert.NilError(t, file.EnsureDir(), "EnsureDir")
+	assert.NilError(t, file.WriteFile([]byte("{}"), 0644), "WriteFile")
+	contents, err := file.ReadFile()
+	assert.NilError(t, err, "ReadFile")
+	assert.Equal(t, string(contents), "{}")
+	assert.NilError(t, root.Join("node_modules").RemoveAll(), "RemoveAll")
+}
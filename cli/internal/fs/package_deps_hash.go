@@ -21,6 +21,9 @@ type PackageDepsOptions struct {
 	PackagePath string
 
 	InputPatterns []string
+
+	// ContentHashes, if set, is used to avoid hashing files that were hashed before
+	ContentHashes *ContentHashes
 }
 
 // GetPackageDeps Builds an object containing git hashes for the files under the specified `packagePath` folder.
@@ -58,7 +61,13 @@ func GetPackageDeps(rootPath AbsolutePath, p *PackageDepsOptions) (map[turbopath
 		}
 	}
 
-	hashes, err := gitHashObject(turbopath.AbsoluteSystemPathFromUpstream(pkgPath.ToString()), filesToHash)
+	anchor := turbopath.AbsoluteSystemPathFromUpstream(pkgPath.ToString())
+	var hashes map[turbopath.AnchoredUnixPath]string
+	if p.ContentHashes != nil {
+		hashes, err = p.ContentHashes.gitHashObject(anchor, filesToHash)
+	} else {
+		hashes, err = gitHashObject(anchor, filesToHash)
+	}
 	if err != nil {
 		return nil, err
 	}
@@ -71,6 +80,40 @@ func GetPackageDeps(rootPath AbsolutePath, p *PackageDepsOptions) (map[turbopath
 	return result, nil
 }
 
+// GetPackageTreeHash returns the ID of the git tree object for the given package folder at HEAD,
+// which changes whenever any tracked file in the folder does. It is only meaningful when the
+// working tree matches HEAD, so the second return value is false if anything in the folder,
+// including untracked files, differs from HEAD. In that case use GetPackageDeps instead.
+func GetPackageTreeHash(rootPath AbsolutePath, packagePath string) (string, bool, error) {
+	pkgPath := rootPath.Join(packagePath)
+	gitStatusOutput, err := gitStatus(pkgPath, nil)
+	if err != nil {
+		return "", false, fmt.Errorf("could not get git status for package %s: %w", packagePath, err)
+	}
+	if len(gitStatusOutput) > 0 {
+		return "", false, nil
+	}
+	treeID, err := gitRevParseTree(pkgPath)
+	if err != nil {
+		return "", false, fmt.Errorf("could not get git tree for package %s: %w", packagePath, err)
+	}
+	return treeID, true, nil
+}
+
+// GetPackageTrackedFiles returns the files in the given package folder that git tracks at HEAD,
+// relative to the package folder.
+func GetPackageTrackedFiles(rootPath AbsolutePath, packagePath string) ([]turbopath.AnchoredUnixPath, error) {
+	entries, err := gitLsTree(rootPath.Join(packagePath))
+	if err != nil {
+		return nil, fmt.Errorf("could not list tracked files for package %s: %w", packagePath, err)
+	}
+	files := make([]turbopath.AnchoredUnixPath, 0, len(entries))
+	for path := range entries {
+		files = append(files, path)
+	}
+	return files, nil
+}
+
 // GetHashableDeps hashes the list of given files, then returns a map of normalized path to hash
 // this map is suitable for cross-platform caching.
 func GetHashableDeps(rootPath AbsolutePath, files []turbopath.AbsoluteSystemPath) (map[turbopath.AnchoredUnixPath]string, error) {
@@ -258,6 +301,26 @@ func gitLsTree(rootPath AbsolutePath) (map[turbopath.AnchoredUnixPath]string, er
 	return output, nil
 }
 
+// gitRevParseTree returns the ID of the git tree object for a particular directory at HEAD
+func gitRevParseTree(rootPath AbsolutePath) (string, error) {
+	cmd := exec.Command(
+		"git",       // Using `git` from $PATH,
+		"rev-parse", // look up the object name
+		"HEAD:./",   // of the tree for the invocation directory at this specified version.
+	)
+	cmd.Dir = rootPath.ToString() // Resolve ./ relative to this directory.
+
+	out, err := cmd.Output()
+	if err != nil {
+		return "", fmt.Errorf("failed to read `git rev-parse`: %w", err)
+	}
+	treeID := strings.TrimSpace(string(out))
+	if err := gitoutput.CheckObjectName([]byte(treeID)); err != nil {
+		return "", fmt.Errorf("failed to read `git rev-parse`: %s", "invalid hash received")
+	}
+	return treeID, nil
+}
+
 // gitLsTree returns a map of paths to their SHA hashes starting from a list of patterns relative to a directory
 // that are present in the `git` index at a particular revision.
 func gitLsFiles(rootPath AbsolutePath, patterns []string) (map[turbopath.AnchoredUnixPath]string, error) {
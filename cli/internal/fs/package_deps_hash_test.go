@@ -296,3 +296,43 @@ func Test_memoizedGetTraversePath(t *testing.T) {
 
 	assert.Check(t, gotOne == gotTwo, "The strings are identical.")
 }
+
+func TestGetPackageTreeHash(t *testing.T) {
+	repoRoot := AbsolutePathFromUpstream(t.TempDir())
+	myPkgDir := repoRoot.Join("my-pkg")
+	committedFilePath := myPkgDir.Join("committed-file")
+	err := committedFilePath.EnsureDir()
+	assert.NilError(t, err, "EnsureDir")
+	err = committedFilePath.WriteFile([]byte("committed bytes"), 0644)
+	assert.NilError(t, err, "WriteFile")
+	otherFilePath := repoRoot.Join("other-pkg", "other-file")
+	err = otherFilePath.EnsureDir()
+	assert.NilError(t, err, "EnsureDir")
+	err = otherFilePath.WriteFile([]byte("other bytes"), 0644)
+	assert.NilError(t, err, "WriteFile")
+	requireGitCmd(t, repoRoot, "init", ".")
+	requireGitCmd(t, repoRoot, "config", "--local", "user.name", "test")
+	requireGitCmd(t, repoRoot, "config", "--local", "user.email", "test@example.com")
+	requireGitCmd(t, repoRoot, "add", ".")
+	requireGitCmd(t, repoRoot, "commit", "-m", "foo")
+
+	treeID, clean, err := GetPackageTreeHash(repoRoot, "my-pkg")
+	assert.NilError(t, err, "GetPackageTreeHash")
+	assert.Assert(t, clean, "expected a freshly committed package to be clean")
+	// The tree containing a single file named committed-file with "committed bytes"
+	assert.Equal(t, treeID, "05c51850aa053b1a907aa953f3bd9aae38638102")
+
+	// Changes outside of the package don't matter
+	err = otherFilePath.WriteFile([]byte("changed bytes"), 0644)
+	assert.NilError(t, err, "WriteFile")
+	_, clean, err = GetPackageTreeHash(repoRoot, "my-pkg")
+	assert.NilError(t, err, "GetPackageTreeHash")
+	assert.Assert(t, clean, "expected changes in another package to be ignored")
+
+	uncommittedFilePath := myPkgDir.Join("uncommitted-file")
+	err = uncommittedFilePath.WriteFile([]byte("uncommitted bytes"), 0644)
+	assert.NilError(t, err, "WriteFile")
+	_, clean, err = GetPackageTreeHash(repoRoot, "my-pkg")
+	assert.NilError(t, err, "GetPackageTreeHash")
+	assert.Assert(t, !clean, "expected an untracked file to require hashing individual files")
+}
@@ -15,6 +15,7 @@ type PackageJSON struct {
 	DevDependencies        map[string]string `json:"devDependencies,omitempty"`
 	OptionalDependencies   map[string]string `json:"optionalDependencies,omitempty"`
 	PeerDependencies       map[string]string `json:"peerDependencies,omitempty"`
+	Resolutions            map[string]string `json:"resolutions,omitempty"`
 	PackageManager         string            `json:"packageManager,omitempty"`
 	Os                     []string          `json:"os,omitempty"`
 	Workspaces             Workspaces        `json:"workspaces,omitempty"`
@@ -28,6 +29,9 @@ type PackageJSON struct {
 	LegacyTurboConfig      *TurboJSON `json:"turbo"`
 	Mu                     sync.Mutex
 	ExternalDepsHash       string
+	// ShellScripts is true for packages that aren't JavaScript packages, whose scripts
+	// turbo runs in a shell rather than through the package manager
+	ShellScripts bool
 }
 
 type Workspaces []string
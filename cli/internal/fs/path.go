@@ -72,23 +72,29 @@ func (ap AbsolutePath) Join(args ...string) AbsolutePath {
 func (ap AbsolutePath) asString() string {
 	return string(ap)
 }
+
+// osPath is the form of this path to hand to the operating system. On Windows,
+// long paths are converted to the extended-length form.
+func (ap AbsolutePath) osPath() string {
+	return longPath(ap.asString())
+}
 func (ap AbsolutePath) Dir() AbsolutePath {
 	return AbsolutePath(filepath.Dir(ap.asString()))
 }
 
 // MkdirAll implements os.MkdirAll(ap, DirPermissions|0644)
 func (ap AbsolutePath) MkdirAll() error {
-	return os.MkdirAll(ap.asString(), DirPermissions|0644)
+	return os.MkdirAll(ap.osPath(), DirPermissions|0644)
 }
 
 // Open implements os.Open(ap) for an absolute path
 func (ap AbsolutePath) Open() (*os.File, error) {
-	return os.Open(ap.asString())
+	return os.Open(ap.osPath())
 }
 
 // OpenFile implements os.OpenFile for an absolute path
 func (ap AbsolutePath) OpenFile(flags int, mode os.FileMode) (*os.File, error) {
-	return os.OpenFile(ap.asString(), flags, mode)
+	return os.OpenFile(ap.osPath(), flags, mode)
 }
 
 func (ap AbsolutePath) FileExists() bool {
@@ -97,7 +103,7 @@ func (ap AbsolutePath) FileExists() bool {
 
 // Lstat implements os.Lstat for absolute path
 func (ap AbsolutePath) Lstat() (os.FileInfo, error) {
-	return os.Lstat(ap.asString())
+	return os.Lstat(ap.osPath())
 }
 
 // DirExists returns true if this path points to a directory
@@ -114,22 +120,22 @@ func (ap AbsolutePath) ContainsPath(other AbsolutePath) (bool, error) {
 
 // ReadFile reads the contents of the specified file
 func (ap AbsolutePath) ReadFile() ([]byte, error) {
-	return ioutil.ReadFile(ap.asString())
+	return ioutil.ReadFile(ap.osPath())
 }
 
 // WriteFile writes the contents of the specified file
 func (ap AbsolutePath) WriteFile(contents []byte, mode os.FileMode) error {
-	return ioutil.WriteFile(ap.asString(), contents, mode)
+	return ioutil.WriteFile(ap.osPath(), contents, mode)
 }
 
 // EnsureDir ensures that the directory containing this file exists
 func (ap AbsolutePath) EnsureDir() error {
-	return EnsureDir(ap.asString())
+	return EnsureDir(ap.osPath())
 }
 
 // Create is the AbsolutePath wrapper for os.Create
 func (ap AbsolutePath) Create() (*os.File, error) {
-	return os.Create(ap.asString())
+	return os.Create(ap.osPath())
 }
 
 // Ext implements filepath.Ext(ap) for an absolute path
@@ -150,27 +156,27 @@ func (ap AbsolutePath) RelativePathString(path string) (string, error) {
 
 // Symlink implements os.Symlink(target, ap) for absolute path
 func (ap AbsolutePath) Symlink(target string) error {
-	return os.Symlink(target, ap.asString())
+	return os.Symlink(target, ap.osPath())
 }
 
 // Readlink implements os.Readlink(ap) for an absolute path
 func (ap AbsolutePath) Readlink() (string, error) {
-	return os.Readlink(ap.asString())
+	return os.Readlink(ap.osPath())
 }
 
 // Link implements os.Link(ap, target) for absolute path
 func (ap AbsolutePath) Link(target string) error {
-	return os.Link(ap.asString(), target)
+	return os.Link(ap.osPath(), longPath(target))
 }
 
 // Remove removes the file or (empty) directory at the given path
 func (ap AbsolutePath) Remove() error {
-	return os.Remove(ap.asString())
+	return os.Remove(ap.osPath())
 }
 
 // RemoveAll implements os.RemoveAll for absolute paths.
 func (ap AbsolutePath) RemoveAll() error {
-	return os.RemoveAll(ap.asString())
+	return os.RemoveAll(ap.osPath())
 }
 
 // Base implements filepath.Base for an absolute path
@@ -180,7 +186,7 @@ func (ap AbsolutePath) Base() string {
 
 // Rename implements os.Rename(ap, dest) for absolute paths
 func (ap AbsolutePath) Rename(dest AbsolutePath) error {
-	return os.Rename(ap.asString(), dest.asString())
+	return os.Rename(ap.osPath(), dest.osPath())
 }
 
 // GetVolumeRoot returns the root directory given an absolute path.
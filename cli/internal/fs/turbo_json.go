@@ -4,7 +4,13 @@ import (
 	"encoding/json"
 	"fmt"
 	"log"
+	"os"
+	"path/filepath"
+	"regexp"
+	"sort"
+	"strconv"
 	"strings"
+	"time"
 
 	"github.com/vercel/turborepo/cli/internal/util"
 	"github.com/yosuke-furukawa/json5/encoding/json5"
@@ -21,6 +27,20 @@ type TurboJSON struct {
 	Pipeline Pipeline
 	// Configuration options when interfacing with the remote cache
 	RemoteCacheOptions RemoteCacheOptions `json:"remoteCache,omitempty"`
+	// Configuration options for the daemon's file watching
+	WatchOptions WatchOptions `json:"watchOptions,omitempty"`
+	// Configuration options for finding the packages in the monorepo
+	Workspaces WorkspacesOptions `json:"workspaces,omitempty"`
+	// Strict turns references to tasks that the pipeline doesn't define into errors,
+	// rather than being silently ignored
+	Strict bool `json:"strict,omitempty"`
+	// Commands that turbo runs before and after a run, and before and after each task
+	Hooks Hooks `json:"hooks,omitempty"`
+	// CacheKeySuffix is mixed into the global hash, and so into every task's hash
+	CacheKeySuffix string `json:"cacheKeySuffix,omitempty"`
+	// InferPackageFromCwd makes turbo run, when run from a package directory without any
+	// filters, run in just that package
+	InferPackageFromCwd bool `json:"inferPackageFromCwd,omitempty"`
 }
 
 // ReadTurboConfig toggles between reading from package.json or turbo.json to support early adopters.
@@ -44,6 +64,11 @@ func ReadTurboConfig(rootPath AbsolutePath, rootPackageJSON *PackageJSON) (*Turb
 	if err != nil {
 		return nil, fmt.Errorf("turbo.json: %w", err)
 	}
+	if turboJSON.Strict {
+		if err := turboJSON.Pipeline.ValidateTaskReferences(); err != nil {
+			return nil, fmt.Errorf("turbo.json: %w", err)
+		}
+	}
 
 	if rootPackageJSON.LegacyTurboConfig != nil {
 		log.Println("[WARNING] Ignoring legacy \"turbo\" key in package.json, using turbo.json instead. Consider deleting the \"turbo\" key from package.json")
@@ -76,42 +101,136 @@ type RemoteCacheOptions struct {
 	Signature bool   `json:"signature,omitempty"`
 }
 
+// Hooks is a struct for deserializing .hooks of turbo.json. Each hook is a command that
+// runs in a shell at the repository root.
+type Hooks struct {
+	PreRun   string `json:"preRun,omitempty"`
+	PostRun  string `json:"postRun,omitempty"`
+	PreTask  string `json:"preTask,omitempty"`
+	PostTask string `json:"postTask,omitempty"`
+	// Hash includes the hook commands in the global hash. By default they don't
+	// affect any task's hash.
+	Hash bool `json:"hash,omitempty"`
+}
+
+// WatchOptions is a struct for deserializing .watchOptions of turbo.json
+type WatchOptions struct {
+	// Ignore is a list of repo-relative directories, or globs matching directories,
+	// that file watching should never descend into
+	Ignore []string `json:"ignore,omitempty"`
+}
+
+// WorkspacesOptions is a struct for deserializing .workspaces of turbo.json
+type WorkspacesOptions struct {
+	// Exclude is a list of repo-relative globs matching workspace directories that
+	// aren't packages, in addition to those the package manager skips
+	Exclude []string `json:"exclude,omitempty"`
+}
+
 type pipelineJSON struct {
-	Outputs    *[]string           `json:"outputs"`
-	Cache      *bool               `json:"cache,omitempty"`
-	DependsOn  []string            `json:"dependsOn,omitempty"`
-	Inputs     []string            `json:"inputs,omitempty"`
-	OutputMode util.TaskOutputMode `json:"outputMode,omitempty"`
+	Outputs                  *[]string                 `json:"outputs"`
+	Cache                    *bool                     `json:"cache,omitempty"`
+	DependsOn                []string                  `json:"dependsOn,omitempty"`
+	Inputs                   []string                  `json:"inputs,omitempty"`
+	InputsTransform          map[string]InputTransform `json:"inputsTransform,omitempty"`
+	OutputMode               util.TaskOutputMode       `json:"outputMode,omitempty"`
+	ShutdownTimeout          *float64                  `json:"shutdownTimeout,omitempty"`
+	Ports                    []interface{}             `json:"ports,omitempty"`
+	DynamicOutputs           []string                  `json:"dynamicOutputs,omitempty"`
+	Redact                   []string                  `json:"redact,omitempty"`
+	ExcludeOutputsFromInputs *bool                     `json:"excludeOutputsFromInputs,omitempty"`
+	CaptureOnFailure         []string                  `json:"captureOnFailure,omitempty"`
+	With                     []string                  `json:"with,omitempty"`
+	Timeout                  string                    `json:"timeout,omitempty"`
+	Remote                   *RemoteCacheRole          `json:"remote,omitempty"`
+	ReadyWhen                *ReadyWhen                `json:"readyWhen,omitempty"`
+}
+
+// ReadyWhen is the marker that a task emits once its dependents may start, before it
+// finishes: either a package-relative file that it writes, or a pattern that matches a
+// line it logs
+type ReadyWhen struct {
+	File string `json:"file,omitempty"`
+	Log  string `json:"log,omitempty"`
+}
+
+// RemoteCacheRole is whether a task may read from and write to the remote cache.
+// Either defaults to true. TTL is how long the remote cache is asked to keep the task's
+// artifacts.
+type RemoteCacheRole struct {
+	Read  *bool  `json:"read,omitempty"`
+	Write *bool  `json:"write,omitempty"`
+	TTL   string `json:"ttl,omitempty"`
 }
 
 // Pipeline is a struct for deserializing .pipeline in turbo.json
 type Pipeline map[string]TaskDefinition
 
-// GetTaskDefinition returns a TaskDefinition from a serialized definition in turbo.json
+// Keys returns the keys of every task definition in the pipeline
+func (pc Pipeline) Keys() []string {
+	keys := make([]string, 0, len(pc))
+	for key := range pc {
+		keys = append(keys, key)
+	}
+	return keys
+}
+
+// GetTaskDefinition returns a TaskDefinition from a serialized definition in turbo.json.
+// Keys with wildcards, like "test:*", define every task they match. See util.ResolveTaskKey
+// for which definition applies when several keys match.
 func (pc Pipeline) GetTaskDefinition(taskID string) (TaskDefinition, bool) {
 	if entry, ok := pc[taskID]; ok {
 		return entry, true
 	}
-	_, task := util.GetPackageTaskFromId(taskID)
-	entry, ok := pc[task]
-	return entry, ok
+	key, ok := util.ResolveTaskKey(pc.Keys(), taskID)
+	if !ok {
+		return TaskDefinition{}, false
+	}
+	return pc[key], true
 }
 
 // HasTask returns true if the given task is defined in the pipeline, either directly or
-// via a package task (`pkg#task`)
+// via a package task (`pkg#task`), or matches a key with a wildcard
 func (pc Pipeline) HasTask(task string) bool {
 	for key := range pc {
-		if key == task {
+		taskName := key
+		if util.IsPackageTask(key) {
+			_, taskName = util.GetPackageTaskFromId(key)
+		}
+		if util.MatchWildcard(taskName, task) {
 			return true
 		}
-		if util.IsPackageTask(key) {
-			_, taskName := util.GetPackageTaskFromId(key)
-			if taskName == task {
-				return true
+	}
+	return false
+}
+
+// ValidateTaskReferences returns an error listing every dependsOn entry that names a task
+// which no key in the pipeline defines, such as a typo like "biuld"
+func (pc Pipeline) ValidateTaskReferences() error {
+	keys := pc.Keys()
+	sort.Strings(keys)
+	var problems []string
+	for _, key := range keys {
+		taskDefinition := pc[key]
+		for _, dependency := range taskDefinition.TaskDependencies {
+			task := dependency
+			if util.IsPackageTask(dependency) {
+				_, task = util.GetPackageTaskFromId(dependency)
+			}
+			if !pc.HasTask(task) {
+				problems = append(problems, fmt.Sprintf("pipeline[%q].dependsOn: %q is not defined in the pipeline", key, dependency))
+			}
+		}
+		for _, dependency := range taskDefinition.TopologicalDependencies {
+			if !pc.HasTask(dependency) {
+				problems = append(problems, fmt.Sprintf("pipeline[%q].dependsOn: %q is not defined in the pipeline", key, topologicalPipelineDelimiter+dependency))
 			}
 		}
 	}
-	return false
+	if len(problems) > 0 {
+		return fmt.Errorf("undefined tasks in strict mode:\n  %v", strings.Join(problems, "\n  "))
+	}
+	return nil
 }
 
 // TaskDefinition is a representation of the turbo.json pipeline for further computation.
@@ -122,18 +241,169 @@ type TaskDefinition struct {
 	TopologicalDependencies []string
 	TaskDependencies        []string
 	Inputs                  []string
+	InputsTransform         map[string]InputTransform
 	OutputMode              util.TaskOutputMode
+	// ShutdownTimeout is how long the task is given to exit after each signal when
+	// turbo stops it, before moving on to the next signal
+	ShutdownTimeout time.Duration
+	// Ports that the task listens on, either port numbers or AutoPort
+	Ports []string
+	// DynamicOutputs are package-relative directories in which the task may declare
+	// more outputs while it runs
+	DynamicOutputs []string
+	// PackageInputs are input globs in other packages, keyed by package name,
+	// from inputs of the form $PKG(<package>)/<glob>
+	PackageInputs map[string][]string
+	// EnvVarExclusions are patterns from dependsOn entries of the form !$<pattern>. They
+	// remove variables that a wildcard in EnvVarDependencies matched.
+	EnvVarExclusions []string
+	// PinnedEnvVars are from dependsOn entries of the form $<name>=<value>. The task is
+	// hashed as if they had that value, and runs with them set to it.
+	PinnedEnvVars map[string]string
+	// RedactedEnvVars are names of environment variables, which may contain wildcards,
+	// whose values are redacted from the task's logs in addition to EnvVarDependencies
+	RedactedEnvVars []string
+	// ExcludeOutputsFromInputs leaves the outputs of every task in the package out of
+	// this task's inputs, when the task doesn't declare its own inputs
+	ExcludeOutputsFromInputs bool
+	// CaptureOnFailure are package-relative globs for files that are kept, without
+	// caching them, when the task fails
+	CaptureOnFailure []string
+	// Mutexes are names, from with, of mutexes that the task holds while it runs. Tasks
+	// that share a mutex never run at the same time, even in different packages.
+	Mutexes []string
+	// Timeout is how long the task may run before turbo stops it and fails it. Zero
+	// means no limit.
+	Timeout time.Duration
+	// OutputsDeclared is whether turbo.json lists the task's outputs, rather than
+	// leaving them to the defaults
+	OutputsDeclared bool
+	// SkipRemoteReads and SkipRemoteWrites keep the task from restoring outputs from,
+	// or saving them to, the remote cache. The local cache is still used.
+	SkipRemoteReads  bool
+	SkipRemoteWrites bool
+	// RemoteCacheTTL is sent with the task's artifacts as a hint of how long the remote
+	// cache should keep them. Zero leaves it to the remote cache.
+	RemoteCacheTTL time.Duration
+	// Variant is the condition of the variant that was selected for this run, such as
+	// "CI=true", or empty if the base definition is used. It is part of the task's hash.
+	Variant string
+	// ReadyFile and ReadyLogPattern, from readyWhen, are a package-relative file that the
+	// task writes, or a regular expression matching a line that it logs, once the tasks
+	// that depend on it may start. At most one is set.
+	ReadyFile       string
+	ReadyLogPattern string
 }
 
+// AutoPort in a task's ports asks turbo to pick a free port for the task
+const AutoPort = "auto"
+
+// InputTransform normalizes the contents of a task's input files before they are hashed
+type InputTransform string
+
+const (
+	// InputTransformIgnore leaves matching files out of the hash entirely
+	InputTransformIgnore InputTransform = "ignore"
+	// InputTransformStripWhitespace hashes matching files without trailing whitespace,
+	// blank lines, or differences in line endings
+	InputTransformStripWhitespace InputTransform = "strip-whitespace"
+	// InputTransformStripComments hashes matching files without `//` and `/* */` comments,
+	// and then strips whitespace as InputTransformStripWhitespace does
+	InputTransformStripComments InputTransform = "strip-comments"
+)
+
+var _inputTransforms = []InputTransform{InputTransformIgnore, InputTransformStripWhitespace, InputTransformStripComments}
+
 const (
 	envPipelineDelimiter         = "$"
+	envExclusionDelimiter        = "!$"
 	topologicalPipelineDelimiter = "^"
 )
 
 var defaultOutputs = []string{"dist/**/*", "build/**/*"}
 
+const defaultShutdownTimeout = 10 * time.Second
+
+// variantCondition is the "when" of a task definition variant. The variant applies when
+// every listed environment variable has the given value.
+type variantCondition struct {
+	Env map[string]string `json:"env"`
+}
+
+func (w *variantCondition) matches() bool {
+	for name, value := range w.Env {
+		if os.Getenv(name) != value {
+			return false
+		}
+	}
+	return true
+}
+
+// String describes the condition, e.g. "CI=true,NODE_ENV=production"
+func (w *variantCondition) String() string {
+	pairs := make([]string, 0, len(w.Env))
+	for name, value := range w.Env {
+		pairs = append(pairs, fmt.Sprintf("%v=%v", name, value))
+	}
+	sort.Strings(pairs)
+	return strings.Join(pairs, ",")
+}
+
+// resolveVariant applies the first of a task definition's variants whose condition holds
+// in the current environment. The variant's fields replace the same fields of the base
+// definition. It returns the resulting definition, and the condition of the variant that
+// was applied, if any.
+func resolveVariant(data []byte) ([]byte, string, error) {
+	var fields map[string]json.RawMessage
+	if err := json.Unmarshal(data, &fields); err != nil {
+		return nil, "", err
+	}
+	rawVariants, ok := fields["variants"]
+	if !ok {
+		return data, "", nil
+	}
+	delete(fields, "variants")
+	var variants []map[string]json.RawMessage
+	if err := json.Unmarshal(rawVariants, &variants); err != nil {
+		return nil, "", fmt.Errorf("variants must be a list of task definitions: %w", err)
+	}
+	selected := ""
+	for i, variant := range variants {
+		rawWhen, ok := variant["when"]
+		if !ok {
+			return nil, "", fmt.Errorf("variants[%v] needs a \"when\" condition", i)
+		}
+		when := &variantCondition{}
+		if err := json.Unmarshal(rawWhen, when); err != nil || len(when.Env) == 0 {
+			return nil, "", fmt.Errorf("variants[%v].when must list environment variables, like {\"env\": {\"CI\": \"true\"}}", i)
+		}
+		if _, ok := variant["variants"]; ok {
+			return nil, "", fmt.Errorf("variants[%v] can't have variants of its own", i)
+		}
+		if selected != "" || !when.matches() {
+			continue
+		}
+		for key, value := range variant {
+			if key != "when" {
+				fields[key] = value
+			}
+		}
+		selected = when.String()
+	}
+	merged, err := json.Marshal(fields)
+	if err != nil {
+		return nil, "", err
+	}
+	return merged, selected, nil
+}
+
 // UnmarshalJSON deserializes JSON into a TaskDefinition
 func (c *TaskDefinition) UnmarshalJSON(data []byte) error {
+	data, variant, err := resolveVariant(data)
+	if err != nil {
+		return err
+	}
+	c.Variant = variant
 	rawPipeline := &pipelineJSON{}
 	if err := json.Unmarshal(data, &rawPipeline); err != nil {
 		return err
@@ -145,6 +415,7 @@ func (c *TaskDefinition) UnmarshalJSON(data []byte) error {
 	// always unmarshal into an empty array which is not what we want.
 	if rawPipeline.Outputs != nil {
 		c.Outputs = *rawPipeline.Outputs
+		c.OutputsDeclared = true
 	} else {
 		c.Outputs = defaultOutputs
 	}
@@ -157,15 +428,149 @@ func (c *TaskDefinition) UnmarshalJSON(data []byte) error {
 	c.TopologicalDependencies = []string{}
 	c.TaskDependencies = []string{}
 	for _, dependency := range rawPipeline.DependsOn {
-		if strings.HasPrefix(dependency, envPipelineDelimiter) {
-			c.EnvVarDependencies = append(c.EnvVarDependencies, strings.TrimPrefix(dependency, envPipelineDelimiter))
+		if strings.HasPrefix(dependency, envExclusionDelimiter) {
+			c.EnvVarExclusions = append(c.EnvVarExclusions, strings.TrimPrefix(dependency, envExclusionDelimiter))
+		} else if strings.HasPrefix(dependency, envPipelineDelimiter) {
+			envVar := strings.TrimPrefix(dependency, envPipelineDelimiter)
+			if name, value, ok := util.ParsePinnedEnvVar(envVar); ok {
+				if strings.Contains(name, util.TaskWildcard) {
+					return fmt.Errorf("invalid dependsOn %q: environment variables with a wildcard can't be pinned to a value", dependency)
+				}
+				if c.PinnedEnvVars == nil {
+					c.PinnedEnvVars = make(map[string]string)
+				}
+				c.PinnedEnvVars[name] = value
+			} else {
+				c.EnvVarDependencies = append(c.EnvVarDependencies, envVar)
+			}
 		} else if strings.HasPrefix(dependency, topologicalPipelineDelimiter) {
 			c.TopologicalDependencies = append(c.TopologicalDependencies, strings.TrimPrefix(dependency, topologicalPipelineDelimiter))
 		} else {
 			c.TaskDependencies = append(c.TaskDependencies, dependency)
 		}
 	}
-	c.Inputs = rawPipeline.Inputs
+	for _, input := range rawPipeline.Inputs {
+		if pkg, glob, ok := parsePackageInput(input); ok {
+			if c.PackageInputs == nil {
+				c.PackageInputs = make(map[string][]string)
+			}
+			c.PackageInputs[pkg] = append(c.PackageInputs[pkg], glob)
+		} else if strings.HasPrefix(input, packageInputPrefix) {
+			return fmt.Errorf("invalid input %q: inputs in other packages must look like %v<package>)/<glob>", input, packageInputPrefix)
+		} else {
+			c.Inputs = append(c.Inputs, input)
+		}
+	}
+	for glob, transform := range rawPipeline.InputsTransform {
+		if !isInputTransform(transform) {
+			names := make([]string, len(_inputTransforms))
+			for i, known := range _inputTransforms {
+				names[i] = string(known)
+			}
+			return fmt.Errorf("inputsTransform for %q must be one of \"%v\", got %q", glob, strings.Join(names, "|"), transform)
+		}
+	}
+	c.InputsTransform = rawPipeline.InputsTransform
 	c.OutputMode = rawPipeline.OutputMode
+	c.ShutdownTimeout = defaultShutdownTimeout
+	if rawPipeline.ShutdownTimeout != nil {
+		if *rawPipeline.ShutdownTimeout < 0 {
+			return fmt.Errorf("shutdownTimeout must be a number of seconds that is not negative, got %v", *rawPipeline.ShutdownTimeout)
+		}
+		c.ShutdownTimeout = time.Duration(*rawPipeline.ShutdownTimeout * float64(time.Second))
+	}
+	for _, port := range rawPipeline.Ports {
+		switch port := port.(type) {
+		case string:
+			if port == AutoPort {
+				c.Ports = append(c.Ports, port)
+				continue
+			}
+		case float64:
+			if port == float64(int(port)) && port >= 1 && port <= 65535 {
+				c.Ports = append(c.Ports, strconv.Itoa(int(port)))
+				continue
+			}
+		}
+		return fmt.Errorf("ports must be \"%v\" or a port number, got %v", AutoPort, port)
+	}
+	for _, dir := range rawPipeline.DynamicOutputs {
+		cleaned := filepath.Clean(dir)
+		if filepath.IsAbs(dir) || cleaned == ".." || strings.HasPrefix(cleaned, ".."+string(filepath.Separator)) {
+			return fmt.Errorf("dynamicOutputs must be directories inside the package, got %q", dir)
+		}
+	}
+	c.DynamicOutputs = rawPipeline.DynamicOutputs
+	c.RedactedEnvVars = rawPipeline.Redact
+	c.ExcludeOutputsFromInputs = rawPipeline.ExcludeOutputsFromInputs == nil || *rawPipeline.ExcludeOutputsFromInputs
+	c.CaptureOnFailure = rawPipeline.CaptureOnFailure
+	for _, name := range rawPipeline.With {
+		if name == "" {
+			return fmt.Errorf("with must list names of mutexes, got an empty name")
+		}
+	}
+	c.Mutexes = rawPipeline.With
+	if rawPipeline.Timeout != "" {
+		timeout, err := time.ParseDuration(rawPipeline.Timeout)
+		if err != nil || timeout < 0 {
+			return fmt.Errorf("timeout must be a duration like \"10m\" or \"90s\", got %q", rawPipeline.Timeout)
+		}
+		c.Timeout = timeout
+	}
+	if rawPipeline.Remote != nil {
+		c.SkipRemoteReads = rawPipeline.Remote.Read != nil && !*rawPipeline.Remote.Read
+		c.SkipRemoteWrites = rawPipeline.Remote.Write != nil && !*rawPipeline.Remote.Write
+		if rawPipeline.Remote.TTL != "" {
+			ttl, err := time.ParseDuration(rawPipeline.Remote.TTL)
+			if err != nil || ttl < time.Second {
+				return fmt.Errorf("remote.ttl must be a duration of at least a second, like \"168h\", got %q", rawPipeline.Remote.TTL)
+			}
+			c.RemoteCacheTTL = ttl
+		}
+	}
+	if rawPipeline.ReadyWhen != nil {
+		readyWhen := rawPipeline.ReadyWhen
+		if (readyWhen.File == "") == (readyWhen.Log == "") {
+			return fmt.Errorf("readyWhen must have either a file or a log pattern")
+		}
+		if readyWhen.File != "" {
+			cleaned := filepath.Clean(readyWhen.File)
+			if filepath.IsAbs(readyWhen.File) || cleaned == "." || cleaned == ".." || strings.HasPrefix(cleaned, ".."+string(filepath.Separator)) {
+				return fmt.Errorf("readyWhen.file must be a file inside the package, got %q", readyWhen.File)
+			}
+			c.ReadyFile = cleaned
+		} else {
+			if _, err := regexp.Compile(readyWhen.Log); err != nil {
+				return fmt.Errorf("readyWhen.log must be a regular expression: %w", err)
+			}
+			c.ReadyLogPattern = readyWhen.Log
+		}
+	}
 	return nil
 }
+
+// packageInputPrefix starts an input in another package, e.g. $PKG(ui)/src/**
+const packageInputPrefix = "$PKG("
+
+// parsePackageInput splits an input of the form $PKG(<package>)/<glob> into the package
+// and the glob, relative to that package
+func parsePackageInput(input string) (string, string, bool) {
+	if !strings.HasPrefix(input, packageInputPrefix) {
+		return "", "", false
+	}
+	rest := strings.TrimPrefix(input, packageInputPrefix)
+	end := strings.Index(rest, ")/")
+	if end < 1 || end+2 == len(rest) {
+		return "", "", false
+	}
+	return rest[:end], rest[end+2:], true
+}
+
+func isInputTransform(transform InputTransform) bool {
+	for _, known := range _inputTransforms {
+		if transform == known {
+			return true
+		}
+	}
+	return false
+}
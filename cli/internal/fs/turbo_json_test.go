@@ -2,8 +2,10 @@ package fs
 
 import (
 	"os"
+	"path/filepath"
 	"strings"
 	"testing"
+	"time"
 
 	"github.com/stretchr/testify/assert"
 	"github.com/vercel/turborepo/cli/internal/util"
@@ -26,20 +28,33 @@ func Test_ReadTurboJSON(t *testing.T) {
 
 	pipelineExpected := map[string]TaskDefinition{
 		"build": {
-			Outputs:                 []string{"dist/**", ".next/**"},
-			TopologicalDependencies: []string{"build"},
-			EnvVarDependencies:      []string{},
-			TaskDependencies:        []string{},
-			ShouldCache:             true,
-			OutputMode:              util.NewTaskOutput,
+			Outputs:                  []string{"dist/**", ".next/**"},
+			TopologicalDependencies:  []string{"build"},
+			EnvVarDependencies:       []string{},
+			TaskDependencies:         []string{},
+			ShouldCache:              true,
+			OutputMode:               util.NewTaskOutput,
+			ShutdownTimeout:          defaultShutdownTimeout,
+			ExcludeOutputsFromInputs: true,
+			OutputsDeclared:          true,
+			SkipRemoteWrites:         true,
 		},
 		"lint": {
-			Outputs:                 []string{},
-			TopologicalDependencies: []string{},
-			EnvVarDependencies:      []string{"MY_VAR"},
-			TaskDependencies:        []string{},
-			ShouldCache:             true,
-			OutputMode:              util.NewTaskOutput,
+			Outputs:                  []string{},
+			TopologicalDependencies:  []string{},
+			EnvVarDependencies:       []string{"MY_VAR", "MYAPP_*"},
+			TaskDependencies:         []string{},
+			ShouldCache:              true,
+			OutputMode:               util.NewTaskOutput,
+			ShutdownTimeout:          defaultShutdownTimeout,
+			EnvVarExclusions:         []string{"MYAPP_SECRET_*"},
+			PinnedEnvVars:            map[string]string{"NODE_ENV": "production"},
+			ExcludeOutputsFromInputs: true,
+			CaptureOnFailure:         []string{"**/junit.xml"},
+			Mutexes:                  []string{"db"},
+			Timeout:                  10 * time.Minute,
+			OutputsDeclared:          true,
+			RemoteCacheTTL:           168 * time.Hour,
 		},
 		"dev": {
 			Outputs:                 defaultOutputs,
@@ -48,15 +63,19 @@ func Test_ReadTurboJSON(t *testing.T) {
 			TaskDependencies:        []string{},
 			ShouldCache:             false,
 			OutputMode:              util.FullTaskOutput,
+			ShutdownTimeout:         2500 * time.Millisecond,
 		},
 		"publish": {
-			Outputs:                 []string{"dist/**"},
-			EnvVarDependencies:      []string{},
-			TopologicalDependencies: []string{"publish"},
-			TaskDependencies:        []string{"build", "admin#lint"},
-			ShouldCache:             false,
-			Inputs:                  []string{"build/**/*"},
-			OutputMode:              util.FullTaskOutput,
+			Outputs:                  []string{"dist/**"},
+			EnvVarDependencies:       []string{},
+			TopologicalDependencies:  []string{"publish"},
+			TaskDependencies:         []string{"build", "admin#lint"},
+			ShouldCache:              false,
+			Inputs:                   []string{"build/**/*"},
+			OutputMode:               util.FullTaskOutput,
+			ShutdownTimeout:          defaultShutdownTimeout,
+			ExcludeOutputsFromInputs: true,
+			OutputsDeclared:          true,
 		},
 	}
 
@@ -80,4 +99,92 @@ func Test_ReadTurboJSON(t *testing.T) {
 		assert.EqualValuesf(t, expectedTaskDefinition, actualTaskDefinition, "task definition mismatch for %v", taskName)
 	}
 	assert.EqualValues(t, remoteCacheOptionsExpected, turboJSON.RemoteCacheOptions)
+	assert.EqualValues(t, []string{"bazel-out", "**/target"}, turboJSON.WatchOptions.Ignore)
+}
+
+func Test_PackageInputs(t *testing.T) {
+	var taskDefinition TaskDefinition
+	err := taskDefinition.UnmarshalJSON([]byte(`{"inputs": ["src/**", "$PKG(@repo/ui)/src/**", "$PKG(@repo/ui)/package.json", "$PKG(config)/tsconfig.json"]}`))
+	assert.NoError(t, err)
+	assert.Equal(t, []string{"src/**"}, taskDefinition.Inputs)
+	assert.Equal(t, map[string][]string{
+		"@repo/ui": {"src/**", "package.json"},
+		"config":   {"tsconfig.json"},
+	}, taskDefinition.PackageInputs)
+
+	err = taskDefinition.UnmarshalJSON([]byte(`{"inputs": ["$PKG(ui)"]}`))
+	assert.EqualError(t, err, `invalid input "$PKG(ui)": inputs in other packages must look like $PKG(<package>)/<glob>`)
+}
+
+func Test_TaskVariants(t *testing.T) {
+	definition := []byte(`{
+		"outputs": ["dist/**"],
+		"dependsOn": ["^build"],
+		"variants": [
+			{"when": {"env": {"CI": "true", "DEPLOY": "1"}}, "outputs": ["dist/**", "stats.json"]},
+			{"when": {"env": {"CI": "true"}}, "cache": false}
+		]
+	}`)
+	t.Setenv("CI", "")
+	t.Setenv("DEPLOY", "")
+	var taskDefinition TaskDefinition
+	assert.NoError(t, taskDefinition.UnmarshalJSON(definition))
+	assert.Equal(t, "", taskDefinition.Variant)
+	assert.Equal(t, []string{"dist/**"}, taskDefinition.Outputs)
+	assert.True(t, taskDefinition.ShouldCache)
+
+	t.Setenv("CI", "true")
+	taskDefinition = TaskDefinition{}
+	assert.NoError(t, taskDefinition.UnmarshalJSON(definition))
+	assert.Equal(t, "CI=true", taskDefinition.Variant)
+	assert.Equal(t, []string{"dist/**"}, taskDefinition.Outputs)
+	assert.Equal(t, []string{"build"}, taskDefinition.TopologicalDependencies)
+	assert.False(t, taskDefinition.ShouldCache)
+
+	t.Setenv("DEPLOY", "1")
+	taskDefinition = TaskDefinition{}
+	assert.NoError(t, taskDefinition.UnmarshalJSON(definition))
+	assert.Equal(t, "CI=true,DEPLOY=1", taskDefinition.Variant, "the first matching variant applies")
+	assert.Equal(t, []string{"dist/**", "stats.json"}, taskDefinition.Outputs)
+	assert.True(t, taskDefinition.ShouldCache)
+
+	err := taskDefinition.UnmarshalJSON([]byte(`{"variants": [{"cache": false}]}`))
+	assert.EqualError(t, err, `variants[0] needs a "when" condition`)
+}
+
+func Test_ReadyWhen(t *testing.T) {
+	var taskDefinition TaskDefinition
+	assert.NoError(t, taskDefinition.UnmarshalJSON([]byte(`{"readyWhen": {"file": "./dist/types/index.d.ts"}}`)))
+	assert.Equal(t, filepath.Join("dist", "types", "index.d.ts"), taskDefinition.ReadyFile)
+	assert.Equal(t, "", taskDefinition.ReadyLogPattern)
+
+	taskDefinition = TaskDefinition{}
+	assert.NoError(t, taskDefinition.UnmarshalJSON([]byte(`{"readyWhen": {"log": "^Declarations emitted"}}`)))
+	assert.Equal(t, "", taskDefinition.ReadyFile)
+	assert.Equal(t, "^Declarations emitted", taskDefinition.ReadyLogPattern)
+
+	err := taskDefinition.UnmarshalJSON([]byte(`{"readyWhen": {"file": "types.d.ts", "log": "done"}}`))
+	assert.EqualError(t, err, "readyWhen must have either a file or a log pattern")
+	err = taskDefinition.UnmarshalJSON([]byte(`{"readyWhen": {"file": "../ui/types.d.ts"}}`))
+	assert.EqualError(t, err, `readyWhen.file must be a file inside the package, got "../ui/types.d.ts"`)
+	err = taskDefinition.UnmarshalJSON([]byte(`{"readyWhen": {"log": "("}}`))
+	assert.ErrorContains(t, err, "readyWhen.log must be a regular expression")
+}
+
+func Test_ValidateTaskReferences(t *testing.T) {
+	pipeline := Pipeline{
+		"build":      {TopologicalDependencies: []string{"build"}, TaskDependencies: []string{"codegen"}},
+		"codegen-*":  {TopologicalDependencies: []string{}, TaskDependencies: []string{}},
+		"web#deploy": {TopologicalDependencies: []string{}, TaskDependencies: []string{"build", "ui#build"}},
+		"lint":       {TopologicalDependencies: []string{"biuld"}, TaskDependencies: []string{"web#tset"}},
+	}
+	err := pipeline.ValidateTaskReferences()
+	assert.EqualError(t, err, `undefined tasks in strict mode:
+  pipeline["build"].dependsOn: "codegen" is not defined in the pipeline
+  pipeline["lint"].dependsOn: "web#tset" is not defined in the pipeline
+  pipeline["lint"].dependsOn: "^biuld" is not defined in the pipeline`)
+
+	delete(pipeline, "lint")
+	pipeline["build"] = TaskDefinition{TopologicalDependencies: []string{"build"}, TaskDependencies: []string{"codegen-api"}}
+	assert.NoError(t, pipeline.ValidateTaskReferences())
 }
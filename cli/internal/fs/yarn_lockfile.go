@@ -12,3 +12,55 @@ type LockfileEntry struct {
 }
 
 type YarnLockfile map[string]*LockfileEntry
+
+// Subtree returns the entries that the given dependencies resolve to, along with the
+// entries for everything those depend on, keyed as they are in the lockfile
+func (l YarnLockfile) Subtree(deps map[string]string, resolutions map[string]string) YarnLockfile {
+	subtree := make(YarnLockfile)
+	var visit func(deps map[string]string)
+	visit = func(deps map[string]string) {
+		for name, version := range deps {
+			key, entry, ok := l.lookup(name, version)
+			if !ok {
+				// Yarn 2+ records a dependency that is overridden by a root "resolutions"
+				// entry under the overriding range, rather than the range that was requested.
+				resolution, hasResolution := ResolutionFor(resolutions, name)
+				if !hasResolution {
+					continue
+				}
+				if key, entry, ok = l.lookup(name, resolution); !ok {
+					continue
+				}
+			}
+			if _, seen := subtree[key]; seen {
+				continue
+			}
+			subtree[key] = entry
+			visit(entry.Dependencies)
+			visit(entry.OptionalDependencies)
+		}
+	}
+	visit(deps)
+	return subtree
+}
+
+// lookup finds the entry for a dependency range, which yarn 2+ prefixes with "npm:"
+func (l YarnLockfile) lookup(name string, version string) (string, *LockfileEntry, bool) {
+	for _, key := range []string{name + "@" + version, name + "@npm:" + version} {
+		if entry, ok := l[key]; ok {
+			return key, entry, true
+		}
+	}
+	return "", nil, false
+}
+
+// ResolutionFor returns the range from the root "resolutions" field that applies to
+// every instance of the given dependency, if there is one. Resolutions that only apply
+// beneath a specific parent, such as "parent/dep", are not supported.
+func ResolutionFor(resolutions map[string]string, depName string) (string, bool) {
+	if resolution, ok := resolutions[depName]; ok {
+		return resolution, true
+	}
+	resolution, ok := resolutions["**/"+depName]
+	return resolution, ok
+}
@@ -2,12 +2,14 @@ package globby
 
 import (
 	"fmt"
+	"path"
 	"path/filepath"
 	"strings"
 
 	iofs "io/fs"
 
 	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
 
 	"github.com/vercel/turborepo/cli/internal/doublestar"
 	"github.com/vercel/turborepo/cli/internal/util"
@@ -20,6 +22,65 @@ func GlobFiles(basePath string, includePatterns []string, excludePatterns []stri
 	return globFilesFs(fsys, fsysRoot, basePath, includePatterns, excludePatterns)
 }
 
+// MatchFiles partitions a list of files, such as the output of `git ls-files`, into the
+// files that match any of the include patterns and none of the exclude patterns, and the
+// rest, without touching the filesystem. Files and patterns are anchored at the same root.
+// As in GlobFiles, an exclude pattern also excludes everything inside the folders it matches.
+func MatchFiles(files []turbopath.AnchoredSystemPath, includePatterns []string, excludePatterns []string) ([]turbopath.AnchoredSystemPath, []turbopath.AnchoredSystemPath, error) {
+	includes, err := anchoredPatterns(includePatterns)
+	if err != nil {
+		return nil, nil, err
+	}
+	excludes, err := anchoredPatterns(excludePatterns)
+	if err != nil {
+		return nil, nil, err
+	}
+	for i, exclude := range excludes {
+		excludes[i] = path.Join(exclude, "**")
+	}
+
+	var matched []turbopath.AnchoredSystemPath
+	var unmatched []turbopath.AnchoredSystemPath
+	for _, file := range files {
+		unixPath := file.ToUnixPath().ToString()
+		isIncluded := matchesAny(includes, unixPath)
+		if isIncluded && !matchesAny(excludes, unixPath) {
+			matched = append(matched, file)
+		} else {
+			unmatched = append(unmatched, file)
+		}
+	}
+	return matched, unmatched, nil
+}
+
+// anchoredPatterns validates patterns that are relative to the root of the files being
+// matched, and returns them cleaned and with unix separators, ready for doublestar.Match
+func anchoredPatterns(patterns []string) ([]string, error) {
+	processed := make([]string, len(patterns))
+	for i, pattern := range patterns {
+		cleaned := path.Clean(filepath.ToSlash(pattern))
+		if cleaned == ".." || strings.HasPrefix(cleaned, "../") || path.IsAbs(cleaned) {
+			return nil, fmt.Errorf("the path you are attempting to specify (%s) is outside of the root", pattern)
+		}
+		if !doublestar.ValidatePattern(cleaned) {
+			return nil, fmt.Errorf("invalid glob %v: %w", pattern, doublestar.ErrBadPattern)
+		}
+		processed[i] = cleaned
+	}
+	return processed, nil
+}
+
+// matchesAny reports whether the unix path matches any of the validated patterns
+func matchesAny(patterns []string, unixPath string) bool {
+	for _, pattern := range patterns {
+		// Patterns are validated up front, so Match can't fail
+		if matches, _ := doublestar.Match(pattern, unixPath); matches {
+			return true
+		}
+	}
+	return false
+}
+
 // checkRelativePath ensures that the the requested file path is a child of `from`.
 func checkRelativePath(from string, to string) error {
 	relativePath, err := filepath.Rel(from, to)
@@ -8,6 +8,8 @@ import (
 	"testing"
 
 	"testing/fstest"
+
+	"github.com/vercel/turborepo/cli/internal/turbopath"
 )
 
 // setup prepares the test file system contents and returns the file system.
@@ -536,3 +538,68 @@ func TestGlobFilesFs(t *testing.T) {
 		})
 	}
 }
+
+func TestMatchFiles(t *testing.T) {
+	files := []turbopath.AnchoredSystemPath{
+		turbopath.AnchoredSystemPath(filepath.FromSlash("apps/web/dist/index.js")),
+		turbopath.AnchoredSystemPath(filepath.FromSlash("apps/web/dist/cache/chunk.js")),
+		turbopath.AnchoredSystemPath(filepath.FromSlash("apps/web/src/dist/helpers.ts")),
+		turbopath.AnchoredSystemPath(filepath.FromSlash("apps/web/package.json")),
+	}
+	tests := []struct {
+		name            string
+		includePatterns []string
+		excludePatterns []string
+		wantMatched     []string
+		wantErr         bool
+	}{
+		{
+			name:            "anchored at the root",
+			includePatterns: []string{"apps/web/dist/**"},
+			wantMatched:     []string{"apps/web/dist/cache/chunk.js", "apps/web/dist/index.js"},
+		},
+		{
+			name:            "excludes whole folders",
+			includePatterns: []string{"apps/web/**/*.js"},
+			excludePatterns: []string{"apps/web/dist/cache"},
+			wantMatched:     []string{"apps/web/dist/index.js"},
+		},
+		{
+			name:            "cleans patterns",
+			includePatterns: []string{"./apps/web/package.json", "apps/web/src/../src/**"},
+			wantMatched:     []string{"apps/web/package.json", "apps/web/src/dist/helpers.ts"},
+		},
+		{
+			name:            "rejects patterns outside of the root",
+			includePatterns: []string{"../other/**"},
+			wantErr:         true,
+		},
+		{
+			name:            "rejects invalid patterns",
+			includePatterns: []string{"apps/[web/**"},
+			wantErr:         true,
+		},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			matched, unmatched, err := MatchFiles(files, tt.includePatterns, tt.excludePatterns)
+			if (err != nil) != tt.wantErr {
+				t.Fatalf("MatchFiles() error = %v, wantErr %v", err, tt.wantErr)
+			}
+			if tt.wantErr {
+				return
+			}
+			if len(matched)+len(unmatched) != len(files) {
+				t.Errorf("MatchFiles() returned %v matched and %v unmatched files, want %v in total", len(matched), len(unmatched), len(files))
+			}
+			got := make([]string, len(matched))
+			for index, path := range matched {
+				got[index] = path.ToUnixPath().ToString()
+			}
+			sort.Strings(got)
+			if !reflect.DeepEqual(got, tt.wantMatched) {
+				t.Errorf("MatchFiles() = %v, want %v", got, tt.wantMatched)
+			}
+		})
+	}
+}
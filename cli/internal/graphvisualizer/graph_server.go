@@ -0,0 +1,85 @@
+package graphvisualizer
+
+import (
+	"context"
+	_ "embed"
+	"encoding/json"
+	"fmt"
+	"net"
+	"net/http"
+	"os"
+	"os/signal"
+	"syscall"
+
+	"github.com/vercel/turborepo/cli/internal/ui"
+	"github.com/vercel/turborepo/cli/internal/util/browser"
+)
+
+//go:embed graph.html
+var _graphPage []byte
+
+// GraphNode is a task in the graph served by Serve
+type GraphNode struct {
+	TaskID  string `json:"taskId"`
+	Task    string `json:"task"`
+	Package string `json:"package"`
+	Hash    string `json:"hash"`
+	Command string `json:"command"`
+	// Cached is true if the outputs for this hash are in the local cache, meaning
+	// that the task would be restored rather than run
+	Cached bool `json:"cached"`
+	// Dependencies are the tasks this task directly depends on
+	Dependencies []string `json:"dependencies"`
+}
+
+// graphHandler serves the graph page, and the graph itself as JSON
+func graphHandler(nodes []GraphNode) http.Handler {
+	mux := http.NewServeMux()
+	mux.HandleFunc("/", func(w http.ResponseWriter, req *http.Request) {
+		if req.URL.Path != "/" {
+			http.NotFound(w, req)
+			return
+		}
+		w.Header().Set("Content-Type", "text/html; charset=utf-8")
+		_, _ = w.Write(_graphPage)
+	})
+	mux.HandleFunc("/graph.json", func(w http.ResponseWriter, req *http.Request) {
+		w.Header().Set("Content-Type", "application/json")
+		if err := json.NewEncoder(w).Encode(nodes); err != nil {
+			http.Error(w, err.Error(), http.StatusInternalServerError)
+		}
+	})
+	return mux
+}
+
+// Serve serves an interactive page for exploring the given task graph on addr until
+// turbo is interrupted. Use port 0 to pick any available port.
+func (g *GraphVisualizer) Serve(addr string, nodes []GraphNode) error {
+	listener, err := net.Listen("tcp", addr)
+	if err != nil {
+		return fmt.Errorf("failed to serve task graph on %v: %w", addr, err)
+	}
+	server := &http.Server{Handler: graphHandler(nodes)}
+
+	url := fmt.Sprintf("http://%v", listener.Addr().String())
+	g.ui.Output("")
+	g.ui.Output(fmt.Sprintf("✔ Serving task graph at %s", ui.Bold(url)))
+	g.ui.Output(ui.Dim("Press Ctrl+C to stop"))
+	if ui.IsTTY {
+		if err := browser.OpenBrowser(url); err != nil {
+			g.ui.Warn(fmt.Sprintf("failed to open browser. Please navigate to %v", url))
+		}
+	}
+
+	signals := make(chan os.Signal, 1)
+	signal.Notify(signals, os.Interrupt, syscall.SIGTERM)
+	defer signal.Stop(signals)
+	go func() {
+		<-signals
+		_ = server.Shutdown(context.Background())
+	}()
+	if err := server.Serve(listener); err != http.ErrServerClosed {
+		return err
+	}
+	return nil
+}
@@ -0,0 +1,40 @@
+package graphvisualizer
+
+import (
+	"encoding/json"
+	"io/ioutil"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+
+	"gotest.tools/v3/assert"
+)
+
+func TestGraphHandler(t *testing.T) {
+	nodes := []GraphNode{
+		{TaskID: "web#build", Task: "build", Package: "web", Hash: "abc", Cached: true, Dependencies: []string{"ui#build"}},
+		{TaskID: "ui#build", Task: "build", Package: "ui", Hash: "def", Dependencies: []string{}},
+	}
+	server := httptest.NewServer(graphHandler(nodes))
+	defer server.Close()
+
+	resp, err := http.Get(server.URL + "/graph.json")
+	assert.NilError(t, err, "GET /graph.json")
+	defer func() { _ = resp.Body.Close() }()
+	var served []GraphNode
+	assert.NilError(t, json.NewDecoder(resp.Body).Decode(&served), "Decode")
+	assert.DeepEqual(t, served, nodes)
+
+	page, err := http.Get(server.URL + "/")
+	assert.NilError(t, err, "GET /")
+	defer func() { _ = page.Body.Close() }()
+	body, err := ioutil.ReadAll(page.Body)
+	assert.NilError(t, err, "ReadAll")
+	assert.Assert(t, strings.Contains(string(body), "/graph.json"))
+
+	missing, err := http.Get(server.URL + "/missing")
+	assert.NilError(t, err, "GET /missing")
+	_ = missing.Body.Close()
+	assert.Equal(t, missing.StatusCode, http.StatusNotFound)
+}
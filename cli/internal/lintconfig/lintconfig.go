@@ -0,0 +1,586 @@
+package lintconfig
+
+import (
+	"encoding/json"
+	"fmt"
+	"io/ioutil"
+	"os/exec"
+	"path"
+	"path/filepath"
+	"regexp"
+	"sort"
+	"strconv"
+	"strings"
+
+	"github.com/fatih/color"
+	"github.com/hashicorp/go-hclog"
+	"github.com/mitchellh/cli"
+	"github.com/pkg/errors"
+	"github.com/spf13/cobra"
+	"github.com/spf13/pflag"
+	"github.com/vercel/turborepo/cli/internal/cache"
+	"github.com/vercel/turborepo/cli/internal/config"
+	"github.com/vercel/turborepo/cli/internal/context"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// LintConfigCommand is a Command implementation that checks turbo.json for common mistakes
+type LintConfigCommand struct {
+	Config *config.Config
+	UI     *cli.ColoredUi
+}
+
+// Synopsis of lint-config command
+func (c *LintConfigCommand) Synopsis() string {
+	return getCmd(c.Config, c.UI).Short
+}
+
+// Help returns information about the `lint-config` command
+func (c *LintConfigCommand) Help() string {
+	cmd := getCmd(c.Config, c.UI)
+	return util.HelpForCobraCmd(cmd)
+}
+
+// Run implements cli.Command.Run
+func (c *LintConfigCommand) Run(args []string) int {
+	cmd := getCmd(c.Config, c.UI)
+	cmd.SetArgs(args)
+	if err := cmd.Execute(); err != nil {
+		return 1
+	}
+	return 0
+}
+
+type opts struct {
+	json bool
+	fix  bool
+}
+
+func addFlags(opts *opts, flags *pflag.FlagSet) {
+	flags.BoolVar(&opts.json, "json", false, "Print the problems found as JSON")
+	flags.BoolVar(&opts.fix, "fix", false, "Fix the problems that can be fixed by removing a key")
+	// No-op the cwd flag while the root level command is not yet cobra
+	_ = flags.String("cwd", "", "")
+	if err := flags.MarkHidden("cwd"); err != nil {
+		// Fail fast if we have misconfigured our flags
+		panic(err)
+	}
+}
+
+// errProblemsFound makes the command exit with a non-zero code once the problems are printed
+var errProblemsFound = errors.New("turbo configuration has problems")
+
+func getCmd(config *config.Config, ui cli.Ui) *cobra.Command {
+	opts := &opts{}
+	cmd := &cobra.Command{
+		Use:                   "turbo lint-config [<flags>]",
+		Short:                 "Check turbo.json for unused, ineffective and deprecated configuration.",
+		SilenceUsage:          true,
+		SilenceErrors:         true,
+		DisableFlagsInUseLine: true,
+		RunE: func(cmd *cobra.Command, args []string) error {
+			logger := config.Logger.Named("lint-config")
+			diagnostics, err := lint(config, logger)
+			if err != nil {
+				logError(logger, ui, err)
+				return err
+			}
+			if opts.fix {
+				if err := fix(config.Cwd, diagnostics); err != nil {
+					logError(logger, ui, err)
+					return err
+				}
+			}
+			if opts.json {
+				encoded, err := json.MarshalIndent(diagnostics, "", "  ")
+				if err != nil {
+					logError(logger, ui, err)
+					return err
+				}
+				ui.Output(string(encoded))
+			} else {
+				printDiagnostics(ui, diagnostics)
+			}
+			for _, d := range diagnostics {
+				if !d.Fixed {
+					return errProblemsFound
+				}
+			}
+			return nil
+		},
+	}
+	addFlags(opts, cmd.Flags())
+	return cmd
+}
+
+func logError(logger hclog.Logger, ui cli.Ui, err error) {
+	logger.Error("error", err)
+	pref := color.New(color.Bold, color.FgRed, color.ReverseVideo).Sprint(" ERROR ")
+	ui.Error(fmt.Sprintf("%s%s", pref, color.RedString(" %v", err)))
+}
+
+// The checks that lint-config runs
+const (
+	checkUnusedTask        = "unused-task"
+	checkOutputsNotIgnored = "outputs-not-ignored"
+	checkUnusedEnv         = "unused-env"
+	checkDeprecatedKey     = "deprecated-key"
+	checkInconsistentCache = "inconsistent-cache"
+)
+
+// diagnostic is a problem found in a configuration file
+type diagnostic struct {
+	Check   string `json:"check"`
+	File    string `json:"file"`
+	Line    int    `json:"line"`
+	Column  int    `json:"column"`
+	Message string `json:"message"`
+	// Fixable diagnostics are fixed by removing the member they point at
+	Fixable bool `json:"fixable"`
+	Fixed   bool `json:"fixed,omitempty"`
+	// source is the line that the diagnostic points at, and width is how much of it
+	source string
+	width  int
+	// path is the member that the diagnostic points at, within its file
+	path []string
+}
+
+// document is a configuration file, with where each of its members is
+type document struct {
+	// file is relative to the root of the monorepo
+	file  string
+	data  []byte
+	spans map[string]span
+	// prefix is where the turbo configuration is within the file: nowhere for turbo.json,
+	// and "turbo" for package.json
+	prefix []string
+}
+
+func readDocument(root fs.AbsolutePath, file string) (*document, error) {
+	data, err := ioutil.ReadFile(root.Join(file).ToString())
+	if err != nil {
+		return nil, err
+	}
+	spans, err := locateSpans(data)
+	if err != nil {
+		return nil, fmt.Errorf("%v:%v", file, err)
+	}
+	return &document{file: file, data: data, spans: spans}, nil
+}
+
+// locate finds a member of the turbo configuration within the document
+func (d *document) locate(keys ...string) ([]string, span, bool) {
+	path := append(append([]string{}, d.prefix...), keys...)
+	sp, ok := d.spans[spanPath(path...)]
+	return path, sp, ok
+}
+
+// diagnose returns a diagnostic that points at a member of the turbo configuration, or at
+// the closest of its parents that can be found
+func (d *document) diagnose(check string, message string, fixable bool, keys ...string) diagnostic {
+	for len(keys) > 0 {
+		if path, sp, ok := d.locate(keys...); ok {
+			return d.diagnoseSpan(check, message, fixable, path, sp)
+		}
+		keys = keys[:len(keys)-1]
+		fixable = false
+	}
+	return diagnostic{Check: check, File: d.file, Line: 1, Column: 1, Message: message}
+}
+
+func (d *document) diagnoseSpan(check string, message string, fixable bool, path []string, sp span) diagnostic {
+	line, column := position(d.data, sp.start)
+	source := string(d.data[sp.start-column+1:])
+	if end := strings.IndexByte(source, '\n'); end >= 0 {
+		source = source[:end]
+	}
+	source = strings.TrimRight(source, "\r")
+	width := sp.keyEnd - sp.start
+	if width > len(source)-column+1 {
+		width = len(source) - column + 1
+	}
+	return diagnostic{
+		Check:   check,
+		File:    d.file,
+		Line:    line,
+		Column:  column,
+		Message: message,
+		Fixable: fixable,
+		source:  source,
+		width:   width,
+		path:    path,
+	}
+}
+
+// ignoredPaths returns which of the given paths, relative to the root of the monorepo,
+// git ignores
+type ignoredPaths func(paths []string) (util.Set, error)
+
+func lint(config *config.Config, logger hclog.Logger) ([]diagnostic, error) {
+	turboJSON, err := fs.ReadTurboConfig(config.Cwd, config.RootPackageJSON)
+	if err != nil {
+		return nil, err
+	}
+	ctx, err := context.New(context.WithGraph(config, turboJSON, cache.DefaultLocation(config.Cwd)))
+	if err != nil {
+		return nil, errors.Wrap(err, "could not construct graph")
+	}
+	packageJSON, err := readDocument(config.Cwd, "package.json")
+	if err != nil {
+		return nil, err
+	}
+	legacy := *packageJSON
+	legacy.prefix = []string{"turbo"}
+	doc := &legacy
+	hasTurboJSON := config.Cwd.Join("turbo.json").FileExists()
+	if hasTurboJSON {
+		doc, err = readDocument(config.Cwd, "turbo.json")
+		if err != nil {
+			return nil, err
+		}
+	}
+	ignored := func(paths []string) (util.Set, error) {
+		return gitIgnored(config.Cwd, paths)
+	}
+
+	diagnostics := lintPipeline(doc, turboJSON, ctx.PackageInfos, ignored, logger)
+	if _, sp, ok := packageJSON.locate("turbo"); ok {
+		if hasTurboJSON {
+			diagnostics = append(diagnostics, packageJSON.diagnoseSpan(checkDeprecatedKey, `"turbo" in package.json is ignored, since turbo.json is used instead`, true, []string{"turbo"}, sp))
+		} else {
+			diagnostics = append(diagnostics, packageJSON.diagnoseSpan(checkDeprecatedKey, `"turbo" in package.json is deprecated. Move it to turbo.json by running "npx @turbo/codemod create-turbo-config"`, false, []string{"turbo"}, sp))
+		}
+	}
+	return diagnostics, nil
+}
+
+// usage is a package script that a pipeline key defines
+type usage struct {
+	pkg  *fs.PackageJSON
+	task string
+}
+
+// lintPipeline runs every check other than the one for the legacy "turbo" key in package.json
+func lintPipeline(doc *document, turboJSON *fs.TurboJSON, packageInfos map[interface{}]*fs.PackageJSON, ignored ignoredPaths, logger hclog.Logger) []diagnostic {
+	diagnostics := []diagnostic{}
+	if turboJSON.Base != "" {
+		diagnostics = append(diagnostics, doc.diagnose(checkDeprecatedKey, `"baseBranch" is no longer used. Pass --filter=...[origin/main] to run only what changed since a branch`, true, "baseBranch"))
+	}
+
+	usages := pipelineUsages(turboJSON.Pipeline, packageInfos)
+	keys := turboJSON.Pipeline.Keys()
+	sort.Strings(keys)
+	var outputChecks []outputCheck
+	for _, key := range keys {
+		definition := turboJSON.Pipeline[key]
+		if len(usages[key]) == 0 {
+			diagnostics = append(diagnostics, doc.diagnose(checkUnusedTask, unusedTaskMessage(key, packageInfos), false, "pipeline", key))
+			continue
+		}
+		if !definition.ShouldCache {
+			if definition.OutputsDeclared && len(definition.Outputs) > 0 {
+				diagnostics = append(diagnostics, doc.diagnose(checkInconsistentCache, `"outputs" has no effect, since "cache" is false`, true, "pipeline", key, "outputs"))
+			}
+			if _, _, ok := doc.locate("pipeline", key, "remote"); ok {
+				diagnostics = append(diagnostics, doc.diagnose(checkInconsistentCache, `"remote" has no effect, since "cache" is false`, true, "pipeline", key, "remote"))
+			}
+		} else if definition.OutputsDeclared {
+			outputChecks = append(outputChecks, newOutputCheck(doc, key, definition.Outputs, usages[key])...)
+		}
+		diagnostics = append(diagnostics, lintEnv(doc, key, definition, usages[key])...)
+	}
+	if len(outputChecks) > 0 {
+		checked, err := lintOutputs(doc, outputChecks, ignored)
+		if err != nil {
+			logger.Debug("skipping outputs check", "error", err)
+		}
+		diagnostics = append(diagnostics, checked...)
+	}
+	return diagnostics
+}
+
+// pipelineUsages returns the package scripts that each pipeline key defines. Scripts in
+// the root package are only run for keys that start with "//#".
+func pipelineUsages(pipeline fs.Pipeline, packageInfos map[interface{}]*fs.PackageJSON) map[string][]usage {
+	var rootKeys []string
+	for _, key := range pipeline.Keys() {
+		if pkg, _ := util.GetPackageTaskFromId(key); util.IsPackageTask(key) && pkg == util.RootPkgName {
+			rootKeys = append(rootKeys, key)
+		}
+	}
+	names := make([]string, 0, len(packageInfos))
+	for name := range packageInfos {
+		names = append(names, name.(string))
+	}
+	sort.Strings(names)
+
+	usages := make(map[string][]usage)
+	for _, name := range names {
+		pkg := packageInfos[name]
+		keys := pipeline.Keys()
+		if name == util.RootPkgName {
+			keys = rootKeys
+		}
+		for task := range pkg.Scripts {
+			if key, ok := util.ResolveTaskKey(keys, util.GetTaskId(name, task)); ok {
+				usages[key] = append(usages[key], usage{pkg: pkg, task: task})
+			}
+		}
+	}
+	for _, keyUsages := range usages {
+		sort.Slice(keyUsages, func(i, j int) bool {
+			if keyUsages[i].pkg.Name != keyUsages[j].pkg.Name {
+				return keyUsages[i].pkg.Name < keyUsages[j].pkg.Name
+			}
+			return keyUsages[i].task < keyUsages[j].task
+		})
+	}
+	return usages
+}
+
+func unusedTaskMessage(key string, packageInfos map[interface{}]*fs.PackageJSON) string {
+	if util.IsPackageTask(key) {
+		pkg, task := util.GetPackageTaskFromId(key)
+		if pkg == util.RootPkgName {
+			return fmt.Sprintf("%q doesn't apply to anything, since the root package.json has no %q script", key, task)
+		}
+		if _, ok := packageInfos[pkg]; !ok && !util.IsTaskPattern(pkg) {
+			return fmt.Sprintf("%q doesn't apply to anything, since there is no package named %v", key, pkg)
+		}
+		return fmt.Sprintf("%q doesn't apply to anything, since %v has no %q script", key, pkg, task)
+	}
+	return fmt.Sprintf("%q doesn't apply to anything, since no package has a %q script", key, key)
+}
+
+// lintEnv reports environment variables in a key's dependsOn that none of the scripts it
+// defines mention, counting their pre and post scripts
+func lintEnv(doc *document, key string, definition fs.TaskDefinition, usages []usage) []diagnostic {
+	var diagnostics []diagnostic
+	for _, envVar := range definition.EnvVarDependencies {
+		if strings.Contains(envVar, util.TaskWildcard) {
+			continue
+		}
+		mention := regexp.MustCompile(`\b` + regexp.QuoteMeta(envVar) + `\b`)
+		mentioned := false
+		for _, u := range usages {
+			for _, script := range []string{u.task, "pre" + u.task, "post" + u.task} {
+				if mention.MatchString(u.pkg.Scripts[script]) {
+					mentioned = true
+				}
+			}
+		}
+		if mentioned {
+			continue
+		}
+		message := fmt.Sprintf("$%v is in dependsOn, but none of the scripts that %q defines mention it", envVar, key)
+		keys := append([]string{"pipeline", key}, dependsOnEntry(doc, key, "$"+envVar)...)
+		diagnostics = append(diagnostics, doc.diagnose(checkUnusedEnv, message, false, keys...))
+	}
+	return diagnostics
+}
+
+// dependsOnEntry returns the path to an entry in a key's dependsOn, or to dependsOn itself
+// if the entry comes from a variant
+func dependsOnEntry(doc *document, key string, entry string) []string {
+	for i := 0; ; i++ {
+		_, sp, ok := doc.locate("pipeline", key, "dependsOn", strconv.Itoa(i))
+		if !ok {
+			return []string{"dependsOn"}
+		}
+		if value, ok := sp.unquote(); ok && value == entry {
+			return []string{"dependsOn", strconv.Itoa(i)}
+		}
+	}
+}
+
+// outputCheck is an output glob, and the paths it stands for in each package, that git
+// should ignore
+type outputCheck struct {
+	diagnostic diagnostic
+	output     string
+	paths      []string
+}
+
+// newOutputCheck returns the paths that the given outputs cover in each package. Only the
+// part of a glob before its first wildcard is checked, and negated globs are skipped.
+func newOutputCheck(doc *document, key string, outputs []string, usages []usage) []outputCheck {
+	var checks []outputCheck
+	for i, output := range outputs {
+		prefix := staticPrefix(output)
+		if strings.HasPrefix(output, "!") || prefix == "" {
+			continue
+		}
+		seen := make(util.Set)
+		var paths []string
+		for _, u := range usages {
+			if seen.Includes(u.pkg.Dir) {
+				continue
+			}
+			seen.Add(u.pkg.Dir)
+			p := path.Join(filepath.ToSlash(u.pkg.Dir), prefix)
+			if strings.HasSuffix(prefix, "/") {
+				p += "/"
+			}
+			paths = append(paths, p)
+		}
+		checks = append(checks, outputCheck{
+			diagnostic: doc.diagnose(checkOutputsNotIgnored, "", false, "pipeline", key, "outputs", strconv.Itoa(i)),
+			output:     output,
+			paths:      paths,
+		})
+	}
+	return checks
+}
+
+// staticPrefix returns the path that a glob is within, with a trailing slash if the glob
+// matches files under it
+func staticPrefix(glob string) string {
+	segments := strings.Split(glob, "/")
+	for i, segment := range segments {
+		if strings.ContainsAny(segment, "*?[{") {
+			if i == 0 {
+				return ""
+			}
+			return strings.Join(segments[:i], "/") + "/"
+		}
+	}
+	return glob
+}
+
+// lintOutputs reports outputs that git doesn't ignore. turbo hashes the files in a
+// package that git doesn't ignore, so outputs that aren't ignored are inputs as well,
+// and each build misses the cache for the next.
+func lintOutputs(doc *document, checks []outputCheck, ignored ignoredPaths) ([]diagnostic, error) {
+	var all []string
+	for _, check := range checks {
+		all = append(all, check.paths...)
+	}
+	ignoredSet, err := ignored(all)
+	if err != nil {
+		return nil, err
+	}
+	var diagnostics []diagnostic
+	for _, check := range checks {
+		var notIgnored []string
+		for _, path := range check.paths {
+			if !ignoredSet.Includes(path) {
+				notIgnored = append(notIgnored, path)
+			}
+		}
+		if len(notIgnored) == 0 {
+			continue
+		}
+		listed := notIgnored
+		more := ""
+		if len(listed) > 3 {
+			more = fmt.Sprintf(" and %v more", len(listed)-3)
+			listed = listed[:3]
+		}
+		d := check.diagnostic
+		d.Message = fmt.Sprintf("%q is in outputs, but git doesn't ignore %v%v, so builds are hashed as inputs", check.output, strings.Join(listed, ", "), more)
+		diagnostics = append(diagnostics, d)
+	}
+	return diagnostics, nil
+}
+
+// gitIgnored returns which of the given paths git ignores, whether or not they exist
+func gitIgnored(root fs.AbsolutePath, paths []string) (util.Set, error) {
+	cmd := exec.Command("git", append([]string{"check-ignore", "--no-index", "--"}, paths...)...)
+	cmd.Dir = root.ToString()
+	out, err := cmd.Output()
+	var exitErr *exec.ExitError
+	// git check-ignore exits with 1 when none of the paths are ignored
+	if err != nil && !(errors.As(err, &exitErr) && exitErr.ExitCode() == 1) {
+		return nil, err
+	}
+	ignored := make(util.Set)
+	for _, line := range strings.Split(string(out), "\n") {
+		if line != "" {
+			ignored.Add(line)
+		}
+	}
+	return ignored, nil
+}
+
+// fix removes the members that fixable diagnostics point at, and marks them as fixed
+func fix(root fs.AbsolutePath, diagnostics []diagnostic) error {
+	files := make(map[string][]byte)
+	for i, d := range diagnostics {
+		if !d.Fixable {
+			continue
+		}
+		data, ok := files[d.File]
+		if !ok {
+			var err error
+			data, err = ioutil.ReadFile(root.Join(d.File).ToString())
+			if err != nil {
+				return err
+			}
+		}
+		fixed, err := removeMember(data, d.path)
+		if err != nil {
+			return fmt.Errorf("%v: %w", d.File, err)
+		}
+		files[d.File] = fixed
+		diagnostics[i].Fixed = true
+	}
+	for file, data := range files {
+		if err := root.Join(file).WriteFile(data, 0644); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// removeMember removes the member at the given path. The document is scanned again each
+// time, since removing a member moves the ones after it.
+func removeMember(data []byte, path []string) ([]byte, error) {
+	spans, err := locateSpans(data)
+	if err != nil {
+		return nil, err
+	}
+	sp, ok := spans[spanPath(path...)]
+	if !ok {
+		return nil, fmt.Errorf("could not find %v", strings.Join(path, "."))
+	}
+	return removeSpan(data, sp), nil
+}
+
+func printDiagnostics(ui cli.Ui, diagnostics []diagnostic) {
+	if len(diagnostics) == 0 {
+		ui.Output("No problems found in turbo.json")
+		return
+	}
+	fixable := 0
+	fixed := 0
+	for _, d := range diagnostics {
+		// Messages can contain "$", so they are kept out of util.Sprintf
+		status := util.Sprintf("${BOLD}%v:%v:%v${RESET} ${GREY}[%v]${RESET}", d.File, d.Line, d.Column, d.Check)
+		switch {
+		case d.Fixed:
+			fixed++
+			status += util.Sprintf(" ${GREEN}fixed${RESET}")
+		case d.Fixable:
+			fixable++
+		}
+		ui.Output(status + " " + d.Message)
+		if d.source != "" {
+			width := d.width
+			if width < 1 {
+				width = 1
+			}
+			ui.Output("  " + d.source)
+			ui.Output("  " + strings.Repeat(" ", d.Column-1) + util.Sprintf("${YELLOW}%v${RESET}", strings.Repeat("^", width)))
+		}
+		ui.Output("")
+	}
+	summary := fmt.Sprintf("Found %v problems", len(diagnostics))
+	if fixed > 0 {
+		summary += fmt.Sprintf(", fixed %v", fixed)
+	}
+	if fixable > 0 {
+		summary += fmt.Sprintf(" (%v fixable with --fix)", fixable)
+	}
+	ui.Output(summary)
+}
@@ -0,0 +1,172 @@
+package lintconfig
+
+import (
+	"testing"
+
+	"github.com/hashicorp/go-hclog"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/util"
+	"github.com/yosuke-furukawa/json5/encoding/json5"
+	"gotest.tools/v3/assert"
+)
+
+func TestLocateSpans(t *testing.T) {
+	data := []byte(`{
+  // comment with a "quote"
+  'single': "a, b",
+  unquoted: [1, {"nested": true},],
+  "last": {},
+}`)
+	spans, err := locateSpans(data)
+	assert.NilError(t, err)
+
+	single, ok := spans[spanPath("single")]
+	assert.Assert(t, ok)
+	line, column := position(data, single.start)
+	assert.Equal(t, line, 3)
+	assert.Equal(t, column, 3)
+	value, ok := single.unquote()
+	assert.Assert(t, ok)
+	assert.Equal(t, value, "a, b")
+
+	nested, ok := spans[spanPath("unquoted", "1", "nested")]
+	assert.Assert(t, ok)
+	assert.Equal(t, nested.value, "true")
+	line, column = position(data, nested.start)
+	assert.Equal(t, line, 4)
+	assert.Equal(t, column, 18)
+
+	_, ok = spans[spanPath("last")]
+	assert.Assert(t, ok)
+
+	_, err = locateSpans([]byte(`{"a": 1 "b": 2}`))
+	assert.ErrorContains(t, err, `1:9: expected ',' after "a"`)
+}
+
+func TestRemoveSpan(t *testing.T) {
+	data := []byte(`{
+  "a": 1,
+  "b": [1, 2],
+  "c": 3
+}
+`)
+	testCases := []struct {
+		path []string
+		want string
+	}{
+		{[]string{"a"}, "{\n  \"b\": [1, 2],\n  \"c\": 3\n}\n"},
+		{[]string{"c"}, "{\n  \"a\": 1,\n  \"b\": [1, 2]\n}\n"},
+		{[]string{"b", "1"}, "{\n  \"a\": 1,\n  \"b\": [1],\n  \"c\": 3\n}\n"},
+	}
+	for _, tc := range testCases {
+		got, err := removeMember(data, tc.path)
+		assert.NilError(t, err)
+		assert.Equal(t, string(got), tc.want)
+	}
+}
+
+func TestLintPipeline(t *testing.T) {
+	data := []byte(`{
+  // Shared by every package
+  "baseBranch": "origin/main",
+  "pipeline": {
+    "build": {
+      "dependsOn": ["^build", "$API_URL", "$NODE_ENV"],
+      "outputs": ["dist/**", ".next/**", "!.next/cache/**"]
+    },
+    "lint": {
+      "outputs": []
+    },
+    "dev": {
+      "cache": false,
+      "outputs": ["dist/**"]
+    },
+    "docs#deploy": {
+      "cache": false
+    },
+    "//#format": {}
+  }
+}
+`)
+	var turboJSON fs.TurboJSON
+	assert.NilError(t, json5.Unmarshal(data, &turboJSON))
+	spans, err := locateSpans(data)
+	assert.NilError(t, err)
+	doc := &document{file: "turbo.json", data: data, spans: spans}
+	packageInfos := map[interface{}]*fs.PackageJSON{
+		"web": {
+			Name:    "web",
+			Dir:     "apps/web",
+			Scripts: map[string]string{"build": "API_URL=$API_URL next build", "dev": "next dev"},
+		},
+		"docs": {
+			Name:    "docs",
+			Dir:     "apps/docs",
+			Scripts: map[string]string{"build": "next build", "lint": "eslint ."},
+		},
+		util.RootPkgName: {
+			Name:    "monorepo",
+			Dir:     ".",
+			Scripts: map[string]string{"build": "turbo run build", "format": "prettier -w ."},
+		},
+	}
+	var checked []string
+	ignored := func(paths []string) (util.Set, error) {
+		checked = paths
+		ignoredSet := make(util.Set)
+		ignoredSet.Add("apps/docs/dist/")
+		ignoredSet.Add("apps/web/dist/")
+		ignoredSet.Add("apps/docs/.next/")
+		return ignoredSet, nil
+	}
+
+	diagnostics := lintPipeline(doc, &turboJSON, packageInfos, ignored, hclog.NewNullLogger())
+	assert.DeepEqual(t, checked, []string{"apps/docs/dist/", "apps/web/dist/", "apps/docs/.next/", "apps/web/.next/"})
+
+	type summary struct {
+		Check   string
+		Line    int
+		Column  int
+		Fixable bool
+		Message string
+	}
+	var got []summary
+	for _, d := range diagnostics {
+		got = append(got, summary{d.Check, d.Line, d.Column, d.Fixable, d.Message})
+	}
+	assert.DeepEqual(t, got, []summary{
+		{checkDeprecatedKey, 3, 3, true, `"baseBranch" is no longer used. Pass --filter=...[origin/main] to run only what changed since a branch`},
+		{checkUnusedEnv, 6, 43, false, `$NODE_ENV is in dependsOn, but none of the scripts that "build" defines mention it`},
+		{checkInconsistentCache, 14, 7, true, `"outputs" has no effect, since "cache" is false`},
+		{checkUnusedTask, 16, 5, false, `"docs#deploy" doesn't apply to anything, since docs has no "deploy" script`},
+		{checkOutputsNotIgnored, 7, 30, false, `".next/**" is in outputs, but git doesn't ignore apps/web/.next/, so builds are hashed as inputs`},
+	})
+
+	fixed := data
+	for _, d := range diagnostics {
+		if d.Fixable {
+			fixed, err = removeMember(fixed, d.path)
+			assert.NilError(t, err)
+		}
+	}
+	assert.Equal(t, string(fixed), `{
+  // Shared by every package
+  "pipeline": {
+    "build": {
+      "dependsOn": ["^build", "$API_URL", "$NODE_ENV"],
+      "outputs": ["dist/**", ".next/**", "!.next/cache/**"]
+    },
+    "lint": {
+      "outputs": []
+    },
+    "dev": {
+      "cache": false
+    },
+    "docs#deploy": {
+      "cache": false
+    },
+    "//#format": {}
+  }
+}
+`)
+}
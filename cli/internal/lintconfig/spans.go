@@ -0,0 +1,258 @@
+package lintconfig
+
+import (
+	"encoding/json"
+	"fmt"
+	"strconv"
+	"strings"
+)
+
+// span is where an object member, or an array element, is in a JSON document. For an
+// array element, the "key" is the element itself.
+type span struct {
+	// start and keyEnd are the offsets of the key
+	start  int
+	keyEnd int
+	// end is the offset just past the value, and the comma after it if there is one
+	end int
+	// value is the raw text of the value
+	value string
+}
+
+// spanPath joins the keys, and array indexes, that lead to a member into a key for
+// the map that locateSpans returns. Task names can contain "/", so it isn't used.
+func spanPath(keys ...string) string {
+	return strings.Join(keys, "\x00")
+}
+
+// locateSpans finds every object member and array element in a JSON document, keyed by
+// spanPath. Comments, single-quoted strings, unquoted keys and trailing commas are
+// allowed, as in turbo.json.
+func locateSpans(data []byte) (map[string]span, error) {
+	s := &spanScanner{data: data, spans: make(map[string]span)}
+	if err := s.value(nil); err != nil {
+		return nil, err
+	}
+	return s.spans, nil
+}
+
+type spanScanner struct {
+	data  []byte
+	pos   int
+	spans map[string]span
+}
+
+func (s *spanScanner) errorf(format string, args ...interface{}) error {
+	line, column := position(s.data, s.pos)
+	return fmt.Errorf("%v:%v: %v", line, column, fmt.Sprintf(format, args...))
+}
+
+func (s *spanScanner) skipSpace() {
+	for s.pos < len(s.data) {
+		switch {
+		case isSpace(s.data[s.pos]):
+			s.pos++
+		case strings.HasPrefix(string(s.data[s.pos:]), "//"):
+			for s.pos < len(s.data) && s.data[s.pos] != '\n' {
+				s.pos++
+			}
+		case strings.HasPrefix(string(s.data[s.pos:]), "/*"):
+			end := strings.Index(string(s.data[s.pos+2:]), "*/")
+			if end < 0 {
+				s.pos = len(s.data)
+			} else {
+				s.pos += end + 4
+			}
+		default:
+			return
+		}
+	}
+}
+
+func (s *spanScanner) value(path []string) error {
+	s.skipSpace()
+	if s.pos >= len(s.data) {
+		return s.errorf("unexpected end of file")
+	}
+	switch s.data[s.pos] {
+	case '{':
+		return s.object(path)
+	case '[':
+		return s.array(path)
+	case '"', '\'':
+		_, err := s.str()
+		return err
+	default:
+		start := s.pos
+		for s.pos < len(s.data) && !strings.ContainsRune(",:]} \t\r\n/", rune(s.data[s.pos])) {
+			s.pos++
+		}
+		if s.pos == start {
+			return s.errorf("unexpected %q", s.data[s.pos])
+		}
+		return nil
+	}
+}
+
+func (s *spanScanner) object(path []string) error {
+	s.pos++
+	for {
+		s.skipSpace()
+		if s.pos >= len(s.data) {
+			return s.errorf("unexpected end of file")
+		}
+		if s.data[s.pos] == '}' {
+			s.pos++
+			return nil
+		}
+		start := s.pos
+		var key string
+		var err error
+		if c := s.data[s.pos]; c == '"' || c == '\'' {
+			key, err = s.str()
+			if err != nil {
+				return err
+			}
+		} else {
+			for s.pos < len(s.data) && !strings.ContainsRune(": \t\r\n", rune(s.data[s.pos])) {
+				s.pos++
+			}
+			key = string(s.data[start:s.pos])
+		}
+		keyEnd := s.pos
+		s.skipSpace()
+		if s.pos >= len(s.data) || s.data[s.pos] != ':' {
+			return s.errorf("expected ':' after %q", key)
+		}
+		s.pos++
+		if err := s.member(append(path[:len(path):len(path)], key), start, keyEnd); err != nil {
+			return err
+		}
+	}
+}
+
+func (s *spanScanner) array(path []string) error {
+	s.pos++
+	for i := 0; ; i++ {
+		s.skipSpace()
+		if s.pos >= len(s.data) {
+			return s.errorf("unexpected end of file")
+		}
+		if s.data[s.pos] == ']' {
+			s.pos++
+			return nil
+		}
+		if err := s.member(append(path[:len(path):len(path)], strconv.Itoa(i)), s.pos, -1); err != nil {
+			return err
+		}
+	}
+}
+
+// member scans a value and the comma after it, and records its span
+func (s *spanScanner) member(path []string, start int, keyEnd int) error {
+	s.skipSpace()
+	valueStart := s.pos
+	if err := s.value(path); err != nil {
+		return err
+	}
+	if keyEnd < 0 {
+		keyEnd = s.pos
+	}
+	value := string(s.data[valueStart:s.pos])
+	end := s.pos
+	s.skipSpace()
+	if s.pos < len(s.data) && s.data[s.pos] == ',' {
+		s.pos++
+		end = s.pos
+	} else if s.pos < len(s.data) && s.data[s.pos] != '}' && s.data[s.pos] != ']' {
+		return s.errorf("expected ',' after %q", path[len(path)-1])
+	}
+	s.spans[spanPath(path...)] = span{start: start, keyEnd: keyEnd, end: end, value: value}
+	return nil
+}
+
+// str scans a quoted string and returns its contents
+func (s *spanScanner) str() (string, error) {
+	quote := s.data[s.pos]
+	start := s.pos
+	s.pos++
+	for s.pos < len(s.data) && s.data[s.pos] != quote {
+		if s.data[s.pos] == '\\' {
+			s.pos++
+		}
+		s.pos++
+	}
+	if s.pos >= len(s.data) {
+		return "", s.errorf("unterminated string")
+	}
+	s.pos++
+	raw := string(s.data[start:s.pos])
+	if quote == '\'' {
+		return strings.ReplaceAll(raw[1:len(raw)-1], `\'`, `'`), nil
+	}
+	var str string
+	if err := json.Unmarshal([]byte(raw), &str); err != nil {
+		return "", s.errorf("invalid string %v", raw)
+	}
+	return str, nil
+}
+
+// unquote returns the string that a span's value holds, if it is a string
+func (sp span) unquote() (string, bool) {
+	if len(sp.value) < 2 || (sp.value[0] != '"' && sp.value[0] != '\'') {
+		return "", false
+	}
+	s := &spanScanner{data: []byte(sp.value)}
+	str, err := s.str()
+	return str, err == nil
+}
+
+// position returns the 1-based line and column of an offset
+func position(data []byte, offset int) (int, int) {
+	line := 1 + strings.Count(string(data[:offset]), "\n")
+	column := offset + 1
+	if lineStart := strings.LastIndexByte(string(data[:offset]), '\n'); lineStart >= 0 {
+		column = offset - lineStart
+	}
+	return line, column
+}
+
+// removeSpan deletes a member, or array element, along with its comma. If it is the last
+// one, the comma before it goes instead, so that no trailing comma is left. If nothing
+// else is on its line, the whole line goes.
+func removeSpan(data []byte, sp span) []byte {
+	data = append([]byte{}, data...)
+	start, end := sp.start, sp.end
+	if data[end-1] != ',' {
+		before := start
+		for before > 0 && isSpace(data[before-1]) {
+			before--
+		}
+		if before > 0 && data[before-1] == ',' {
+			if strings.ContainsRune(string(data[before:start]), '\n') {
+				data = append(data[:before-1], data[before:]...)
+				start--
+				end--
+			} else {
+				start = before - 1
+			}
+		}
+	}
+	lineStart := start
+	for lineStart > 0 && (data[lineStart-1] == ' ' || data[lineStart-1] == '\t') {
+		lineStart--
+	}
+	lineEnd := end
+	for lineEnd < len(data) && (data[lineEnd] == ' ' || data[lineEnd] == '\t' || data[lineEnd] == '\r') {
+		lineEnd++
+	}
+	if (lineStart == 0 || data[lineStart-1] == '\n') && lineEnd < len(data) && data[lineEnd] == '\n' {
+		start = lineStart
+		end = lineEnd + 1
+	}
+	return append(data[:start], data[end:]...)
+}
+
+func isSpace(c byte) bool {
+	return c == ' ' || c == '\t' || c == '\r' || c == '\n'
+}
@@ -0,0 +1,108 @@
+package logstreamer
+
+import (
+	"encoding/json"
+	"strings"
+	"time"
+
+	"github.com/mitchellh/cli"
+	"github.com/vercel/turborepo/cli/internal/ui"
+)
+
+// Streams used in JSON log lines. Output from the task itself is tagged with
+// the stream it was written to, while messages from turbo about the task
+// (cache status, errors) are tagged as coming from turbo.
+const (
+	StreamStdout = "stdout"
+	StreamStderr = "stderr"
+	StreamTurbo  = "turbo"
+)
+
+// JSONLine is a single line of output in the json log format
+type JSONLine struct {
+	Timestamp time.Time `json:"timestamp"`
+	Package   string    `json:"package"`
+	Task      string    `json:"task"`
+	Stream    string    `json:"stream"`
+	Text      string    `json:"text"`
+}
+
+// NewJSONLine returns the serialized form of a line of output for the given package and task.
+// Colors are stripped, since consumers of this format are not terminals.
+func NewJSONLine(packageName string, task string, stream string, text string) string {
+	line := &JSONLine{
+		Timestamp: time.Now(),
+		Package:   packageName,
+		Task:      task,
+		Stream:    stream,
+		Text:      ui.StripAnsi(strings.TrimSuffix(text, "\n")),
+	}
+	// JSONLine only contains strings and a time, so encoding can't fail
+	encoded, _ := json.Marshal(line)
+	return string(encoded)
+}
+
+// ParseJSONLine returns the JSONLine that the given text is the serialized form of, if any
+func ParseJSONLine(text string) (*JSONLine, bool) {
+	if !strings.HasPrefix(text, "{") {
+		return nil, false
+	}
+	var line JSONLine
+	if json.Unmarshal([]byte(text), &line) != nil || line.Stream == "" {
+		return nil, false
+	}
+	return &line, true
+}
+
+// isJSONLine returns true if the given text is already a serialized JSONLine
+func isJSONLine(text string) bool {
+	_, ok := ParseJSONLine(text)
+	return ok
+}
+
+// JSONUi is a cli.Ui that renders every message as a JSONLine attributed to
+// turbo for the given package and task. Messages that are already JSONLines,
+// such as those replayed from a log file, are passed through unchanged.
+type JSONUi struct {
+	cli.Ui
+	packageName string
+	task        string
+}
+
+// NewJSONUi returns a JSONUi wrapping the given Ui
+func NewJSONUi(ui cli.Ui, packageName string, task string) *JSONUi {
+	return &JSONUi{
+		Ui:          ui,
+		packageName: packageName,
+		task:        task,
+	}
+}
+
+func (u *JSONUi) format(text string) string {
+	if isJSONLine(text) {
+		return text
+	}
+	return NewJSONLine(u.packageName, u.task, StreamTurbo, text)
+}
+
+// Output implements cli.Ui.Output
+func (u *JSONUi) Output(text string) {
+	u.Ui.Output(u.format(text))
+}
+
+// Info implements cli.Ui.Info
+func (u *JSONUi) Info(text string) {
+	u.Ui.Info(u.format(text))
+}
+
+// Warn implements cli.Ui.Warn
+func (u *JSONUi) Warn(text string) {
+	u.Ui.Warn(u.format(text))
+}
+
+// Error implements cli.Ui.Error
+func (u *JSONUi) Error(text string) {
+	u.Ui.Error(u.format(text))
+}
+
+var _ cli.Ui = &JSONUi{}
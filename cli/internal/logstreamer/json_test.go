@@ -0,0 +1,67 @@
+package logstreamer
+
+import (
+	"bufio"
+	"bytes"
+	"encoding/json"
+	"log"
+	"strings"
+	"testing"
+
+	"github.com/mitchellh/cli"
+)
+
+func TestJSONLogstreamer(t *testing.T) {
+	var buffer bytes.Buffer
+	byteWriter := bufio.NewWriter(&buffer)
+
+	logger := log.New(byteWriter, "", 0)
+	logStreamerErr := NewJSONLogstreamer(logger, "my-pkg", "build", StreamStderr, false)
+
+	logStreamerErr.Write([]byte("\x1b[31mfirst line\x1b[0m\nsecond "))
+	logStreamerErr.Write([]byte("line\n"))
+	logStreamerErr.Close()
+	byteWriter.Flush()
+
+	lines := strings.Split(strings.TrimSpace(buffer.String()), "\n")
+	if len(lines) != 2 {
+		t.Fatalf("Expected 2 lines, got %v: %v", len(lines), lines)
+	}
+	expected := []string{"first line", "second line"}
+	for i, text := range lines {
+		var line JSONLine
+		if err := json.Unmarshal([]byte(text), &line); err != nil {
+			t.Fatalf("Expected valid JSON, got '%s': %v", text, err)
+		}
+		if line.Package != "my-pkg" || line.Task != "build" || line.Stream != StreamStderr {
+			t.Errorf("Unexpected fields in '%s'", text)
+		}
+		if line.Text != expected[i] {
+			t.Errorf("Expected text '%s', got '%s'", expected[i], line.Text)
+		}
+	}
+}
+
+func TestJSONUiPassesThroughJSONLines(t *testing.T) {
+	mockUi := cli.NewMockUi()
+	jsonUi := NewJSONUi(mockUi, "my-pkg", "build")
+
+	replayed := NewJSONLine("my-pkg", "build", StreamStdout, "replayed output")
+	jsonUi.Output(replayed)
+	jsonUi.Output("cache miss")
+
+	lines := strings.Split(strings.TrimSpace(mockUi.OutputWriter.String()), "\n")
+	if len(lines) != 2 {
+		t.Fatalf("Expected 2 lines, got %v: %v", len(lines), lines)
+	}
+	if lines[0] != replayed {
+		t.Errorf("Expected '%s' to be passed through, got '%s'", replayed, lines[0])
+	}
+	var line JSONLine
+	if err := json.Unmarshal([]byte(lines[1]), &line); err != nil {
+		t.Fatalf("Expected valid JSON, got '%s': %v", lines[1], err)
+	}
+	if line.Stream != StreamTurbo || line.Text != "cache miss" {
+		t.Errorf("Unexpected line '%s'", lines[1])
+	}
+}
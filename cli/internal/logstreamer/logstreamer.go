@@ -27,6 +27,15 @@ type Logstreamer struct {
 	colorOkay  string
 	colorFail  string
 	colorReset string
+
+	// If set, each line is written as a JSONLine rather than with a prefix
+	jsonOutput *jsonFields
+}
+
+type jsonFields struct {
+	packageName string
+	task        string
+	stream      string
 }
 
 func NewLogstreamer(logger *log.Logger, prefix string, record bool) *Logstreamer {
@@ -50,6 +59,18 @@ func NewLogstreamer(logger *log.Logger, prefix string, record bool) *Logstreamer
 	return streamer
 }
 
+// NewJSONLogstreamer returns a Logstreamer that writes each line of output as a
+// JSONLine for the given package, task, and stream.
+func NewJSONLogstreamer(logger *log.Logger, packageName string, task string, stream string, record bool) *Logstreamer {
+	streamer := NewLogstreamer(logger, "", record)
+	streamer.jsonOutput = &jsonFields{
+		packageName: packageName,
+		task:        task,
+		stream:      stream,
+	}
+	return streamer
+}
+
 func (l *Logstreamer) Write(p []byte) (n int, err error) {
 	if n, err = l.buf.Write(p); err != nil {
 		return
@@ -121,6 +142,11 @@ func (l *Logstreamer) out(str string) {
 		l.persist = l.persist + str
 	}
 
+	if l.jsonOutput != nil {
+		l.Logger.Print(NewJSONLine(l.jsonOutput.packageName, l.jsonOutput.task, l.jsonOutput.stream, str))
+		return
+	}
+
 	if l.prefix == "stdout" {
 		str = l.colorOkay + l.prefix + l.colorReset + " " + str
 	} else if l.prefix == "stderr" {
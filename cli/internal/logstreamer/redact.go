@@ -0,0 +1,54 @@
+package logstreamer
+
+import (
+	"io"
+	"sort"
+	"strings"
+)
+
+// Redacted replaces secrets in task output
+const Redacted = "***"
+
+// _minSecretLength is the length below which values aren't redacted, since values
+// like "1" or "true" would redact too much unrelated output
+const _minSecretLength = 6
+
+type redactingWriter struct {
+	io.WriteCloser
+	replacer *strings.Replacer
+}
+
+// NewRedactingWriter returns a writer that replaces each of the given secrets with
+// Redacted before writing to w. Secrets are only found within a single write, which
+// is a whole line of output when writing through a Logstreamer.
+func NewRedactingWriter(w io.WriteCloser, secrets []string) io.WriteCloser {
+	var redacted []string
+	for _, secret := range secrets {
+		if len(secret) >= _minSecretLength {
+			redacted = append(redacted, secret)
+		}
+	}
+	if len(redacted) == 0 {
+		return w
+	}
+	// Replace longer secrets first, in case one secret contains another
+	sort.Slice(redacted, func(i, j int) bool {
+		return len(redacted[i]) > len(redacted[j])
+	})
+	oldnew := make([]string, 0, 2*len(redacted))
+	for _, secret := range redacted {
+		oldnew = append(oldnew, secret, Redacted)
+	}
+	return &redactingWriter{
+		WriteCloser: w,
+		replacer:    strings.NewReplacer(oldnew...),
+	}
+}
+
+// Write implements io.Writer.Write
+func (r *redactingWriter) Write(p []byte) (int, error) {
+	if _, err := io.WriteString(r.WriteCloser, r.replacer.Replace(string(p))); err != nil {
+		return 0, err
+	}
+	return len(p), nil
+}
@@ -0,0 +1,31 @@
+package logstreamer
+
+import (
+	"bytes"
+	"io"
+	"log"
+	"testing"
+)
+
+type nopCloser struct {
+	io.Writer
+}
+
+func (nopCloser) Close() error {
+	return nil
+}
+
+func TestRedactingWriter(t *testing.T) {
+	var buffer bytes.Buffer
+	writer := NewRedactingWriter(nopCloser{&buffer}, []string{"hunter2", "hunter2-extended", "true", ""})
+
+	logger := log.New(writer, "", 0)
+	logStreamer := NewLogstreamer(logger, "web:build: ", false)
+	logStreamer.Write([]byte("token=hunter2-extended\npassword=hunter2 verbose=true\n"))
+	logStreamer.Close()
+
+	expected := "web:build: token=***\nweb:build: password=*** verbose=true\n"
+	if buffer.String() != expected {
+		t.Errorf("Expected %q, got %q", expected, buffer.String())
+	}
+}
@@ -117,6 +117,13 @@ func detectPackageManager(projectDirectory fs.AbsolutePath) (packageManager *Pac
 
 // GetWorkspaces returns the list of package.json files for the current repository.
 func (pm PackageManager) GetWorkspaces(rootpath fs.AbsolutePath) ([]string, error) {
+	return pm.GetWorkspaceManifests(rootpath, "package.json")
+}
+
+// GetWorkspaceManifests returns the list of files with the given name, such as
+// package.json, in the workspace directories of the current repository. Workspace
+// directories matching any of the excludes, which are repo-relative globs, are skipped.
+func (pm PackageManager) GetWorkspaceManifests(rootpath fs.AbsolutePath, manifest string, excludes ...string) ([]string, error) {
 	globs, err := pm.getWorkspaceGlobs(rootpath)
 	if err != nil {
 		return nil, err
@@ -124,13 +131,14 @@ func (pm PackageManager) GetWorkspaces(rootpath fs.AbsolutePath) ([]string, erro
 
 	justJsons := make([]string, len(globs))
 	for i, space := range globs {
-		justJsons[i] = filepath.Join(space, "package.json")
+		justJsons[i] = filepath.Join(space, manifest)
 	}
 
 	ignores, err := pm.getWorkspaceIgnores(pm, rootpath)
 	if err != nil {
 		return nil, err
 	}
+	ignores = append(ignores, excludes...)
 
 	f, err := globby.GlobFiles(rootpath.ToStringDuringMigration(), justJsons, ignores)
 	if err != nil {
@@ -282,6 +282,24 @@ func Test_GetWorkspaces(t *testing.T) {
 	}
 }
 
+func Test_GetWorkspaceManifestsExcludes(t *testing.T) {
+	cwd, _ := os.Getwd()
+	repoRoot, err := fs.GetCwd()
+	assert.NilError(t, err, "GetCwd")
+
+	gotWorkspaces, err := nodejsNpm.GetWorkspaceManifests(repoRoot.Join("../../../examples/basic"), "package.json", "apps/*", "packages/tsconfig")
+	assert.NilError(t, err, "GetWorkspaceManifests")
+	gotToSlash := make([]string, len(gotWorkspaces))
+	for index, workspace := range gotWorkspaces {
+		gotToSlash[index] = filepath.ToSlash(workspace)
+	}
+	sort.Strings(gotToSlash)
+	assert.DeepEqual(t, gotToSlash, []string{
+		filepath.ToSlash(filepath.Join(cwd, "../../../examples/basic/packages/eslint-config-custom/package.json")),
+		filepath.ToSlash(filepath.Join(cwd, "../../../examples/basic/packages/ui/package.json")),
+	})
+}
+
 func Test_GetWorkspaceIgnores(t *testing.T) {
 	type test struct {
 		name     string
@@ -42,6 +42,10 @@ var (
 	ExitCodeError = 127
 )
 
+// _groupPollInterval is how often to check whether the processes a child started
+// have exited, once the child itself has
+const _groupPollInterval = 50 * time.Millisecond
+
 // Child is a wrapper around a child process which can be used to send signals
 // and manage the processes' lifecycle.
 type Child struct {
@@ -49,8 +53,9 @@ type Child struct {
 
 	timeout time.Duration
 
-	killSignal  os.Signal
-	killTimeout time.Duration
+	killSignal      os.Signal
+	terminateSignal os.Signal
+	killTimeout     time.Duration
 
 	splay time.Duration
 
@@ -70,6 +75,10 @@ type Child struct {
 	// whether to set process group id or not (default on)
 	setpgid bool
 
+	// group tracks the child along with everything it starts, so that they can
+	// all be torn down together
+	group processGroup
+
 	Label string
 
 	logger hclog.Logger
@@ -88,6 +97,11 @@ type NewInput struct {
 	// value may be nil.
 	KillSignal os.Signal
 
+	// TerminateSignal is sent if the process is still running KillTimeout after
+	// KillSignal was sent. The process is given another KillTimeout to exit before
+	// being force-killed. This value may be nil.
+	TerminateSignal os.Signal
+
 	// KillTimeout is the amount of time to wait for the process to gracefully
 	// terminate before force-killing.
 	KillTimeout time.Duration
@@ -111,15 +125,16 @@ func newChild(i NewInput) (*Child, error) {
 	// we only need the arguments here, it will include the command itself.
 	label := fmt.Sprintf("(%v) %v", i.Cmd.Dir, strings.Join(i.Cmd.Args, " "))
 	child := &Child{
-		cmd:         i.Cmd,
-		timeout:     i.Timeout,
-		killSignal:  i.KillSignal,
-		killTimeout: i.KillTimeout,
-		splay:       i.Splay,
-		stopCh:      make(chan struct{}, 1),
-		setpgid:     true,
-		Label:       label,
-		logger:      i.Logger.Named(label),
+		cmd:             i.Cmd,
+		timeout:         i.Timeout,
+		killSignal:      i.KillSignal,
+		terminateSignal: i.TerminateSignal,
+		killTimeout:     i.KillTimeout,
+		splay:           i.Splay,
+		stopCh:          make(chan struct{}, 1),
+		setpgid:         true,
+		Label:           label,
+		logger:          i.Logger.Named(label),
 	}
 
 	return child, nil
@@ -217,6 +232,11 @@ func (c *Child) start() error {
 	if err := c.cmd.Start(); err != nil {
 		return err
 	}
+	if c.setpgid {
+		if err := c.group.attach(c.cmd); err != nil {
+			c.logger.Debug("failed to track child processes: %v", err)
+		}
+	}
 
 	// Create a new exitCh so that previously invoked commands (if any) don't
 	// cause us to exit, and start a goroutine to wait for that process to end.
@@ -332,9 +352,11 @@ func (c *Child) signal(s os.Signal) error {
 // kill sends the signal to kill the process using the configured signal
 // if set, else the default system signal
 func (c *Child) kill(immediately bool) {
-
 	if !c.running() {
 		c.logger.Debug("Kill() called but process dead; not waiting for splay.")
+		// Anything it started that outlived it would otherwise be orphaned, e.g. a dev
+		// server still holding a port
+		c.killGroup()
 		return
 	} else if immediately {
 		c.logger.Debug("Kill() called but performing immediate shutdown; not waiting for splay.")
@@ -349,37 +371,91 @@ func (c *Child) kill(immediately bool) {
 	var exited bool
 	defer func() {
 		if !exited {
-			c.logger.Debug("PKill")
+			c.logger.Info("force-killing process")
 			c.cmd.Process.Kill()
 		}
 		c.cmd = nil
+		// Anything in the group that survived every signal is force-killed
+		c.killGroup()
 	}()
 
 	if c.killSignal == nil {
 		return
 	}
 
-	if err := c.signal(c.killSignal); err != nil {
-		c.logger.Debug("Kill failed: %s", err)
-		if processNotFoundErr(err) {
-			exited = true // checked in defer
+	// Escalate through the configured signals, giving the process killTimeout to
+	// exit after each one
+	signals := []os.Signal{c.killSignal}
+	if c.terminateSignal != nil {
+		signals = append(signals, c.terminateSignal)
+	}
+	var killCh chan struct{}
+	for _, sig := range signals {
+		c.logger.Info("stopping process", "signal", sig.String())
+		if exited {
+			// The process is gone, but some of what it started is still running
+			if err := c.group.signal(sig); err != nil {
+				c.logger.Debug("Kill failed: %s", err)
+				return
+			}
+		} else if err := c.signal(sig); err != nil {
+			c.logger.Debug("Kill failed: %s", err)
+			if processNotFoundErr(err) {
+				exited = true // checked in defer
+			}
+			return
 		}
-		return
+
+		if killCh == nil {
+			killCh = make(chan struct{}, 1)
+			go func() {
+				defer close(killCh)
+				c.cmd.Process.Wait()
+			}()
+		}
+
+		timeout := time.After(c.killTimeout)
+		if !exited {
+			select {
+			case <-c.stopCh:
+				return
+			case <-killCh:
+				c.logger.Info("process exited", "signal", sig.String())
+				exited = true
+			case <-timeout:
+				c.logger.Info("process did not exit in time", "signal", sig.String(), "timeout", c.killTimeout)
+				continue
+			}
+		}
+		// The process is often a wrapper, such as npm, which exits before the processes
+		// it started. They get the rest of the timeout to exit as well.
+		if c.waitForGroup(timeout) {
+			return
+		}
+		c.logger.Info("child processes did not exit in time", "signal", sig.String(), "timeout", c.killTimeout)
 	}
+}
 
-	killCh := make(chan struct{}, 1)
-	go func() {
-		defer close(killCh)
-		c.cmd.Process.Wait()
-	}()
+// waitForGroup waits for every process in the child's group to exit, and returns
+// false if some are still running when timeout fires
+func (c *Child) waitForGroup(timeout <-chan time.Time) bool {
+	ticker := time.NewTicker(_groupPollInterval)
+	defer ticker.Stop()
+	for c.group.alive() {
+		select {
+		case <-timeout:
+			return false
+		case <-ticker.C:
+		}
+	}
+	return true
+}
 
-	select {
-	case <-c.stopCh:
-	case <-killCh:
-		exited = true
-	case <-time.After(c.killTimeout):
-		c.logger.Debug("timeout")
+func (c *Child) killGroup() {
+	if err := c.group.kill(); err != nil {
+		c.logger.Debug("failed to kill child processes: %v", err)
 	}
+	c.group.close()
 }
 
 func (c *Child) running() bool {
@@ -11,7 +11,12 @@ package process
  */
 
 import (
+	"io/ioutil"
+	"os"
 	"os/exec"
+	"path/filepath"
+	"strconv"
+	"strings"
 	"syscall"
 	"testing"
 	"time"
@@ -188,3 +193,88 @@ func TestSetpgid(t *testing.T) {
 		}
 	})
 }
+
+func TestStop_killsOrphanedDescendants(t *testing.T) {
+	c := testChild(t)
+	// The background process ignores SIGTERM, so it outlives the shell
+	c.cmd = exec.Command("sh", "-c", "(trap '' TERM; exec sleep 30) & echo $!; wait")
+	c.killSignal = syscall.SIGTERM
+	c.killTimeout = 200 * time.Millisecond
+	out := gatedio.NewByteBuffer()
+	c.cmd.Stdout = out
+
+	if err := c.Start(); err != nil {
+		t.Fatal(err)
+	}
+
+	// For some reason bash doesn't start immediately
+	time.Sleep(fileWaitSleepDelay)
+
+	descendant, err := strconv.Atoi(strings.TrimSpace(out.String()))
+	if err != nil {
+		t.Fatalf("failed to read pid of background process from %q: %v", out.String(), err)
+	}
+
+	c.Stop()
+
+	deadline := time.Now().Add(2 * time.Second)
+	for syscall.Kill(descendant, 0) == nil {
+		if time.Now().After(deadline) {
+			_ = syscall.Kill(descendant, syscall.SIGKILL)
+			t.Fatal("expected background process to be killed along with its parent")
+		}
+		time.Sleep(10 * time.Millisecond)
+	}
+}
+
+func TestStop_escalatesToTerminateSignal(t *testing.T) {
+	c := testChild(t)
+	c.cmd = exec.Command("sh", "-c", "trap '' INT; trap 'echo terminated; exit 0' TERM; while true; do sleep 0.1; done")
+	c.killSignal = syscall.SIGINT
+	c.terminateSignal = syscall.SIGTERM
+	c.killTimeout = 200 * time.Millisecond
+	out := gatedio.NewByteBuffer()
+	c.cmd.Stdout = out
+
+	if err := c.Start(); err != nil {
+		t.Fatal(err)
+	}
+
+	// For some reason bash doesn't start immediately
+	time.Sleep(fileWaitSleepDelay)
+
+	c.Stop()
+
+	expected := "terminated\n"
+	if out.String() != expected {
+		t.Errorf("expected %q to be %q", out.String(), expected)
+	}
+}
+
+func TestStop_waitsForDescendantsOfExitedWrapper(t *testing.T) {
+	c := testChild(t)
+	stopped := filepath.Join(t.TempDir(), "stopped")
+	// Like npm running a dev server, the shell exits on SIGTERM right away, while the
+	// process it started takes a moment to shut down
+	c.cmd = exec.Command("sh", "-c", "(trap 'sleep 0.3; echo stopped > \"$STOPPED\"; exit 0' TERM; while true; do sleep 0.05; done) & wait")
+	c.cmd.Env = append(os.Environ(), "STOPPED="+stopped)
+	c.killSignal = syscall.SIGTERM
+	c.killTimeout = 2 * time.Second
+
+	if err := c.Start(); err != nil {
+		t.Fatal(err)
+	}
+
+	// For some reason bash doesn't start immediately
+	time.Sleep(fileWaitSleepDelay)
+
+	c.Stop()
+
+	contents, err := ioutil.ReadFile(stopped)
+	if err != nil {
+		t.Fatalf("expected the background process to shut down before being killed: %v", err)
+	}
+	if string(contents) != "stopped\n" {
+		t.Errorf("expected %q to be %q", string(contents), "stopped\n")
+	}
+}
@@ -6,6 +6,7 @@ import (
 	"os"
 	"os/exec"
 	"sync"
+	"syscall"
 	"time"
 
 	"github.com/hashicorp/go-hclog"
@@ -26,6 +27,17 @@ func (ce *ChildExit) Error() string {
 	return fmt.Sprintf("command %s exited (%d)", ce.Command, ce.ExitCode)
 }
 
+// TimedOut is returned when a child process was stopped because it was still running
+// when its timeout elapsed
+type TimedOut struct {
+	Timeout time.Duration
+	Command string
+}
+
+func (to *TimedOut) Error() string {
+	return fmt.Sprintf("command %s did not finish within %v", to.Command, to.Timeout)
+}
+
 // Manager tracks all of the child processes that have been spawned
 type Manager struct {
 	done     bool
@@ -44,11 +56,29 @@ func NewManager(logger hclog.Logger) *Manager {
 	}
 }
 
+// DefaultShutdownTimeout is how long a child process is given to exit after
+// each step of stopping it, unless another timeout is given
+const DefaultShutdownTimeout = 10 * time.Second
+
 // Exec spawns a child process to run the given command, then blocks
 // until it completes. Returns a nil error if the child process finished
 // successfully, ErrClosing if the manager closed during execution, and
 // a ChildExit error if the child process exited with a non-zero exit code.
 func (m *Manager) Exec(cmd *exec.Cmd) error {
+	return m.ExecWithShutdownTimeout(cmd, DefaultShutdownTimeout)
+}
+
+// ExecWithShutdownTimeout behaves like Exec, but when the manager closes, the child
+// process is stopped by sending SIGINT, then SIGTERM after shutdownTimeout, and then
+// force-killing it after another shutdownTimeout.
+func (m *Manager) ExecWithShutdownTimeout(cmd *exec.Cmd, shutdownTimeout time.Duration) error {
+	return m.ExecWithTimeout(cmd, shutdownTimeout, 0)
+}
+
+// ExecWithTimeout behaves like ExecWithShutdownTimeout, but if the child process is still
+// running after timeout, it is stopped the same way and a TimedOut error is returned. A
+// timeout of 0 lets the child process run until it exits.
+func (m *Manager) ExecWithTimeout(cmd *exec.Cmd, shutdownTimeout time.Duration, timeout time.Duration) error {
 	m.mu.Lock()
 	if m.done {
 		m.mu.Unlock()
@@ -59,11 +89,12 @@ func (m *Manager) Exec(cmd *exec.Cmd) error {
 		Cmd: cmd,
 		// Run forever by default
 		Timeout: 0,
-		// When it's time to exit, give a 10 second timeout
-		KillTimeout: 10 * time.Second,
-		// Send SIGINT to stop children
-		KillSignal: os.Interrupt,
-		Logger:     m.logger,
+		// When it's time to exit, give the child time to exit after each signal
+		KillTimeout: shutdownTimeout,
+		// Send SIGINT to stop children, followed by SIGTERM if that doesn't work
+		KillSignal:      os.Interrupt,
+		TerminateSignal: syscall.SIGTERM,
+		Logger:          m.logger,
 	})
 	if err != nil {
 		return err
@@ -78,14 +109,29 @@ func (m *Manager) Exec(cmd *exec.Cmd) error {
 		m.mu.Unlock()
 		return err
 	}
+	var timedOut <-chan time.Time
+	if timeout > 0 {
+		timer := time.NewTimer(timeout)
+		defer timer.Stop()
+		timedOut = timer.C
+	}
 	err = nil
-	exitCode, ok := <-child.ExitCh()
-	if !ok {
-		err = ErrClosing
-	} else if exitCode != ExitCodeOK {
-		err = &ChildExit{
-			ExitCode: exitCode,
-			Command:  child.Command(),
+	select {
+	case exitCode, ok := <-child.ExitCh():
+		if !ok {
+			err = ErrClosing
+		} else if exitCode != ExitCodeOK {
+			err = &ChildExit{
+				ExitCode: exitCode,
+				Command:  child.Command(),
+			}
+		}
+	case <-timedOut:
+		// Stop blocks until the child has exited or been killed
+		child.Stop()
+		err = &TimedOut{
+			Timeout: timeout,
+			Command: child.Command(),
 		}
 	}
 
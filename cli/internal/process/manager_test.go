@@ -92,3 +92,24 @@ func TestExitCode(t *testing.T) {
 		t.Error("expected non-zero exit code , got 0")
 	}
 }
+
+func TestExecWithTimeout(t *testing.T) {
+	mgr := newManager()
+
+	start := time.Now()
+	err := mgr.ExecWithTimeout(exec.Command("sleep", "5"), 100*time.Millisecond, 100*time.Millisecond)
+	timedOut := &TimedOut{}
+	if !errors.As(err, &timedOut) {
+		t.Errorf("expected a TimedOut err, got %q", err)
+	}
+	if timedOut.Timeout != 100*time.Millisecond {
+		t.Errorf("expected a timeout of 100ms, got %v", timedOut.Timeout)
+	}
+	if duration := time.Since(start); duration >= 5*time.Second {
+		t.Errorf("expected the child to be stopped at its timeout, took %v", duration)
+	}
+
+	if err := mgr.ExecWithTimeout(exec.Command("sleep", "0.1"), 100*time.Millisecond, 5*time.Second); err != nil {
+		t.Errorf("expected %q to be nil", err)
+	}
+}
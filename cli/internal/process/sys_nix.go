@@ -9,6 +9,8 @@ package process
  */
 
 import (
+	"fmt"
+	"os"
 	"os/exec"
 	"syscall"
 )
@@ -21,3 +23,48 @@ func processNotFoundErr(err error) bool {
 	// ESRCH == no such process, ie. already exited
 	return err == syscall.ESRCH
 }
+
+// processGroup is everything started by a child process. On unix, this is the
+// child's process group, which it leads when setpgid is enabled.
+type processGroup struct {
+	pgid int
+}
+
+func (g *processGroup) attach(cmd *exec.Cmd) error {
+	g.pgid = cmd.Process.Pid
+	return nil
+}
+
+// signal sends sig to every process still in the group
+func (g *processGroup) signal(sig os.Signal) error {
+	if g.pgid == 0 {
+		return nil
+	}
+	unixSig, ok := sig.(syscall.Signal)
+	if !ok {
+		return fmt.Errorf("bad signal: %s", sig)
+	}
+	if err := syscall.Kill(-g.pgid, unixSig); err != nil && !processNotFoundErr(err) {
+		return err
+	}
+	return nil
+}
+
+// alive returns true if any process in the group is still running
+func (g *processGroup) alive() bool {
+	return g.pgid != 0 && syscall.Kill(-g.pgid, 0) == nil
+}
+
+// kill force-kills every process still in the group, including any that
+// outlived the child that started them
+func (g *processGroup) kill() error {
+	if g.pgid == 0 {
+		return nil
+	}
+	if err := syscall.Kill(-g.pgid, syscall.SIGKILL); err != nil && !processNotFoundErr(err) {
+		return err
+	}
+	return nil
+}
+
+func (g *processGroup) close() {}
@@ -8,10 +8,81 @@ package process
  * https://github.com/hashicorp/consul-template/tree/3ea7d99ad8eff17897e0d63dac86d74770170bb8/child/sys_windows.go
  */
 
-import "os/exec"
+import (
+	"os"
+	"os/exec"
+	"unsafe"
+
+	"golang.org/x/sys/windows"
+)
 
 func setSetpgid(cmd *exec.Cmd, value bool) {}
 
 func processNotFoundErr(err error) bool {
 	return false
 }
+
+// processGroup is everything started by a child process. On windows, this is a
+// job object that the child is assigned to, which its own children join as well.
+// The job is configured to kill everything in it when its last handle is closed,
+// so that nothing outlives turbo, even if turbo itself exits abruptly.
+// Anything the child starts before it is assigned to the job is not included.
+type processGroup struct {
+	job windows.Handle
+}
+
+func (g *processGroup) attach(cmd *exec.Cmd) error {
+	job, err := windows.CreateJobObject(nil, nil)
+	if err != nil {
+		return err
+	}
+	info := windows.JOBOBJECT_EXTENDED_LIMIT_INFORMATION{
+		BasicLimitInformation: windows.JOBOBJECT_BASIC_LIMIT_INFORMATION{
+			LimitFlags: windows.JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+		},
+	}
+	if _, err := windows.SetInformationJobObject(job, windows.JobObjectExtendedLimitInformation, uintptr(unsafe.Pointer(&info)), uint32(unsafe.Sizeof(info))); err != nil {
+		_ = windows.CloseHandle(job)
+		return err
+	}
+	process, err := windows.OpenProcess(windows.PROCESS_SET_QUOTA|windows.PROCESS_TERMINATE, false, uint32(cmd.Process.Pid))
+	if err != nil {
+		_ = windows.CloseHandle(job)
+		return err
+	}
+	defer func() { _ = windows.CloseHandle(process) }()
+	if err := windows.AssignProcessToJobObject(job, process); err != nil {
+		_ = windows.CloseHandle(job)
+		return err
+	}
+	g.job = job
+	return nil
+}
+
+// signal does nothing, since windows processes can't be sent signals. Whatever is
+// left in the job is terminated by kill.
+func (g *processGroup) signal(sig os.Signal) error {
+	return nil
+}
+
+// alive always returns false, so that nothing waits for the job to empty before
+// kill terminates it
+func (g *processGroup) alive() bool {
+	return false
+}
+
+// kill terminates every process still in the job, including any that
+// outlived the child that started them
+func (g *processGroup) kill() error {
+	if g.job == 0 {
+		return nil
+	}
+	return windows.TerminateJobObject(g.job, 1)
+}
+
+func (g *processGroup) close() {
+	if g.job != 0 {
+		_ = windows.CloseHandle(g.job)
+		g.job = 0
+	}
+}
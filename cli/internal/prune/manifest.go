@@ -0,0 +1,136 @@
+package prune
+
+import (
+	"encoding/json"
+	"path/filepath"
+	"sort"
+
+	"github.com/pkg/errors"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+// Reasons that a file was copied into the pruned output
+const (
+	reasonWorkspace = "workspace"
+	reasonRootFile  = "root-file"
+	reasonLockfile  = "lockfile"
+	reasonPatch     = "patch"
+)
+
+// manifestEntry describes a single file written to the pruned output. Paths are
+// unix-style and relative to the repository root.
+type manifestEntry struct {
+	Source      string `json:"source"`
+	Destination string `json:"destination"`
+	Reason      string `json:"reason"`
+	Package     string `json:"package,omitempty"`
+}
+
+// manifest records every file written by prune so that other tools can consume
+// the result without walking the output directory. A nil manifest records nothing.
+type manifest struct {
+	repoRoot fs.AbsolutePath
+	Files    []manifestEntry `json:"files"`
+}
+
+func newManifest(repoRoot fs.AbsolutePath) *manifest {
+	return &manifest{
+		repoRoot: repoRoot,
+		Files:    []manifestEntry{},
+	}
+}
+
+// readManifest loads the manifest written by a previous prune, so that appending to
+// the pruned output keeps its entries. A missing manifest starts out empty.
+func readManifest(repoRoot fs.AbsolutePath, path fs.AbsolutePath) (*manifest, error) {
+	m := newManifest(repoRoot)
+	if !path.FileExists() {
+		return m, nil
+	}
+	contents, err := path.ReadFile()
+	if err != nil {
+		return nil, err
+	}
+	if err := json.Unmarshal(contents, m); err != nil {
+		return nil, errors.Wrapf(err, "failed to parse prune manifest %v", path)
+	}
+	return m, nil
+}
+
+func (m *manifest) relative(path fs.AbsolutePath) (string, error) {
+	relativePath, err := m.repoRoot.RelativePathString(path.ToString())
+	if err != nil {
+		return "", err
+	}
+	return filepath.ToSlash(relativePath), nil
+}
+
+func (m *manifest) add(source fs.AbsolutePath, destination fs.AbsolutePath, reason string, packageName string) error {
+	sourcePath, err := m.relative(source)
+	if err != nil {
+		return err
+	}
+	destinationPath, err := m.relative(destination)
+	if err != nil {
+		return err
+	}
+	m.Files = append(m.Files, manifestEntry{
+		Source:      sourcePath,
+		Destination: destinationPath,
+		Reason:      reason,
+		Package:     packageName,
+	})
+	return nil
+}
+
+// record adds an entry for source, or for every file underneath source if it is a
+// directory, having been copied to destination
+func (m *manifest) record(source fs.AbsolutePath, destination fs.AbsolutePath, reason string, packageName string) error {
+	if m == nil {
+		return nil
+	}
+	info, err := source.Lstat()
+	if err != nil {
+		return err
+	}
+	if !info.IsDir() {
+		return m.add(source, destination, reason, packageName)
+	}
+	return fs.Walk(source.ToStringDuringMigration(), func(name string, isDir bool) error {
+		if isDir {
+			return nil
+		}
+		relativePath, err := source.RelativePathString(name)
+		if err != nil {
+			return err
+		}
+		return m.add(source.Join(relativePath), destination.Join(relativePath), reason, packageName)
+	})
+}
+
+// write saves the manifest as JSON, sorted by destination. Where a destination was
+// written more than once, the latest entry wins.
+func (m *manifest) write(path fs.AbsolutePath) error {
+	latest := make(map[string]int, len(m.Files))
+	files := make([]manifestEntry, 0, len(m.Files))
+	for _, entry := range m.Files {
+		if i, ok := latest[entry.Destination]; ok {
+			files[i] = entry
+			continue
+		}
+		latest[entry.Destination] = len(files)
+		files = append(files, entry)
+	}
+	m.Files = files
+	sort.Slice(m.Files, func(i, j int) bool {
+		return m.Files[i].Destination < m.Files[j].Destination
+	})
+	contents, err := json.MarshalIndent(m, "", "  ")
+	if err != nil {
+		return err
+	}
+	if err := path.EnsureDir(); err != nil {
+		return errors.Wrap(err, "failed to create folder for prune manifest")
+	}
+	return path.WriteFile(append(contents, '\n'), 0644)
+}
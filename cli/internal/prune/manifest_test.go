@@ -0,0 +1,68 @@
+package prune
+
+import (
+	"encoding/json"
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+func TestManifestRecord(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	for _, file := range []string{"package.json", "src/index.ts"} {
+		path := repoRoot.Join("packages", "ui", file)
+		assert.NilError(t, path.EnsureDir(), "EnsureDir")
+		assert.NilError(t, path.WriteFile([]byte("{}"), 0644), "WriteFile")
+	}
+	assert.NilError(t, repoRoot.Join("turbo.json").WriteFile([]byte("{}"), 0644), "WriteFile")
+
+	m := newManifest(repoRoot)
+	err := m.record(repoRoot.Join("packages", "ui"), repoRoot.Join("out", "packages", "ui"), reasonWorkspace, "ui")
+	assert.NilError(t, err, "record")
+	err = m.record(repoRoot.Join("turbo.json"), repoRoot.Join("out", "turbo.json"), reasonRootFile, "")
+	assert.NilError(t, err, "record")
+
+	manifestPath := repoRoot.Join("prune-manifest.json")
+	assert.NilError(t, m.write(manifestPath), "write")
+	contents, err := manifestPath.ReadFile()
+	assert.NilError(t, err, "ReadFile")
+	var written manifest
+	assert.NilError(t, json.Unmarshal(contents, &written), "Unmarshal")
+	assert.DeepEqual(t, written.Files, []manifestEntry{
+		{Source: "packages/ui/package.json", Destination: "out/packages/ui/package.json", Reason: reasonWorkspace, Package: "ui"},
+		{Source: "packages/ui/src/index.ts", Destination: "out/packages/ui/src/index.ts", Reason: reasonWorkspace, Package: "ui"},
+		{Source: "turbo.json", Destination: "out/turbo.json", Reason: reasonRootFile},
+	})
+
+	var nilManifest *manifest
+	assert.NilError(t, nilManifest.record(repoRoot.Join("turbo.json"), repoRoot.Join("out", "turbo.json"), reasonRootFile, ""))
+}
+
+func TestManifestAppend(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	for _, file := range []string{"turbo.json", "yarn.lock"} {
+		assert.NilError(t, repoRoot.Join(file).WriteFile([]byte("{}"), 0644), "WriteFile")
+	}
+	manifestPath := repoRoot.Join("prune-manifest.json")
+
+	m, err := readManifest(repoRoot, manifestPath)
+	assert.NilError(t, err, "readManifest")
+	assert.NilError(t, m.record(repoRoot.Join("turbo.json"), repoRoot.Join("out", "turbo.json"), reasonRootFile, ""), "record")
+	assert.NilError(t, m.record(repoRoot.Join("yarn.lock"), repoRoot.Join("out", "yarn.lock"), reasonLockfile, ""), "record")
+	assert.NilError(t, m.write(manifestPath), "write")
+
+	m, err = readManifest(repoRoot, manifestPath)
+	assert.NilError(t, err, "readManifest")
+	assert.NilError(t, m.record(repoRoot.Join("yarn.lock"), repoRoot.Join("out", "yarn.lock"), reasonLockfile, ""), "record")
+	assert.NilError(t, m.write(manifestPath), "write")
+
+	contents, err := manifestPath.ReadFile()
+	assert.NilError(t, err, "ReadFile")
+	var written manifest
+	assert.NilError(t, json.Unmarshal(contents, &written), "Unmarshal")
+	assert.DeepEqual(t, written.Files, []manifestEntry{
+		{Source: "turbo.json", Destination: "out/turbo.json", Reason: reasonRootFile},
+		{Source: "yarn.lock", Destination: "out/yarn.lock", Reason: reasonLockfile},
+	})
+}
@@ -0,0 +1,103 @@
+package prune
+
+import (
+	"fmt"
+	"net/url"
+	"path/filepath"
+	"sort"
+	"strings"
+
+	"github.com/pkg/errors"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+// _patchPackageDir is where patch-package reads patches from by default
+const _patchPackageDir = "patches"
+
+// yarnPatchFiles returns the repo-relative, unix-style paths of the patch files
+// referenced by `patch:` protocol entries in the given lockfile, for instance
+// "resolve@patch:resolve@npm%3A1.22.0#~/.yarn/patches/resolve-npm-1.22.0-1a2b3c.patch::version=1.22.0".
+// Patches that ship with yarn itself (~builtin<...>) don't exist on disk and are skipped.
+func yarnPatchFiles(lockfile fs.YarnLockfile) []string {
+	seen := make(map[string]struct{})
+	for key := range lockfile {
+		for _, descriptor := range strings.Split(key, ",") {
+			descriptor = strings.Trim(strings.TrimSpace(descriptor), "\"")
+			patchIndex := strings.Index(descriptor, "@patch:")
+			if patchIndex == -1 {
+				continue
+			}
+			hashIndex := strings.LastIndex(descriptor[patchIndex:], "#")
+			if hashIndex == -1 {
+				continue
+			}
+			source := descriptor[patchIndex+hashIndex+1:]
+			if paramsIndex := strings.Index(source, "::"); paramsIndex != -1 {
+				source = source[:paramsIndex]
+			}
+			for _, patch := range strings.Split(source, "&") {
+				if unescaped, err := url.PathUnescape(patch); err == nil {
+					patch = unescaped
+				}
+				if patch == "" || strings.HasPrefix(patch, "~builtin") {
+					continue
+				}
+				patch = strings.TrimPrefix(patch, "~/")
+				seen[filepath.ToSlash(filepath.Clean(patch))] = struct{}{}
+			}
+		}
+	}
+	patches := make([]string, 0, len(seen))
+	for patch := range seen {
+		patches = append(patches, patch)
+	}
+	sort.Strings(patches)
+	return patches
+}
+
+// usesPatchPackage returns true if any of the root package's scripts run patch-package
+func usesPatchPackage(rootPackageJSON *fs.PackageJSON) bool {
+	for _, script := range rootPackageJSON.Scripts {
+		if strings.Contains(script, "patch-package") {
+			return true
+		}
+	}
+	return false
+}
+
+// copyPatches copies the dependency patches needed to install the pruned monorepo
+// into each of the given output directories. Patches that live inside a workspace
+// are copied along with that workspace, so only patches at the root are handled here.
+func (p *prune) copyPatches(lockfile fs.YarnLockfile, outDirs ...fs.AbsolutePath) error {
+	patches := yarnPatchFiles(lockfile)
+	if usesPatchPackage(p.config.RootPackageJSON) && p.config.Cwd.Join(_patchPackageDir).DirExists() {
+		patches = append(patches, _patchPackageDir)
+	}
+	for _, patch := range patches {
+		source := p.config.Cwd.Join(filepath.FromSlash(patch))
+		if contained, err := p.config.Cwd.ContainsPath(source); err != nil {
+			return err
+		} else if !contained {
+			p.logger.Warn(fmt.Sprintf("skipping patch %v outside of the repository", patch))
+			continue
+		}
+		if _, err := source.Lstat(); err != nil {
+			p.logger.Warn(fmt.Sprintf("skipping missing patch %v", patch))
+			continue
+		}
+		for _, outDir := range outDirs {
+			target := outDir.Join(filepath.FromSlash(patch))
+			if err := target.EnsureDir(); err != nil {
+				return errors.Wrapf(err, "failed to create folder for patch %v", patch)
+			}
+			if err := fs.RecursiveCopy(source.ToStringDuringMigration(), target.ToStringDuringMigration()); err != nil {
+				return errors.Wrapf(err, "failed to copy patch %v", patch)
+			}
+			if err := p.manifest.record(source, target, reasonPatch, ""); err != nil {
+				return errors.Wrapf(err, "failed to record patch %v in prune manifest", patch)
+			}
+		}
+		p.ui.Output(fmt.Sprintf(" - Added patch %v", patch))
+	}
+	return nil
+}
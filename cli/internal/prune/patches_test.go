@@ -0,0 +1,29 @@
+package prune
+
+import (
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+func TestYarnPatchFiles(t *testing.T) {
+	lockfile := make(fs.YarnLockfile)
+	for _, key := range []string{
+		"lodash@npm:^4.17.21",
+		"resolve@patch:resolve@^1.20.0#~builtin<compat/resolve>",
+		"left-pad@patch:left-pad@npm%3A1.3.0#~/.yarn/patches/left-pad-npm-1.3.0-abc.patch::version=1.3.0&hash=123&locator=root%40workspace%3A.",
+		"react@patch:react@npm%3A18.2.0#./patches/react.patch::locator=root%40workspace%3A., react@patch:react@npm%3A18.2.0#./patches/react.patch",
+	} {
+		lockfile[key] = &fs.LockfileEntry{}
+	}
+	assert.DeepEqual(t, yarnPatchFiles(lockfile), []string{
+		".yarn/patches/left-pad-npm-1.3.0-abc.patch",
+		"patches/react.patch",
+	})
+}
+
+func TestUsesPatchPackage(t *testing.T) {
+	assert.Assert(t, usesPatchPackage(&fs.PackageJSON{Scripts: map[string]string{"postinstall": "patch-package"}}))
+	assert.Assert(t, !usesPatchPackage(&fs.PackageJSON{Scripts: map[string]string{"build": "turbo run build"}}))
+}
@@ -5,8 +5,10 @@ import (
 	"bytes"
 	"fmt"
 	"os"
+	"sort"
 	"strings"
 
+	"github.com/pyr-sh/dag"
 	"github.com/spf13/cobra"
 	"github.com/spf13/pflag"
 	"github.com/vercel/turborepo/cli/internal/cache"
@@ -51,15 +53,19 @@ func (c *PruneCommand) Run(args []string) int {
 }
 
 type opts struct {
-	scope     string
+	scope     []string
 	docker    bool
 	outputDir string
+	append    bool
+	manifest  string
 }
 
 func addPruneFlags(opts *opts, flags *pflag.FlagSet) {
-	flags.StringVar(&opts.scope, "scope", "", "Specify package to act as entry point for pruned monorepo (required).")
+	flags.StringArrayVar(&opts.scope, "scope", nil, "Specify package to act as entry point for pruned monorepo (required). Can be passed multiple times.")
 	flags.BoolVar(&opts.docker, "docker", false, "Output pruned workspace into 'full' and 'json' directories optimized for Docker layer caching.")
 	flags.StringVar(&opts.outputDir, "out-dir", "out", "Set the root directory for files output by this command")
+	flags.StringVar(&opts.manifest, "manifest", "", "Write a JSON manifest of every file written to the output directory, with its source and the reason it was included, to the given path. With --append, entries already in the manifest are kept.")
+	flags.BoolVar(&opts.append, "append", false, "Add to an existing pruned workspace in the output directory. Packages already there are copied again to pick up changes, and the lockfile covers both old and new packages.")
 	// No-op the cwd flag while the root level command is not yet cobra
 	_ = flags.String("cwd", "", "")
 	if err := flags.MarkHidden("cwd"); err != nil {
@@ -71,19 +77,15 @@ func addPruneFlags(opts *opts, flags *pflag.FlagSet) {
 func getCmd(config *config.Config, ui cli.Ui) *cobra.Command {
 	opts := &opts{}
 	cmd := &cobra.Command{
-		Use:                   "turbo prune --scope=<package name> [<flags>]",
+		Use:                   "turbo prune [<package name>...] [--scope=<package name>...] [<flags>]",
 		Short:                 "Prepare a subset of your monorepo.",
 		SilenceUsage:          true,
 		SilenceErrors:         true,
 		DisableFlagsInUseLine: true,
 		RunE: func(cmd *cobra.Command, args []string) error {
 			logger := config.Logger.Named("prune")
-			if len(args) > 0 {
-				err := errors.Errorf("unexpected arguments: %v", args)
-				logError(logger, ui, err)
-				return err
-			}
-			if opts.scope == "" {
+			opts.scope = append(opts.scope, args...)
+			if len(opts.scope) == 0 {
 				err := errors.New("at least one target must be specified")
 				logError(logger, ui, err)
 				return err
@@ -111,9 +113,10 @@ func logError(logger hclog.Logger, ui cli.Ui, err error) {
 }
 
 type prune struct {
-	logger hclog.Logger
-	ui     cli.Ui
-	config *config.Config
+	logger   hclog.Logger
+	ui       cli.Ui
+	config   *config.Config
+	manifest *manifest
 }
 
 // Prune creates a smaller monorepo with only the required workspaces
@@ -128,17 +131,20 @@ func (p *prune) prune(opts *opts) error {
 		return errors.Wrap(err, "could not construct graph")
 	}
 	p.logger.Trace("scope", "value", opts.scope)
-	target, scopeIsValid := ctx.PackageInfos[opts.scope]
-	if !scopeIsValid {
-		return errors.Errorf("invalid scope: package %v not found", opts.scope)
+	for _, scope := range opts.scope {
+		target, scopeIsValid := ctx.PackageInfos[scope]
+		if !scopeIsValid {
+			return errors.Errorf("invalid scope: package %v not found", scope)
+		}
+		p.logger.Trace("target", "value", target.Name)
+		p.logger.Trace("directory", "value", target.Dir)
+		p.logger.Trace("external deps", "value", target.UnresolvedExternalDeps)
+		p.logger.Trace("internal deps", "value", target.InternalDeps)
 	}
 	outDir := p.config.Cwd.Join(opts.outputDir)
-	p.logger.Trace("target", "value", target.Name)
-	p.logger.Trace("directory", "value", target.Dir)
-	p.logger.Trace("external deps", "value", target.UnresolvedExternalDeps)
-	p.logger.Trace("internal deps", "value", target.InternalDeps)
 	p.logger.Trace("docker", "value", opts.docker)
 	p.logger.Trace("out dir", "value", outDir.ToString())
+	p.logger.Trace("append", "value", opts.append)
 
 	if !util.IsYarn(ctx.PackageManager.Name) {
 		return errors.Errorf("this command is not yet implemented for %s", ctx.PackageManager.Name)
@@ -150,7 +156,16 @@ func (p *prune) prune(opts *opts) error {
 		}
 	}
 
-	p.ui.Output(fmt.Sprintf("Generating pruned monorepo for %v in %v", ui.Bold(opts.scope), ui.Bold(outDir.ToString())))
+	if opts.manifest != "" && opts.append {
+		p.manifest, err = readManifest(p.config.Cwd, p.config.Cwd.Join(opts.manifest))
+		if err != nil {
+			return err
+		}
+	} else if opts.manifest != "" {
+		p.manifest = newManifest(p.config.Cwd)
+	}
+
+	p.ui.Output(fmt.Sprintf("Generating pruned monorepo for %v in %v", ui.Bold(strings.Join(opts.scope, ", ")), ui.Bold(outDir.ToString())))
 
 	packageJSONPath := outDir.Join("package.json")
 	if err := packageJSONPath.EnsureDir(); err != nil {
@@ -158,88 +173,82 @@ func (p *prune) prune(opts *opts) error {
 	}
 	workspaces := []string{}
 	lockfile := p.config.RootPackageJSON.SubLockfile
-	targets := []interface{}{opts.scope}
-	internalDeps, err := ctx.TopologicalGraph.Ancestors(opts.scope)
+	// When appending, packages from previous prunes stay in the output and in the lockfile.
+	// They're copied again so that the output reflects their current contents.
+	existing := make(dag.Set)
+	if opts.append {
+		existing = p.previouslyPruned(ctx, outDir, opts.docker)
+		p.logger.Trace("previously pruned", "value", existing.List())
+	}
+	sortedTargets, err := pruneTargets(&ctx.TopologicalGraph, ctx.RootNode, opts.scope, existing)
 	if err != nil {
 		return errors.Wrap(err, "could find traverse the dependency graph to find topological dependencies")
 	}
-	targets = append(targets, internalDeps.List()...)
 
-	for _, internalDep := range targets {
-		if internalDep == ctx.RootNode {
-			continue
-		}
-		workspaces = append(workspaces, ctx.PackageInfos[internalDep].Dir)
-		if opts.docker {
-			targetDir := outDir.Join("full", ctx.PackageInfos[internalDep].Dir)
-			jsonDir := outDir.Join("json", ctx.PackageInfos[internalDep].PackageJSONPath)
-			if err := targetDir.EnsureDir(); err != nil {
-				return errors.Wrapf(err, "failed to create folder %v for %v", targetDir, internalDep)
-			}
-			if err := fs.RecursiveCopy(ctx.PackageInfos[internalDep].Dir, targetDir.ToStringDuringMigration()); err != nil {
-				return errors.Wrapf(err, "failed to copy %v into %v", internalDep, targetDir)
-			}
-			if err := jsonDir.EnsureDir(); err != nil {
-				return errors.Wrapf(err, "failed to create folder %v for %v", jsonDir, internalDep)
-			}
-			if err := fs.RecursiveCopy(ctx.PackageInfos[internalDep].PackageJSONPath, jsonDir.ToStringDuringMigration()); err != nil {
-				return errors.Wrapf(err, "failed to copy %v into %v", internalDep, jsonDir)
-			}
-		} else {
-			targetDir := outDir.Join(ctx.PackageInfos[internalDep].Dir)
-			if err := targetDir.EnsureDir(); err != nil {
-				return errors.Wrapf(err, "failed to create folder %v for %v", targetDir, internalDep)
-			}
-			if err := fs.RecursiveCopy(ctx.PackageInfos[internalDep].Dir, targetDir.ToStringDuringMigration()); err != nil {
-				return errors.Wrapf(err, "failed to copy %v into %v", internalDep, targetDir)
-			}
+	for _, internalDep := range sortedTargets {
+		pkg := ctx.PackageInfos[internalDep]
+		workspaces = append(workspaces, pkg.Dir)
+		if err := p.copyWorkspace(pkg, outDir, opts.docker); err != nil {
+			return err
 		}
 
-		for k, v := range ctx.PackageInfos[internalDep].SubLockfile {
+		for k, v := range pkg.SubLockfile {
 			lockfile[k] = v
 		}
 
-		p.ui.Output(fmt.Sprintf(" - Added %v", ctx.PackageInfos[internalDep].Name))
+		if existing.Include(internalDep) {
+			p.ui.Output(fmt.Sprintf(" - Updated %v", pkg.Name))
+		} else {
+			p.ui.Output(fmt.Sprintf(" - Added %v", pkg.Name))
+		}
 	}
 	p.logger.Trace("new workspaces", "value", workspaces)
 	if opts.docker {
 		if fs.FileExists(".gitignore") {
-			if err := fs.CopyFile(&fs.LstatCachedFile{Path: p.config.Cwd.Join(".gitignore")}, outDir.Join("full", ".gitignore").ToStringDuringMigration()); err != nil {
+			if err := p.copyRootFile(".gitignore", outDir.Join("full", ".gitignore")); err != nil {
 				return errors.Wrap(err, "failed to copy root .gitignore")
 			}
 		}
 		// We only need to actually copy turbo.json into "full" folder since it isn't needed for installation in docker
 		if fs.FileExists("turbo.json") {
-			if err := fs.CopyFile(&fs.LstatCachedFile{Path: p.config.Cwd.Join("turbo.json")}, outDir.Join("full", "turbo.json").ToStringDuringMigration()); err != nil {
+			if err := p.copyRootFile("turbo.json", outDir.Join("full", "turbo.json")); err != nil {
 				return errors.Wrap(err, "failed to copy root turbo.json")
 			}
 		}
 
-		if err := fs.CopyFile(&fs.LstatCachedFile{Path: p.config.Cwd.Join("package.json")}, outDir.Join("full", "package.json").ToStringDuringMigration()); err != nil {
+		if err := p.copyRootFile("package.json", outDir.Join("full", "package.json")); err != nil {
 			return errors.Wrap(err, "failed to copy root package.json")
 		}
 
-		if err := fs.CopyFile(&fs.LstatCachedFile{Path: p.config.Cwd.Join("package.json")}, outDir.Join("json", "package.json").ToStringDuringMigration()); err != nil {
+		if err := p.copyRootFile("package.json", outDir.Join("json", "package.json")); err != nil {
 			return errors.Wrap(err, "failed to copy root package.json")
 		}
 	} else {
 		if fs.FileExists(".gitignore") {
-			if err := fs.CopyFile(&fs.LstatCachedFile{Path: p.config.Cwd.Join(".gitignore")}, outDir.Join(".gitignore").ToStringDuringMigration()); err != nil {
+			if err := p.copyRootFile(".gitignore", outDir.Join(".gitignore")); err != nil {
 				return errors.Wrap(err, "failed to copy root .gitignore")
 			}
 		}
 
 		if fs.FileExists("turbo.json") {
-			if err := fs.CopyFile(&fs.LstatCachedFile{Path: p.config.Cwd.Join("turbo.json")}, outDir.Join("turbo.json").ToStringDuringMigration()); err != nil {
+			if err := p.copyRootFile("turbo.json", outDir.Join("turbo.json")); err != nil {
 				return errors.Wrap(err, "failed to copy root turbo.json")
 			}
 		}
 
-		if err := fs.CopyFile(&fs.LstatCachedFile{Path: p.config.Cwd.Join("package.json")}, outDir.Join("package.json").ToStringDuringMigration()); err != nil {
+		if err := p.copyRootFile("package.json", outDir.Join("package.json")); err != nil {
 			return errors.Wrap(err, "failed to copy root package.json")
 		}
 	}
 
+	patchDirs := []fs.AbsolutePath{outDir}
+	if opts.docker {
+		patchDirs = []fs.AbsolutePath{outDir.Join("json"), outDir.Join("full")}
+	}
+	if err := p.copyPatches(lockfile, patchDirs...); err != nil {
+		return err
+	}
+
 	var b bytes.Buffer
 	yamlEncoder := yaml.NewEncoder(&b)
 	yamlEncoder.SetIndent(2)
@@ -298,5 +307,105 @@ func (p *prune) prune(opts *opts) error {
 	if err := os.Rename(yarnTmpFilePath.ToStringDuringMigration(), lockFilePath.ToStringDuringMigration()); err != nil {
 		return errors.Wrap(err, "failed finalize lockfile")
 	}
+
+	if p.manifest != nil {
+		if err := p.manifest.record(p.config.Cwd.Join("yarn.lock"), lockFilePath, reasonLockfile, ""); err != nil {
+			return errors.Wrap(err, "failed to record lockfile in prune manifest")
+		}
+		if err := p.manifest.write(p.config.Cwd.Join(opts.manifest)); err != nil {
+			return errors.Wrap(err, "failed to write prune manifest")
+		}
+	}
 	return nil
 }
+
+// pruneTargets returns the sorted names of the packages to include in the pruned output:
+// each scope, its internal dependencies, and any packages that already exist there
+func pruneTargets(graph *dag.AcyclicGraph, rootNode string, scopes []string, existing dag.Set) ([]string, error) {
+	targets := make(dag.Set)
+	for _, scope := range scopes {
+		targets.Add(scope)
+		internalDeps, err := graph.Ancestors(scope)
+		if err != nil {
+			return nil, err
+		}
+		for _, internalDep := range internalDeps.List() {
+			targets.Add(internalDep)
+		}
+	}
+	for _, existingDep := range existing.List() {
+		targets.Add(existingDep)
+	}
+	sortedTargets := make([]string, 0, targets.Len())
+	for _, target := range targets.List() {
+		if target == rootNode {
+			continue
+		}
+		sortedTargets = append(sortedTargets, target.(string))
+	}
+	sort.Strings(sortedTargets)
+	return sortedTargets, nil
+}
+
+// copyWorkspace copies a workspace package into the pruned output, overwriting any
+// files from a previous prune. With docker, its package.json is also copied into
+// the "json" directory.
+func (p *prune) copyWorkspace(pkg *fs.PackageJSON, outDir fs.AbsolutePath, docker bool) error {
+	source := p.config.Cwd.Join(pkg.Dir)
+	targetDir := outDir.Join(pkg.Dir)
+	if docker {
+		targetDir = outDir.Join("full", pkg.Dir)
+	}
+	if err := targetDir.EnsureDir(); err != nil {
+		return errors.Wrapf(err, "failed to create folder %v for %v", targetDir, pkg.Name)
+	}
+	if err := fs.RecursiveCopy(source.ToStringDuringMigration(), targetDir.ToStringDuringMigration()); err != nil {
+		return errors.Wrapf(err, "failed to copy %v into %v", pkg.Name, targetDir)
+	}
+	if err := p.manifest.record(source, targetDir, reasonWorkspace, pkg.Name); err != nil {
+		return errors.Wrapf(err, "failed to record %v in prune manifest", pkg.Name)
+	}
+	if !docker {
+		return nil
+	}
+	packageJSON := p.config.Cwd.Join(pkg.PackageJSONPath)
+	jsonDir := outDir.Join("json", pkg.PackageJSONPath)
+	if err := jsonDir.EnsureDir(); err != nil {
+		return errors.Wrapf(err, "failed to create folder %v for %v", jsonDir, pkg.Name)
+	}
+	if err := fs.RecursiveCopy(packageJSON.ToStringDuringMigration(), jsonDir.ToStringDuringMigration()); err != nil {
+		return errors.Wrapf(err, "failed to copy %v into %v", pkg.Name, jsonDir)
+	}
+	if err := p.manifest.record(packageJSON, jsonDir, reasonWorkspace, pkg.Name); err != nil {
+		return errors.Wrapf(err, "failed to record %v in prune manifest", pkg.Name)
+	}
+	return nil
+}
+
+// copyRootFile copies a file from the root of the repository into the pruned output
+func (p *prune) copyRootFile(name string, destination fs.AbsolutePath) error {
+	source := p.config.Cwd.Join(name)
+	if err := fs.CopyFile(&fs.LstatCachedFile{Path: source}, destination.ToStringDuringMigration()); err != nil {
+		return err
+	}
+	return p.manifest.record(source, destination, reasonRootFile, "")
+}
+
+// previouslyPruned returns the packages that have already been written to outDir
+// by an earlier prune
+func (p *prune) previouslyPruned(ctx *context.Context, outDir fs.AbsolutePath, docker bool) dag.Set {
+	packageJSONDir := outDir
+	if docker {
+		packageJSONDir = outDir.Join("json")
+	}
+	existing := make(dag.Set)
+	for name, pkg := range ctx.PackageInfos {
+		if name == ctx.RootNode {
+			continue
+		}
+		if packageJSONDir.Join(pkg.Dir, "package.json").FileExists() {
+			existing.Add(name)
+		}
+	}
+	return existing
+}
@@ -0,0 +1,79 @@
+package prune
+
+import (
+	"testing"
+
+	"github.com/pyr-sh/dag"
+	"github.com/vercel/turborepo/cli/internal/config"
+	"github.com/vercel/turborepo/cli/internal/context"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+func TestPruneTargets(t *testing.T) {
+	rootNode := "___ROOT___"
+	var graph dag.AcyclicGraph
+	for _, name := range []string{rootNode, "web", "docs", "ui", "utils", "api"} {
+		graph.Add(name)
+	}
+	for _, edge := range [][2]string{
+		{"web", "ui"},
+		{"docs", "ui"},
+		{"ui", "utils"},
+		{"web", rootNode},
+		{"docs", rootNode},
+		{"ui", rootNode},
+		{"utils", rootNode},
+		{"api", rootNode},
+	} {
+		graph.Connect(dag.BasicEdge(edge[0], edge[1]))
+	}
+
+	targets, err := pruneTargets(&graph, rootNode, []string{"web"}, make(dag.Set))
+	assert.NilError(t, err, "pruneTargets")
+	assert.DeepEqual(t, targets, []string{"ui", "utils", "web"})
+
+	targets, err = pruneTargets(&graph, rootNode, []string{"web", "docs"}, make(dag.Set))
+	assert.NilError(t, err, "pruneTargets")
+	assert.DeepEqual(t, targets, []string{"docs", "ui", "utils", "web"})
+
+	existing := make(dag.Set)
+	existing.Add("api")
+	targets, err = pruneTargets(&graph, rootNode, []string{"docs"}, existing)
+	assert.NilError(t, err, "pruneTargets")
+	assert.DeepEqual(t, targets, []string{"api", "docs", "ui", "utils"})
+}
+
+func TestPruneAppend(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	writeFile := func(path fs.AbsolutePath, contents string) {
+		assert.NilError(t, path.EnsureDir(), "EnsureDir")
+		assert.NilError(t, path.WriteFile([]byte(contents), 0644), "WriteFile")
+	}
+	packageInfos := map[interface{}]*fs.PackageJSON{
+		"___ROOT___": {Name: "monorepo", Dir: "."},
+	}
+	for _, dir := range []string{"apps/web", "apps/docs"} {
+		pkg := &fs.PackageJSON{Name: dir[len("apps/"):], Dir: dir, PackageJSONPath: dir + "/package.json"}
+		packageInfos[pkg.Name] = pkg
+		writeFile(repoRoot.Join(dir, "package.json"), "{}")
+		writeFile(repoRoot.Join(dir, "index.js"), "v1")
+	}
+	ctx := &context.Context{PackageInfos: packageInfos, RootNode: "___ROOT___"}
+	p := &prune{config: &config.Config{Cwd: repoRoot}}
+	outDir := repoRoot.Join("out")
+
+	assert.NilError(t, p.copyWorkspace(packageInfos["web"], outDir, true), "copyWorkspace")
+	existing := p.previouslyPruned(ctx, outDir, true)
+	assert.DeepEqual(t, existing.List(), []interface{}{"web"})
+	assert.Equal(t, p.previouslyPruned(ctx, outDir, false).Len(), 0)
+
+	// A package that is pruned again picks up changes made since the last prune
+	writeFile(repoRoot.Join("apps", "web", "index.js"), "v2")
+	assert.NilError(t, p.copyWorkspace(packageInfos["web"], outDir, true), "copyWorkspace")
+	contents, err := outDir.Join("full", "apps", "web", "index.js").ReadFile()
+	assert.NilError(t, err, "ReadFile")
+	assert.Equal(t, string(contents), "v2")
+	assert.Assert(t, outDir.Join("json", "apps", "web", "package.json").FileExists())
+	assert.Assert(t, !outDir.Join("json", "apps", "web", "index.js").FileExists())
+}
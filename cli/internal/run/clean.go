@@ -0,0 +1,239 @@
+package run
+
+import (
+	"fmt"
+	"os"
+	"sort"
+	"strings"
+
+	"github.com/fatih/color"
+	"github.com/mitchellh/cli"
+	"github.com/pkg/errors"
+	"github.com/spf13/cobra"
+	"github.com/vercel/turborepo/cli/internal/config"
+	"github.com/vercel/turborepo/cli/internal/context"
+	"github.com/vercel/turborepo/cli/internal/daemon"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/globby"
+	"github.com/vercel/turborepo/cli/internal/scm"
+	"github.com/vercel/turborepo/cli/internal/scope"
+	"github.com/vercel/turborepo/cli/internal/ui"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// CleanCommand is a Command implementation that removes task outputs and turbo's local state
+type CleanCommand struct {
+	Config *config.Config
+	UI     *cli.ColoredUi
+}
+
+type cleanOpts struct {
+	outputs     bool
+	cache       bool
+	daemonState bool
+	dryRun      bool
+}
+
+var _cleanCmdLong = `
+Remove the outputs that turbo.json declares for the tasks of each package in
+scope, along with the tasks' log files. With --cache, remove the local cache,
+and with --daemon-state, stop the turbo daemon and remove its files.
+
+Without --outputs, --cache or --daemon-state, only outputs are removed. The
+local cache and the daemon aren't specific to a package, so --filter only
+narrows which outputs are removed.
+`
+
+func getCleanCmd(config *config.Config, ui cli.Ui) *cobra.Command {
+	opts := getDefaultOptions(config)
+	cleanOpts := &cleanOpts{}
+	cmd := &cobra.Command{
+		Use:                   "turbo clean [<flags>]",
+		Short:                 "Remove task outputs, the local cache and daemon state",
+		Long:                  _cleanCmdLong,
+		SilenceUsage:          true,
+		SilenceErrors:         true,
+		DisableFlagsInUseLine: true,
+		Args:                  cobra.NoArgs,
+		RunE: func(cmd *cobra.Command, args []string) error {
+			if !cleanOpts.outputs && !cleanOpts.cache && !cleanOpts.daemonState {
+				cleanOpts.outputs = true
+			}
+			return clean(config, ui, opts, cleanOpts)
+		},
+	}
+	flags := cmd.Flags()
+	scope.AddFlags(&opts.scopeOpts, flags)
+	fs.AbsolutePathVar(flags, &opts.cacheOpts.Dir, "cache-dir", config.Cwd, "Specify local filesystem cache directory.", "./node_modules/.cache/turbo")
+	flags.BoolVar(&cleanOpts.outputs, "outputs", false, "Remove the declared outputs and log files of the tasks in scope")
+	flags.BoolVar(&cleanOpts.cache, "cache", false, "Remove the local cache")
+	flags.BoolVar(&cleanOpts.daemonState, "daemon-state", false, "Stop the turbo daemon serving this repository and remove its socket, pid file and log file")
+	flags.BoolVar(&cleanOpts.dryRun, "dry-run", false, "List what would be removed, without removing anything")
+	return cmd
+}
+
+// Synopsis of the clean command
+func (c *CleanCommand) Synopsis() string {
+	cmd := getCleanCmd(c.Config, c.UI)
+	return cmd.Short
+}
+
+// Help returns information about the `clean` command
+func (c *CleanCommand) Help() string {
+	cmd := getCleanCmd(c.Config, c.UI)
+	return util.HelpForCobraCmd(cmd)
+}
+
+// Run removes task outputs and turbo's local state
+func (c *CleanCommand) Run(args []string) int {
+	cmd := getCleanCmd(c.Config, c.UI)
+	cmd.SetArgs(args)
+	if err := cmd.Execute(); err != nil {
+		c.Config.Logger.Error("", "error", err)
+		c.UI.Error(fmt.Sprintf("%s%s", ui.ERROR_PREFIX, color.RedString(" %v", err)))
+		return 1
+	}
+	return 0
+}
+
+// cleanTarget is a file or directory to remove. Directories inside stopAt that are
+// left empty once it is removed are removed as well.
+type cleanTarget struct {
+	path   fs.AbsolutePath
+	stopAt fs.AbsolutePath
+}
+
+func clean(config *config.Config, output cli.Ui, opts *Opts, cleanOpts *cleanOpts) error {
+	var targets []cleanTarget
+	if cleanOpts.outputs {
+		outputs, err := outputsToClean(config, output, opts)
+		if err != nil {
+			return err
+		}
+		targets = append(targets, outputs...)
+	}
+	if cleanOpts.cache {
+		targets = append(targets, cleanTarget{path: opts.cacheOpts.Dir})
+	}
+	if cleanOpts.daemonState {
+		// The daemon removes its socket and pid file as it exits, but it may still be
+		// exiting, so its files are removed here as well
+		if !cleanOpts.dryRun {
+			if err := daemon.Stop(config, output); err != nil {
+				return errors.Wrap(err, "failed to stop turbo daemon")
+			}
+		}
+		for _, path := range daemon.StatePaths(config.TurboVersion) {
+			targets = append(targets, cleanTarget{path: path})
+		}
+	}
+
+	removed := 0
+	for _, target := range targets {
+		if _, err := target.path.Lstat(); os.IsNotExist(err) {
+			continue
+		}
+		if cleanOpts.dryRun {
+			output.Output(fmt.Sprintf("Would remove %v", displayPath(config.Cwd, target.path)))
+			removed++
+			continue
+		}
+		if err := target.path.RemoveAll(); err != nil {
+			return errors.Wrapf(err, "failed to remove %v", displayPath(config.Cwd, target.path))
+		}
+		removed++
+		if target.stopAt != "" {
+			removeEmptyParents(target.path.Dir(), target.stopAt)
+		}
+	}
+	if cleanOpts.dryRun {
+		output.Output(fmt.Sprintf("Would remove %v files and directories", removed))
+	} else {
+		output.Output(fmt.Sprintf("Removed %v files and directories", removed))
+	}
+	return nil
+}
+
+// outputsToClean returns the files matching the outputs of every task defined for the
+// packages in scope, along with the tasks' log files
+func outputsToClean(config *config.Config, output cli.Ui, opts *Opts) ([]cleanTarget, error) {
+	turboJSON, err := fs.ReadTurboConfig(config.Cwd, config.RootPackageJSON)
+	if err != nil {
+		return nil, err
+	}
+	pkgDepGraph, err := context.New(context.WithGraph(config, turboJSON, opts.cacheOpts.Dir))
+	if err != nil {
+		return nil, err
+	}
+	scmInstance, err := scm.FromInRepo(config.Cwd.ToStringDuringMigration())
+	if err != nil && !errors.Is(err, scm.ErrFallback) {
+		return nil, errors.Wrap(err, "failed to create SCM")
+	}
+	filteredPkgs, _, err := scope.ResolvePackages(&opts.scopeOpts, config.Cwd.ToStringDuringMigration(), scmInstance, pkgDepGraph, output, config.Logger)
+	if err != nil {
+		return nil, errors.Wrap(err, "failed to resolve packages to clean")
+	}
+	pkgNames := filteredPkgs.UnsafeListOfStrings()
+	sort.Strings(pkgNames)
+
+	var targets []cleanTarget
+	for _, pkgName := range pkgNames {
+		pkg, ok := pkgDepGraph.PackageInfos[pkgName]
+		if !ok {
+			continue
+		}
+		globs := packageOutputGlobs(turboJSON.Pipeline, pkg)
+		if len(globs) == 0 {
+			continue
+		}
+		pkgDir := config.Cwd.Join(pkg.Dir)
+		files, err := globby.GlobFiles(pkgDir.ToStringDuringMigration(), globs, nil)
+		if err != nil {
+			return nil, errors.Wrapf(err, "failed to find outputs of %v", pkgName)
+		}
+		sort.Strings(files)
+		for _, file := range files {
+			targets = append(targets, cleanTarget{path: fs.AbsolutePathFromUpstream(file), stopAt: pkgDir})
+		}
+	}
+	return targets, nil
+}
+
+// packageOutputGlobs returns the package-relative outputs of each of the package's scripts
+// that the pipeline defines a task for, along with the scripts' log files
+func packageOutputGlobs(pipeline fs.Pipeline, pkg *fs.PackageJSON) []string {
+	globs := make(util.Set)
+	for script := range pkg.Scripts {
+		taskDefinition, ok := pipeline.GetTaskDefinition(util.GetTaskId(pkg.Name, script))
+		if !ok {
+			continue
+		}
+		globs.Add(fmt.Sprintf(".turbo/turbo-%v.log", script))
+		for _, output := range taskDefinition.Outputs {
+			globs.Add(output)
+		}
+	}
+	list := globs.UnsafeListOfStrings()
+	sort.Strings(list)
+	return list
+}
+
+// removeEmptyParents removes dir, and then each of its parents, until it reaches one
+// that isn't empty or stopAt, which is never removed
+func removeEmptyParents(dir fs.AbsolutePath, stopAt fs.AbsolutePath) {
+	for dir != stopAt && strings.HasPrefix(dir.ToString(), stopAt.ToString()) {
+		if err := dir.Remove(); err != nil {
+			return
+		}
+		dir = dir.Dir()
+	}
+}
+
+// displayPath returns the path relative to the repository, if it is inside it
+func displayPath(repoRoot fs.AbsolutePath, path fs.AbsolutePath) string {
+	relative, err := repoRoot.RelativePathString(path.ToString())
+	if err != nil || strings.HasPrefix(relative, "..") {
+		return path.ToString()
+	}
+	return relative
+}
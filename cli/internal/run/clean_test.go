@@ -0,0 +1,39 @@
+package run
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+func Test_packageOutputGlobs(t *testing.T) {
+	pipeline := fs.Pipeline{
+		"build":     {Outputs: []string{"dist/**", ".next/**"}},
+		"web#build": {Outputs: []string{".next/**"}},
+		"test:*":    {Outputs: []string{"coverage/**"}},
+		"deploy":    {Outputs: []string{"deploy.json"}},
+	}
+	pkg := &fs.PackageJSON{
+		Name: "web",
+		Scripts: map[string]string{
+			"build":     "next build",
+			"test:unit": "jest",
+			"start":     "next start",
+		},
+	}
+	assert.Equal(t, []string{".next/**", ".turbo/turbo-build.log", ".turbo/turbo-test:unit.log", "coverage/**"}, packageOutputGlobs(pipeline, pkg))
+}
+
+func Test_removeEmptyParents(t *testing.T) {
+	pkgDir := fs.AbsolutePathFromUpstream(t.TempDir())
+	kept := pkgDir.Join("dist", "kept.js")
+	assert.NoError(t, kept.EnsureDir())
+	assert.NoError(t, kept.WriteFile([]byte("kept"), 0644))
+	assert.NoError(t, pkgDir.Join("dist", "nested", "deeper").MkdirAll())
+
+	removeEmptyParents(pkgDir.Join("dist", "nested", "deeper"), pkgDir)
+	assert.False(t, pkgDir.Join("dist", "nested").DirExists())
+	assert.True(t, kept.FileExists(), "directories that aren't empty should be kept")
+	assert.True(t, pkgDir.DirExists())
+}
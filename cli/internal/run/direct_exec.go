@@ -0,0 +1,161 @@
+package run
+
+import (
+	"os"
+	"os/exec"
+	"path/filepath"
+	"runtime"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+)
+
+// _shellSyntax are characters that only a shell can interpret. Scripts containing any of
+// them, outside of quotes, are left to the package manager.
+const _shellSyntax = "|&;<>()$`\\*?[]{}~!#%\n"
+
+// parseSimpleCommand splits a script into argv if it is a simple command: words separated
+// by spaces or tabs, optionally quoted with single or double quotes. Scripts that use
+// any other shell syntax, including variables, globs, escapes and environment variable
+// assignments, are not simple commands.
+func parseSimpleCommand(script string) ([]string, bool) {
+	var argv []string
+	var word strings.Builder
+	inWord := false
+	var quote rune
+	for _, c := range script {
+		switch {
+		case quote != 0:
+			if c == quote {
+				quote = 0
+			} else if quote == '"' && strings.ContainsRune("$`\\!", c) {
+				// These are still interpreted inside double quotes
+				return nil, false
+			} else {
+				word.WriteRune(c)
+			}
+		case c == '\'' || c == '"':
+			quote = c
+			inWord = true
+		case c == ' ' || c == '\t':
+			if inWord {
+				argv = append(argv, word.String())
+				word.Reset()
+				inWord = false
+			}
+		case strings.ContainsRune(_shellSyntax, c):
+			return nil, false
+		default:
+			word.WriteRune(c)
+			inWord = true
+		}
+	}
+	if quote != 0 {
+		return nil, false
+	}
+	if inWord {
+		argv = append(argv, word.String())
+	}
+	// A leading FOO=bar sets an environment variable for the command
+	if len(argv) == 0 || strings.ContainsRune(argv[0], '=') {
+		return nil, false
+	}
+	return argv, true
+}
+
+// binDirs returns the node_modules/.bin directories that a package manager puts on the PATH
+// for scripts in the given package, from the package up to the repository root
+func binDirs(repoRoot fs.AbsolutePath, pkgDir string) []string {
+	var dirs []string
+	dir := repoRoot.Join(pkgDir).ToString()
+	for {
+		dirs = append(dirs, filepath.Join(dir, "node_modules", ".bin"))
+		if dir == repoRoot.ToString() {
+			return dirs
+		}
+		parent := filepath.Dir(dir)
+		if parent == dir {
+			return dirs
+		}
+		dir = parent
+	}
+}
+
+// lookPath finds an executable in the given directories, and then on the PATH
+func lookPath(name string, dirs []string) (string, error) {
+	if strings.ContainsRune(name, '/') || strings.ContainsRune(name, filepath.Separator) {
+		return exec.LookPath(name)
+	}
+	for _, dir := range dirs {
+		if path, err := exec.LookPath(filepath.Join(dir, name)); err == nil {
+			return path, nil
+		}
+	}
+	return exec.LookPath(name)
+}
+
+// directCommand returns a command that runs the task's script directly, without the package
+// manager or a shell, if that can be done without changing what the script does. Otherwise
+// it returns false, and the task should be run through the package manager.
+//
+// The command gets the environment variables that every package manager sets for a script:
+// the PATH, INIT_CWD and the npm_lifecycle_* and npm_package_* variables that describe it.
+// Those that depend on the package manager itself, like npm_config_*, npm_execpath and
+// npm_node_execpath, can't be known without running it, so they aren't set.
+func directCommand(repoRoot fs.AbsolutePath, pt *nodes.PackageTask, passThroughArgs []string) (*exec.Cmd, bool) {
+	script, ok := pt.Command()
+	if !ok {
+		return nil, false
+	}
+	// Package managers run pre and post scripts along with the script itself
+	if _, ok := pt.Pkg.Scripts["pre"+pt.Task]; ok {
+		return nil, false
+	}
+	if _, ok := pt.Pkg.Scripts["post"+pt.Task]; ok {
+		return nil, false
+	}
+	argv, ok := parseSimpleCommand(script)
+	if !ok {
+		return nil, false
+	}
+	dirs := binDirs(repoRoot, pt.Pkg.Dir)
+	path, err := lookPath(argv[0], dirs)
+	if err != nil {
+		return nil, false
+	}
+	// Batch files can only be run by cmd.exe, which has its own quoting rules
+	if runtime.GOOS == "windows" {
+		ext := strings.ToLower(filepath.Ext(path))
+		if ext == ".cmd" || ext == ".bat" {
+			return nil, false
+		}
+	}
+
+	pkgDir := repoRoot.Join(pt.Pkg.Dir).ToString()
+	cmd := exec.Command(path, append(argv[1:], passThroughArgs...)...)
+	cmd.Args[0] = argv[0]
+	cmd.Dir = pt.Pkg.Dir
+	cmd.Env = append(os.Environ(),
+		"PATH="+strings.Join(append(dirs, os.Getenv("PATH")), string(os.PathListSeparator)),
+		// The package manager is started in the package's directory
+		"INIT_CWD="+pkgDir,
+		"npm_package_json="+filepath.Join(pkgDir, "package.json"),
+		"npm_lifecycle_event="+pt.Task,
+		"npm_lifecycle_script="+script,
+		"npm_package_name="+pt.Pkg.Name,
+		"npm_package_version="+pt.Pkg.Version,
+	)
+	return cmd, true
+}
+
+// shellCommand returns a command that runs a script in the system shell, for packages that
+// the package manager doesn't run scripts for. Arguments passed after -- are appended to
+// the script.
+func shellCommand(script string, passThroughArgs []string) *exec.Cmd {
+	if runtime.GOOS == "windows" {
+		return exec.Command("cmd", append([]string{"/C", script}, passThroughArgs...)...)
+	}
+	// sh -c sets $0 from the first argument after the script, and "$@" to the rest
+	return exec.Command("sh", append([]string{"-c", script + ` "$@"`, "sh"}, passThroughArgs...)...)
+}
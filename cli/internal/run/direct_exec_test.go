@@ -0,0 +1,75 @@
+package run
+
+import (
+	"path/filepath"
+	"runtime"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+)
+
+func Test_parseSimpleCommand(t *testing.T) {
+	testCases := []struct {
+		script   string
+		expected []string
+		simple   bool
+	}{
+		{"tsc", []string{"tsc"}, true},
+		{"tsc --build  --verbose", []string{"tsc", "--build", "--verbose"}, true},
+		{"next dev --port=3001", []string{"next", "dev", "--port=3001"}, true},
+		{`jest "src/**/*.test.ts" 'a b'`, []string{"jest", "src/**/*.test.ts", "a b"}, true},
+		{`eslint --ext "".ts`, []string{"eslint", "--ext", ".ts"}, true},
+		{"tsc && vite build", nil, false},
+		{"jest src/*.ts", nil, false},
+		{"echo $HOME", nil, false},
+		{`echo "$HOME"`, nil, false},
+		{"NODE_ENV=production next build", nil, false},
+		{"cat < input > output", nil, false},
+		{"echo 'unterminated", nil, false},
+		{"", nil, false},
+	}
+	for _, tc := range testCases {
+		argv, simple := parseSimpleCommand(tc.script)
+		assert.Equal(t, tc.simple, simple, "simple command %q", tc.script)
+		assert.Equal(t, tc.expected, argv, "argv for %q", tc.script)
+	}
+}
+
+func Test_shellCommand(t *testing.T) {
+	if runtime.GOOS == "windows" {
+		t.Skip("runs a POSIX shell")
+	}
+	cmd := shellCommand("echo $((1 + 1)) built", []string{"--release", "a b"})
+	out, err := cmd.Output()
+	assert.NoError(t, err)
+	assert.Equal(t, "2 built --release a b\n", string(out))
+}
+
+func Test_directCommandEnv(t *testing.T) {
+	if runtime.GOOS == "windows" {
+		t.Skip("runs a POSIX command")
+	}
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	pt := &nodes.PackageTask{
+		Task: "build",
+		Pkg: &fs.PackageJSON{
+			Name:    "web",
+			Version: "1.0.0",
+			Dir:     filepath.Join("apps", "web"),
+			Scripts: map[string]string{"build": "true"},
+		},
+	}
+	cmd, ok := directCommand(repoRoot, pt, nil)
+	assert.True(t, ok)
+	pkgDir := repoRoot.Join("apps", "web").ToString()
+	assert.Subset(t, cmd.Env, []string{
+		"INIT_CWD=" + pkgDir,
+		"npm_package_json=" + filepath.Join(pkgDir, "package.json"),
+		"npm_lifecycle_event=build",
+		"npm_lifecycle_script=true",
+		"npm_package_name=web",
+		"npm_package_version=1.0.0",
+	})
+}
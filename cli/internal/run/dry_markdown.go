@@ -0,0 +1,113 @@
+package run
+
+import (
+	"fmt"
+	"io"
+	"sort"
+	"strings"
+
+	"github.com/pyr-sh/dag"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+// cachePrediction is what a dry run expects the cache to do for a task
+type cachePrediction int
+
+const (
+	predictedMiss cachePrediction = iota
+	predictedHit
+	// predictedUncached tasks have "cache": false
+	predictedUncached
+	// predictedUnknown is used when the local cache is off, since only it is checked
+	predictedUnknown
+)
+
+var _cachePredictions = map[cachePrediction]string{
+	predictedMiss:     "🔨 Will run",
+	predictedHit:      "⚡ Cached",
+	predictedUncached: "🚫 Not cached",
+	predictedUnknown:  "❔ Unknown",
+}
+
+// predictCache predicts whether a task will be restored from the cache, from whether the
+// local cache has its hash. The remote cache isn't checked.
+func predictCache(pipeline fs.Pipeline, opts *Opts, task hashedTask) cachePrediction {
+	if definition, ok := pipeline.GetTaskDefinition(task.TaskID); ok && !definition.ShouldCache {
+		return predictedUncached
+	}
+	if opts.runcacheOpts.SkipReads {
+		return predictedMiss
+	}
+	if opts.cacheOpts.SkipFilesystem {
+		return predictedUnknown
+	}
+	if opts.cacheOpts.Dir.Join(task.Hash).DirExists() {
+		return predictedHit
+	}
+	return predictedMiss
+}
+
+// taskDependencies returns the tasks that the given task directly depends on, sorted
+func taskDependencies(taskGraph *dag.AcyclicGraph, taskID string) []string {
+	dependencies := []string{}
+	for _, dep := range taskGraph.DownEdges(taskID) {
+		// Don't leak out internal ROOT_NODE_NAME nodes, which are just placeholders
+		if !strings.Contains(dep.(string), core.ROOT_NODE_NAME) {
+			dependencies = append(dependencies, dep.(string))
+		}
+	}
+	sort.Strings(dependencies)
+	return dependencies
+}
+
+// dryRunReport is what --dry=markdown shows about a run
+type dryRunReport struct {
+	Targets  []string
+	Packages []string
+	Tasks    []hashedTask
+	// Dependencies are the tasks that each task directly depends on, keyed by task ID
+	Dependencies map[string][]string
+	// Predictions are what the cache is expected to do for each task, keyed by task ID
+	Predictions    map[string]cachePrediction
+	GlobalHash     string
+	CacheKeySuffix string
+}
+
+// writeDryRunMarkdown writes a Markdown plan of the run, to be posted as a pull request
+// comment or a job summary
+func writeDryRunMarkdown(w io.Writer, report *dryRunReport) error {
+	var b strings.Builder
+	b.WriteString("### Turbo dry run\n\n")
+	fmt.Fprintf(&b, "**Tasks:** `%v` in %v packages  \n", strings.Join(report.Targets, "`, `"), len(report.Packages))
+	cached := 0
+	for _, prediction := range report.Predictions {
+		if prediction == predictedHit {
+			cached++
+		}
+	}
+	fmt.Fprintf(&b, "**Predicted:** %v cached, %v to run, %v total  \n", cached, len(report.Tasks)-cached, len(report.Tasks))
+	if report.CacheKeySuffix != "" {
+		fmt.Fprintf(&b, "**Cache key suffix:** `%v`  \n", report.CacheKeySuffix)
+	}
+	fmt.Fprintf(&b, "**Global hash:** `%v`\n\n", report.GlobalHash)
+	if len(report.Tasks) > 0 {
+		b.WriteString("| Task | Hash | Cache | Depends on |\n")
+		b.WriteString("| --- | --- | --- | --- |\n")
+		for _, task := range report.Tasks {
+			dependencies := ""
+			if deps := report.Dependencies[task.TaskID]; len(deps) > 0 {
+				dependencies = fmt.Sprintf("`%v`", strings.Join(deps, "`, `"))
+			}
+			fmt.Fprintf(&b, "| `%v` | `%v` | %v | %v |\n", task.TaskID, task.Hash, _cachePredictions[report.Predictions[task.TaskID]], dependencies)
+		}
+		b.WriteString("\n")
+	}
+	fmt.Fprintf(&b, "<details><summary>Packages in scope (%v)</summary>\n\n", len(report.Packages))
+	for _, pkg := range report.Packages {
+		fmt.Fprintf(&b, "- `%v`\n", pkg)
+	}
+	b.WriteString("\n</details>\n")
+	_, err := io.WriteString(w, b.String())
+	return err
+}
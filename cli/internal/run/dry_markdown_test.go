@@ -0,0 +1,43 @@
+package run
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestWriteDryRunMarkdown(t *testing.T) {
+	report := &dryRunReport{
+		Targets:  []string{"build", "test"},
+		Packages: []string{"docs", "ui", "web"},
+		Tasks: []hashedTask{
+			{TaskID: "ui#build", Hash: "aaa"},
+			{TaskID: "web#build", Hash: "bbb"},
+			{TaskID: "web#test", Hash: "ccc"},
+		},
+		Dependencies: map[string][]string{
+			"ui#build":  {},
+			"web#build": {"ui#build"},
+			"web#test":  {"ui#build", "web#build"},
+		},
+		Predictions: map[string]cachePrediction{
+			"ui#build":  predictedHit,
+			"web#build": predictedMiss,
+			"web#test":  predictedUncached,
+		},
+		GlobalHash: "ggg",
+	}
+
+	var markdown strings.Builder
+	err := writeDryRunMarkdown(&markdown, report)
+	assert.NoError(t, err, "writeDryRunMarkdown")
+	assert.Contains(t, markdown.String(), "**Tasks:** `build`, `test` in 3 packages")
+	assert.Contains(t, markdown.String(), "**Predicted:** 1 cached, 2 to run, 3 total")
+	assert.Contains(t, markdown.String(), "**Global hash:** `ggg`")
+	assert.NotContains(t, markdown.String(), "Cache key suffix")
+	assert.Contains(t, markdown.String(), "| Task | Hash | Cache | Depends on |\n| --- | --- | --- | --- |\n| `ui#build` | `aaa` | ⚡ Cached |  |\n")
+	assert.Contains(t, markdown.String(), "| `web#build` | `bbb` | 🔨 Will run | `ui#build` |\n")
+	assert.Contains(t, markdown.String(), "| `web#test` | `ccc` | 🚫 Not cached | `ui#build`, `web#build` |\n")
+	assert.Contains(t, markdown.String(), "<details><summary>Packages in scope (3)</summary>\n\n- `docs`\n- `ui`\n- `web`\n\n</details>")
+}
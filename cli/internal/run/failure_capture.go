@@ -0,0 +1,56 @@
+package run
+
+import (
+	"fmt"
+	"path/filepath"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/globby"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+	"github.com/vercel/turborepo/cli/internal/runcache"
+)
+
+// _failuresDir is where files captured from failed tasks are kept, relative to the repository
+var _failuresDir = filepath.Join(".turbo", "failures")
+
+// captureFailure copies the files matching the task's captureOnFailure globs, along with
+// the task's log file, into .turbo/failures/<hash>, at the same repo-relative paths. They
+// aren't cached: they are only kept to debug the failure. It returns the repo-relative
+// directory the files were copied into.
+func captureFailure(repoRoot fs.AbsolutePath, pt *nodes.PackageTask, hash string) (string, error) {
+	if hash == "" {
+		return "", fmt.Errorf("%v has no hash to keep its files under", pt.TaskID)
+	}
+	pkgDir := repoRoot.Join(pt.Pkg.Dir)
+	files, err := globby.GlobFiles(pkgDir.ToStringDuringMigration(), pt.TaskDefinition.CaptureOnFailure, nil)
+	if err != nil {
+		return "", err
+	}
+	logFile := repoRoot.Join(pt.RepoRelativeLogFile())
+	for _, logFile := range []fs.AbsolutePath{logFile, runcache.CompressedLogFile(logFile)} {
+		if logFile.FileExists() {
+			files = append(files, logFile.ToString())
+		}
+	}
+
+	relativeDir := filepath.Join(_failuresDir, hash)
+	dir := repoRoot.Join(relativeDir)
+	// Files from an earlier failure of the same task with the same inputs are replaced
+	if err := dir.RemoveAll(); err != nil {
+		return "", err
+	}
+	for _, file := range files {
+		relativePath, err := repoRoot.RelativePathString(file)
+		if err != nil {
+			return "", err
+		}
+		to := dir.Join(relativePath)
+		if err := to.EnsureDir(); err != nil {
+			return "", err
+		}
+		if err := fs.CopyFile(&fs.LstatCachedFile{Path: fs.AbsolutePathFromUpstream(file)}, to.ToString()); err != nil {
+			return "", err
+		}
+	}
+	return relativeDir, nil
+}
@@ -0,0 +1,43 @@
+package run
+
+import (
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+)
+
+func Test_captureFailure(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	files := map[string]string{
+		"apps/web/reports/junit.xml":        "<testsuites/>",
+		"apps/web/.turbo/turbo-test.log":    "1 failing",
+		"apps/web/src/index.test.js":        "test()",
+		".turbo/failures/abc123/stale.json": "{}",
+	}
+	for name, contents := range files {
+		file := repoRoot.Join(filepath.FromSlash(name))
+		assert.NoError(t, file.EnsureDir())
+		assert.NoError(t, file.WriteFile([]byte(contents), 0644))
+	}
+	pt := &nodes.PackageTask{
+		TaskID:         "web#test",
+		Task:           "test",
+		PackageName:    "web",
+		Pkg:            &fs.PackageJSON{Name: "web", Dir: filepath.Join("apps", "web")},
+		TaskDefinition: &fs.TaskDefinition{CaptureOnFailure: []string{"**/junit.xml"}},
+	}
+
+	dir, err := captureFailure(repoRoot, pt, "abc123")
+	assert.NoError(t, err)
+	assert.Equal(t, filepath.Join(".turbo", "failures", "abc123"), dir)
+	captured := repoRoot.Join(dir)
+	contents, err := captured.Join("apps", "web", "reports", "junit.xml").ReadFile()
+	assert.NoError(t, err)
+	assert.Equal(t, "<testsuites/>", string(contents))
+	assert.True(t, captured.Join("apps", "web", ".turbo", "turbo-test.log").FileExists(), "the task's log should be captured")
+	assert.False(t, captured.Join("apps", "web", "src", "index.test.js").FileExists())
+	assert.False(t, captured.Join("stale.json").FileExists(), "files from an earlier failure should be replaced")
+}
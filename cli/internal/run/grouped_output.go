@@ -0,0 +1,91 @@
+package run
+
+import (
+	"bytes"
+	"io"
+	"sync"
+
+	"github.com/mitchellh/cli"
+	"github.com/vercel/turborepo/cli/internal/ci"
+)
+
+// groupedOutput buffers all of the terminal output for a single task, so that it
+// can be written as one contiguous, collapsible group once the task finishes.
+// Without this, output from concurrently running tasks is interleaved line by line.
+type groupedOutput struct {
+	title   string
+	grouper ci.Grouper
+
+	mu  sync.Mutex
+	buf bytes.Buffer
+}
+
+func newGroupedOutput(title string, grouper ci.Grouper) *groupedOutput {
+	return &groupedOutput{
+		title:   title,
+		grouper: grouper,
+	}
+}
+
+// Write implements io.Writer.Write
+func (g *groupedOutput) Write(p []byte) (int, error) {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+	return g.buf.Write(p)
+}
+
+// len returns how many bytes are buffered
+func (g *groupedOutput) len() int {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+	return g.buf.Len()
+}
+
+// ui returns a cli.Ui that writes into this group. Errors and warnings are kept
+// in line with the rest of the task's output rather than going to stderr.
+func (g *groupedOutput) ui() cli.Ui {
+	return &cli.ColoredUi{
+		Ui: &cli.BasicUi{
+			Writer:      g,
+			ErrorWriter: g,
+		},
+		OutputColor: cli.UiColorNone,
+		InfoColor:   cli.UiColorNone,
+		WarnColor:   cli.UiColorYellow,
+		ErrorColor:  cli.UiColorRed,
+	}
+}
+
+// flush writes everything buffered so far to out, wrapped in group markers.
+// Nothing is written if the task produced no output.
+func (g *groupedOutput) flush(out io.Writer) error {
+	g.mu.Lock()
+	defer g.mu.Unlock()
+	if g.buf.Len() == 0 {
+		return nil
+	}
+	var block bytes.Buffer
+	block.WriteString(g.grouper.Start(g.title))
+	block.Write(g.buf.Bytes())
+	if !bytes.HasSuffix(g.buf.Bytes(), []byte("\n")) {
+		block.WriteString("\n")
+	}
+	block.WriteString(g.grouper.End(g.title))
+	g.buf.Reset()
+	_, err := out.Write(block.Bytes())
+	return err
+}
+
+// noMarkers is a ci.Grouper for output that is buffered, but not wrapped in
+// collapsible groups
+type noMarkers struct{}
+
+// Start implements Grouper.Start
+func (noMarkers) Start(title string) string {
+	return ""
+}
+
+// End implements Grouper.End
+func (noMarkers) End(title string) string {
+	return ""
+}
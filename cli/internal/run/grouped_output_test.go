@@ -0,0 +1,46 @@
+package run
+
+import (
+	"bytes"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/ci"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+)
+
+func TestGroupedOutput(t *testing.T) {
+	group := newGroupedOutput("web:build", ci.GitHubGrouper)
+
+	var out bytes.Buffer
+	err := group.flush(&out)
+	assert.NoError(t, err, "flush")
+	assert.Equal(t, "", out.String(), "empty groups should not be written")
+
+	_, err = group.Write([]byte("web:build: compiling\n"))
+	assert.NoError(t, err, "Write")
+	group.ui().Output("web:build: no trailing newline from the task")
+	_, err = group.Write([]byte("web:build: done"))
+	assert.NoError(t, err, "Write")
+
+	err = group.flush(&out)
+	assert.NoError(t, err, "flush")
+	expected := "::group::web:build\n" +
+		"web:build: compiling\n" +
+		"web:build: no trailing newline from the task\n" +
+		"web:build: done\n" +
+		"::endgroup::\n"
+	assert.Equal(t, expected, out.String())
+}
+
+func TestGroupsOutput(t *testing.T) {
+	build := &nodes.PackageTask{TaskDefinition: &fs.TaskDefinition{ShouldCache: true}}
+	assert.True(t, groupsOutput(build), "cached tasks are grouped")
+
+	dev := &nodes.PackageTask{TaskDefinition: &fs.TaskDefinition{ShouldCache: false}}
+	assert.False(t, groupsOutput(dev), "uncached tasks may never finish, so they aren't grouped")
+
+	server := &nodes.PackageTask{TaskDefinition: &fs.TaskDefinition{ShouldCache: true, ReadyLogPattern: "listening"}}
+	assert.False(t, groupsOutput(server), "tasks with readyWhen aren't grouped")
+}
@@ -0,0 +1,157 @@
+package run
+
+import (
+	gocontext "context"
+	"encoding/json"
+	"fmt"
+	"os"
+	"sort"
+	"strings"
+	"text/tabwriter"
+
+	"github.com/fatih/color"
+	"github.com/mitchellh/cli"
+	"github.com/pkg/errors"
+	"github.com/spf13/cobra"
+	"github.com/vercel/turborepo/cli/internal/config"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/scope"
+	"github.com/vercel/turborepo/cli/internal/signals"
+	"github.com/vercel/turborepo/cli/internal/taskhash"
+	"github.com/vercel/turborepo/cli/internal/ui"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// HashCommand is a Command implementation that prints task hashes without running anything
+type HashCommand struct {
+	Config        *config.Config
+	UI            *cli.ColoredUi
+	SignalWatcher *signals.Watcher
+}
+
+var _hashCmdLong = `
+Print the hash of each task that 'turbo run' would run, without running
+anything or reading from or writing to the cache.
+
+Arguments passed after '--' are part of the hashes of the named tasks,
+as they are for 'turbo run'.
+`
+
+func getHashCmd(config *config.Config, ui cli.Ui, signalWatcher *signals.Watcher) *cobra.Command {
+	opts := getDefaultOptions(config)
+	cmd := &cobra.Command{
+		Use:                   "turbo hash <task> [...<task>] [<flags>] -- <args passed to tasks>",
+		Short:                 "Print task hashes without running anything",
+		Long:                  _hashCmdLong,
+		SilenceUsage:          true,
+		SilenceErrors:         true,
+		DisableFlagsInUseLine: true,
+		RunE: func(cmd *cobra.Command, args []string) error {
+			tasks, passThroughArgs := parseTasksAndPassthroughArgs(args, cmd.Flags())
+			if len(tasks) == 0 {
+				return errors.New("at least one task must be specified")
+			}
+			opts.runOpts.passThroughArgs = passThroughArgs
+			taskArgs, err := parseTaskArgs(opts.runOpts.taskArgFlags, tasks)
+			if err != nil {
+				return err
+			}
+			opts.runOpts.taskArgs = taskArgs
+			opts.runOpts.hashOnly = true
+			run := configureRun(config, ui, opts, signalWatcher)
+			return run.run(cmd.Context(), tasks)
+		},
+	}
+	flags := cmd.Flags()
+	scope.AddFlags(&opts.scopeOpts, flags)
+	flags.StringArrayVar(&opts.runOpts.taskArgFlags, "args", nil, _taskArgsHelp)
+	flags.BoolVar(&opts.runOpts.hashJSON, "json", false, "Print the hashes as JSON")
+	flags.BoolVar(&opts.runOpts.explainHash, "explain", false, "Also print everything that contributes to each hash")
+	return cmd
+}
+
+// Synopsis of the hash command
+func (c *HashCommand) Synopsis() string {
+	cmd := getHashCmd(c.Config, c.UI, c.SignalWatcher)
+	return cmd.Short
+}
+
+// Help returns information about the `hash` command
+func (c *HashCommand) Help() string {
+	cmd := getHashCmd(c.Config, c.UI, c.SignalWatcher)
+	return util.HelpForCobraCmd(cmd)
+}
+
+// Run prints task hashes
+func (c *HashCommand) Run(args []string) int {
+	cmd := getHashCmd(c.Config, c.UI, c.SignalWatcher)
+	cmd.SetArgs(args)
+	if err := cmd.Execute(); err != nil {
+		c.Config.Logger.Error("", "error", err)
+		c.UI.Error(fmt.Sprintf("%s%s", ui.ERROR_PREFIX, color.RedString(" %v", err)))
+		return 1
+	}
+	return 0
+}
+
+// taskHash is a task's hash, along with everything that contributed to it if requested
+type taskHash struct {
+	TaskID string                   `json:"taskId"`
+	Hash   string                   `json:"hash"`
+	Inputs *taskhash.TaskHashInputs `json:"inputs,omitempty"`
+}
+
+func (r *run) printHashes(ctx gocontext.Context, engine *core.Scheduler, g *completeGraph, hashTracker *taskhash.Tracker, rs *runSpec) error {
+	tasks, err := r.executeDryRun(ctx, engine, g, hashTracker, rs)
+	if err != nil {
+		return err
+	}
+	hashes := make([]taskHash, len(tasks))
+	for i, task := range tasks {
+		hashes[i] = taskHash{TaskID: task.TaskID, Hash: task.Hash}
+		if rs.Opts.runOpts.explainHash {
+			if inputs, ok := hashTracker.HashInputs(task.TaskID); ok {
+				hashes[i].Inputs = inputs
+			}
+		}
+	}
+	sort.Slice(hashes, func(i, j int) bool {
+		return hashes[i].TaskID < hashes[j].TaskID
+	})
+
+	if rs.Opts.runOpts.hashJSON {
+		bytes, err := json.MarshalIndent(hashes, "", "  ")
+		if err != nil {
+			return errors.Wrap(err, "failed to render JSON")
+		}
+		r.ui.Output(string(bytes))
+		return nil
+	}
+	for _, hash := range hashes {
+		r.ui.Output(fmt.Sprintf("%v %v", hash.TaskID, hash.Hash))
+		if hash.Inputs == nil {
+			continue
+		}
+		w := tabwriter.NewWriter(os.Stdout, 0, 0, 1, ' ', 0)
+		fmt.Fprintf(w, "  Files\t=\t%v\t\n", hash.Inputs.FilesHash)
+		fmt.Fprintf(w, "  External Dependencies\t=\t%v\t\n", hash.Inputs.ExternalDepsHash)
+		fmt.Fprintf(w, "  Outputs\t=\t%v\t\n", strings.Join(hash.Inputs.Outputs, ", "))
+		fmt.Fprintf(w, "  Arguments\t=\t%v\t\n", strings.Join(hash.Inputs.PassThroughArgs, " "))
+		fmt.Fprintf(w, "  Environment Variables\t=\t%v\t\n", formatEnvVarHashes(hash.Inputs.EnvVars))
+		fmt.Fprintf(w, "  Global Hash\t=\t%v\t\n", hash.Inputs.GlobalHash)
+		fmt.Fprintf(w, "  Dependencies\t=\t%v\t\n", strings.Join(hash.Inputs.DependencyHashes, ", "))
+		if hash.Inputs.Variant != "" {
+			fmt.Fprintf(w, "  Variant\t=\t%v\t\n", hash.Inputs.Variant)
+		}
+		w.Flush()
+	}
+	return nil
+}
+
+func formatEnvVarHashes(envVars map[string]string) string {
+	pairs := make([]string, 0, len(envVars))
+	for _, name := range envVarNames(envVars) {
+		pairs = append(pairs, fmt.Sprintf("%v=%v", name, envVars[name]))
+	}
+	return strings.Join(pairs, ", ")
+}
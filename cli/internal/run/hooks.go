@@ -0,0 +1,68 @@
+package run
+
+import (
+	"bytes"
+	"fmt"
+	"os"
+	"sort"
+	"strings"
+
+	"github.com/mitchellh/cli"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+	"github.com/vercel/turborepo/cli/internal/process"
+)
+
+// runHook runs a hook from turbo.json in a shell at the repository root, with the given
+// metadata about the run or task in its environment, and writes what it prints to ui.
+// It does nothing if command is empty.
+func runHook(processes *process.Manager, repoRoot fs.AbsolutePath, name string, command string, env []string, ui cli.Ui) error {
+	if command == "" {
+		return nil
+	}
+	cmd := shellCommand(command, nil)
+	cmd.Dir = repoRoot.ToString()
+	cmd.Env = append(append(os.Environ(), "TURBO_HOOK="+name), env...)
+	var output bytes.Buffer
+	cmd.Stdout = &output
+	cmd.Stderr = &output
+	err := processes.Exec(cmd)
+	if text := strings.TrimRight(output.String(), "\n"); text != "" {
+		for _, line := range strings.Split(text, "\n") {
+			ui.Output(line)
+		}
+	}
+	if err != nil {
+		return fmt.Errorf("%v hook failed: %w", name, err)
+	}
+	return nil
+}
+
+// runHookEnv is the metadata about a run that preRun and postRun hooks get
+func runHookEnv(rs *runSpec) []string {
+	packages := rs.FilteredPkgs.UnsafeListOfStrings()
+	sort.Strings(packages)
+	return []string{
+		"TURBO_RUN_TASKS=" + strings.Join(rs.Targets, ","),
+		"TURBO_RUN_PACKAGES=" + strings.Join(packages, ","),
+	}
+}
+
+// taskHookEnv is the metadata about a task that preTask and postTask hooks get
+func taskHookEnv(pt *nodes.PackageTask, hash string) []string {
+	return []string{
+		"TURBO_TASK_ID=" + pt.TaskID,
+		"TURBO_TASK=" + pt.Task,
+		"TURBO_PACKAGE=" + pt.PackageName,
+		"TURBO_PACKAGE_DIR=" + pt.Pkg.Dir,
+		"TURBO_HASH=" + hash,
+	}
+}
+
+// hookStatus is the status that post hooks get, from the error of what they follow
+func hookStatus(err error) string {
+	if err != nil {
+		return "failure"
+	}
+	return "success"
+}
@@ -0,0 +1,70 @@
+package run
+
+import (
+	"errors"
+	"os/exec"
+	"runtime"
+	"testing"
+
+	"github.com/hashicorp/go-hclog"
+	"github.com/mitchellh/cli"
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/config"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+	"github.com/vercel/turborepo/cli/internal/process"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+func Test_runHook(t *testing.T) {
+	if runtime.GOOS == "windows" {
+		t.Skip("runs a POSIX shell")
+	}
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	processes := process.NewManager(hclog.NewNullLogger())
+	pt := &nodes.PackageTask{
+		TaskID:      "web#build",
+		Task:        "build",
+		PackageName: "web",
+		Pkg:         &fs.PackageJSON{Name: "web", Dir: "apps/web"},
+	}
+
+	ui := cli.NewMockUi()
+	env := append(taskHookEnv(pt, "abc123"), "TURBO_TASK_STATUS="+hookStatus(nil))
+	err := runHook(processes, repoRoot, "postTask", `echo "$TURBO_HOOK $TURBO_TASK_ID $TURBO_HASH $TURBO_TASK_STATUS"`, env, ui)
+	assert.NoError(t, err)
+	assert.Equal(t, "postTask web#build abc123 success\n", ui.OutputWriter.String())
+
+	ui = cli.NewMockUi()
+	err = runHook(processes, repoRoot, "preTask", "echo not ready; exit 3", nil, ui)
+	exitErr := &process.ChildExit{}
+	assert.ErrorAs(t, err, &exitErr)
+	assert.Equal(t, 3, exitErr.ExitCode)
+	assert.Equal(t, "not ready\n", ui.OutputWriter.String())
+
+	assert.NoError(t, runHook(processes, repoRoot, "preRun", "", nil, ui))
+}
+
+func Test_runPostRunHookAfterTaskFailure(t *testing.T) {
+	if runtime.GOOS == "windows" {
+		t.Skip("runs a POSIX shell")
+	}
+	ui := cli.NewMockUi()
+	r := &run{
+		config:        &config.Config{Cwd: fs.AbsolutePathFromUpstream(t.TempDir()), Logger: hclog.NewNullLogger()},
+		ui:            ui,
+		processes:     process.NewManager(hclog.NewNullLogger()),
+		hookProcesses: process.NewManager(hclog.NewNullLogger()),
+	}
+
+	// Without --continue, a failing task stops every other task
+	err := r.processes.Exec(exec.Command("sh", "-c", "exit 2"))
+	exitErr := &process.ChildExit{}
+	assert.True(t, errors.As(err, &exitErr))
+	r.processes.Close()
+
+	rs := &runSpec{Targets: []string{"build"}, FilteredPkgs: make(util.Set)}
+	r.runPostRunHook(fs.Hooks{PostRun: `echo "$TURBO_HOOK $TURBO_RUN_EXIT_CODE"`}, rs, exitErr.ExitCode)
+	assert.Equal(t, "postRun 2\n", ui.OutputWriter.String())
+	assert.Equal(t, "", ui.ErrorWriter.String())
+}
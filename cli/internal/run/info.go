@@ -0,0 +1,257 @@
+package run
+
+import (
+	gocontext "context"
+	"encoding/json"
+	"fmt"
+	"os"
+	"sort"
+	"strings"
+	"text/tabwriter"
+
+	"github.com/fatih/color"
+	"github.com/mitchellh/cli"
+	"github.com/pkg/errors"
+	"github.com/spf13/cobra"
+	"github.com/vercel/turborepo/cli/internal/config"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/signals"
+	"github.com/vercel/turborepo/cli/internal/taskhash"
+	"github.com/vercel/turborepo/cli/internal/ui"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// InfoCommand is a Command implementation that describes a single package
+type InfoCommand struct {
+	Config        *config.Config
+	UI            *cli.ColoredUi
+	SignalWatcher *signals.Watcher
+}
+
+var _infoCmdLong = `
+Describe a package: where it is, which workspace packages it depends on
+and which depend on it, and, for each of its scripts, the pipeline entry
+that defines the task, the task's current hash, and whether that hash is
+in the local cache. Nothing is run.
+`
+
+func getInfoCmd(config *config.Config, ui cli.Ui, signalWatcher *signals.Watcher) *cobra.Command {
+	opts := getDefaultOptions(config)
+	cmd := &cobra.Command{
+		Use:                   "turbo info <package> [<flags>]",
+		Short:                 "Describe a package and its tasks",
+		Long:                  _infoCmdLong,
+		SilenceUsage:          true,
+		SilenceErrors:         true,
+		DisableFlagsInUseLine: true,
+		Args:                  cobra.ExactArgs(1),
+		RunE: func(cmd *cobra.Command, args []string) error {
+			opts.runOpts.infoPackage = args[0]
+			opts.scopeOpts.FilterPatterns = []string{args[0]}
+			run := configureRun(config, ui, opts, signalWatcher)
+			return run.run(cmd.Context(), nil)
+		},
+	}
+	flags := cmd.Flags()
+	flags.BoolVar(&opts.runOpts.infoJSON, "json", false, "Print the package's information as JSON")
+	fs.AbsolutePathVar(flags, &opts.cacheOpts.Dir, "cache-dir", config.Cwd, "Specify local filesystem cache directory.", "./node_modules/.cache/turbo")
+	return cmd
+}
+
+// Synopsis of the info command
+func (c *InfoCommand) Synopsis() string {
+	cmd := getInfoCmd(c.Config, c.UI, c.SignalWatcher)
+	return cmd.Short
+}
+
+// Help returns information about the `info` command
+func (c *InfoCommand) Help() string {
+	cmd := getInfoCmd(c.Config, c.UI, c.SignalWatcher)
+	return util.HelpForCobraCmd(cmd)
+}
+
+// Run describes a package
+func (c *InfoCommand) Run(args []string) int {
+	cmd := getInfoCmd(c.Config, c.UI, c.SignalWatcher)
+	cmd.SetArgs(args)
+	if err := cmd.Execute(); err != nil {
+		c.Config.Logger.Error("", "error", err)
+		c.UI.Error(fmt.Sprintf("%s%s", ui.ERROR_PREFIX, color.RedString(" %v", err)))
+		return 1
+	}
+	return 0
+}
+
+// packageInfo is what turbo info reports about a package
+type packageInfo struct {
+	Name         string            `json:"name"`
+	Directory    string            `json:"directory"`
+	Dependencies []string          `json:"dependencies"`
+	Dependents   []string          `json:"dependents"`
+	Tasks        []packageTaskInfo `json:"tasks"`
+	// Scripts that no pipeline entry defines a task for, so turbo never runs them
+	UndefinedScripts []string `json:"undefinedScripts"`
+}
+
+// packageTaskInfo is a package's task as the pipeline resolves it for that package
+type packageTaskInfo struct {
+	Task string `json:"task"`
+	// PipelineKey is the key in turbo.json's pipeline whose definition applies to the task
+	PipelineKey string   `json:"pipelineKey"`
+	Hash        string   `json:"hash"`
+	Cached      bool     `json:"cachedLocally"`
+	DependsOn   []string `json:"dependsOn"`
+	Env         []string `json:"env"`
+	Inputs      []string `json:"inputs"`
+	Outputs     []string `json:"outputs"`
+	Cache       bool     `json:"cache"`
+	OutputMode  string   `json:"outputMode"`
+}
+
+// infoTargets returns the package's scripts that the pipeline defines a task for, which
+// are the tasks that turbo info hashes
+func infoTargets(pipeline fs.Pipeline, packageInfos map[interface{}]*fs.PackageJSON, pkgName string) ([]string, error) {
+	pkg, ok := packageInfos[pkgName]
+	if !ok {
+		return nil, fmt.Errorf("package %v not found in the workspace", pkgName)
+	}
+	var targets []string
+	for script := range pkg.Scripts {
+		if _, ok := pipeline.GetTaskDefinition(util.GetTaskId(pkgName, script)); ok {
+			targets = append(targets, script)
+		}
+	}
+	sort.Strings(targets)
+	return targets, nil
+}
+
+func (r *run) printPackageInfo(ctx gocontext.Context, engine *core.Scheduler, g *completeGraph, hashTracker *taskhash.Tracker, rs *runSpec) error {
+	var tasks []hashedTask
+	if len(rs.Targets) > 0 {
+		var err error
+		tasks, err = r.executeDryRun(ctx, engine, g, hashTracker, rs)
+		if err != nil {
+			return err
+		}
+	}
+	info, err := describePackage(g, rs.Opts.runOpts.infoPackage, tasks, rs.Opts.cacheOpts.Dir)
+	if err != nil {
+		return err
+	}
+
+	if rs.Opts.runOpts.infoJSON {
+		bytes, err := json.MarshalIndent(info, "", "  ")
+		if err != nil {
+			return errors.Wrap(err, "failed to render JSON")
+		}
+		r.ui.Output(string(bytes))
+		return nil
+	}
+	r.ui.Output(ui.Bold(info.Name))
+	w := tabwriter.NewWriter(os.Stdout, 0, 0, 1, ' ', 0)
+	fmt.Fprintf(w, "  Directory\t=\t%v\t\n", info.Directory)
+	fmt.Fprintf(w, "  Dependencies\t=\t%v\t\n", formatList(info.Dependencies))
+	fmt.Fprintf(w, "  Dependents\t=\t%v\t\n", formatList(info.Dependents))
+	w.Flush()
+	for _, task := range info.Tasks {
+		cached := "not cached"
+		if task.Cached {
+			cached = "cached locally"
+		}
+		r.ui.Output("")
+		r.ui.Output(fmt.Sprintf("%v %v", ui.Bold(task.Task), ui.Dim(fmt.Sprintf("(from %q)", task.PipelineKey))))
+		w := tabwriter.NewWriter(os.Stdout, 0, 0, 1, ' ', 0)
+		fmt.Fprintf(w, "  Hash\t=\t%v, %v\t\n", task.Hash, cached)
+		fmt.Fprintf(w, "  Depends On\t=\t%v\t\n", formatList(task.DependsOn))
+		fmt.Fprintf(w, "  Environment Variables\t=\t%v\t\n", formatList(task.Env))
+		fmt.Fprintf(w, "  Inputs\t=\t%v\t\n", formatList(task.Inputs))
+		fmt.Fprintf(w, "  Outputs\t=\t%v\t\n", formatList(task.Outputs))
+		fmt.Fprintf(w, "  Cache\t=\t%v\t\n", task.Cache)
+		fmt.Fprintf(w, "  Output Mode\t=\t%v\t\n", task.OutputMode)
+		w.Flush()
+	}
+	if len(info.UndefinedScripts) > 0 {
+		r.ui.Output("")
+		r.ui.Output(fmt.Sprintf("Scripts without a pipeline entry: %v", strings.Join(info.UndefinedScripts, ", ")))
+	}
+	return nil
+}
+
+// describePackage collects what turbo info reports about the package. tasks are the hashed
+// tasks of the run, which may include tasks in other packages that the package's tasks
+// depend on.
+func describePackage(g *completeGraph, pkgName string, tasks []hashedTask, cacheDir fs.AbsolutePath) (*packageInfo, error) {
+	pkg, ok := g.PackageInfos[pkgName]
+	if !ok {
+		return nil, fmt.Errorf("package %v not found in the workspace", pkgName)
+	}
+	info := &packageInfo{
+		Name:             pkgName,
+		Directory:        pkg.Dir,
+		Dependencies:     append([]string{}, pkg.InternalDeps...),
+		Dependents:       []string{},
+		Tasks:            []packageTaskInfo{},
+		UndefinedScripts: []string{},
+	}
+	sort.Strings(info.Dependencies)
+	for name, other := range g.PackageInfos {
+		for _, dep := range other.InternalDeps {
+			if dep == pkgName {
+				info.Dependents = append(info.Dependents, name.(string))
+				break
+			}
+		}
+	}
+	sort.Strings(info.Dependents)
+
+	hashes := make(map[string]string)
+	for _, task := range tasks {
+		hashes[task.TaskID] = task.Hash
+	}
+	for script := range pkg.Scripts {
+		taskID := util.GetTaskId(pkgName, script)
+		taskDefinition, ok := g.Pipeline.GetTaskDefinition(taskID)
+		if !ok {
+			info.UndefinedScripts = append(info.UndefinedScripts, script)
+			continue
+		}
+		pipelineKey := taskID
+		if _, ok := g.Pipeline[taskID]; !ok {
+			pipelineKey, _ = util.ResolveTaskKey(g.Pipeline.Keys(), taskID)
+		}
+		dependsOn := append([]string{}, taskDefinition.TaskDependencies...)
+		for _, dep := range taskDefinition.TopologicalDependencies {
+			dependsOn = append(dependsOn, "^"+dep)
+		}
+		outputMode, err := util.ToTaskOutputModeString(taskDefinition.OutputMode)
+		if err != nil {
+			return nil, err
+		}
+		hash := hashes[taskID]
+		info.Tasks = append(info.Tasks, packageTaskInfo{
+			Task:        script,
+			PipelineKey: pipelineKey,
+			Hash:        hash,
+			Cached:      hash != "" && cacheDir.Join(hash).DirExists(),
+			DependsOn:   dependsOn,
+			Env:         append([]string{}, taskDefinition.EnvVarDependencies...),
+			Inputs:      append([]string{}, taskDefinition.Inputs...),
+			Outputs:     append([]string{}, taskDefinition.Outputs...),
+			Cache:       taskDefinition.ShouldCache,
+			OutputMode:  outputMode,
+		})
+	}
+	sort.Slice(info.Tasks, func(i, j int) bool {
+		return info.Tasks[i].Task < info.Tasks[j].Task
+	})
+	sort.Strings(info.UndefinedScripts)
+	return info, nil
+}
+
+func formatList(items []string) string {
+	if len(items) == 0 {
+		return "<none>"
+	}
+	return strings.Join(items, ", ")
+}
@@ -0,0 +1,94 @@
+package run
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+func TestDescribePackage(t *testing.T) {
+	cacheDir := fs.AbsolutePathFromUpstream(t.TempDir())
+	assert.NoError(t, cacheDir.Join("build-hash").MkdirAll(), "MkdirAll")
+	g := &completeGraph{
+		Pipeline: fs.Pipeline{
+			"build": fs.TaskDefinition{
+				Outputs:                 []string{"dist/**"},
+				ShouldCache:             true,
+				TopologicalDependencies: []string{"build"},
+			},
+			"web#test:*": fs.TaskDefinition{
+				TaskDependencies: []string{"build"},
+				OutputMode:       util.NewTaskOutput,
+			},
+		},
+		PackageInfos: map[interface{}]*fs.PackageJSON{
+			"web": {
+				Name:         "web",
+				Dir:          "apps/web",
+				Scripts:      map[string]string{"build": "next build", "test:unit": "jest", "start": "next start"},
+				InternalDeps: []string{"ui", "config"},
+			},
+			"ui": {
+				Name:         "ui",
+				Dir:          "packages/ui",
+				InternalDeps: []string{"config"},
+			},
+			"config": {
+				Name: "config",
+				Dir:  "packages/config",
+			},
+			"docs": {
+				Name:         "docs",
+				Dir:          "apps/docs",
+				InternalDeps: []string{"web"},
+			},
+		},
+	}
+
+	targets, err := infoTargets(g.Pipeline, g.PackageInfos, "web")
+	assert.NoError(t, err, "infoTargets")
+	assert.Equal(t, []string{"build", "test:unit"}, targets)
+
+	tasks := []hashedTask{
+		{TaskID: "web#build", Hash: "build-hash"},
+		{TaskID: "web#test:unit", Hash: "test-hash"},
+		{TaskID: "ui#build", Hash: "ui-hash"},
+	}
+	info, err := describePackage(g, "web", tasks, cacheDir)
+	assert.NoError(t, err, "describePackage")
+	assert.Equal(t, "apps/web", info.Directory)
+	assert.Equal(t, []string{"config", "ui"}, info.Dependencies)
+	assert.Equal(t, []string{"docs"}, info.Dependents)
+	assert.Equal(t, []string{"start"}, info.UndefinedScripts)
+	assert.Equal(t, []packageTaskInfo{
+		{
+			Task:        "build",
+			PipelineKey: "build",
+			Hash:        "build-hash",
+			Cached:      true,
+			DependsOn:   []string{"^build"},
+			Env:         []string{},
+			Inputs:      []string{},
+			Outputs:     []string{"dist/**"},
+			Cache:       true,
+			OutputMode:  "full",
+		},
+		{
+			Task:        "test:unit",
+			PipelineKey: "web#test:*",
+			Hash:        "test-hash",
+			Cached:      false,
+			DependsOn:   []string{"build"},
+			Env:         []string{},
+			Inputs:      []string{},
+			Outputs:     []string{},
+			Cache:       false,
+			OutputMode:  "new-only",
+		},
+	}, info.Tasks)
+
+	_, err = infoTargets(g.Pipeline, g.PackageInfos, "missing")
+	assert.EqualError(t, err, "package missing not found in the workspace")
+}
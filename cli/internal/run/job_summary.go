@@ -0,0 +1,90 @@
+package run
+
+import (
+	"fmt"
+	"io"
+	"os"
+	"strings"
+	"time"
+
+	"github.com/vercel/turborepo/cli/internal/ci"
+)
+
+var _jobSummaryStatuses = map[RunResultStatus]string{
+	TargetBuilding:     "⏳ Running",
+	TargetBuildStopped: "⏹️ Stopped",
+	TargetBuilt:        "✅ Executed",
+	TargetCached:       "⚡ Cached",
+	TargetBuildFailed:  "❌ Failed",
+	TargetTimedOut:     "⌛ Timed out",
+}
+
+// writeJobSummary writes a Markdown table with the status and duration of every task in the run
+func (r *RunState) writeJobSummary(w io.Writer) error {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	var b strings.Builder
+	b.WriteString("### Turbo run summary\n\n")
+	fmt.Fprintf(&b, "**Tasks:** %v successful, %v total  \n", r.Cached+r.Success, r.Attempted)
+	fmt.Fprintf(&b, "**Cached:** %v cached, %v total  \n", r.Cached, r.Attempted)
+	if r.cacheKeySuffix != "" {
+		fmt.Fprintf(&b, "**Cache key suffix:** `%v`  \n", r.cacheKeySuffix)
+	}
+	fmt.Fprintf(&b, "**Time:** %v\n\n", time.Since(r.startedAt).Truncate(time.Millisecond))
+	if skipped := r.remoteWritesSkipped(); len(skipped) > 0 {
+		fmt.Fprintf(&b, "**Uploads skipped:** remote cache writes are off for `%v`\n\n", strings.Join(skipped, "`, `"))
+	}
+	if r.runTimeout > 0 {
+		fmt.Fprintf(&b, "**Timed out:** the run didn't finish within %v\n\n", r.runTimeout)
+	}
+	if len(r.Ordered) > 0 {
+		b.WriteString("| Task | Status | Duration |\n")
+		b.WriteString("| --- | --- | --- |\n")
+		for _, label := range r.Ordered {
+			state := r.state[label]
+			fmt.Fprintf(&b, "| `%v` | %v | %v |\n", label, _jobSummaryStatuses[state.Status], state.Duration.Truncate(time.Millisecond))
+		}
+		b.WriteString("\n")
+	}
+	if failures := r.failureArtifacts(); len(failures) > 0 {
+		b.WriteString("**Files captured from failed tasks:**\n\n")
+		for _, state := range failures {
+			fmt.Fprintf(&b, "- `%v`: `%v`\n", state.Label, state.FailureArtifact)
+		}
+		b.WriteString("\n")
+	}
+	if emptyOutputs := r.emptyOutputs(); len(emptyOutputs) > 0 {
+		b.WriteString("**Outputs that matched no files:**\n\n")
+		for _, state := range emptyOutputs {
+			fmt.Fprintf(&b, "- `%v`: `%v`\n", state.Label, strings.Join(state.EmptyOutputs, "`, `"))
+		}
+		b.WriteString("\n")
+	}
+	if undeclaredWrites := r.undeclaredWrites(); len(undeclaredWrites) > 0 {
+		b.WriteString("**Files written outside of outputs:**\n\n")
+		for _, state := range undeclaredWrites {
+			fmt.Fprintf(&b, "- `%v`: `%v`\n", state.Label, strings.Join(state.UndeclaredWrites, "`, `"))
+		}
+		b.WriteString("\n")
+	}
+	_, err := io.WriteString(w, b.String())
+	return err
+}
+
+// writeGitHubJobSummary appends the run's summary to the GitHub Actions job summary,
+// if we are running in GitHub Actions
+func (r *RunState) writeGitHubJobSummary() error {
+	path := os.Getenv(ci.GitHubStepSummaryEnv)
+	if ci.Constant() != "GITHUB_ACTIONS" || path == "" {
+		return nil
+	}
+	f, err := os.OpenFile(path, os.O_APPEND|os.O_CREATE|os.O_WRONLY, 0644)
+	if err != nil {
+		return err
+	}
+	if err := r.writeJobSummary(f); err != nil {
+		_ = f.Close()
+		return err
+	}
+	return f.Close()
+}
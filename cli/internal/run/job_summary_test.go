@@ -0,0 +1,48 @@
+package run
+
+import (
+	"errors"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestWriteJobSummary(t *testing.T) {
+	runState := NewRunState(time.Now(), "", nil)
+	runState.Run("web#build")(TargetBuilt, nil)
+	runState.Run("docs#build")(TargetCached, nil)
+	runState.Run("web#test")(TargetBuildFailed, errors.New("exit status 1"))
+	runState.SetFailureArtifact("web#test", ".turbo/failures/abc123")
+	runState.SetEmptyOutputs("web#build", []string{"build/**", "storybook-static/**"})
+	runState.SetCacheKeySuffix("node18-musl")
+	runState.SetRemoteWriteSkipped("web#build")
+
+	var summary strings.Builder
+	err := runState.writeJobSummary(&summary)
+	assert.NoError(t, err, "writeJobSummary")
+	assert.Contains(t, summary.String(), "**Tasks:** 2 successful, 3 total")
+	assert.Contains(t, summary.String(), "**Cached:** 1 cached, 3 total")
+	assert.Contains(t, summary.String(), "**Cache key suffix:** `node18-musl`")
+	assert.Contains(t, summary.String(), "**Uploads skipped:** remote cache writes are off for `web#build`")
+	assert.Contains(t, summary.String(), "| Task | Status | Duration |\n| --- | --- | --- |\n| `web#build` | ✅ Executed |")
+	assert.Contains(t, summary.String(), "| `docs#build` | ⚡ Cached |")
+	assert.Contains(t, summary.String(), "| `web#test` | ❌ Failed |")
+	assert.Contains(t, summary.String(), "**Files captured from failed tasks:**\n\n- `web#test`: `.turbo/failures/abc123`")
+	assert.Contains(t, summary.String(), "**Outputs that matched no files:**\n\n- `web#build`: `build/**`, `storybook-static/**`")
+}
+
+func TestWriteJobSummaryTimedOut(t *testing.T) {
+	runState := NewRunState(time.Now(), "", nil)
+	runState.Run("web#build")(TargetBuilt, nil)
+	runState.Run("web#test")(TargetTimedOut, errors.New("command did not finish within 10m0s"))
+	runState.SetRunTimedOut(30 * time.Minute)
+
+	var summary strings.Builder
+	err := runState.writeJobSummary(&summary)
+	assert.NoError(t, err, "writeJobSummary")
+	assert.Contains(t, summary.String(), "**Tasks:** 1 successful, 2 total")
+	assert.Contains(t, summary.String(), "**Timed out:** the run didn't finish within 30m0s")
+	assert.Contains(t, summary.String(), "| `web#test` | ⌛ Timed out |")
+}
@@ -0,0 +1,151 @@
+package run
+
+import (
+	"io"
+	"sort"
+	"strings"
+	"sync"
+
+	"github.com/pyr-sh/dag"
+	"github.com/vercel/turborepo/cli/internal/core"
+)
+
+// orderedOutput writes tasks' grouped output in a fixed dependency order, rather than
+// in the order that tasks finish. A task's output is held until the output of every
+// task before it has been written. If more than maxBytes of output is held, the
+// earliest tasks that haven't finished yet are skipped over, and their output is
+// written as soon as they finish.
+type orderedOutput struct {
+	out      io.Writer
+	maxBytes int
+
+	mu sync.Mutex
+	// order is the task IDs in the order their output is written
+	order    []string
+	position map[string]int
+	// next is the position of the next task whose output will be written
+	next     int
+	groups   map[string]*groupedOutput
+	finished map[string]bool
+	held     int
+}
+
+func newOrderedOutput(out io.Writer, taskGraph *dag.AcyclicGraph, maxBytes int) *orderedOutput {
+	order := dependencyOrder(taskGraph)
+	position := make(map[string]int, len(order))
+	for i, taskID := range order {
+		position[taskID] = i
+	}
+	return &orderedOutput{
+		out:      out,
+		maxBytes: maxBytes,
+		order:    order,
+		position: position,
+		groups:   make(map[string]*groupedOutput),
+		finished: make(map[string]bool),
+	}
+}
+
+// dependencyOrder returns the tasks in the graph with every task after its dependencies.
+// Ties are broken by task ID, so the order is the same on every run.
+func dependencyOrder(taskGraph *dag.AcyclicGraph) []string {
+	remainingDeps := make(map[string]int)
+	var ready []string
+	for _, v := range taskGraph.Vertices() {
+		taskID := dag.VertexName(v)
+		if strings.Contains(taskID, core.ROOT_NODE_NAME) {
+			continue
+		}
+		count := 0
+		for _, dep := range taskGraph.DownEdges(taskID) {
+			if !strings.Contains(dag.VertexName(dep), core.ROOT_NODE_NAME) {
+				count++
+			}
+		}
+		remainingDeps[taskID] = count
+		if count == 0 {
+			ready = append(ready, taskID)
+		}
+	}
+	order := make([]string, 0, len(remainingDeps))
+	for len(ready) > 0 {
+		sort.Strings(ready)
+		taskID := ready[0]
+		ready = ready[1:]
+		order = append(order, taskID)
+		for _, dependent := range taskGraph.UpEdges(taskID) {
+			dependentID := dag.VertexName(dependent)
+			if _, ok := remainingDeps[dependentID]; !ok {
+				continue
+			}
+			remainingDeps[dependentID]--
+			if remainingDeps[dependentID] == 0 {
+				ready = append(ready, dependentID)
+			}
+		}
+	}
+	return order
+}
+
+// hold registers the output of a task that is about to run
+func (o *orderedOutput) hold(taskID string, group *groupedOutput) {
+	o.mu.Lock()
+	defer o.mu.Unlock()
+	o.groups[taskID] = group
+}
+
+// finish marks a task as done, and writes any output that is no longer waiting on
+// an earlier task
+func (o *orderedOutput) finish(taskID string) error {
+	o.mu.Lock()
+	defer o.mu.Unlock()
+	position, ok := o.position[taskID]
+	if !ok || position < o.next {
+		// This task was skipped over, so nothing is waiting on it
+		return o.write(taskID)
+	}
+	o.finished[taskID] = true
+	if group, ok := o.groups[taskID]; ok {
+		o.held += group.len()
+	}
+	for o.next < len(o.order) {
+		nextID := o.order[o.next]
+		if !o.finished[nextID] && o.held <= o.maxBytes {
+			break
+		}
+		o.next++
+		if o.finished[nextID] {
+			if group, ok := o.groups[nextID]; ok {
+				o.held -= group.len()
+			}
+			if err := o.write(nextID); err != nil {
+				return err
+			}
+		}
+	}
+	return nil
+}
+
+// close writes the output of every task that is still held, such as tasks that were
+// waiting on a task that never ran because a dependency failed
+func (o *orderedOutput) close() error {
+	o.mu.Lock()
+	defer o.mu.Unlock()
+	for ; o.next < len(o.order); o.next++ {
+		if err := o.write(o.order[o.next]); err != nil {
+			return err
+		}
+	}
+	o.held = 0
+	return nil
+}
+
+func (o *orderedOutput) write(taskID string) error {
+	group, ok := o.groups[taskID]
+	if !ok {
+		return nil
+	}
+	delete(o.groups, taskID)
+	delete(o.finished, taskID)
+	return group.flush(o.out)
+}
@@ -0,0 +1,73 @@
+package run
+
+import (
+	"bytes"
+	"testing"
+
+	"github.com/pyr-sh/dag"
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/core"
+)
+
+func orderedOutputTestGraph() *dag.AcyclicGraph {
+	g := &dag.AcyclicGraph{}
+	for _, v := range []string{core.ROOT_NODE_NAME, "a#build", "b#build", "c#build", "web#build"} {
+		g.Add(v)
+	}
+	g.Connect(dag.BasicEdge("a#build", core.ROOT_NODE_NAME))
+	g.Connect(dag.BasicEdge("c#build", core.ROOT_NODE_NAME))
+	g.Connect(dag.BasicEdge("b#build", "c#build"))
+	g.Connect(dag.BasicEdge("web#build", "a#build"))
+	g.Connect(dag.BasicEdge("web#build", "b#build"))
+	return g
+}
+
+func Test_dependencyOrder(t *testing.T) {
+	order := dependencyOrder(orderedOutputTestGraph())
+	assert.Equal(t, []string{"a#build", "c#build", "b#build", "web#build"}, order)
+}
+
+func runOrderedTask(o *orderedOutput, taskID string, output string) error {
+	group := newGroupedOutput(taskID, noMarkers{})
+	o.hold(taskID, group)
+	if _, err := group.Write([]byte(output)); err != nil {
+		return err
+	}
+	return o.finish(taskID)
+}
+
+func TestOrderedOutput(t *testing.T) {
+	var out bytes.Buffer
+	o := newOrderedOutput(&out, orderedOutputTestGraph(), 1024)
+
+	assert.NoError(t, runOrderedTask(o, "c#build", "c\n"))
+	assert.Equal(t, "", out.String(), "c#build should wait for a#build")
+	assert.NoError(t, runOrderedTask(o, "b#build", "b\n"))
+	assert.NoError(t, runOrderedTask(o, "a#build", "a\n"))
+	assert.Equal(t, "a\nc\nb\n", out.String())
+	assert.NoError(t, runOrderedTask(o, "web#build", "web\n"))
+	assert.Equal(t, "a\nc\nb\nweb\n", out.String())
+}
+
+func TestOrderedOutput_maxBytes(t *testing.T) {
+	var out bytes.Buffer
+	o := newOrderedOutput(&out, orderedOutputTestGraph(), 3)
+
+	assert.NoError(t, runOrderedTask(o, "c#build", "c\n"))
+	assert.Equal(t, "", out.String())
+	// Holding more than 3 bytes skips past a#build, which hasn't finished
+	assert.NoError(t, runOrderedTask(o, "b#build", "b\n"))
+	assert.Equal(t, "c\nb\n", out.String())
+	assert.NoError(t, runOrderedTask(o, "a#build", "a\n"))
+	assert.Equal(t, "c\nb\na\n", out.String())
+}
+
+func TestOrderedOutput_close(t *testing.T) {
+	var out bytes.Buffer
+	o := newOrderedOutput(&out, orderedOutputTestGraph(), 1024)
+
+	// a#build never finishes, e.g. because the run was cancelled
+	assert.NoError(t, runOrderedTask(o, "c#build", "c\n"))
+	assert.NoError(t, o.close())
+	assert.Equal(t, "c\n", out.String())
+}
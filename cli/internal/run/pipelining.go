@@ -0,0 +1,165 @@
+package run
+
+import (
+	"bytes"
+	"regexp"
+	"sync"
+	"time"
+
+	"github.com/pyr-sh/dag"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+// readySignal is closed once a task that declares readyWhen emits its marker
+type readySignal struct {
+	ready chan struct{}
+	once  sync.Once
+}
+
+func newReadySignal() *readySignal {
+	return &readySignal{ready: make(chan struct{})}
+}
+
+func (r *readySignal) fire() {
+	r.once.Do(func() { close(r.ready) })
+}
+
+// _maxReadyLineBytes bounds how much of a line without a newline is kept for matching
+const _maxReadyLineBytes = 64 * 1024
+
+// readyLogWriter fires its signal once the task logs a line that matches the pattern.
+// Each of a task's output streams needs its own, since lines are split across writes.
+type readyLogWriter struct {
+	pattern *regexp.Regexp
+	signal  *readySignal
+	line    []byte
+}
+
+func (w *readyLogWriter) Write(p []byte) (int, error) {
+	select {
+	case <-w.signal.ready:
+		return len(p), nil
+	default:
+	}
+	w.line = append(w.line, p...)
+	for {
+		end := bytes.IndexByte(w.line, '\n')
+		if end < 0 {
+			break
+		}
+		if w.pattern.Match(bytes.TrimRight(w.line[:end], "\r")) {
+			w.signal.fire()
+			w.line = nil
+			return len(p), nil
+		}
+		w.line = w.line[end+1:]
+	}
+	if len(w.line) > _maxReadyLineBytes {
+		w.line = w.line[len(w.line)-_maxReadyLineBytes:]
+	}
+	return len(p), nil
+}
+
+// _readyFilePollInterval is how often a task's readyWhen file is checked for
+const _readyFilePollInterval = 100 * time.Millisecond
+
+// watchReadyFile fires the signal once the file has been written since the task started,
+// so that a file left over from an earlier run doesn't count. It stops when done is closed.
+func watchReadyFile(file fs.AbsolutePath, started time.Time, signal *readySignal, done <-chan struct{}) {
+	// Some filesystems only keep modification times to the second
+	since := started.Truncate(time.Second)
+	ticker := time.NewTicker(_readyFilePollInterval)
+	defer ticker.Stop()
+	for {
+		if info, err := file.Lstat(); err == nil && !info.ModTime().Before(since) {
+			signal.fire()
+			return
+		}
+		select {
+		case <-done:
+			return
+		case <-ticker.C:
+		}
+	}
+}
+
+// pipelinedTask is a task whose dependents were started before it finished
+type pipelinedTask struct {
+	done chan struct{}
+	err  error
+}
+
+// pipelinedTasks tracks the tasks that are still running after their dependents started,
+// and the tasks that shouldn't be cached because one of those failed
+type pipelinedTasks struct {
+	mu    sync.Mutex
+	tasks map[string]*pipelinedTask
+	// uncached maps tasks that weren't cached to the failed task that caused it
+	uncached map[string]string
+}
+
+func newPipelinedTasks() *pipelinedTasks {
+	return &pipelinedTasks{
+		tasks:    make(map[string]*pipelinedTask),
+		uncached: make(map[string]string),
+	}
+}
+
+func (p *pipelinedTasks) add(taskID string, task *pipelinedTask) {
+	p.mu.Lock()
+	defer p.mu.Unlock()
+	p.tasks[taskID] = task
+}
+
+// failedDependency waits for any of the given dependencies that are still running after
+// their dependents started, and returns the first one that failed, either itself or in a
+// task it depends on. The outputs of a task that started early rely on outputs that were
+// never completed in that case, so they mustn't be cached.
+func (p *pipelinedTasks) failedDependency(deps dag.Set) (string, bool) {
+	for _, dep := range deps.List() {
+		taskID := dag.VertexName(dep)
+		p.mu.Lock()
+		task, ok := p.tasks[taskID]
+		p.mu.Unlock()
+		if ok {
+			<-task.done
+			if task.err != nil {
+				return taskID, true
+			}
+		}
+		p.mu.Lock()
+		failed, ok := p.uncached[taskID]
+		p.mu.Unlock()
+		if ok {
+			return failed, true
+		}
+	}
+	return "", false
+}
+
+// setUncached records that a task wasn't cached because the given task failed, so that
+// the tasks that depend on it aren't cached either
+func (p *pipelinedTasks) setUncached(taskID string, failed string) {
+	p.mu.Lock()
+	defer p.mu.Unlock()
+	p.uncached[taskID] = failed
+}
+
+// wait waits for every task whose dependents started before it finished, and returns the
+// errors of those that failed
+func (p *pipelinedTasks) wait() []error {
+	p.mu.Lock()
+	tasks := make([]*pipelinedTask, 0, len(p.tasks))
+	for _, task := range p.tasks {
+		tasks = append(tasks, task)
+	}
+	p.mu.Unlock()
+	var errs []error
+	for _, task := range tasks {
+		<-task.done
+		if task.err != nil {
+			errs = append(errs, task.err)
+		}
+	}
+	return errs
+}
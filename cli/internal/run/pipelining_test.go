@@ -0,0 +1,82 @@
+package run
+
+import (
+	"errors"
+	"io"
+	"os"
+	"regexp"
+	"testing"
+	"time"
+
+	"github.com/pyr-sh/dag"
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+func isReady(signal *readySignal) bool {
+	select {
+	case <-signal.ready:
+		return true
+	default:
+		return false
+	}
+}
+
+func Test_readyLogWriter(t *testing.T) {
+	signal := newReadySignal()
+	w := &readyLogWriter{pattern: regexp.MustCompile("^Declarations emitted$"), signal: signal}
+
+	_, err := io.WriteString(w, "Building...\nDeclarations emi")
+	assert.NoError(t, err)
+	assert.False(t, isReady(signal), "a partial line shouldn't match")
+	_, err = io.WriteString(w, "tted\r\nBundling...\n")
+	assert.NoError(t, err)
+	assert.True(t, isReady(signal))
+}
+
+func Test_watchReadyFile(t *testing.T) {
+	dir := fs.AbsolutePathFromUpstream(t.TempDir())
+	file := dir.Join("index.d.ts")
+	assert.NoError(t, file.WriteFile([]byte("stale"), 0644))
+	stale := time.Now().Add(-time.Hour)
+	assert.NoError(t, os.Chtimes(file.ToString(), stale, stale))
+
+	signal := newReadySignal()
+	done := make(chan struct{})
+	watched := make(chan struct{})
+	go func() {
+		watchReadyFile(file, time.Now(), signal, done)
+		close(watched)
+	}()
+	time.Sleep(2 * _readyFilePollInterval)
+	assert.False(t, isReady(signal), "a file from an earlier run shouldn't count")
+
+	assert.NoError(t, file.WriteFile([]byte("fresh"), 0644))
+	<-watched
+	assert.True(t, isReady(signal))
+}
+
+func Test_pipelinedTasks(t *testing.T) {
+	pipelined := newPipelinedTasks()
+	upstream := &pipelinedTask{done: make(chan struct{})}
+	pipelined.add("ui#build", upstream)
+
+	deps := make(dag.Set)
+	deps.Add("ui#build")
+	go func() {
+		upstream.err = errors.New("exit status 1")
+		close(upstream.done)
+	}()
+	failed, ok := pipelined.failedDependency(deps)
+	assert.True(t, ok, "dependents should wait for, and see, the failure")
+	assert.Equal(t, "ui#build", failed)
+
+	pipelined.setUncached("web#typecheck", failed)
+	deps = make(dag.Set)
+	deps.Add("web#typecheck")
+	failed, ok = pipelined.failedDependency(deps)
+	assert.True(t, ok, "tasks that weren't cached shouldn't be built on")
+	assert.Equal(t, "ui#build", failed)
+
+	assert.Equal(t, []error{upstream.err}, pipelined.wait())
+}
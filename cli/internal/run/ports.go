@@ -0,0 +1,112 @@
+package run
+
+import (
+	"fmt"
+	"net"
+	"regexp"
+	"sort"
+	"strconv"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+// portAllocation is a port that a task declared in its "ports", for the duration of a run
+type portAllocation struct {
+	TaskID string
+	// EnvVar is the environment variable that the port is exported as, to every task in the run
+	EnvVar string
+	Port   int
+}
+
+var _nonEnvVarChars = regexp.MustCompile("[^A-Z0-9]+")
+
+// portEnvVar returns the name of the environment variable for the index-th port of a task,
+// e.g. TURBO_PORT_WEB_DEV for the first port of web#dev, and TURBO_PORT_WEB_DEV_1 for its second
+func portEnvVar(taskID string, index int) string {
+	name := "TURBO_PORT_" + strings.Trim(_nonEnvVarChars.ReplaceAllString(strings.ToUpper(taskID), "_"), "_")
+	if index > 0 {
+		name = fmt.Sprintf("%v_%v", name, index)
+	}
+	return name
+}
+
+// freePort asks the OS for a port that is currently free on the loopback interface
+func freePort() (int, error) {
+	listener, err := net.Listen("tcp", "127.0.0.1:0")
+	if err != nil {
+		return 0, err
+	}
+	defer func() { _ = listener.Close() }()
+	return listener.Addr().(*net.TCPAddr).Port, nil
+}
+
+// allocatePorts assigns the ports declared by the given tasks. Fixed ports are checked for
+// conflicts between tasks, and "auto" ports are assigned a free port that no other task uses.
+func allocatePorts(taskIDs []string, pipeline fs.Pipeline, getFreePort func() (int, error)) ([]portAllocation, error) {
+	sortedTaskIDs := append([]string{}, taskIDs...)
+	sort.Strings(sortedTaskIDs)
+
+	var allocations []portAllocation
+	owners := make(map[int]string)
+	// Fixed ports go first, so that auto ports can avoid them
+	for _, taskID := range sortedTaskIDs {
+		taskDefinition, ok := pipeline.GetTaskDefinition(taskID)
+		if !ok {
+			continue
+		}
+		for i, port := range taskDefinition.Ports {
+			if port == fs.AutoPort {
+				continue
+			}
+			number, err := strconv.Atoi(port)
+			if err != nil {
+				return nil, fmt.Errorf("invalid port %q for %v", port, taskID)
+			}
+			if owner, ok := owners[number]; ok {
+				return nil, fmt.Errorf("port %v is used by both %v and %v. Use \"auto\" for one of them to have turbo pick a free port", number, owner, taskID)
+			}
+			owners[number] = taskID
+			allocations = append(allocations, portAllocation{TaskID: taskID, EnvVar: portEnvVar(taskID, i), Port: number})
+		}
+	}
+	for _, taskID := range sortedTaskIDs {
+		taskDefinition, ok := pipeline.GetTaskDefinition(taskID)
+		if !ok {
+			continue
+		}
+		for i, port := range taskDefinition.Ports {
+			if port != fs.AutoPort {
+				continue
+			}
+			number, err := getFreePort()
+			if err != nil {
+				return nil, fmt.Errorf("failed to find a free port for %v: %w", taskID, err)
+			}
+			// The OS may hand back a port that was just released, so retry a few times
+			for attempt := 0; owners[number] != "" && attempt < 10; attempt++ {
+				if number, err = getFreePort(); err != nil {
+					return nil, fmt.Errorf("failed to find a free port for %v: %w", taskID, err)
+				}
+			}
+			if owner, ok := owners[number]; ok {
+				return nil, fmt.Errorf("failed to find a free port for %v: %v is already used by %v", taskID, number, owner)
+			}
+			owners[number] = taskID
+			allocations = append(allocations, portAllocation{TaskID: taskID, EnvVar: portEnvVar(taskID, i), Port: number})
+		}
+	}
+	sort.Slice(allocations, func(i, j int) bool {
+		return allocations[i].EnvVar < allocations[j].EnvVar
+	})
+	return allocations, nil
+}
+
+// portEnv returns the environment variables that export the given port allocations
+func portEnv(allocations []portAllocation) []string {
+	env := make([]string, len(allocations))
+	for i, allocation := range allocations {
+		env[i] = fmt.Sprintf("%v=%v", allocation.EnvVar, allocation.Port)
+	}
+	return env
+}
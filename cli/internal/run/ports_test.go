@@ -0,0 +1,54 @@
+package run
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+func Test_portEnvVar(t *testing.T) {
+	assert.Equal(t, "TURBO_PORT_WEB_DEV", portEnvVar("web#dev", 0))
+	assert.Equal(t, "TURBO_PORT_SCOPE_API_DEV_1", portEnvVar("@scope/api#dev", 1))
+}
+
+func Test_allocatePorts(t *testing.T) {
+	pipeline := fs.Pipeline{
+		"dev":         {Ports: []string{fs.AutoPort}},
+		"docs#dev":    {Ports: []string{"3000"}},
+		"storybook":   {Ports: []string{fs.AutoPort, "6006"}},
+		"build":       {},
+		"web#preview": {Ports: []string{"3000"}},
+	}
+	// Hand out 3000 first, which is taken by docs#dev and must be skipped
+	next := []int{3000, 4001, 4002}
+	getFreePort := func() (int, error) {
+		port := next[0]
+		next = next[1:]
+		return port, nil
+	}
+
+	allocations, err := allocatePorts([]string{"web#dev", "docs#dev", "ui#storybook", "web#build"}, pipeline, getFreePort)
+	assert.NoError(t, err)
+	assert.Equal(t, []portAllocation{
+		{TaskID: "docs#dev", EnvVar: "TURBO_PORT_DOCS_DEV", Port: 3000},
+		{TaskID: "ui#storybook", EnvVar: "TURBO_PORT_UI_STORYBOOK", Port: 4001},
+		{TaskID: "ui#storybook", EnvVar: "TURBO_PORT_UI_STORYBOOK_1", Port: 6006},
+		{TaskID: "web#dev", EnvVar: "TURBO_PORT_WEB_DEV", Port: 4002},
+	}, allocations)
+	assert.Equal(t, []string{
+		"TURBO_PORT_DOCS_DEV=3000",
+		"TURBO_PORT_UI_STORYBOOK=4001",
+		"TURBO_PORT_UI_STORYBOOK_1=6006",
+		"TURBO_PORT_WEB_DEV=4002",
+	}, portEnv(allocations))
+}
+
+func Test_allocatePortsConflict(t *testing.T) {
+	pipeline := fs.Pipeline{
+		"docs#dev":    {Ports: []string{"3000"}},
+		"web#preview": {Ports: []string{"3000"}},
+	}
+	_, err := allocatePorts([]string{"docs#dev", "web#preview"}, pipeline, freePort)
+	assert.EqualError(t, err, "port 3000 is used by both docs#dev and web#preview. Use \"auto\" for one of them to have turbo pick a free port")
+}
@@ -4,6 +4,7 @@ import (
 	gocontext "context"
 	"encoding/json"
 	"fmt"
+	"io"
 	"log"
 	"os"
 	"os/exec"
@@ -20,6 +21,8 @@ import (
 	"github.com/spf13/pflag"
 	"github.com/vercel/turborepo/cli/internal/analytics"
 	"github.com/vercel/turborepo/cli/internal/cache"
+	"github.com/vercel/turborepo/cli/internal/chrometracing"
+	"github.com/vercel/turborepo/cli/internal/ci"
 	"github.com/vercel/turborepo/cli/internal/colorcache"
 	"github.com/vercel/turborepo/cli/internal/config"
 	"github.com/vercel/turborepo/cli/internal/context"
@@ -35,6 +38,7 @@ import (
 	"github.com/vercel/turborepo/cli/internal/runcache"
 	"github.com/vercel/turborepo/cli/internal/scm"
 	"github.com/vercel/turborepo/cli/internal/scope"
+	scope_filter "github.com/vercel/turborepo/cli/internal/scope/filter"
 	"github.com/vercel/turborepo/cli/internal/signals"
 	"github.com/vercel/turborepo/cli/internal/taskhash"
 	"github.com/vercel/turborepo/cli/internal/ui"
@@ -60,7 +64,9 @@ type completeGraph struct {
 	Pipeline         fs.Pipeline
 	PackageInfos     map[interface{}]*fs.PackageJSON
 	GlobalHash       string
+	GlobalHashInputs *context.GlobalHashInputs
 	RootNode         string
+	Hooks            fs.Hooks
 }
 
 // runSpec contains the run-specific configuration elements that come from a particular
@@ -69,6 +75,19 @@ type runSpec struct {
 	Targets      []string
 	FilteredPkgs util.Set
 	Opts         *Opts
+	// PackageSelections explains how the filters selected packages, if --explain-filter was passed
+	PackageSelections []scope_filter.PackageSelection
+	// NeededTasks are the tasks to run, if --only-needed-for was passed. The other tasks
+	// in the graph are only hashed.
+	NeededTasks util.Set
+	// InferredPackage is the package that was selected because turbo was run from its
+	// directory, if inferPackageFromCwd is set
+	InferredPackage string
+}
+
+// isNeeded returns whether a task should run, rather than only be hashed
+func (rs *runSpec) isNeeded(taskID string) bool {
+	return rs.NeededTasks == nil || rs.NeededTasks.Includes(taskID)
 }
 
 func (rs *runSpec) ArgsForTask(task string) []string {
@@ -78,7 +97,7 @@ func (rs *runSpec) ArgsForTask(task string) []string {
 			passThroughArgs = append(passThroughArgs, rs.Opts.runOpts.passThroughArgs...)
 		}
 	}
-	return passThroughArgs
+	return append(passThroughArgs, rs.Opts.runOpts.taskArgs[task]...)
 }
 
 var _cmdLong = `
@@ -91,6 +110,7 @@ artifacts from the cache into the correct output folders (as if the task
 occurred again).
 
 Arguments passed after '--' will be passed through to the named tasks.
+Use --args <task>:<args> to pass arguments to a single task.
 `
 
 func getCmd(config *config.Config, ui cli.Ui, signalWatcher *signals.Watcher) *cobra.Command {
@@ -108,7 +128,18 @@ func getCmd(config *config.Config, ui cli.Ui, signalWatcher *signals.Watcher) *c
 			if len(tasks) == 0 {
 				return errors.New("at least one task must be specified")
 			}
+			if opts.runOpts.explainGlobalHash && !opts.runOpts.dryRun {
+				return errors.New("--explain-global-hash can only be used with --dry")
+			}
+			if opts.runOpts.explainFilter && !opts.runOpts.dryRun {
+				return errors.New("--explain-filter can only be used with --dry")
+			}
 			opts.runOpts.passThroughArgs = passThroughArgs
+			taskArgs, err := parseTaskArgs(opts.runOpts.taskArgFlags, tasks)
+			if err != nil {
+				return err
+			}
+			opts.runOpts.taskArgs = taskArgs
 			run := configureRun(config, ui, opts, signalWatcher)
 			ctx := cmd.Context()
 			return run.run(ctx, tasks)
@@ -154,16 +185,23 @@ func configureRun(config *config.Config, output cli.Ui, opts *Opts, signalWatche
 		opts.cacheOpts.SkipFilesystem = true
 	}
 
+	if suffix := os.Getenv("TURBO_CACHE_KEY_SUFFIX"); suffix != "" && opts.runOpts.cacheKeySuffix == "" {
+		opts.runOpts.cacheKeySuffix = suffix
+	}
+
 	if !config.IsLoggedIn() {
 		opts.cacheOpts.SkipRemote = true
 	}
 	processes := process.NewManager(config.Logger.Named("processes"))
 	signalWatcher.AddOnClose(processes.Close)
+	hookProcesses := process.NewManager(config.Logger.Named("hooks"))
+	signalWatcher.AddOnClose(hookProcesses.Close)
 	return &run{
-		opts:      opts,
-		config:    config,
-		ui:        output,
-		processes: processes,
+		opts:          opts,
+		config:        config,
+		ui:            output,
+		processes:     processes,
+		hookProcesses: hookProcesses,
 	}
 }
 
@@ -200,17 +238,34 @@ type run struct {
 	config    *config.Config
 	ui        cli.Ui
 	processes *process.Manager
+	// hookProcesses runs the preRun and postRun hooks. It is separate from processes,
+	// which is closed to stop the tasks when one fails or the run times out, so that
+	// postRun still runs in those cases.
+	hookProcesses *process.Manager
 }
 
 func (r *run) run(ctx gocontext.Context, targets []string) error {
 	startAt := time.Now()
+	if r.opts.runOpts.profile != "" {
+		// Start tracing before the package graph is built, so that the profile
+		// also covers the phases before any task runs
+		chrometracing.EnableTracing()
+	}
 	turboJSON, err := fs.ReadTurboConfig(r.config.Cwd, r.config.RootPackageJSON)
 	if err != nil {
+		if ci.Constant() == "GITHUB_ACTIONS" {
+			r.ui.Output(strings.TrimSuffix(ci.GitHubErrorAnnotation("turbo.json", err.Error()), "\n"))
+		}
 		return err
 	}
 	// TODO: these values come from a config file, hopefully viper can help us merge these
 	r.opts.cacheOpts.RemoteCacheOpts = turboJSON.RemoteCacheOptions
+	if r.opts.runOpts.cacheKeySuffix != "" {
+		turboJSON.CacheKeySuffix = r.opts.runOpts.cacheKeySuffix
+	}
+	graphEvent := chrometracing.Event("build package graph")
 	pkgDepGraph, err := context.New(context.WithGraph(r.config, turboJSON, r.opts.cacheOpts.Dir))
+	graphEvent.Done()
 	if err != nil {
 		return err
 	}
@@ -225,18 +280,43 @@ func (r *run) run(ctx gocontext.Context, targets []string) error {
 			r.config.Logger.Debug("running in daemon mode")
 			daemonClient := daemonclient.New(turbodClient)
 			r.opts.runcacheOpts.OutputWatcher = daemonClient
+			r.opts.runOpts.fileQuerier = daemonClient
+			r.opts.runOpts.fileHashSource = func(repoRelativeFiles []string) (map[string]string, error) {
+				return daemonClient.GetFileHashes(ctx, repoRelativeFiles)
+			}
 		}
 	}
+	if r.opts.runOpts.warnUndeclaredWrites && r.opts.runOpts.fileQuerier == nil {
+		r.logWarning("", errors.New("--warn-undeclared-writes needs the turbo daemon, so it is ignored"))
+	}
 
 	if err := util.ValidateGraph(&pkgDepGraph.TopologicalGraph); err != nil {
 		return errors.Wrap(err, "Invalid package dependency graph")
 	}
 
 	pipeline := turboJSON.Pipeline
+	if r.opts.runOpts.infoPackage != "" {
+		targets, err = infoTargets(pipeline, pkgDepGraph.PackageInfos, r.opts.runOpts.infoPackage)
+		if err != nil {
+			return err
+		}
+	}
 	if err := validateTasks(pipeline, targets); err != nil {
 		return err
 	}
 
+	inferredPackage := ""
+	if turboJSON.InferPackageFromCwd && r.config.InvocationDir != "" && !r.opts.scopeOpts.HasFilters() {
+		invocationDir, err := r.config.Cwd.RelativePathString(r.config.InvocationDir.ToStringDuringMigration())
+		if err != nil {
+			return err
+		}
+		if pkg, ok := packageContaining(pkgDepGraph.PackageInfos, invocationDir); ok {
+			inferredPackage = pkg
+			r.opts.scopeOpts.FilterPatterns = []string{pkg}
+		}
+	}
+
 	scmInstance, err := scm.FromInRepo(r.config.Cwd.ToStringDuringMigration())
 	if err != nil {
 		if errors.Is(err, scm.ErrFallback) {
@@ -252,14 +332,18 @@ func (r *run) run(ctx gocontext.Context, targets []string) error {
 	if isAllPackages {
 		// if there is a root task for any of our targets, we need to add it
 		for _, target := range targets {
-			key := util.RootTaskID(target)
-			if _, ok := pipeline[key]; ok {
+			if key, ok := util.ResolveTaskKey(pipeline.Keys(), util.RootTaskID(target)); ok && util.IsPackageTask(key) {
 				filteredPkgs.Add(util.RootPkgName)
 				// we only need to know we're running a root task once to add it for consideration
 				break
 			}
 		}
 	}
+	if turboJSON.Strict {
+		if err := validateScriptCoverage(pipeline, pkgDepGraph.PackageInfos, filteredPkgs, targets); err != nil {
+			return err
+		}
+	}
 	r.config.Logger.Debug("global hash", "value", pkgDepGraph.GlobalHash)
 	r.config.Logger.Debug("local cache folder", "path", r.opts.cacheOpts.Dir)
 
@@ -269,12 +353,22 @@ func (r *run) run(ctx gocontext.Context, targets []string) error {
 		Pipeline:         pipeline,
 		PackageInfos:     pkgDepGraph.PackageInfos,
 		GlobalHash:       pkgDepGraph.GlobalHash,
+		GlobalHashInputs: pkgDepGraph.GlobalHashInputs,
 		RootNode:         pkgDepGraph.RootNode,
+		Hooks:            turboJSON.Hooks,
 	}
 	rs := &runSpec{
-		Targets:      targets,
-		FilteredPkgs: filteredPkgs,
-		Opts:         r.opts,
+		Targets:         targets,
+		FilteredPkgs:    filteredPkgs,
+		Opts:            r.opts,
+		InferredPackage: inferredPackage,
+	}
+	if r.opts.runOpts.explainFilter {
+		selections, err := scope.ExplainPackages(&r.opts.scopeOpts, r.config.Cwd.ToStringDuringMigration(), scmInstance, pkgDepGraph)
+		if err != nil {
+			return errors.Wrap(err, "failed to explain package selection")
+		}
+		rs.PackageSelections = selections
 	}
 	packageManager := pkgDepGraph.PackageManager
 	return r.runOperation(ctx, g, rs, packageManager, startAt)
@@ -286,12 +380,21 @@ func (r *run) runOperation(ctx gocontext.Context, g *completeGraph, rs *runSpec,
 		vertexSet.Add(v)
 	}
 
+	if rs.Opts.runOpts.infoPackage != "" && len(rs.Targets) == 0 {
+		// None of the package's scripts are tasks, so there is nothing to hash
+		return r.printPackageInfo(ctx, nil, g, nil, rs)
+	}
 	engine, err := buildTaskGraph(&g.TopologicalGraph, g.Pipeline, rs)
 	if err != nil {
 		return errors.Wrap(err, "error preparing engine")
 	}
 	hashTracker := taskhash.NewTracker(g.RootNode, g.GlobalHash, g.Pipeline, g.PackageInfos)
+	if rs.Opts.runOpts.fileHashSource != nil {
+		hashTracker.UseFileHashSource(r.config.Cwd, rs.Opts.runOpts.fileHashSource)
+	}
+	fileHashEvent := chrometracing.Event("hash package files")
 	err = hashTracker.CalculateFileHashes(engine.TaskGraph.Vertices(), rs.Opts.runOpts.concurrency, r.config.Cwd)
+	fileHashEvent.Done()
 	if err != nil {
 		return errors.Wrap(err, "error hashing package files")
 	}
@@ -310,8 +413,42 @@ func (r *run) runOperation(ctx gocontext.Context, g *completeGraph, rs *runSpec,
 			return errors.Wrap(err, "error preparing engine")
 		}
 	}
+	if len(rs.Opts.runOpts.onlyNeededFor) > 0 {
+		rs.NeededTasks, err = engine.PruneToNeeded(rs.Opts.runOpts.onlyNeededFor, func(taskID string) bool {
+			taskDefinition, ok := g.Pipeline.GetTaskDefinition(taskID)
+			return ok && (len(taskDefinition.Outputs) > 0 || len(taskDefinition.DynamicOutputs) > 0)
+		})
+		if err != nil {
+			return errors.Wrap(err, "error preparing engine")
+		}
+	}
 
-	if rs.Opts.runOpts.graphFile != "" || rs.Opts.runOpts.graphDot {
+	if rs.Opts.runOpts.hashOnly {
+		return r.printHashes(ctx, engine, g, hashTracker, rs)
+	} else if rs.Opts.runOpts.warmOnly {
+		return r.warmCache(ctx, engine, g, hashTracker, rs)
+	} else if rs.Opts.runOpts.infoPackage != "" {
+		return r.printPackageInfo(ctx, engine, g, hashTracker, rs)
+	} else if rs.Opts.runOpts.serveGraph != "" {
+		tasks, err := r.executeDryRun(ctx, engine, g, hashTracker, rs)
+		if err != nil {
+			return err
+		}
+		graphNodes := make([]graphvisualizer.GraphNode, len(tasks))
+		for i, task := range tasks {
+			graphNodes[i] = graphvisualizer.GraphNode{
+				TaskID:       task.TaskID,
+				Task:         task.Task,
+				Package:      task.Package,
+				Hash:         task.Hash,
+				Command:      task.Command,
+				Cached:       rs.Opts.cacheOpts.Dir.Join(task.Hash).DirExists(),
+				Dependencies: taskDependencies(engine.TaskGraph, task.TaskID),
+			}
+		}
+		visualizer := graphvisualizer.New(r.config, r.ui, engine.TaskGraph)
+		return visualizer.Serve(rs.Opts.runOpts.serveGraph, graphNodes)
+	} else if rs.Opts.runOpts.graphFile != "" || rs.Opts.runOpts.graphDot {
 		visualizer := graphvisualizer.New(r.config, r.ui, engine.TaskGraph)
 
 		if rs.Opts.runOpts.graphDot {
@@ -329,19 +466,57 @@ func (r *run) runOperation(ctx gocontext.Context, g *completeGraph, rs *runSpec,
 		}
 		packagesInScope := rs.FilteredPkgs.UnsafeListOfStrings()
 		sort.Strings(packagesInScope)
+		var globalHash *globalHashExplanation
+		if rs.Opts.runOpts.explainGlobalHash {
+			globalHash = &globalHashExplanation{
+				Hash:   g.GlobalHash,
+				Inputs: g.GlobalHashInputs,
+			}
+		}
+		var taskSelections []TaskSelection
+		if rs.Opts.runOpts.explainSelection {
+			taskSelections = explainTaskSelection(engine.TaskGraph, g.Pipeline, rs)
+		}
 		if rs.Opts.runOpts.dryRunJSON {
 			dryRun := &struct {
-				Packages []string     `json:"packages"`
-				Tasks    []hashedTask `json:"tasks"`
+				Packages          []string                        `json:"packages"`
+				Tasks             []hashedTask                    `json:"tasks"`
+				GlobalHash        *globalHashExplanation          `json:"globalHash,omitempty"`
+				PackageSelections []scope_filter.PackageSelection `json:"packageSelections,omitempty"`
+				TaskSelections    []TaskSelection                 `json:"taskSelections,omitempty"`
+				CacheKeySuffix    string                          `json:"cacheKeySuffix,omitempty"`
 			}{
-				Packages: packagesInScope,
-				Tasks:    tasksRun,
+				Packages:          packagesInScope,
+				Tasks:             tasksRun,
+				GlobalHash:        globalHash,
+				PackageSelections: rs.PackageSelections,
+				TaskSelections:    taskSelections,
+				CacheKeySuffix:    cacheKeySuffix(g.GlobalHashInputs),
 			}
 			bytes, err := json.MarshalIndent(dryRun, "", "  ")
 			if err != nil {
 				return errors.Wrap(err, "failed to render JSON")
 			}
 			r.ui.Output(string(bytes))
+		} else if rs.Opts.runOpts.dryRunMarkdown {
+			report := &dryRunReport{
+				Targets:        rs.Targets,
+				Packages:       packagesInScope,
+				Tasks:          tasksRun,
+				Dependencies:   make(map[string][]string, len(tasksRun)),
+				Predictions:    make(map[string]cachePrediction, len(tasksRun)),
+				GlobalHash:     g.GlobalHash,
+				CacheKeySuffix: cacheKeySuffix(g.GlobalHashInputs),
+			}
+			for _, task := range tasksRun {
+				report.Dependencies[task.TaskID] = taskDependencies(engine.TaskGraph, task.TaskID)
+				report.Predictions[task.TaskID] = predictCache(g.Pipeline, rs.Opts, task)
+			}
+			var b strings.Builder
+			if err := writeDryRunMarkdown(&b, report); err != nil {
+				return err
+			}
+			r.ui.Output(b.String())
 		} else {
 			r.ui.Output("")
 			r.ui.Info(util.Sprintf("${CYAN}${BOLD}Packages in Scope${RESET}"))
@@ -367,26 +542,93 @@ func (r *run) runOperation(ctx gocontext.Context, g *completeGraph, rs *runSpec,
 				fmt.Fprintln(w, util.Sprintf("  ${GREY}Log File\t=\t%s\t${RESET}", task.LogFile))
 				fmt.Fprintln(w, util.Sprintf("  ${GREY}Dependencies\t=\t%s\t${RESET}", strings.Join(task.Dependencies, ", ")))
 				fmt.Fprintln(w, util.Sprintf("  ${GREY}Dependendents\t=\t%s\t${RESET}", strings.Join(task.Dependents, ", ")))
+				fmt.Fprintln(w, util.Sprintf("  ${GREY}Environment Variables\t=\t%s\t${RESET}", strings.Join(envVarNames(task.EnvVars), ", ")))
 				w.Flush()
 			}
+
+			if rs.PackageSelections != nil {
+				r.ui.Output("")
+				r.ui.Info(util.Sprintf("${CYAN}${BOLD}Package Selection${RESET}"))
+				for _, selection := range rs.PackageSelections {
+					status := "not selected"
+					if selection.Selected {
+						status = "selected"
+					}
+					r.ui.Info(util.Sprintf("${BOLD}%s${RESET} %s", selection.Package, status))
+					for _, reason := range selection.Reasons {
+						r.ui.Info(util.Sprintf("  ${GREY}%s${RESET}", reason))
+					}
+				}
+			}
+
+			if taskSelections != nil {
+				r.ui.Output("")
+				r.ui.Info(util.Sprintf("${CYAN}${BOLD}Task Selection${RESET}"))
+				for _, selection := range taskSelections {
+					r.ui.Info(util.Sprintf("${BOLD}%s${RESET}", selection.TaskID))
+					for _, reason := range selection.Reasons {
+						r.ui.Info(util.Sprintf("  ${GREY}%s${RESET}", reason))
+					}
+				}
+			}
+
+			if globalHash != nil {
+				bytes, err := json.MarshalIndent(globalHash, "", "  ")
+				if err != nil {
+					return errors.Wrap(err, "failed to render JSON")
+				}
+				r.ui.Output("")
+				r.ui.Info(util.Sprintf("${CYAN}${BOLD}Global Hash Inputs${RESET}"))
+				r.ui.Output(string(bytes))
+			}
 		}
 	} else {
 		packagesInScope := rs.FilteredPkgs.UnsafeListOfStrings()
 		sort.Strings(packagesInScope)
+		if rs.InferredPackage != "" {
+			r.ui.Output(ui.Dim(fmt.Sprintf("• Running in %v only, since turbo was run from its directory. Pass --filter to choose other packages", rs.InferredPackage)))
+		}
 		r.ui.Output(fmt.Sprintf(ui.Dim("• Packages in scope: %v"), strings.Join(packagesInScope, ", ")))
 		r.ui.Output(fmt.Sprintf("%s %s %s", ui.Dim("• Running"), ui.Dim(ui.Bold(strings.Join(rs.Targets, ", "))), ui.Dim(fmt.Sprintf("in %v packages", rs.FilteredPkgs.Len()))))
-		return r.executeTasks(ctx, g, rs, engine, packageManager, hashTracker, startAt)
+		if rs.Opts.runOpts.explainSelection {
+			for _, selection := range explainTaskSelection(engine.TaskGraph, g.Pipeline, rs) {
+				r.ui.Output(ui.Dim(fmt.Sprintf("• %v: %v", selection.TaskID, strings.Join(selection.Reasons, "; "))))
+			}
+		}
+		var taskIDs []string
+		for _, v := range engine.TaskGraph.Vertices() {
+			if taskID := v.(string); !strings.Contains(taskID, core.ROOT_NODE_NAME) {
+				taskIDs = append(taskIDs, taskID)
+			}
+		}
+		ports, err := allocatePorts(taskIDs, g.Pipeline, freePort)
+		if err != nil {
+			return err
+		}
+		if len(ports) > 0 {
+			assigned := make([]string, len(ports))
+			for i, allocation := range ports {
+				assigned[i] = fmt.Sprintf("%v=%v", allocation.TaskID, allocation.Port)
+			}
+			r.ui.Output(fmt.Sprintf(ui.Dim("• Ports: %v"), strings.Join(assigned, ", ")))
+		}
+		return r.executeTasks(ctx, g, rs, engine, packageManager, hashTracker, startAt, ports)
 	}
 	return nil
 }
 
 func buildTaskGraph(topoGraph *dag.AcyclicGraph, pipeline fs.Pipeline, rs *runSpec) (*core.Scheduler, error) {
 	engine := core.NewScheduler(topoGraph)
+	// dependsOn entries that refer to more than one package, to point at if there is a cycle
+	var packagePatterns []string
 	for taskName, taskDefinition := range pipeline {
 		topoDeps := make(util.Set)
 		deps := make(util.Set)
 		isPackageTask := util.IsPackageTask(taskName)
 		for _, dependency := range taskDefinition.TaskDependencies {
+			if util.IsPackagePattern(dependency) {
+				packagePatterns = append(packagePatterns, fmt.Sprintf("%q in the dependsOn of %q", dependency, taskName))
+			}
 			if isPackageTask && util.IsPackageTask(dependency) {
 				err := engine.AddDep(dependency, taskName)
 				if err != nil {
@@ -397,6 +639,10 @@ func buildTaskGraph(topoGraph *dag.AcyclicGraph, pipeline fs.Pipeline, rs *runSp
 			}
 		}
 		for _, dependency := range taskDefinition.TopologicalDependencies {
+			// For root tasks, these refer to every workspace package
+			if isPackageTask && strings.HasPrefix(taskName, util.RootPkgName+util.TaskDelimiter) {
+				packagePatterns = append(packagePatterns, fmt.Sprintf("%q in the dependsOn of %q", "^"+dependency, taskName))
+			}
 			topoDeps.Add(dependency)
 		}
 		engine.AddTask(&core.Task{
@@ -415,6 +661,10 @@ func buildTaskGraph(topoGraph *dag.AcyclicGraph, pipeline fs.Pipeline, rs *runSp
 	}
 
 	if err := util.ValidateGraph(engine.TaskGraph); err != nil {
+		if len(packagePatterns) > 0 {
+			sort.Strings(packagePatterns)
+			return nil, fmt.Errorf("Invalid task dependency graph:\n%v\nThese dependencies match more than one package, and may cause the cycle: %v", err, strings.Join(packagePatterns, ", "))
+		}
 		return nil, fmt.Errorf("Invalid task dependency graph:\n%v", err)
 	}
 
@@ -437,32 +687,111 @@ type runOpts struct {
 	concurrency int
 	// Whether to execute in parallel (defaults to false)
 	parallel bool
+	// Task IDs to run, along with their dependencies, ahead of other tasks
+	priority []string
+	// Task IDs to run along with only the tasks whose outputs they consume
+	onlyNeededFor []string
+	// How long the whole run may take before remaining tasks are stopped
+	timeout time.Duration
 	// Whether to emit a perf profile
 	profile string
 	// If true, continue task executions even if a task fails.
 	continueOnError bool
 	passThroughArgs []string
+	// Args for individual tasks, from --args <task>:<args>
+	taskArgFlags []string
+	taskArgs     map[string][]string
 	// Restrict execution to only the listed task names. Default false
 	only bool
 	// Dry run flags
-	dryRun     bool
-	dryRunJSON bool
+	dryRun         bool
+	dryRunJSON     bool
+	dryRunMarkdown bool
+	// Include everything that contributes to the global hash in the dry run
+	explainGlobalHash bool
+	// Explain why each package was or wasn't selected by the filters in the dry run
+	explainFilter bool
+	// Explain why each task is part of the run
+	explainSelection bool
 	// Graph flags
 	graphDot    bool
 	graphFile   string
+	serveGraph  string
 	noDaemon    bool
 	daemonOptIn bool
+	// Run simple scripts directly, rather than through the package manager and a shell
+	directExec bool
+	// Only print task hashes, for turbo hash
+	hashOnly    bool
+	hashJSON    bool
+	explainHash bool
+	// Only download remote cache artifacts into the local cache, for turbo cache warm
+	warmOnly bool
+	// Only describe this package and its tasks, for turbo info
+	infoPackage string
+	infoJSON    bool
+	// Don't redact the values of declared environment variables from task logs
+	noRedact bool
+	// Warn about files that tasks write outside of their outputs, using fileQuerier,
+	// which is only set when running with the daemon
+	warnUndeclaredWrites bool
+	fileQuerier          fileQuerier
+	// fileHashSource is the daemon's file hash cache, if the daemon is in use
+	fileHashSource fs.FileHashSource
+	// Mixed into every task's hash, overriding cacheKeySuffix from turbo.json
+	cacheKeySuffix string
 }
 
 var (
 	_profileHelp = `File to write turbo's performance profile output into.
-You can load the file up in chrome://tracing to see
-which parts of your build were slow.`
+You can load the file up in chrome://tracing or Perfetto
+to see which parts of your build were slow: task runs,
+hashing, cache fetches and uploads, and time tasks spent
+waiting to be scheduled.`
 	_continueHelp = `Continue execution even if a task exits with an error
 or non-zero exit code. The default behavior is to bail`
 	_dryRunHelp = `List the packages in scope and the tasks that would be run,
 but don't actually run them. Passing --dry=json or
---dry-run=json will render the output in JSON format.`
+--dry-run=json will render the output in JSON format, and
+--dry=markdown renders a report for pull request comments.`
+	_explainGlobalHashHelp = `With --dry, also list everything that contributes to the
+global hash: files, environment variables, the root
+package's dependencies and the pipeline.`
+	_serveGraphHelp = `Serve an interactive page for exploring the task graph,
+including task hashes and which tasks are cached locally,
+on the given address. Defaults to a random local port.`
+	_explainFilterHelp = `With --dry, also list every package, whether the filters
+selected it, and which part of the filters decided it.`
+	_explainSelectionHelp = `List every task in the run and why it is included:
+which tasks were requested, and which dependsOn entries
+pulled in the rest.`
+	_directExecHelp = `Run scripts that are a simple command directly, rather
+than through the package manager and a shell. Scripts that
+use shell syntax, or that have pre or post scripts, still
+run through the package manager. Package manager settings,
+like npm_config_* variables, aren't passed to the script.`
+	_taskArgsHelp = `Pass arguments to a single task, as <task>:<args>, e.g.
+--args test:"--runInBand". Can be given more than once.
+These are added after any arguments given after '--'.`
+	_noRedactHelp = `Don't redact the values of the environment variables that
+tasks depend on, or list in "redact", from task logs.`
+	_priorityHelp = `Run a task, as <package>#<task>, and the tasks it depends
+on ahead of other tasks whenever they are ready to run.
+Can be given more than once.`
+	_onlyNeededForHelp = `Run a task, as <package>#<task>, and only the tasks whose
+outputs it consumes, transitively. Dependencies without
+outputs, such as lint tasks, are hashed but not run, and
+tasks it doesn't depend on are left out. Can be given more
+than once.`
+	_timeoutHelp = `Stop the run, and exit with code 124, if it hasn't finished
+within the given duration, e.g. 30m. Running tasks are
+stopped and the tasks that haven't started are skipped.`
+	_cacheKeySuffixHelp = `Mix the given value into every task's hash. Overrides
+cacheKeySuffix from turbo.json. Can also be set with
+TURBO_CACHE_KEY_SUFFIX.`
+	_warnUndeclaredWritesHelp = `Warn about files that a task writes, in its package, which
+aren't covered by its outputs, and so won't be restored on
+a cache hit. Needs the turbo daemon.`
 	_graphHelp = `Generate a graph of the task execution and output to a file when a filename is specified (.svg, .png, .jpg, .pdf, .json, .html).
 Outputs dot graph to stdout when if no filename is provided`
 	_concurrencyHelp = `Limit the concurrency of task execution. Use 1 for serial (i.e. one-at-a-time) execution.`
@@ -483,6 +812,14 @@ func addRunOpts(opts *runOpts, flags *pflag.FlagSet, aliases map[string]string)
 	flags.StringVar(&opts.profile, "profile", "", _profileHelp)
 	flags.BoolVar(&opts.continueOnError, "continue", false, _continueHelp)
 	flags.BoolVar(&opts.only, "only", false, _onlyHelp)
+	flags.BoolVar(&opts.directExec, "direct-exec", false, _directExecHelp)
+	flags.StringArrayVar(&opts.taskArgFlags, "args", nil, _taskArgsHelp)
+	flags.BoolVar(&opts.noRedact, "no-redact", false, _noRedactHelp)
+	flags.StringArrayVar(&opts.priority, "priority", nil, _priorityHelp)
+	flags.StringArrayVar(&opts.onlyNeededFor, "only-needed-for", nil, _onlyNeededForHelp)
+	flags.DurationVar(&opts.timeout, "timeout", 0, _timeoutHelp)
+	flags.BoolVar(&opts.warnUndeclaredWrites, "warn-undeclared-writes", false, _warnUndeclaredWritesHelp)
+	flags.StringVar(&opts.cacheKeySuffix, "cache-key-suffix", "", _cacheKeySuffixHelp)
 	flags.BoolVar(&opts.noDaemon, "no-daemon", false, "Run without using turbo's daemon process")
 	flags.BoolVar(&opts.daemonOptIn, "experimental-use-daemon", false, "Use the experimental turbo daemon")
 	// Daemon-related flags hidden for now, we can unhide when daemon is ready.
@@ -504,6 +841,9 @@ func addRunOpts(opts *runOpts, flags *pflag.FlagSet, aliases map[string]string)
 		NoOptDefVal: _dryRunNoValue,
 		Value:       &dryRunValue{opts: opts},
 	})
+	flags.BoolVar(&opts.explainGlobalHash, "explain-global-hash", false, _explainGlobalHashHelp)
+	flags.BoolVar(&opts.explainFilter, "explain-filter", false, _explainFilterHelp)
+	flags.BoolVar(&opts.explainSelection, "explain-selection", false, _explainSelectionHelp)
 	flags.AddFlag(&pflag.Flag{
 		Name:        "graph",
 		Usage:       _graphHelp,
@@ -511,6 +851,8 @@ func addRunOpts(opts *runOpts, flags *pflag.FlagSet, aliases map[string]string)
 		NoOptDefVal: _graphNoValue,
 		Value:       &graphValue{opts: opts},
 	})
+	flags.StringVar(&opts.serveGraph, "serve-graph", "", _serveGraphHelp)
+	flags.Lookup("serve-graph").NoOptDefVal = "127.0.0.1:0"
 }
 
 var _persistentFlags = []string{
@@ -585,11 +927,12 @@ func (d *graphValue) Type() string {
 
 // dry run custom flag
 const (
-	_dryRunText      = "dry run"
-	_dryRunJSONText  = "json"
-	_dryRunJSONValue = "json"
-	_dryRunNoValue   = "text|json"
-	_dryRunTextValue = "text"
+	_dryRunText          = "dry run"
+	_dryRunJSONText      = "json"
+	_dryRunJSONValue     = "json"
+	_dryRunMarkdownValue = "markdown"
+	_dryRunNoValue       = "text|json|markdown"
+	_dryRunTextValue     = "text"
 )
 
 // dryRunValue implements a flag that can be treated as a boolean (--dry-run)
@@ -603,6 +946,8 @@ var _ pflag.Value = &dryRunValue{}
 func (d *dryRunValue) String() string {
 	if d.opts.dryRunJSON {
 		return _dryRunJSONText
+	} else if d.opts.dryRunMarkdown {
+		return _dryRunMarkdownValue
 	} else if d.opts.dryRun {
 		return _dryRunText
 	}
@@ -613,6 +958,9 @@ func (d *dryRunValue) Set(value string) error {
 	if value == _dryRunJSONValue {
 		d.opts.dryRun = true
 		d.opts.dryRunJSON = true
+	} else if value == _dryRunMarkdownValue {
+		d.opts.dryRun = true
+		d.opts.dryRunMarkdown = true
 	} else if value == _dryRunNoValue {
 		// this case matches the NoOptDefValue, which is used when the flag
 		// is passed, but does not have a value (i.e. boolean flag)
@@ -638,8 +986,9 @@ func getDefaultOptions(config *config.Config) *Opts {
 			concurrency: 10,
 		},
 		cacheOpts: cache.Opts{
-			Dir:     cache.DefaultLocation(config.Cwd),
-			Workers: config.Cache.Workers,
+			Dir:                cache.DefaultLocation(config.Cwd),
+			Workers:            config.Cache.Workers,
+			CompressionWorkers: config.Cache.CompressionWorkers,
 		},
 		scopeOpts: scope.Opts{},
 	}
@@ -667,7 +1016,15 @@ func (r *run) logWarning(prefix string, err error) {
 	r.ui.Error(fmt.Sprintf("%s%s%s", ui.WARNING_PREFIX, prefix, color.YellowString(" %v", err)))
 }
 
-func (r *run) executeTasks(ctx gocontext.Context, g *completeGraph, rs *runSpec, engine *core.Scheduler, packageManager *packagemanager.PackageManager, hashes *taskhash.Tracker, startAt time.Time) error {
+// runPostRunHook runs the postRun hook, if there is one, with the run's exit code
+func (r *run) runPostRunHook(hooks fs.Hooks, rs *runSpec, exitCode int) {
+	env := append(runHookEnv(rs), fmt.Sprintf("TURBO_RUN_EXIT_CODE=%v", exitCode))
+	if err := runHook(r.hookProcesses, r.config.Cwd, "postRun", hooks.PostRun, env, r.ui); err != nil {
+		r.logWarning("", err)
+	}
+}
+
+func (r *run) executeTasks(ctx gocontext.Context, g *completeGraph, rs *runSpec, engine *core.Scheduler, packageManager *packagemanager.PackageManager, hashes *taskhash.Tracker, startAt time.Time, ports []portAllocation) error {
 	apiClient := r.config.NewClient()
 	var analyticsSink analytics.Sink
 	if r.config.IsLoggedIn() {
@@ -697,6 +1054,7 @@ func (r *run) executeTasks(ctx gocontext.Context, g *completeGraph, rs *runSpec,
 	defer turboCache.Shutdown()
 	colorCache := colorcache.New()
 	runState := NewRunState(startAt, rs.Opts.runOpts.profile, r.config)
+	runState.SetCacheKeySuffix(cacheKeySuffix(g.GlobalHashInputs))
 	runCache := runcache.New(turboCache, r.config.Cwd, rs.Opts.runcacheOpts, colorCache)
 	argSeparator := []string{"--"}
 	if is7PlusPnpm, err := util.Is7PlusPnpm(packageManager.Name); err != nil {
@@ -711,6 +1069,15 @@ func (r *run) executeTasks(ctx gocontext.Context, g *completeGraph, rs *runSpec,
 		// just as easily be []string{}, but the style guide says to prefer nil for empty slices.
 		argSeparator = nil
 	}
+	logGrouper := rs.Opts.runcacheOpts.LogGrouper()
+	var ordered *orderedOutput
+	if rs.Opts.runcacheOpts.LogOrder == runcache.DependencyLogOrder {
+		// Output has to be buffered to be reordered, even if it isn't grouped
+		if logGrouper == nil {
+			logGrouper = noMarkers{}
+		}
+		ordered = newOrderedOutput(os.Stdout, engine.TaskGraph, rs.Opts.runcacheOpts.LogOrderMaxBytes)
+	}
 	ec := &execContext{
 		colorCache:     colorCache,
 		runState:       runState,
@@ -723,22 +1090,66 @@ func (r *run) executeTasks(ctx gocontext.Context, g *completeGraph, rs *runSpec,
 		processes:      r.processes,
 		taskHashes:     hashes,
 		argSeparator:   argSeparator,
+		logGrouper:     logGrouper,
+		orderedOutput:  ordered,
+		portEnv:        portEnv(ports),
+		repoRoot:       r.config.Cwd,
+		pinnedEnv:      globalPinnedEnv(g.GlobalHashInputs),
+		globalEnvVars:  globalEnvVarNames(g.GlobalHashInputs),
+		hooks:          g.Hooks,
+		pipelined:      newPipelinedTasks(),
+	}
+
+	if err := runHook(r.hookProcesses, r.config.Cwd, "preRun", g.Hooks.PreRun, runHookEnv(rs), r.ui); err != nil {
+		return err
+	}
+	if timeout := rs.Opts.runOpts.timeout; timeout > 0 {
+		timer := time.AfterFunc(timeout, func() {
+			runState.SetRunTimedOut(timeout)
+			r.ui.Error(fmt.Sprintf("The run didn't finish within %v. Stopping the tasks that are still running...", timeout))
+			r.processes.Close()
+		})
+		defer timer.Stop()
 	}
 
 	// run the thing
-	errs := engine.Execute(g.getPackageTaskVisitor(ctx, func(ctx gocontext.Context, pt *nodes.PackageTask) error {
+	visitor := g.getPackageTaskVisitor(ctx, func(ctx gocontext.Context, pt *nodes.PackageTask) error {
 		deps := engine.TaskGraph.DownEdges(pt.TaskID)
 		return ec.exec(ctx, pt, deps)
-	}), core.ExecOpts{
+	})
+	if ordered != nil {
+		taskVisitor := visitor
+		visitor = func(taskID string) error {
+			err := taskVisitor(taskID)
+			if flushErr := ordered.finish(taskID); flushErr != nil {
+				r.config.Logger.Error("failed to write grouped output", "error", flushErr)
+			}
+			return err
+		}
+	}
+	errs := engine.Execute(visitor, core.ExecOpts{
 		Parallel:    rs.Opts.runOpts.parallel,
 		Concurrency: rs.Opts.runOpts.concurrency,
+		Mutexes:     taskMutexes(engine.TaskGraph, g.Pipeline),
+		Priority:    rs.Opts.runOpts.priority,
 	})
+	// Tasks that were ready before they finished may still be running
+	errs = append(errs, ec.pipelined.wait()...)
+	if ordered != nil {
+		if err := ordered.close(); err != nil {
+			r.config.Logger.Error("failed to write grouped output", "error", err)
+		}
+	}
 
 	// Track if we saw any child with a non-zero exit code
 	exitCode := 0
 	exitCodeErr := &process.ChildExit{}
+	timedOutErr := &process.TimedOut{}
+	_, timedOut := runState.RunTimedOutAt()
 	for _, err := range errs {
-		if errors.As(err, &exitCodeErr) {
+		if errors.As(err, &timedOutErr) {
+			timedOut = true
+		} else if errors.As(err, &exitCodeErr) {
 			if exitCodeErr.ExitCode > exitCode {
 				exitCode = exitCodeErr.ExitCode
 			}
@@ -748,10 +1159,19 @@ func (r *run) executeTasks(ctx gocontext.Context, g *completeGraph, rs *runSpec,
 		}
 		r.ui.Error(err.Error())
 	}
+	if timedOut {
+		exitCode = _timeoutExitCode
+	}
 
+	r.runPostRunHook(g.Hooks, rs, exitCode)
+
+	runState.RemoteRetries = apiClient.RetryCount()
 	if err := runState.Close(r.ui, rs.Opts.runOpts.profile); err != nil {
 		return errors.Wrap(err, "error with profiler")
 	}
+	if err := runState.writeGitHubJobSummary(); err != nil {
+		r.logWarning("failed to write GitHub Actions job summary", err)
+	}
 	if exitCode != 0 {
 		return &process.ChildExit{
 			ExitCode: exitCode,
@@ -760,6 +1180,12 @@ func (r *run) executeTasks(ctx gocontext.Context, g *completeGraph, rs *runSpec,
 	return nil
 }
 
+// globalHashExplanation is the global hash along with everything that contributed to it
+type globalHashExplanation struct {
+	Hash   string                    `json:"hash"`
+	Inputs *context.GlobalHashInputs `json:"inputs"`
+}
+
 type hashedTask struct {
 	TaskID       string   `json:"taskId"`
 	Task         string   `json:"task"`
@@ -771,6 +1197,68 @@ type hashedTask struct {
 	Dir          string   `json:"directory"`
 	Dependencies []string `json:"dependencies"`
 	Dependents   []string `json:"dependents"`
+	// Hashes of the values of the environment variables in the task's hash, keyed by name
+	EnvVars map[string]string `json:"envVars"`
+}
+
+// cacheKeySuffix returns the suffix mixed into every task's hash, if there is one
+func cacheKeySuffix(inputs *context.GlobalHashInputs) string {
+	if inputs == nil {
+		return ""
+	}
+	return inputs.CacheKeySuffix
+}
+
+// globalPinnedEnv returns the environment variables that globalDependencies pins, as NAME=value
+func globalPinnedEnv(inputs *context.GlobalHashInputs) []string {
+	if inputs == nil {
+		return nil
+	}
+	var env []string
+	for _, name := range envVarNames(inputs.PinnedEnvVars) {
+		env = append(env, fmt.Sprintf("%v=%v", name, inputs.PinnedEnvVars[name]))
+	}
+	return env
+}
+
+// globalEnvVarNames returns the names of the environment variables in the global hash
+// that turbo reads from the environment, rather than pinning
+func globalEnvVarNames(inputs *context.GlobalHashInputs) []string {
+	if inputs == nil {
+		return nil
+	}
+	var names []string
+	for _, name := range envVarNames(inputs.EnvVars) {
+		if _, ok := inputs.PinnedEnvVars[name]; !ok {
+			names = append(names, name)
+		}
+	}
+	return names
+}
+
+// taskMutexes returns the mutexes that each task in the graph declares with "with"
+func taskMutexes(taskGraph *dag.AcyclicGraph, pipeline fs.Pipeline) map[string][]string {
+	mutexes := make(map[string][]string)
+	for _, v := range taskGraph.Vertices() {
+		taskID := dag.VertexName(v)
+		if strings.Contains(taskID, core.ROOT_NODE_NAME) {
+			continue
+		}
+		if taskDefinition, ok := pipeline.GetTaskDefinition(taskID); ok && len(taskDefinition.Mutexes) > 0 {
+			mutexes[taskID] = taskDefinition.Mutexes
+		}
+	}
+	return mutexes
+}
+
+// envVarNames returns the sorted names of the given environment variables
+func envVarNames(envVars map[string]string) []string {
+	names := make([]string, 0, len(envVars))
+	for name := range envVars {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	return names
 }
 
 func (r *run) executeDryRun(ctx gocontext.Context, engine *core.Scheduler, g *completeGraph, taskHashes *taskhash.Tracker, rs *runSpec) ([]hashedTask, error) {
@@ -782,6 +1270,13 @@ func (r *run) executeDryRun(ctx gocontext.Context, engine *core.Scheduler, g *co
 		if err != nil {
 			return err
 		}
+		if !rs.isNeeded(pt.TaskID) {
+			return nil
+		}
+		envVars, err := taskhash.EnvVarHashes(pt)
+		if err != nil {
+			return err
+		}
 		command, ok := pt.Command()
 		if !ok {
 			command = "<NONEXISTENT>"
@@ -813,6 +1308,10 @@ func (r *run) executeDryRun(ctx gocontext.Context, engine *core.Scheduler, g *co
 			}
 		}
 		sort.Strings(stringDescendents)
+		logFile := pt.RepoRelativeLogFile()
+		if rs.Opts.runcacheOpts.CompressLogs {
+			logFile += runcache.CompressedLogSuffix
+		}
 
 		taskIDs = append(taskIDs, hashedTask{
 			TaskID:       pt.TaskID,
@@ -822,9 +1321,10 @@ func (r *run) executeDryRun(ctx gocontext.Context, engine *core.Scheduler, g *co
 			Command:      command,
 			Dir:          pt.Pkg.Dir,
 			Outputs:      pt.TaskDefinition.Outputs,
-			LogFile:      pt.RepoRelativeLogFile(),
+			LogFile:      logFile,
 			Dependencies: stringAncestors,
 			Dependents:   stringDescendents,
+			EnvVars:      envVars,
 		})
 		return nil
 	}), core.ExecOpts{
@@ -840,6 +1340,23 @@ func (r *run) executeDryRun(ctx gocontext.Context, engine *core.Scheduler, g *co
 	return taskIDs, nil
 }
 
+// packageContaining returns the package whose directory is, or contains, the given
+// repo-relative directory. Packages can be nested, so the deepest one wins.
+func packageContaining(packageInfos map[interface{}]*fs.PackageJSON, dir string) (string, bool) {
+	name := ""
+	pkgDir := ""
+	for key, pkg := range packageInfos {
+		if key == util.RootPkgName {
+			continue
+		}
+		if (dir == pkg.Dir || strings.HasPrefix(dir, pkg.Dir+string(filepath.Separator))) && len(pkg.Dir) > len(pkgDir) {
+			name = pkg.Name
+			pkgDir = pkg.Dir
+		}
+	}
+	return name, name != ""
+}
+
 var _isTurbo = regexp.MustCompile(fmt.Sprintf("(?:^|%v|\\s)turbo(?:$|\\s)", regexp.QuoteMeta(string(filepath.Separator))))
 
 func commandLooksLikeTurbo(command string) bool {
@@ -855,6 +1372,64 @@ func validateTasks(pipeline fs.Pipeline, tasks []string) error {
 	return nil
 }
 
+// validateScriptCoverage returns an error naming each package in scope that has a script
+// for one of the tasks being run, but for which no pipeline key defines that task. Without
+// strict mode, turbo skips those scripts.
+func validateScriptCoverage(pipeline fs.Pipeline, packageInfos map[interface{}]*fs.PackageJSON, pkgs util.Set, tasks []string) error {
+	var problems []string
+	for _, pkg := range pkgs.UnsafeListOfStrings() {
+		if pkg == util.RootPkgName {
+			// The root package's scripts usually invoke turbo itself
+			continue
+		}
+		pkgJSON, ok := packageInfos[pkg]
+		if !ok {
+			continue
+		}
+		for _, task := range tasks {
+			if _, ok := pkgJSON.Scripts[task]; !ok {
+				continue
+			}
+			if _, ok := pipeline.GetTaskDefinition(util.GetTaskId(pkg, task)); !ok {
+				problems = append(problems, fmt.Sprintf("%v has a %q script, but no key in the turbo.json pipeline defines %v", pkg, task, util.GetTaskId(pkg, task)))
+			}
+		}
+	}
+	if len(problems) > 0 {
+		sort.Strings(problems)
+		return fmt.Errorf("scripts that would be skipped in strict mode:\n  %v", strings.Join(problems, "\n  "))
+	}
+	return nil
+}
+
+// warnEmptyOutputs warns that outputs globs of the task matched no files, which usually
+// means that they don't match where the task writes its outputs, and records them for the
+// run summary
+func (e *execContext) warnEmptyOutputs(pt *nodes.PackageTask, globs []string, targetUi cli.Ui) {
+	e.runState.SetEmptyOutputs(pt.TaskID, globs)
+	for _, glob := range globs {
+		message := fmt.Sprintf("outputs %q of %v in turbo.json matched no files, so a cache hit won't restore anything for it", glob, pt.TaskID)
+		targetUi.Warn(message)
+		if ci.Constant() == "GITHUB_ACTIONS" {
+			targetUi.Output(strings.TrimSuffix(ci.GitHubWarningAnnotation("turbo.json", message), "\n"))
+		}
+	}
+}
+
+// warnUndeclaredWrites reports the files that a task wrote outside of its outputs
+func (e *execContext) warnUndeclaredWrites(pt *nodes.PackageTask, files []string, targetUi cli.Ui) {
+	e.runState.SetUndeclaredWrites(pt.TaskID, files)
+	message := fmt.Sprintf("%v wrote files that its outputs in turbo.json don't cover, so a cache hit won't restore them: %v", pt.TaskID, strings.Join(files, ", "))
+	targetUi.Warn(message)
+	if ci.Constant() == "GITHUB_ACTIONS" {
+		targetUi.Output(strings.TrimSuffix(ci.GitHubWarningAnnotation("turbo.json", message), "\n"))
+	}
+}
+
+// _timeoutExitCode is turbo's exit code when the run, or any task, timed out. It matches
+// the exit code of the timeout command.
+const _timeoutExitCode = 124
+
 type execContext struct {
 	colorCache     *colorcache.ColorCache
 	runState       *RunState
@@ -867,6 +1442,38 @@ type execContext struct {
 	processes      *process.Manager
 	taskHashes     *taskhash.Tracker
 	argSeparator   []string
+	logGrouper     ci.Grouper
+	groupMu        sync.Mutex
+	// orderedOutput, if set, writes grouped output in dependency order
+	orderedOutput *orderedOutput
+	// portEnv exports the ports assigned to tasks in this run
+	portEnv  []string
+	repoRoot fs.AbsolutePath
+	// pinnedEnv sets the environment variables that globalDependencies pins
+	pinnedEnv []string
+	// globalEnvVars are the environment variables that globalDependencies declares,
+	// whose values are redacted from every task's logs
+	globalEnvVars []string
+	// hooks are the commands from turbo.json to run before and after each task
+	hooks fs.Hooks
+	// pipelined are the tasks that are still running after their dependents started
+	pipelined *pipelinedTasks
+}
+
+// secrets returns the values of the environment variables that a task declares, or lists
+// in "redact", along with those that globalDependencies declares. Pinned values are
+// already in turbo.json, so they aren't secrets.
+func (e *execContext) secrets(taskDefinition *fs.TaskDefinition) []string {
+	names := append([]string{}, e.globalEnvVars...)
+	names = append(names, taskDefinition.EnvVarDependencies...)
+	names = append(names, taskDefinition.RedactedEnvVars...)
+	var secrets []string
+	for _, value := range util.ResolveEnvVars(os.Environ(), names, nil, nil) {
+		if value != "" {
+			secrets = append(secrets, value)
+		}
+	}
+	return secrets
 }
 
 func (e *execContext) logError(log hclog.Logger, prefix string, err error) {
@@ -879,6 +1486,16 @@ func (e *execContext) logError(log hclog.Logger, prefix string, err error) {
 	e.ui.Error(fmt.Sprintf("%s%s%s", ui.ERROR_PREFIX, prefix, color.RedString(" %v", err)))
 }
 
+// flushGroup writes a task's grouped output. Groups are written one at a time
+// so that they aren't interleaved with each other.
+func (e *execContext) flushGroup(group *groupedOutput) {
+	e.groupMu.Lock()
+	defer e.groupMu.Unlock()
+	if err := group.flush(os.Stdout); err != nil {
+		e.logger.Error("failed to write grouped output", "error", err)
+	}
+}
+
 func (e *execContext) exec(ctx gocontext.Context, pt *nodes.PackageTask, deps dag.Set) error {
 	cmdTime := time.Now()
 
@@ -891,16 +1508,43 @@ func (e *execContext) exec(ctx gocontext.Context, pt *nodes.PackageTask, deps da
 	// Create a logger
 	colorPrefixer := e.colorCache.PrefixColor(pt.PackageName)
 	prettyTaskPrefix := colorPrefixer("%s: ", pt.OutputPrefix())
-	targetUi := &cli.PrefixedUi{
-		Ui:           e.ui,
-		OutputPrefix: prettyTaskPrefix,
-		InfoPrefix:   prettyTaskPrefix,
-		ErrorPrefix:  prettyTaskPrefix,
-		WarnPrefix:   prettyTaskPrefix,
+	// If we're grouping output, everything the task writes to the terminal
+	// is held until it finishes
+	taskUi := e.ui
+	var terminal io.Writer = os.Stdout
+	if e.logGrouper != nil && groupsOutput(pt) {
+		group := newGroupedOutput(pt.OutputPrefix(), e.logGrouper)
+		if e.orderedOutput != nil {
+			e.orderedOutput.hold(pt.TaskID, group)
+		} else {
+			defer e.flushGroup(group)
+		}
+		taskUi = group.ui()
+		terminal = group
+	} else if e.orderedOutput != nil {
+		// Nothing is held for this task, so output from later tasks doesn't wait for it
+		if err := e.orderedOutput.finish(pt.TaskID); err != nil {
+			e.logger.Error("failed to write grouped output", "error", err)
+		}
+	}
+	jsonLogs := e.rs.Opts.runcacheOpts.LogFormat == runcache.JSONLogFormat
+	var targetUi cli.Ui
+	if jsonLogs {
+		targetUi = logstreamer.NewJSONUi(taskUi, pt.PackageName, pt.Task)
+	} else {
+		targetUi = &cli.PrefixedUi{
+			Ui:           taskUi,
+			OutputPrefix: prettyTaskPrefix,
+			InfoPrefix:   prettyTaskPrefix,
+			ErrorPrefix:  prettyTaskPrefix,
+			WarnPrefix:   prettyTaskPrefix,
+		}
 	}
 
 	passThroughArgs := e.rs.ArgsForTask(pt.Task)
+	hashEvent := chrometracing.Event("hash " + pt.TaskID)
 	hash, err := e.taskHashes.CalculateTaskHash(pt, deps, passThroughArgs)
+	hashEvent.DoneWithArgs(chrometracing.Args{"hash": hash})
 	e.logger.Debug("task hash", "value", hash)
 	if err != nil {
 		e.ui.Error(fmt.Sprintf("Hashing error: %v", err))
@@ -916,15 +1560,47 @@ func (e *execContext) exec(ctx gocontext.Context, pt *nodes.PackageTask, deps da
 		targetLogger.Debug("done", "status", "skipped", "duration", time.Since(cmdTime))
 		return nil
 	}
+	if !e.rs.isNeeded(pt.TaskID) {
+		targetLogger.Debug("outputs not needed, skipping")
+		targetLogger.Debug("done", "status", "skipped", "duration", time.Since(cmdTime))
+		return nil
+	}
 	// Cache ---------------------------------------------
 	taskCache := e.runCache.TaskCache(pt, hash)
 	hit, err := taskCache.RestoreOutputs(ctx, targetUi, targetLogger)
-	if err != nil {
+	corrupt := &cache.CorruptArtifactError{}
+	if errors.As(err, &corrupt) {
+		// The outputs on disk can't be trusted, so fail rather than build on top of them
+		tracer(TargetBuildFailed, err)
+		targetUi.Error(fmt.Sprintf("corrupt cache artifact: %s", err))
+		if !e.rs.Opts.runOpts.continueOnError {
+			e.processes.Close()
+		}
+		return err
+	} else if err != nil {
 		targetUi.Error(fmt.Sprintf("error fetching from cache: %s", err))
 	} else if hit {
 		tracer(TargetCached, nil)
 		return nil
 	}
+	if err := taskCache.ClearOutputsManifest(); err != nil {
+		targetUi.Warn(fmt.Sprintf("failed to remove previous %v: %v", runcache.OutputsManifest, err))
+	}
+	if err := runHook(e.processes, e.repoRoot, "preTask", e.hooks.PreTask, taskHookEnv(pt, hash), targetUi); err != nil {
+		tracer(TargetBuildFailed, err)
+		targetUi.Error(err.Error())
+		if !e.rs.Opts.runOpts.continueOnError {
+			e.processes.Close()
+		}
+		return err
+	}
+	var writes *writeCheck
+	if e.rs.Opts.runOpts.warnUndeclaredWrites && e.rs.Opts.runOpts.fileQuerier != nil && pt.TaskDefinition.ShouldCache {
+		writes, err = startWriteCheck(ctx, e.rs.Opts.runOpts.fileQuerier, pt)
+		if err != nil {
+			targetLogger.Warn("failed to start checking for undeclared writes", "error", err)
+		}
+	}
 	// Setup command execution
 	argsactual := append([]string{"run"}, pt.Task)
 	if len(passThroughArgs) > 0 {
@@ -933,15 +1609,35 @@ func (e *execContext) exec(ctx gocontext.Context, pt *nodes.PackageTask, deps da
 		argsactual = append(argsactual, passThroughArgs...)
 	}
 
-	cmd := exec.Command(e.packageManager.Command, argsactual...)
-	cmd.Dir = pt.Pkg.Dir
+	var cmd *exec.Cmd
+	if pt.Pkg.ShellScripts {
+		script, _ := pt.Command()
+		cmd = shellCommand(script, passThroughArgs)
+		cmd.Dir = pt.Pkg.Dir
+		cmd.Env = os.Environ()
+	} else if e.rs.Opts.runOpts.directExec {
+		if direct, ok := directCommand(e.repoRoot, pt, passThroughArgs); ok {
+			targetLogger.Debug("running script directly", "command", direct.String())
+			cmd = direct
+		}
+	}
+	if cmd == nil {
+		cmd = exec.Command(e.packageManager.Command, argsactual...)
+		cmd.Dir = pt.Pkg.Dir
+		cmd.Env = os.Environ()
+	}
 	envs := fmt.Sprintf("TURBO_HASH=%v", hash)
-	cmd.Env = append(os.Environ(), envs)
+	cmd.Env = append(cmd.Env, envs)
+	cmd.Env = append(cmd.Env, e.portEnv...)
+	cmd.Env = append(cmd.Env, e.pinnedEnv...)
+	for _, name := range envVarNames(pt.TaskDefinition.PinnedEnvVars) {
+		cmd.Env = append(cmd.Env, fmt.Sprintf("%v=%v", name, pt.TaskDefinition.PinnedEnvVars[name]))
+	}
 
 	// Setup stdout/stderr
 	// If we are not caching anything, then we don't need to write logs to disk
 	// be careful about this conditional given the default of cache = true
-	writer, err := taskCache.OutputWriter()
+	writer, err := taskCache.OutputWriter(terminal)
 	if err != nil {
 		tracer(TargetBuildFailed, err)
 		e.logError(targetLogger, prettyTaskPrefix, err)
@@ -949,11 +1645,20 @@ func (e *execContext) exec(ctx gocontext.Context, pt *nodes.PackageTask, deps da
 			os.Exit(1)
 		}
 	}
+	if !e.rs.Opts.runOpts.noRedact {
+		writer = logstreamer.NewRedactingWriter(writer, e.secrets(pt.TaskDefinition))
+	}
 	logger := log.New(writer, "", 0)
-	// Setup a streamer that we'll pipe cmd.Stdout to
-	logStreamerOut := logstreamer.NewLogstreamer(logger, prettyTaskPrefix, false)
-	// Setup a streamer that we'll pipe cmd.Stderr to.
-	logStreamerErr := logstreamer.NewLogstreamer(logger, prettyTaskPrefix, false)
+	var logStreamerOut, logStreamerErr *logstreamer.Logstreamer
+	if jsonLogs {
+		logStreamerOut = logstreamer.NewJSONLogstreamer(logger, pt.PackageName, pt.Task, logstreamer.StreamStdout, false)
+		logStreamerErr = logstreamer.NewJSONLogstreamer(logger, pt.PackageName, pt.Task, logstreamer.StreamStderr, false)
+	} else {
+		// Setup a streamer that we'll pipe cmd.Stdout to
+		logStreamerOut = logstreamer.NewLogstreamer(logger, prettyTaskPrefix, false)
+		// Setup a streamer that we'll pipe cmd.Stderr to.
+		logStreamerErr = logstreamer.NewLogstreamer(logger, prettyTaskPrefix, false)
+	}
 	cmd.Stderr = logStreamerErr
 	cmd.Stdout = logStreamerOut
 	// Flush/Reset any error we recorded
@@ -980,40 +1685,147 @@ func (e *execContext) exec(ctx gocontext.Context, pt *nodes.PackageTask, deps da
 		return nil
 	}
 
-	// Run the command
-	if err := e.processes.Exec(cmd); err != nil {
-		// close off our outputs. We errored, so we mostly don't care if we fail to close
-		_ = closeOutputs()
-		// if we already know we're in the process of exiting,
-		// we don't need to record an error to that effect.
-		if errors.Is(err, process.ErrClosing) {
-			return nil
+	var ready *readySignal
+	if e.canPipeline(pt) {
+		ready = newReadySignal()
+		if pattern := pt.TaskDefinition.ReadyLogPattern; pattern != "" {
+			compiled := regexp.MustCompile(pattern)
+			cmd.Stdout = io.MultiWriter(cmd.Stdout, &readyLogWriter{pattern: compiled, signal: ready})
+			cmd.Stderr = io.MultiWriter(cmd.Stderr, &readyLogWriter{pattern: compiled, signal: ready})
 		}
-		tracer(TargetBuildFailed, err)
-		targetLogger.Error("Error: command finished with error: %w", err)
-		if !e.rs.Opts.runOpts.continueOnError {
-			targetUi.Error(fmt.Sprintf("ERROR: command finished with error: %s", err))
-			e.processes.Close()
+	}
+
+	// Run the command, and then close off the outputs and cache them
+	finish := func() error {
+		if err := e.processes.ExecWithTimeout(cmd, pt.TaskDefinition.ShutdownTimeout, pt.TaskDefinition.Timeout); err != nil {
+			// close off our outputs. We errored, so we mostly don't care if we fail to close
+			_ = closeOutputs()
+			// if we already know we're in the process of exiting,
+			// we don't need to record an error to that effect.
+			if errors.Is(err, process.ErrClosing) {
+				// When the run timed out, record which tasks it cut short
+				if timedOutAt, ok := e.runState.RunTimedOutAt(); ok {
+					if cmdTime.Before(timedOutAt) {
+						tracer(TargetTimedOut, err)
+					} else {
+						tracer(TargetBuildStopped, nil)
+					}
+				}
+				return nil
+			}
+			if errors.As(err, new(*process.TimedOut)) {
+				tracer(TargetTimedOut, err)
+			} else {
+				tracer(TargetBuildFailed, err)
+			}
+			targetLogger.Error("Error: command finished with error: %w", err)
+			if len(pt.TaskDefinition.CaptureOnFailure) > 0 {
+				if dir, err := captureFailure(e.repoRoot, pt, hash); err != nil {
+					targetUi.Warn(fmt.Sprintf("failed to capture files from failed task: %v", err))
+				} else {
+					e.runState.SetFailureArtifact(pt.TaskID, dir)
+				}
+			}
+			e.runPostTaskHook(pt, hash, err, targetUi)
+			if !e.rs.Opts.runOpts.continueOnError {
+				targetUi.Error(fmt.Sprintf("ERROR: command finished with error: %s", err))
+				e.processes.Close()
+			} else {
+				targetUi.Warn("command finished with error, but continuing...")
+			}
+			return err
+		}
+
+		duration := time.Since(cmdTime)
+		// Close off our outputs and cache them
+		if err := closeOutputs(); err != nil {
+			e.logError(targetLogger, "", err)
 		} else {
-			targetUi.Warn("command finished with error, but continuing...")
+			if failed, ok := e.pipelined.failedDependency(deps); ok {
+				// This task started before failed finished, so its outputs may be built
+				// from incomplete outputs of failed
+				e.pipelined.setUncached(pt.TaskID, failed)
+				targetUi.Warn(fmt.Sprintf("not caching outputs, since %v failed after this task started", failed))
+			} else if emptyOutputs, err := taskCache.SaveOutputs(ctx, targetLogger, targetUi, int(duration.Milliseconds())); err != nil {
+				e.logError(targetLogger, "", fmt.Errorf("error caching output: %w", err))
+			} else {
+				if len(emptyOutputs) > 0 {
+					e.warnEmptyOutputs(pt, emptyOutputs, targetUi)
+				}
+				if taskCache.SkipsRemoteWrites() && !e.rs.Opts.cacheOpts.SkipRemote {
+					e.runState.SetRemoteWriteSkipped(pt.TaskID)
+				}
+			}
 		}
-		return err
+		if writes != nil {
+			if files, err := writes.undeclaredWrites(ctx); err != nil {
+				targetLogger.Warn("failed to check for undeclared writes", "error", err)
+			} else if len(files) > 0 {
+				e.warnUndeclaredWrites(pt, files, targetUi)
+			}
+		}
+
+		e.runPostTaskHook(pt, hash, nil, targetUi)
+
+		// Clean up tracing
+		tracer(TargetBuilt, nil)
+		targetLogger.Debug("done", "status", "complete", "duration", duration)
+		return nil
+	}
+	if ready == nil {
+		return finish()
 	}
+	return e.pipeline(pt, cmdTime, ready, finish, targetLogger)
+}
 
-	duration := time.Since(cmdTime)
-	// Close off our outputs and cache them
-	if err := closeOutputs(); err != nil {
-		e.logError(targetLogger, "", err)
-	} else {
-		if err = taskCache.SaveOutputs(ctx, targetLogger, targetUi, int(duration.Milliseconds())); err != nil {
-			e.logError(targetLogger, "", fmt.Errorf("error caching output: %w", err))
-		}
+// canPipeline returns whether the task's dependents may start once it is ready, from
+// readyWhen, rather than once it finishes. Mutexes are held until a task finishes, so
+// those tasks aren't pipelined.
+func (e *execContext) canPipeline(pt *nodes.PackageTask) bool {
+	return hasReadyMarker(pt) && len(pt.TaskDefinition.Mutexes) == 0 && !e.rs.Opts.runOpts.parallel
+}
+
+func hasReadyMarker(pt *nodes.PackageTask) bool {
+	return pt.TaskDefinition.ReadyFile != "" || pt.TaskDefinition.ReadyLogPattern != ""
+}
+
+// groupsOutput returns whether a task's output is grouped, when grouping is on. Tasks
+// that aren't cached, like dev servers and watchers, and tasks with readyWhen may run
+// for a long time or never finish, so their output is written as it happens instead
+// of being held until they finish.
+func groupsOutput(pt *nodes.PackageTask) bool {
+	return pt.TaskDefinition.ShouldCache && !hasReadyMarker(pt)
+}
+
+// pipeline runs a task that declares readyWhen, and returns once the task is ready for its
+// dependents to start or once it finishes, whichever comes first. If it is ready first, it
+// finishes in the background, and the run waits for it at the end.
+func (e *execContext) pipeline(pt *nodes.PackageTask, started time.Time, ready *readySignal, finish func() error, logger hclog.Logger) error {
+	task := &pipelinedTask{done: make(chan struct{})}
+	go func() {
+		task.err = finish()
+		close(task.done)
+	}()
+	if file := pt.TaskDefinition.ReadyFile; file != "" {
+		go watchReadyFile(e.repoRoot.Join(pt.Pkg.Dir, file), started, ready, task.done)
+	}
+	select {
+	case <-task.done:
+		return task.err
+	case <-ready.ready:
+		logger.Debug("ready for dependents to start")
+		e.pipelined.add(pt.TaskID, task)
+		return nil
 	}
+}
 
-	// Clean up tracing
-	tracer(TargetBuilt, nil)
-	targetLogger.Debug("done", "status", "complete", "duration", duration)
-	return nil
+// runPostTaskHook runs the postTask hook, if there is one, after the task's command
+// finished with the given error
+func (e *execContext) runPostTaskHook(pt *nodes.PackageTask, hash string, taskErr error, targetUi cli.Ui) {
+	env := append(taskHookEnv(pt, hash), "TURBO_TASK_STATUS="+hookStatus(taskErr))
+	if err := runHook(e.processes, e.repoRoot, "postTask", e.hooks.PostTask, env, targetUi); err != nil {
+		targetUi.Warn(err.Error())
+	}
 }
 
 func (g *completeGraph) getPackageTaskVisitor(ctx gocontext.Context, visitor func(ctx gocontext.Context, pt *nodes.PackageTask) error) func(taskID string) error {
@@ -1024,17 +1836,9 @@ func (g *completeGraph) getPackageTaskVisitor(ctx gocontext.Context, visitor fun
 		if !ok {
 			return fmt.Errorf("cannot find package %v for task %v", name, taskID)
 		}
-		// first check for package-tasks
-		pipeline, ok := g.Pipeline[fmt.Sprintf("%v", taskID)]
+		pipeline, ok := g.Pipeline.GetTaskDefinition(taskID)
 		if !ok {
-			// then check for regular tasks
-			altpipe, notcool := g.Pipeline[task]
-			// if neither, then bail
-			if !notcool && !ok {
-				return nil
-			}
-			// override if we need to...
-			pipeline = altpipe
+			return nil
 		}
 		return visitor(ctx, &nodes.PackageTask{
 			TaskID:         taskID,
@@ -50,6 +50,7 @@ const (
 	TargetTestStopped
 	TargetTested
 	TargetTestFailed
+	TargetTimedOut
 )
 
 type BuildTargetState struct {
@@ -64,6 +65,17 @@ type BuildTargetState struct {
 	Err error
 	// Description of what's going on right now.
 	Description string
+	// FailureArtifact is the repo-relative directory holding the files captured when
+	// the target failed, if it declares captureOnFailure
+	FailureArtifact string
+	// EmptyOutputs are the target's outputs globs that matched no files when it was cached
+	EmptyOutputs []string
+	// UndeclaredWrites are the package-relative files the target wrote outside of its
+	// outputs, when running with --warn-undeclared-writes
+	UndeclaredWrites []string
+	// RemoteWriteSkipped is whether the target's outputs were kept out of the remote
+	// cache because its remote role doesn't allow writing to it
+	RemoteWriteSkipped bool
 }
 
 type RunState struct {
@@ -76,6 +88,15 @@ type RunState struct {
 	Cached    int
 	Attempted int
 
+	// Number of remote cache requests that had to be retried
+	RemoteRetries uint64
+
+	// runTimeout is the --timeout that the run exceeded, if it did, at timedOutAt
+	runTimeout time.Duration
+	timedOutAt time.Time
+	// cacheKeySuffix is mixed into every task's hash in this run
+	cacheKeySuffix string
+
 	startedAt time.Time
 	config    *config.Config
 }
@@ -118,6 +139,15 @@ func (r *RunState) Run(label string) func(outcome RunResultStatus, err error) {
 				Err:         fmt.Errorf("running %v failed: %w", label, err),
 				Description: fmt.Sprintf("running %v failed", label),
 			}, label, false)
+		case outcome == TargetTimedOut:
+			r.add(&RunResult{
+				Time:        time.Now(),
+				Duration:    time.Since(start),
+				Label:       label,
+				Status:      TargetTimedOut,
+				Err:         fmt.Errorf("running %v timed out: %w", label, err),
+				Description: fmt.Sprintf("running %v timed out", label),
+			}, label, false)
 		case outcome == TargetCached:
 			r.add(&RunResult{
 				Time:        time.Now(),
@@ -149,6 +179,124 @@ func (r *RunState) Run(label string) func(outcome RunResultStatus, err error) {
 	}
 }
 
+// SetRunTimedOut records that the run was stopped because it exceeded its timeout
+func (r *RunState) SetRunTimedOut(timeout time.Duration) {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	r.runTimeout = timeout
+	r.timedOutAt = time.Now()
+}
+
+// SetCacheKeySuffix records the suffix that was mixed into every task's hash
+func (r *RunState) SetCacheKeySuffix(suffix string) {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	r.cacheKeySuffix = suffix
+}
+
+// RunTimedOutAt returns when the run was stopped for exceeding its timeout, if it was
+func (r *RunState) RunTimedOutAt() (time.Time, bool) {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	return r.timedOutAt, r.runTimeout > 0
+}
+
+// timedOut returns the targets that were stopped because they, or the run, timed out,
+// in the order the targets started. Callers must hold r.mu.
+func (r *RunState) timedOut() []string {
+	var labels []string
+	for _, label := range r.Ordered {
+		if r.state[label].Status == TargetTimedOut {
+			labels = append(labels, label)
+		}
+	}
+	return labels
+}
+
+// SetFailureArtifact records where the files captured when the target failed were kept
+func (r *RunState) SetFailureArtifact(label string, dir string) {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	if s, ok := r.state[label]; ok {
+		s.FailureArtifact = dir
+	}
+}
+
+// failureArtifacts returns the failure artifact of each target that has one, in the order
+// the targets started. Callers must hold r.mu.
+func (r *RunState) failureArtifacts() []*BuildTargetState {
+	var states []*BuildTargetState
+	for _, label := range r.Ordered {
+		if state := r.state[label]; state.FailureArtifact != "" {
+			states = append(states, state)
+		}
+	}
+	return states
+}
+
+// SetEmptyOutputs records the target's outputs globs that matched no files
+func (r *RunState) SetEmptyOutputs(label string, globs []string) {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	if s, ok := r.state[label]; ok {
+		s.EmptyOutputs = globs
+	}
+}
+
+// emptyOutputs returns each target that has outputs globs that matched no files, in the
+// order the targets started. Callers must hold r.mu.
+func (r *RunState) emptyOutputs() []*BuildTargetState {
+	var states []*BuildTargetState
+	for _, label := range r.Ordered {
+		if state := r.state[label]; len(state.EmptyOutputs) > 0 {
+			states = append(states, state)
+		}
+	}
+	return states
+}
+
+// SetUndeclaredWrites records the files the target wrote outside of its outputs
+func (r *RunState) SetUndeclaredWrites(label string, files []string) {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	if s, ok := r.state[label]; ok {
+		s.UndeclaredWrites = files
+	}
+}
+
+// undeclaredWrites returns each target that wrote files outside of its outputs, in the
+// order the targets started. Callers must hold r.mu.
+func (r *RunState) undeclaredWrites() []*BuildTargetState {
+	var states []*BuildTargetState
+	for _, label := range r.Ordered {
+		if state := r.state[label]; len(state.UndeclaredWrites) > 0 {
+			states = append(states, state)
+		}
+	}
+	return states
+}
+
+// SetRemoteWriteSkipped records that the target's outputs weren't saved to the remote cache
+func (r *RunState) SetRemoteWriteSkipped(label string) {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	if s, ok := r.state[label]; ok {
+		s.RemoteWriteSkipped = true
+	}
+}
+
+// remoteWritesSkipped returns the targets whose outputs weren't saved to the remote
+// cache, in the order the targets started. Callers must hold r.mu.
+func (r *RunState) remoteWritesSkipped() []string {
+	var labels []string
+	for _, label := range r.Ordered {
+		if r.state[label].RemoteWriteSkipped {
+			labels = append(labels, label)
+		}
+	}
+	return labels
+}
+
 func (r *RunState) add(result *RunResult, previous string, active bool) {
 	r.mu.Lock()
 	defer r.mu.Unlock()
@@ -169,7 +317,7 @@ func (r *RunState) add(result *RunResult, previous string, active bool) {
 		r.Ordered = append(r.Ordered, result.Label)
 	}
 	switch {
-	case result.Status == TargetBuildFailed:
+	case result.Status == TargetBuildFailed, result.Status == TargetTimedOut:
 		r.Failure++
 		r.Attempted++
 	case result.Status == TargetCached:
@@ -254,7 +402,51 @@ func (r *RunState) Close(Ui cli.Ui, filename string) error {
 	Ui.Output("") // Clear the line
 	Ui.Output(util.Sprintf("${BOLD} Tasks:${BOLD_GREEN}    %v successful${RESET}${GRAY}, %v total${RESET}", r.Cached+r.Success, r.Attempted))
 	Ui.Output(util.Sprintf("${BOLD}Cached:    %v cached${RESET}${GRAY}, %v total${RESET}", r.Cached, r.Attempted))
+	if r.RemoteRetries > 0 {
+		Ui.Output(util.Sprintf("${BOLD}Retries:   %v remote cache requests retried${RESET}", r.RemoteRetries))
+	}
+	if r.cacheKeySuffix != "" {
+		Ui.Output(util.Sprintf("${BOLD}Suffix:    %v${RESET}${GRAY} cache key suffix${RESET}", r.cacheKeySuffix))
+	}
 	Ui.Output(util.Sprintf("${BOLD}  Time:    %v${RESET} %v${RESET}", time.Since(r.startedAt).Truncate(time.Millisecond), maybeFullTurbo))
+	r.mu.Lock()
+	failures := r.failureArtifacts()
+	emptyOutputs := r.emptyOutputs()
+	undeclaredWrites := r.undeclaredWrites()
+	remoteWritesSkipped := r.remoteWritesSkipped()
+	timedOut := r.timedOut()
+	runTimeout := r.runTimeout
+	r.mu.Unlock()
+	if len(remoteWritesSkipped) > 0 {
+		Ui.Output(util.Sprintf("${BOLD}Uploads:   %v skipped${RESET}${GRAY}, remote cache writes are off for %v${RESET}", len(remoteWritesSkipped), strings.Join(remoteWritesSkipped, ", ")))
+	}
+	if runTimeout > 0 {
+		Ui.Output(util.Sprintf("${BOLD}${RED}Timed out: the run didn't finish within %v${RESET}", runTimeout))
+	}
+	if len(timedOut) > 0 {
+		Ui.Output(util.Sprintf("${BOLD}Timed out tasks:${RESET} %v", strings.Join(timedOut, ", ")))
+	}
+	if len(failures) > 0 {
+		Ui.Output("")
+		Ui.Output(util.Sprintf("${BOLD}Files captured from failed tasks:${RESET}"))
+		for _, state := range failures {
+			Ui.Output(util.Sprintf("  %v ${GRAY}%v${RESET}", state.Label, state.FailureArtifact))
+		}
+	}
+	if len(emptyOutputs) > 0 {
+		Ui.Output("")
+		Ui.Output(util.Sprintf("${BOLD}${YELLOW}Outputs that matched no files:${RESET}"))
+		for _, state := range emptyOutputs {
+			Ui.Output(util.Sprintf("  %v ${GRAY}%v${RESET}", state.Label, strings.Join(state.EmptyOutputs, ", ")))
+		}
+	}
+	if len(undeclaredWrites) > 0 {
+		Ui.Output("")
+		Ui.Output(util.Sprintf("${BOLD}${YELLOW}Files written outside of outputs:${RESET}"))
+		for _, state := range undeclaredWrites {
+			Ui.Output(util.Sprintf("  %v ${GRAY}%v${RESET}", state.Label, strings.Join(state.UndeclaredWrites, ", ")))
+		}
+	}
 	Ui.Output("")
 	return nil
 }
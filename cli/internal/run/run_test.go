@@ -430,6 +430,117 @@ func Test_taskSelfRef(t *testing.T) {
 	}
 }
 
+func Test_wildcardTasks(t *testing.T) {
+	topoGraph := &dag.AcyclicGraph{}
+	topoGraph.Add("a")
+
+	pipeline := fs.Pipeline{
+		"test:*": {
+			TaskDependencies: []string{"build"},
+		},
+		"build": {},
+	}
+	if err := validateTasks(pipeline, []string{"test:unit"}); err != nil {
+		t.Fatalf("expected test:unit to match test:*: %v", err)
+	}
+	filteredPkgs := make(util.Set)
+	filteredPkgs.Add("a")
+	rs := &runSpec{
+		FilteredPkgs: filteredPkgs,
+		Targets:      []string{"test:unit"},
+		Opts:         &Opts{},
+	}
+	engine, err := buildTaskGraph(topoGraph, pipeline, rs)
+	if err != nil {
+		t.Fatalf("failed to build task graph: %v", err)
+	}
+	deps := engine.TaskGraph.DownEdges("a#test:unit")
+	if deps.Len() != 1 || !deps.Include("a#build") {
+		t.Errorf("expected a#test:unit to depend on a#build, got %v", deps.List())
+	}
+}
+
+func Test_packagePatternDependencies(t *testing.T) {
+	topoGraph := &dag.AcyclicGraph{}
+	topoGraph.Add("@acme/ui")
+	topoGraph.Add("@acme/icons")
+	topoGraph.Add("web")
+
+	pipeline := fs.Pipeline{
+		"web#build": {
+			TaskDependencies: []string{"@acme/*#build"},
+		},
+		"build": {},
+	}
+	filteredPkgs := make(util.Set)
+	filteredPkgs.Add("web")
+	rs := &runSpec{
+		FilteredPkgs: filteredPkgs,
+		Targets:      []string{"build"},
+		Opts:         &Opts{},
+	}
+	engine, err := buildTaskGraph(topoGraph, pipeline, rs)
+	if err != nil {
+		t.Fatalf("failed to build task graph: %v", err)
+	}
+	deps := engine.TaskGraph.DownEdges("web#build")
+	if deps.Len() != 2 || !deps.Include("@acme/ui#build") || !deps.Include("@acme/icons#build") {
+		t.Errorf("expected web#build to depend on the build of every @acme package, got %v", deps.List())
+	}
+
+	pipeline["web#build"] = fs.TaskDefinition{
+		TaskDependencies: []string{"@other/*#build"},
+	}
+	if _, err := buildTaskGraph(topoGraph, pipeline, rs); err == nil {
+		t.Error("expected an error for a dependency that matches no packages")
+	}
+}
+
+func Test_validateScriptCoverage(t *testing.T) {
+	pipeline := fs.Pipeline{
+		"build":      {},
+		"web#deploy": {},
+	}
+	packageInfos := map[interface{}]*fs.PackageJSON{
+		util.RootPkgName: {Scripts: map[string]string{"deploy": "turbo run deploy"}},
+		"web":            {Scripts: map[string]string{"build": "next build", "deploy": "vercel"}},
+		"docs":           {Scripts: map[string]string{"build": "next build", "deploy": "vercel"}},
+		"ui":             {Scripts: map[string]string{"build": "tsc"}},
+	}
+	pkgs := make(util.Set)
+	pkgs.Add(util.RootPkgName)
+	pkgs.Add("web")
+	pkgs.Add("docs")
+	pkgs.Add("ui")
+
+	if err := validateScriptCoverage(pipeline, packageInfos, pkgs, []string{"build"}); err != nil {
+		t.Errorf("expected every build script to be covered, got %v", err)
+	}
+	err := validateScriptCoverage(pipeline, packageInfos, pkgs, []string{"deploy"})
+	assert.EqualError(t, err, "scripts that would be skipped in strict mode:\n  docs has a \"deploy\" script, but no key in the turbo.json pipeline defines docs#deploy")
+}
+
+func Test_packageContaining(t *testing.T) {
+	packageInfos := map[interface{}]*fs.PackageJSON{
+		util.RootPkgName: {Name: "monorepo", Dir: "."},
+		"web":            {Name: "web", Dir: filepath.Join("apps", "web")},
+		"web-e2e":        {Name: "web-e2e", Dir: filepath.Join("apps", "web", "e2e")},
+		"webapp":         {Name: "webapp", Dir: filepath.Join("apps", "webapp")},
+	}
+
+	pkg, ok := packageContaining(packageInfos, filepath.Join("apps", "web"))
+	assert.True(t, ok)
+	assert.Equal(t, "web", pkg)
+	pkg, ok = packageContaining(packageInfos, filepath.Join("apps", "web", "src", "pages"))
+	assert.True(t, ok)
+	assert.Equal(t, "web", pkg)
+	pkg, ok = packageContaining(packageInfos, filepath.Join("apps", "web", "e2e", "specs"))
+	assert.True(t, ok)
+	assert.Equal(t, "web-e2e", pkg, "the deepest package should win")
+	_, ok = packageContaining(packageInfos, "apps")
+	assert.False(t, ok, "directories outside any package shouldn't select one")
+}
+
 func TestUsageText(t *testing.T) {
 	defaultCwd, err := fs.GetCwd()
 	if err != nil {
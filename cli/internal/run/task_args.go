@@ -0,0 +1,79 @@
+package run
+
+import (
+	"fmt"
+	"strings"
+)
+
+// parseTaskArgs parses --args flags of the form <task>:<args>, e.g. test:"--runInBand --ci",
+// into the args for each task. The task must be one of the tasks being run. Since task names
+// can themselves contain ":", the longest task name that the flag starts with is used.
+func parseTaskArgs(flags []string, tasks []string) (map[string][]string, error) {
+	taskArgs := make(map[string][]string)
+	for _, flag := range flags {
+		task := ""
+		for _, candidate := range tasks {
+			if strings.HasPrefix(flag, candidate+":") && len(candidate) > len(task) {
+				task = candidate
+			}
+		}
+		if task == "" {
+			return nil, fmt.Errorf("invalid --args %q: expected <task>:<args>, where <task> is one of the tasks being run (%v)", flag, strings.Join(tasks, ", "))
+		}
+		args, err := splitArgs(strings.TrimPrefix(flag, task+":"))
+		if err != nil {
+			return nil, fmt.Errorf("invalid --args %q: %w", flag, err)
+		}
+		taskArgs[task] = append(taskArgs[task], args...)
+	}
+	return taskArgs, nil
+}
+
+// splitArgs splits a string into arguments on spaces and tabs. Single and double quotes
+// group words into one argument, and a backslash escapes the next character outside of
+// single quotes.
+func splitArgs(s string) ([]string, error) {
+	var args []string
+	var arg strings.Builder
+	inArg := false
+	escaped := false
+	var quote rune
+	for _, c := range s {
+		switch {
+		case escaped:
+			arg.WriteRune(c)
+			escaped = false
+		case c == '\\' && quote != '\'':
+			escaped = true
+			inArg = true
+		case quote != 0:
+			if c == quote {
+				quote = 0
+			} else {
+				arg.WriteRune(c)
+			}
+		case c == '\'' || c == '"':
+			quote = c
+			inArg = true
+		case c == ' ' || c == '\t':
+			if inArg {
+				args = append(args, arg.String())
+				arg.Reset()
+				inArg = false
+			}
+		default:
+			arg.WriteRune(c)
+			inArg = true
+		}
+	}
+	if quote != 0 {
+		return nil, fmt.Errorf("unterminated %c quote", quote)
+	}
+	if escaped {
+		return nil, fmt.Errorf("trailing backslash")
+	}
+	if inArg {
+		args = append(args, arg.String())
+	}
+	return args, nil
+}
@@ -0,0 +1,49 @@
+package run
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func Test_parseTaskArgs(t *testing.T) {
+	taskArgs, err := parseTaskArgs([]string{
+		"build:--profile",
+		`test:--runInBand --testNamePattern "renders a button"`,
+		"build:prod:--minify",
+		"test:--ci",
+	}, []string{"build", "build:prod", "test"})
+	assert.NoError(t, err)
+	assert.Equal(t, map[string][]string{
+		"build":      {"--profile"},
+		"build:prod": {"--minify"},
+		"test":       {"--runInBand", "--testNamePattern", "renders a button", "--ci"},
+	}, taskArgs)
+
+	_, err = parseTaskArgs([]string{"lint:--fix"}, []string{"build", "test"})
+	assert.EqualError(t, err, `invalid --args "lint:--fix": expected <task>:<args>, where <task> is one of the tasks being run (build, test)`)
+
+	_, err = parseTaskArgs([]string{`test:--name "unterminated`}, []string{"test"})
+	assert.EqualError(t, err, `invalid --args "test:--name \"unterminated": unterminated " quote`)
+}
+
+func Test_splitArgs(t *testing.T) {
+	args, err := splitArgs(`  --a 'b c' "d 'e'" f\ g "h\"i" ''`)
+	assert.NoError(t, err)
+	assert.Equal(t, []string{"--a", "b c", "d 'e'", "f g", `h"i`, ""}, args)
+}
+
+func Test_ArgsForTask(t *testing.T) {
+	rs := &runSpec{
+		Targets: []string{"build", "test"},
+		Opts: &Opts{
+			runOpts: runOpts{
+				passThroughArgs: []string{"--verbose"},
+				taskArgs:        map[string][]string{"test": {"--runInBand"}},
+			},
+		},
+	}
+	assert.Equal(t, []string{"--verbose"}, rs.ArgsForTask("build"))
+	assert.Equal(t, []string{"--verbose", "--runInBand"}, rs.ArgsForTask("test"))
+	assert.Equal(t, []string{}, rs.ArgsForTask("lint"))
+}
@@ -0,0 +1,104 @@
+package run
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+
+	"github.com/pyr-sh/dag"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// TaskSelection explains why a task is part of a run
+type TaskSelection struct {
+	TaskID  string   `json:"taskId"`
+	Reasons []string `json:"reasons"`
+}
+
+// dependsOnEntry returns the dependsOn entry of the dependent task that pulled in the dependency
+func dependsOnEntry(pipeline fs.Pipeline, dependentID string, dependencyID string) string {
+	dependentPkg, _ := util.GetPackageTaskFromId(dependentID)
+	dependencyPkg, dependencyTask := util.GetPackageTaskFromId(dependencyID)
+	if dependentPkg == dependencyPkg {
+		return dependencyTask
+	}
+	taskDefinition, _ := pipeline.GetTaskDefinition(dependentID)
+	for _, dependency := range taskDefinition.TaskDependencies {
+		if dependency == dependencyID {
+			return dependencyID
+		}
+	}
+	return "^" + dependencyTask
+}
+
+// explainTaskSelection returns, for every task in the task graph, whether it was requested
+// and which tasks depend on it. Tasks that weren't requested also get the shortest chain
+// of dependencies that leads to them from a requested task.
+func explainTaskSelection(taskGraph *dag.AcyclicGraph, pipeline fs.Pipeline, rs *runSpec) []TaskSelection {
+	isTarget := make(util.Set)
+	for _, target := range rs.Targets {
+		isTarget.Add(target)
+	}
+	var taskIDs []string
+	var queue []string
+	// chainParent is the task that first reached each task in a breadth-first search
+	// from the requested tasks
+	chainParent := make(map[string]string)
+	for _, v := range taskGraph.Vertices() {
+		taskID := dag.VertexName(v)
+		if strings.Contains(taskID, core.ROOT_NODE_NAME) {
+			continue
+		}
+		taskIDs = append(taskIDs, taskID)
+		pkg, task := util.GetPackageTaskFromId(taskID)
+		if isTarget.Includes(task) && rs.FilteredPkgs.Includes(pkg) {
+			chainParent[taskID] = ""
+			queue = append(queue, taskID)
+		}
+	}
+	sort.Strings(taskIDs)
+	sort.Strings(queue)
+	for len(queue) > 0 {
+		taskID := queue[0]
+		queue = queue[1:]
+		var deps []string
+		for _, dep := range taskGraph.DownEdges(taskID) {
+			deps = append(deps, dag.VertexName(dep))
+		}
+		sort.Strings(deps)
+		for _, dep := range deps {
+			if _, ok := chainParent[dep]; !ok && !strings.Contains(dep, core.ROOT_NODE_NAME) {
+				chainParent[dep] = taskID
+				queue = append(queue, dep)
+			}
+		}
+	}
+
+	selections := make([]TaskSelection, len(taskIDs))
+	for i, taskID := range taskIDs {
+		var reasons []string
+		pkg, task := util.GetPackageTaskFromId(taskID)
+		if parent, ok := chainParent[taskID]; ok && parent == "" {
+			reasons = append(reasons, fmt.Sprintf("%q was requested, and the filters selected %v", task, pkg))
+		}
+		var dependents []string
+		for _, dependent := range taskGraph.UpEdges(taskID) {
+			dependents = append(dependents, dag.VertexName(dependent))
+		}
+		sort.Strings(dependents)
+		for _, dependent := range dependents {
+			reasons = append(reasons, fmt.Sprintf("dependency of %v (\"dependsOn\": %q)", dependent, dependsOnEntry(pipeline, dependent, taskID)))
+		}
+		if parent := chainParent[taskID]; parent != "" {
+			chain := []string{taskID}
+			for ; parent != ""; parent = chainParent[parent] {
+				chain = append([]string{parent}, chain...)
+			}
+			reasons = append(reasons, fmt.Sprintf("dependsOn chain: %v", strings.Join(chain, " → ")))
+		}
+		selections[i] = TaskSelection{TaskID: taskID, Reasons: reasons}
+	}
+	return selections
+}
@@ -0,0 +1,66 @@
+package run
+
+import (
+	"testing"
+
+	"github.com/pyr-sh/dag"
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+func Test_explainTaskSelection(t *testing.T) {
+	topoGraph := &dag.AcyclicGraph{}
+	topoGraph.Add("web")
+	topoGraph.Add("ui")
+	topoGraph.Connect(dag.BasicEdge("web", "ui"))
+
+	pipeline := map[string]fs.TaskDefinition{
+		"build": {
+			TopologicalDependencies: []string{"build"},
+			TaskDependencies:        []string{"generate"},
+		},
+		"generate": {},
+	}
+	filteredPkgs := make(util.Set)
+	filteredPkgs.Add("web")
+	rs := &runSpec{
+		FilteredPkgs: filteredPkgs,
+		Targets:      []string{"build"},
+		Opts:         &Opts{},
+	}
+	engine, err := buildTaskGraph(topoGraph, pipeline, rs)
+	if err != nil {
+		t.Fatalf("failed to build task graph: %v", err)
+	}
+
+	selections := explainTaskSelection(engine.TaskGraph, pipeline, rs)
+	expected := []TaskSelection{
+		{
+			TaskID: "ui#build",
+			Reasons: []string{
+				`dependency of web#build ("dependsOn": "^build")`,
+				"dependsOn chain: web#build → ui#build",
+			},
+		},
+		{
+			TaskID: "ui#generate",
+			Reasons: []string{
+				`dependency of ui#build ("dependsOn": "generate")`,
+				"dependsOn chain: web#build → ui#build → ui#generate",
+			},
+		},
+		{
+			TaskID:  "web#build",
+			Reasons: []string{`"build" was requested, and the filters selected web`},
+		},
+		{
+			TaskID: "web#generate",
+			Reasons: []string{
+				`dependency of web#build ("dependsOn": "generate")`,
+				"dependsOn chain: web#build → web#generate",
+			},
+		},
+	}
+	assert.Equal(t, expected, selections)
+}
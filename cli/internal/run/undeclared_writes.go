@@ -0,0 +1,94 @@
+package run
+
+import (
+	gocontext "context"
+	"path"
+	"path/filepath"
+	"sort"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/filequery"
+	"github.com/vercel/turborepo/cli/internal/globby"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+	"github.com/vercel/turborepo/cli/internal/runcache"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+// fileQuerier tells which files changed since a clock it handed out. The daemon
+// client implements it.
+type fileQuerier interface {
+	QueryFiles(ctx gocontext.Context, root string, globs []string, since string) (*filequery.Result, error)
+}
+
+// writeCheck looks for files that a task wrote outside of its declared outputs, using
+// the daemon's file watcher. It only sees writes inside the task's package, and writes
+// made by other tasks running in the same package at the same time are attributed to
+// this one as well, so it is a best-effort aid rather than a guarantee.
+type writeCheck struct {
+	querier fileQuerier
+	pt      *nodes.PackageTask
+	clock   string
+}
+
+// startWriteCheck gets a clock from the daemon to compare against once the task is
+// done. The query only has to return a clock, so it asks for the task's log file rather
+// than every file in the package.
+func startWriteCheck(ctx gocontext.Context, querier fileQuerier, pt *nodes.PackageTask) (*writeCheck, error) {
+	result, err := querier.QueryFiles(ctx, filepath.ToSlash(pt.Pkg.Dir), []string{path.Join(".turbo", "turbo-"+pt.Task+".log")}, "")
+	if err != nil {
+		return nil, err
+	}
+	return &writeCheck{
+		querier: querier,
+		pt:      pt,
+		clock:   result.Clock,
+	}, nil
+}
+
+// undeclaredWrites returns the package-relative paths of the files that were written or
+// deleted since the check started, and that the task's outputs don't cover. It returns
+// nothing if the daemon restarted in the meantime, since it can no longer tell.
+func (w *writeCheck) undeclaredWrites(ctx gocontext.Context) ([]string, error) {
+	result, err := w.querier.QueryFiles(ctx, filepath.ToSlash(w.pt.Pkg.Dir), []string{"**"}, w.clock)
+	if err != nil {
+		return nil, err
+	}
+	if result.IsFreshInstance {
+		return nil, nil
+	}
+	files := make([]string, len(result.Files))
+	for i, file := range result.Files {
+		files[i] = file.Name
+	}
+	return filterUndeclaredWrites(w.pt, files)
+}
+
+// filterUndeclaredWrites returns the files, relative to the package, that the task's
+// outputs, log file and dynamicOutputs directories don't cover. Files excluded from the
+// outputs with a "!" glob are still considered declared: the task is expected to write
+// them, they just aren't cached.
+func filterUndeclaredWrites(pt *nodes.PackageTask, files []string) ([]string, error) {
+	var declared []string
+	for _, output := range pt.HashableOutputs() {
+		declared = append(declared, strings.TrimPrefix(output, "!"))
+	}
+	for _, dir := range pt.TaskDefinition.DynamicOutputs {
+		declared = append(declared, path.Join(filepath.ToSlash(dir), "**"))
+	}
+	declared = append(declared, runcache.OutputsManifest)
+
+	anchored := make([]turbopath.AnchoredSystemPath, len(files))
+	for i, file := range files {
+		anchored[i] = turbopath.AnchoredSystemPath(filepath.FromSlash(file))
+	}
+	_, unmatched, err := globby.MatchFiles(anchored, declared, nil)
+	if err != nil {
+		return nil, err
+	}
+	undeclared := make([]string, len(unmatched))
+	for i, file := range unmatched {
+		undeclared[i] = filepath.ToSlash(string(file))
+	}
+	sort.Strings(undeclared)
+	return undeclared, nil
+}
@@ -0,0 +1,66 @@
+package run
+
+import (
+	gocontext "context"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/filequery"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+)
+
+type mockFileQuerier struct {
+	changed         []string
+	isFreshInstance bool
+	queries         []string
+}
+
+func (m *mockFileQuerier) QueryFiles(ctx gocontext.Context, root string, globs []string, since string) (*filequery.Result, error) {
+	m.queries = append(m.queries, root)
+	if since == "" {
+		return &filequery.Result{Clock: "c:1", IsFreshInstance: true}, nil
+	}
+	files := make([]filequery.File, len(m.changed))
+	for i, name := range m.changed {
+		files[i] = filequery.File{Name: name, Exists: true}
+	}
+	return &filequery.Result{Clock: "c:2", IsFreshInstance: m.isFreshInstance, Files: files}, nil
+}
+
+func Test_undeclaredWrites(t *testing.T) {
+	pt := &nodes.PackageTask{
+		TaskID:      "web#build",
+		Task:        "build",
+		PackageName: "web",
+		Pkg:         &fs.PackageJSON{Name: "web", Dir: filepath.Join("apps", "web")},
+		TaskDefinition: &fs.TaskDefinition{
+			Outputs:        []string{"dist/**", "!dist/cache/**"},
+			DynamicOutputs: []string{"generated"},
+		},
+	}
+	querier := &mockFileQuerier{
+		changed: []string{
+			"dist/index.js",
+			"dist/cache/entry",
+			".turbo/turbo-build.log",
+			".turbo/outputs.json",
+			"generated/types.d.ts",
+			"tsconfig.tsbuildinfo",
+			"src/version.ts",
+		},
+	}
+
+	writes, err := startWriteCheck(gocontext.Background(), querier, pt)
+	assert.NoError(t, err)
+	files, err := writes.undeclaredWrites(gocontext.Background())
+	assert.NoError(t, err)
+	assert.Equal(t, []string{"src/version.ts", "tsconfig.tsbuildinfo"}, files)
+	assert.Equal(t, []string{"apps/web", "apps/web"}, querier.queries, "both queries should be rooted at the package")
+
+	querier.isFreshInstance = true
+	files, err = writes.undeclaredWrites(gocontext.Background())
+	assert.NoError(t, err)
+	assert.Empty(t, files, "nothing can be told once the daemon restarted")
+}
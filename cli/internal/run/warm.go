@@ -0,0 +1,153 @@
+package run
+
+import (
+	gocontext "context"
+	"fmt"
+	"sort"
+	"sync"
+
+	"github.com/fatih/color"
+	"github.com/mitchellh/cli"
+	"github.com/pkg/errors"
+	"github.com/spf13/cobra"
+	"github.com/vercel/turborepo/cli/internal/cache"
+	"github.com/vercel/turborepo/cli/internal/config"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/scope"
+	"github.com/vercel/turborepo/cli/internal/signals"
+	"github.com/vercel/turborepo/cli/internal/taskhash"
+	"github.com/vercel/turborepo/cli/internal/ui"
+	"github.com/vercel/turborepo/cli/internal/util"
+	"golang.org/x/sync/errgroup"
+)
+
+// WarmCommand is a Command implementation that downloads remote cache artifacts into the
+// local cache without running anything
+type WarmCommand struct {
+	Config        *config.Config
+	UI            *cli.ColoredUi
+	SignalWatcher *signals.Watcher
+}
+
+var _warmCmdLong = `
+Compute the hash of each task that 'turbo run' would run for the given
+tasks, and download the artifacts the remote cache has for them into the
+local cache. Nothing is run, and nothing is restored into the repository.
+
+Warming the local cache before going offline, or before a large rebase,
+lets later runs replay those tasks without the remote cache.
+`
+
+func getWarmCmd(config *config.Config, ui cli.Ui, signalWatcher *signals.Watcher) *cobra.Command {
+	opts := getDefaultOptions(config)
+	var tasks []string
+	cmd := &cobra.Command{
+		Use:                   "turbo cache warm --tasks=<task>[,<task>] [<flags>]",
+		Short:                 "Download remote cache artifacts for tasks into the local cache",
+		Long:                  _warmCmdLong,
+		SilenceUsage:          true,
+		SilenceErrors:         true,
+		DisableFlagsInUseLine: true,
+		Args:                  cobra.NoArgs,
+		RunE: func(cmd *cobra.Command, args []string) error {
+			if len(tasks) == 0 {
+				return errors.New("at least one task must be specified with --tasks")
+			}
+			opts.runOpts.warmOnly = true
+			run := configureRun(config, ui, opts, signalWatcher)
+			return run.run(cmd.Context(), tasks)
+		},
+	}
+	flags := cmd.Flags()
+	scope.AddFlags(&opts.scopeOpts, flags)
+	flags.StringSliceVar(&tasks, "tasks", nil, "The tasks to download artifacts for, along with the tasks they depend on")
+	fs.AbsolutePathVar(flags, &opts.cacheOpts.Dir, "cache-dir", config.Cwd, "Specify local filesystem cache directory.", "./node_modules/.cache/turbo")
+	return cmd
+}
+
+// Synopsis of the cache warm command
+func (c *WarmCommand) Synopsis() string {
+	cmd := getWarmCmd(c.Config, c.UI, c.SignalWatcher)
+	return cmd.Short
+}
+
+// Help returns information about the `cache warm` command
+func (c *WarmCommand) Help() string {
+	cmd := getWarmCmd(c.Config, c.UI, c.SignalWatcher)
+	return util.HelpForCobraCmd(cmd)
+}
+
+// Run downloads remote cache artifacts into the local cache
+func (c *WarmCommand) Run(args []string) int {
+	cmd := getWarmCmd(c.Config, c.UI, c.SignalWatcher)
+	cmd.SetArgs(args)
+	if err := cmd.Execute(); err != nil {
+		c.Config.Logger.Error("", "error", err)
+		c.UI.Error(fmt.Sprintf("%s%s", ui.ERROR_PREFIX, color.RedString(" %v", err)))
+		return 1
+	}
+	return 0
+}
+
+func (r *run) warmCache(ctx gocontext.Context, engine *core.Scheduler, g *completeGraph, hashTracker *taskhash.Tracker, rs *runSpec) error {
+	warmer, err := cache.NewWarmer(rs.Opts.cacheOpts, r.config)
+	if err != nil {
+		return err
+	}
+	tasks, err := r.executeDryRun(ctx, engine, g, hashTracker, rs)
+	if err != nil {
+		return err
+	}
+	var cacheable []hashedTask
+	for _, task := range tasks {
+		if taskDefinition, ok := g.Pipeline.GetTaskDefinition(task.TaskID); ok && taskDefinition.ShouldCache {
+			cacheable = append(cacheable, task)
+		}
+	}
+	sort.Slice(cacheable, func(i, j int) bool {
+		return cacheable[i].TaskID < cacheable[j].TaskID
+	})
+
+	results := make([]cache.WarmResult, len(cacheable))
+	var mu sync.Mutex
+	group := new(errgroup.Group)
+	jobs := make(chan int, len(cacheable))
+	for i := 0; i < rs.Opts.runOpts.concurrency; i++ {
+		group.Go(func() error {
+			for i := range jobs {
+				result, err := warmer.Warm(cacheable[i].Hash)
+				if err != nil {
+					return errors.Wrapf(err, "failed to warm %v", cacheable[i].TaskID)
+				}
+				results[i] = result
+				mu.Lock()
+				r.ui.Output(fmt.Sprintf("%v %v: %v", cacheable[i].TaskID, ui.Dim(cacheable[i].Hash), warmResultDescriptions[result]))
+				mu.Unlock()
+			}
+			return nil
+		})
+	}
+	for i := range cacheable {
+		jobs <- i
+	}
+	close(jobs)
+	if err := group.Wait(); err != nil {
+		return err
+	}
+
+	counts := make(map[cache.WarmResult]int)
+	for _, result := range results {
+		counts[result]++
+	}
+	r.ui.Output("")
+	r.ui.Output(fmt.Sprintf("%v downloaded, %v already cached locally, %v not in the remote cache",
+		counts[cache.WarmDownloaded], counts[cache.WarmAlreadyLocal], counts[cache.WarmMissing]))
+	return nil
+}
+
+var warmResultDescriptions = map[cache.WarmResult]string{
+	cache.WarmDownloaded:   "downloaded",
+	cache.WarmAlreadyLocal: "already cached locally",
+	cache.WarmMissing:      "not in the remote cache",
+}
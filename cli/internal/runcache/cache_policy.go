@@ -0,0 +1,80 @@
+package runcache
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+
+	"github.com/spf13/pflag"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+)
+
+// CachePolicy controls whether a task's outputs are restored from and saved to the cache
+type CachePolicy string
+
+const (
+	// CachePolicyOn restores and saves outputs, even if turbo.json disables caching for the task
+	CachePolicyOn CachePolicy = "on"
+	// CachePolicyOff neither restores nor saves outputs
+	CachePolicyOff CachePolicy = "off"
+	// CachePolicyReadOnly restores outputs, but doesn't save them
+	CachePolicyReadOnly CachePolicy = "read-only"
+	// CachePolicyWriteOnly always runs the task, and saves its outputs
+	CachePolicyWriteOnly CachePolicy = "write-only"
+)
+
+var _cachePolicies = []CachePolicy{CachePolicyOn, CachePolicyOff, CachePolicyReadOnly, CachePolicyWriteOnly}
+
+// cachePolicyFor returns the policy that overrides caching for the given task, if any.
+// An override for a specific package's task, e.g. "web#build", takes precedence over
+// one for the task in every package, e.g. "build".
+func cachePolicyFor(overrides map[string]CachePolicy, pt *nodes.PackageTask) (CachePolicy, bool) {
+	if policy, ok := overrides[pt.TaskID]; ok {
+		return policy, true
+	}
+	policy, ok := overrides[pt.Task]
+	return policy, ok
+}
+
+// cacheOverridesValue is a repeatable flag of the form <task>=<policy>
+type cacheOverridesValue struct {
+	opts *Opts
+}
+
+func (c *cacheOverridesValue) String() string {
+	overrides := make([]string, 0, len(c.opts.CacheOverrides))
+	for task, policy := range c.opts.CacheOverrides {
+		overrides = append(overrides, fmt.Sprintf("%v=%v", task, policy))
+	}
+	sort.Strings(overrides)
+	return strings.Join(overrides, ",")
+}
+
+func (c *cacheOverridesValue) Set(value string) error {
+	separator := strings.LastIndex(value, "=")
+	if separator <= 0 {
+		return fmt.Errorf("expected <task>=<policy>, got %q", value)
+	}
+	task := value[:separator]
+	policy := CachePolicy(value[separator+1:])
+	for _, known := range _cachePolicies {
+		if policy == known {
+			if c.opts.CacheOverrides == nil {
+				c.opts.CacheOverrides = make(map[string]CachePolicy)
+			}
+			c.opts.CacheOverrides[task] = policy
+			return nil
+		}
+	}
+	policies := make([]string, len(_cachePolicies))
+	for i, policy := range _cachePolicies {
+		policies[i] = string(policy)
+	}
+	return fmt.Errorf("policy for %v must be one of \"%v\"", task, strings.Join(policies, "|"))
+}
+
+func (c *cacheOverridesValue) Type() string {
+	return "task=policy"
+}
+
+var _ pflag.Value = &cacheOverridesValue{}
@@ -0,0 +1,43 @@
+package runcache
+
+import (
+	"testing"
+
+	"github.com/spf13/pflag"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+	"gotest.tools/v3/assert"
+)
+
+func TestCacheOverrides(t *testing.T) {
+	opts := &Opts{}
+	flags := pflag.NewFlagSet("test", pflag.ContinueOnError)
+	AddFlags(opts, flags)
+	err := flags.Parse([]string{"--cache-override=build=off", "--cache-override=web#build=read-only", "--cache-override=lint=on"})
+	assert.NilError(t, err, "Parse")
+	err = flags.Parse([]string{"--cache-override=build=sometimes"})
+	assert.ErrorContains(t, err, "must be one of")
+
+	rc := New(nil, fs.AbsolutePathFromUpstream("/repo"), *opts, nil)
+	taskCache := func(pkg string, task string, shouldCache bool) TaskCache {
+		return rc.TaskCache(&nodes.PackageTask{
+			TaskID:         pkg + "#" + task,
+			Task:           task,
+			PackageName:    pkg,
+			Pkg:            &fs.PackageJSON{Dir: pkg},
+			TaskDefinition: &fs.TaskDefinition{ShouldCache: shouldCache},
+		}, "some-hash")
+	}
+
+	docs := taskCache("docs", "build", true)
+	assert.Assert(t, docs.readsDisabled && docs.writesDisabled, "build=off applies to every package")
+
+	web := taskCache("web", "build", true)
+	assert.Assert(t, !web.readsDisabled && web.writesDisabled, "web#build=read-only takes precedence over build=off")
+
+	lint := taskCache("web", "lint", false)
+	assert.Assert(t, !lint.readsDisabled && !lint.writesDisabled, "lint=on overrides turbo.json")
+
+	test := taskCache("web", "test", false)
+	assert.Assert(t, test.readsDisabled && test.writesDisabled, "tasks without overrides use turbo.json")
+}
@@ -0,0 +1,86 @@
+package runcache
+
+import (
+	"fmt"
+	"path/filepath"
+	"sort"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/globby"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+// caseConflictError is returned when a task's outputs contain files whose paths differ
+// only by case. Restoring such an artifact on a case-insensitive filesystem, the default
+// on macOS and Windows, leaves only one of the files behind.
+type caseConflictError struct {
+	taskID string
+	// conflicts holds groups of repo-relative paths that differ only by case
+	conflicts [][]string
+	// globs maps each conflicting path to the outputs globs from turbo.json that matched it
+	globs map[string][]string
+}
+
+func (e *caseConflictError) Error() string {
+	var b strings.Builder
+	fmt.Fprintf(&b, "outputs of %v contain files whose names differ only by case, which would overwrite each other when restored on a case-insensitive filesystem:", e.taskID)
+	for _, group := range e.conflicts {
+		b.WriteString("\n")
+		for _, path := range group {
+			fmt.Fprintf(&b, "\n  %v", path)
+			if globs := e.globs[path]; len(globs) > 0 {
+				fmt.Fprintf(&b, "  (matched by outputs %q)", strings.Join(globs, `", "`))
+			}
+		}
+	}
+	b.WriteString("\n\nRename one of the files, or narrow the outputs globs in turbo.json to exclude it.")
+	return b.String()
+}
+
+// findCaseConflicts groups the given paths that differ only by case. Each group, and the
+// list of groups, is sorted.
+func findCaseConflicts(paths []string) [][]string {
+	byFolded := make(map[string][]string)
+	for _, path := range paths {
+		folded := strings.ToLower(filepath.ToSlash(path))
+		byFolded[folded] = append(byFolded[folded], path)
+	}
+	var conflicts [][]string
+	for _, group := range byFolded {
+		if len(group) > 1 {
+			sort.Strings(group)
+			conflicts = append(conflicts, group)
+		}
+	}
+	sort.Slice(conflicts, func(i, j int) bool {
+		return conflicts[i][0] < conflicts[j][0]
+	})
+	return conflicts
+}
+
+// checkCaseConflicts returns a caseConflictError if any of the repo-relative paths differ
+// only by case. outputs are the task's globs as written in turbo.json, and repoRelativeGlobs
+// are the same globs made relative to the repository root.
+func checkCaseConflicts(taskID string, paths []string, outputs []string, repoRelativeGlobs []string) error {
+	conflicts := findCaseConflicts(paths)
+	if len(conflicts) == 0 {
+		return nil
+	}
+	var conflicting []turbopath.AnchoredSystemPath
+	for _, group := range conflicts {
+		for _, path := range group {
+			conflicting = append(conflicting, turbopath.AnchoredSystemPath(path))
+		}
+	}
+	globs := make(map[string][]string)
+	for i, glob := range repoRelativeGlobs {
+		matched, _, err := globby.MatchFiles(conflicting, []string{glob}, nil)
+		if err != nil {
+			continue
+		}
+		for _, path := range matched {
+			globs[path.ToString()] = append(globs[path.ToString()], outputs[i])
+		}
+	}
+	return &caseConflictError{taskID: taskID, conflicts: conflicts, globs: globs}
+}
@@ -0,0 +1,47 @@
+package runcache
+
+import (
+	"errors"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"gotest.tools/v3/assert"
+)
+
+func TestFindCaseConflicts(t *testing.T) {
+	paths := []string{
+		"apps/web/dist/logo.png",
+		"apps/web/dist/Logo.png",
+		"apps/web/dist/index.js",
+		"apps/web/Dist/index.js",
+		"apps/web/dist/main.css",
+	}
+	assert.DeepEqual(t, findCaseConflicts(paths), [][]string{
+		{"apps/web/Dist/index.js", "apps/web/dist/index.js"},
+		{"apps/web/dist/Logo.png", "apps/web/dist/logo.png"},
+	})
+	assert.Equal(t, len(findCaseConflicts([]string{"a/one", "a/two"})), 0)
+}
+
+func TestCheckCaseConflicts(t *testing.T) {
+	outputs := []string{".turbo/turbo-build.log", "dist/**", "public/**"}
+	repoRelativeGlobs := make([]string, len(outputs))
+	for i, output := range outputs {
+		repoRelativeGlobs[i] = filepath.Join("apps", "web", output)
+	}
+	paths := []string{
+		filepath.Join("apps", "web", "dist", "logo.png"),
+		filepath.Join("apps", "web", "dist", "Logo.png"),
+		filepath.Join("apps", "web", "public", "robots.txt"),
+	}
+
+	err := checkCaseConflicts("web#build", paths, outputs, repoRelativeGlobs)
+	conflictErr := &caseConflictError{}
+	assert.Assert(t, errors.As(err, &conflictErr), "expected a caseConflictError, got %v", err)
+	assert.DeepEqual(t, conflictErr.globs[paths[0]], []string{"dist/**"})
+	assert.Assert(t, strings.Contains(err.Error(), "web#build"))
+	assert.Assert(t, strings.Contains(err.Error(), `(matched by outputs "dist/**")`))
+
+	assert.NilError(t, checkCaseConflicts("web#build", paths[:1], outputs, repoRelativeGlobs))
+}
@@ -0,0 +1,61 @@
+package runcache
+
+import (
+	"encoding/json"
+	"fmt"
+	"os"
+	"path"
+	"path/filepath"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+// OutputsManifest is where a task with dynamicOutputs can declare more outputs while
+// it runs, relative to its package
+const OutputsManifest = ".turbo/outputs.json"
+
+type outputsManifest struct {
+	// Outputs are globs relative to the package, like the outputs in turbo.json
+	Outputs []string `json:"outputs"`
+}
+
+// isWithinDirs returns true if the given package-relative glob can only match files inside
+// one of the given package-relative directories
+func isWithinDirs(glob string, dirs []string) bool {
+	if path.IsAbs(glob) || strings.HasPrefix(glob, "!") {
+		return false
+	}
+	cleaned := path.Clean(glob)
+	if cleaned == ".." || strings.HasPrefix(cleaned, "../") {
+		return false
+	}
+	for _, dir := range dirs {
+		dir = path.Clean(filepath.ToSlash(dir))
+		if dir == "." || strings.HasPrefix(cleaned, dir+"/") {
+			return true
+		}
+	}
+	return false
+}
+
+// readDynamicOutputs returns the outputs declared in a task's outputs manifest, as package-relative
+// globs. Every output must be inside one of the allowed directories.
+func readDynamicOutputs(pkgDir fs.AbsolutePath, taskID string, allowedDirs []string) ([]string, error) {
+	contents, err := pkgDir.Join(filepath.FromSlash(OutputsManifest)).ReadFile()
+	if os.IsNotExist(err) {
+		return nil, nil
+	} else if err != nil {
+		return nil, err
+	}
+	var manifest outputsManifest
+	if err := json.Unmarshal(contents, &manifest); err != nil {
+		return nil, fmt.Errorf("%v wrote an invalid %v: %w", taskID, OutputsManifest, err)
+	}
+	for _, output := range manifest.Outputs {
+		if !isWithinDirs(filepath.ToSlash(output), allowedDirs) {
+			return nil, fmt.Errorf("%v declared output %q in %v, which is outside of its dynamicOutputs (%v)", taskID, output, OutputsManifest, strings.Join(allowedDirs, ", "))
+		}
+	}
+	return manifest.Outputs, nil
+}
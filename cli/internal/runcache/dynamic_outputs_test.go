@@ -0,0 +1,39 @@
+package runcache
+
+import (
+	"path/filepath"
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+func TestIsWithinDirs(t *testing.T) {
+	dirs := []string{"src/generated", "schema/"}
+	assert.Assert(t, isWithinDirs("src/generated/**", dirs))
+	assert.Assert(t, isWithinDirs("schema/api/types.ts", dirs))
+	assert.Assert(t, !isWithinDirs("src/generated-other/**", dirs))
+	assert.Assert(t, !isWithinDirs("src/generated/../../dist/**", dirs))
+	assert.Assert(t, !isWithinDirs("../other-package/**", dirs))
+	assert.Assert(t, !isWithinDirs("/etc/passwd", dirs))
+	assert.Assert(t, !isWithinDirs("!src/generated/keep.ts", dirs))
+}
+
+func TestReadDynamicOutputs(t *testing.T) {
+	pkgDir := fs.AbsolutePathFromUpstream(t.TempDir())
+	manifest := pkgDir.Join(filepath.FromSlash(OutputsManifest))
+
+	outputs, err := readDynamicOutputs(pkgDir, "web#codegen", []string{"src/generated"})
+	assert.NilError(t, err)
+	assert.Assert(t, outputs == nil, "expected no outputs without a manifest")
+
+	assert.NilError(t, manifest.EnsureDir())
+	assert.NilError(t, manifest.WriteFile([]byte(`{"outputs": ["src/generated/graphql/**"]}`), 0644))
+	outputs, err = readDynamicOutputs(pkgDir, "web#codegen", []string{"src/generated"})
+	assert.NilError(t, err)
+	assert.DeepEqual(t, outputs, []string{"src/generated/graphql/**"})
+
+	assert.NilError(t, manifest.WriteFile([]byte(`{"outputs": ["dist/**"]}`), 0644))
+	_, err = readDynamicOutputs(pkgDir, "web#codegen", []string{"src/generated"})
+	assert.ErrorContains(t, err, `web#codegen declared output "dist/**" in .turbo/outputs.json, which is outside of its dynamicOutputs (src/generated)`)
+}
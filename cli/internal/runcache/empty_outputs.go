@@ -0,0 +1,37 @@
+package runcache
+
+import (
+	"path/filepath"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/globby"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+// findEmptyOutputs returns the task's outputs globs, as written in turbo.json, that match
+// none of the repo-relative paths being cached. Such a glob is usually a typo, like
+// "build/**" for a task that writes to dist, and a cache hit then restores nothing.
+// Tasks that leave their outputs to the defaults aren't checked, since most packages only
+// write to one of the default folders.
+func findEmptyOutputs(pt *nodes.PackageTask, paths []string) []string {
+	if !pt.TaskDefinition.OutputsDeclared {
+		return nil
+	}
+	files := make([]turbopath.AnchoredSystemPath, len(paths))
+	for i, path := range paths {
+		files[i] = turbopath.AnchoredSystemPath(path)
+	}
+	var empty []string
+	for _, output := range pt.TaskDefinition.Outputs {
+		// Negated outputs remove files from the artifact, so they don't need to match any
+		if strings.HasPrefix(output, "!") {
+			continue
+		}
+		matched, _, err := globby.MatchFiles(files, []string{filepath.Join(pt.Pkg.Dir, output)}, nil)
+		if err == nil && len(matched) == 0 {
+			empty = append(empty, output)
+		}
+	}
+	return empty
+}
@@ -0,0 +1,31 @@
+package runcache
+
+import (
+	"path/filepath"
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+	"gotest.tools/v3/assert"
+)
+
+func TestFindEmptyOutputs(t *testing.T) {
+	pt := &nodes.PackageTask{
+		TaskID: "web#build",
+		Pkg:    &fs.PackageJSON{Dir: filepath.Join("apps", "web")},
+		TaskDefinition: &fs.TaskDefinition{
+			Outputs:         []string{"dist/**", "build/**", "!dist/cache/**", "storybook-static/**"},
+			OutputsDeclared: true,
+		},
+	}
+	paths := []string{
+		filepath.Join("apps", "web", ".turbo", "turbo-build.log"),
+		filepath.Join("apps", "web", "dist", "index.js"),
+		// Another package's build folder doesn't count
+		filepath.Join("apps", "docs", "build", "index.html"),
+	}
+	assert.DeepEqual(t, findEmptyOutputs(pt, paths), []string{"build/**", "storybook-static/**"})
+
+	pt.TaskDefinition.OutputsDeclared = false
+	assert.Equal(t, len(findEmptyOutputs(pt, paths)), 0)
+}
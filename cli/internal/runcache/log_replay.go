@@ -0,0 +1,163 @@
+package runcache
+
+import (
+	"bufio"
+	"compress/gzip"
+	"fmt"
+	"io"
+	"os"
+	"regexp"
+
+	"github.com/hashicorp/go-hclog"
+	"github.com/mitchellh/cli"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/logstreamer"
+	"github.com/vercel/turborepo/cli/internal/ui"
+)
+
+// logFileReader reads a task log file, decompressing it if necessary
+type logFileReader struct {
+	io.Reader
+	file *os.File
+	gzip *gzip.Reader
+}
+
+func (l *logFileReader) Close() error {
+	if l.gzip != nil {
+		_ = l.gzip.Close()
+	}
+	return l.file.Close()
+}
+
+// openLogFile opens a task log file for reading. Logs written with --compress-logs
+// are detected by their gzip header, so logs restored from a cache populated with
+// a different setting can still be replayed.
+func openLogFile(logFileName fs.AbsolutePath) (*logFileReader, error) {
+	f, err := logFileName.Open()
+	if err != nil {
+		return nil, err
+	}
+	reader := bufio.NewReader(f)
+	magic, err := reader.Peek(2)
+	if err != nil || magic[0] != 0x1f || magic[1] != 0x8b {
+		return &logFileReader{Reader: reader, file: f}, nil
+	}
+	gzipReader, err := gzip.NewReader(reader)
+	if err != nil {
+		_ = f.Close()
+		return nil, err
+	}
+	return &logFileReader{Reader: gzipReader, file: f, gzip: gzipReader}, nil
+}
+
+// newLogReplayer returns a LogReplayer that replays logs back to the given Ui instance.
+// The first line of a log, which identifies the task, is always shown. If tail is positive,
+// only the last tail lines after it are shown. If maxBytes is positive, replay stops once
+// that many bytes have been shown.
+func newLogReplayer(tail int, maxBytes int) LogReplayer {
+	return func(logger hclog.Logger, output cli.Ui, logFileName fs.AbsolutePath) {
+		logger.Debug("start replaying logs")
+		f, err := openLogFile(logFileName)
+		if err != nil {
+			output.Warn(fmt.Sprintf("error reading logs: %v", err))
+			logger.Error(fmt.Sprintf("error reading logs: %v", err.Error()))
+			return
+		}
+		defer func() { _ = f.Close() }()
+
+		written := 0
+		// emit writes a line, unless doing so would exceed maxBytes
+		emit := func(line string) bool {
+			if maxBytes > 0 && written+len(line) > maxBytes {
+				return false
+			}
+			written += len(line)
+			output.Output(line) //Writing to Stdout
+			return true
+		}
+
+		scan := bufio.NewScanner(f)
+		if scan.Scan() {
+			output.Output(scan.Text())
+		}
+		var tailLines []string
+		omitted := 0
+		truncated := false
+		for scan.Scan() {
+			line := scan.Text()
+			if tail > 0 {
+				tailLines = append(tailLines, line)
+				if len(tailLines) > tail {
+					tailLines = tailLines[1:]
+					omitted++
+				}
+			} else if !emit(line) {
+				truncated = true
+				break
+			}
+		}
+		if err := scan.Err(); err != nil {
+			logger.Error(fmt.Sprintf("error reading logs: %v", err.Error()))
+		}
+		if omitted > 0 {
+			output.Output(ui.Dim(fmt.Sprintf("... %v earlier lines omitted, see %v", omitted, logFileName)))
+		}
+		for _, line := range tailLines {
+			if !emit(line) {
+				truncated = true
+				break
+			}
+		}
+		if truncated {
+			output.Output(ui.Dim(fmt.Sprintf("... output truncated after %v bytes, see %v", written, logFileName)))
+		}
+		logger.Debug("finish replaying logs")
+	}
+}
+
+// convertingUi replays a log that may have been written with a different --log-format
+// than the current run, since the format isn't part of a task's hash. Lines that are
+// already in the current format go to Ui unchanged, since text logs already include
+// the task prefix. Lines in the other format are converted.
+type convertingUi struct {
+	cli.Ui
+	// formatted adds the task prefix to plain text, for text logs
+	formatted   cli.Ui
+	jsonLogs    bool
+	packageName string
+	task        string
+	// textPrefix matches the, possibly colored, task prefix of a line in a text log
+	textPrefix *regexp.Regexp
+}
+
+func newConvertingUi(terminal cli.Ui, jsonLogs bool, packageName string, task string, outputPrefix string) *convertingUi {
+	raw := terminal
+	if prefixedUi, ok := terminal.(*cli.PrefixedUi); ok {
+		raw = prefixedUi.Ui
+	}
+	const ansi = `(?:\x1b\[[0-9;]*m)*`
+	return &convertingUi{
+		Ui:          raw,
+		formatted:   terminal,
+		jsonLogs:    jsonLogs,
+		packageName: packageName,
+		task:        task,
+		textPrefix:  regexp.MustCompile("^" + ansi + regexp.QuoteMeta(outputPrefix+": ") + ansi),
+	}
+}
+
+// Output implements cli.Ui.Output
+func (u *convertingUi) Output(text string) {
+	line, isJSON := logstreamer.ParseJSONLine(text)
+	switch {
+	case isJSON == u.jsonLogs:
+		u.Ui.Output(text)
+	case isJSON:
+		u.formatted.Output(line.Text)
+	case u.textPrefix.MatchString(text):
+		u.Ui.Output(logstreamer.NewJSONLine(u.packageName, u.task, logstreamer.StreamStdout, u.textPrefix.ReplaceAllString(text, "")))
+	default:
+		// Messages from the replayer itself, such as omitted lines
+		u.formatted.Output(text)
+	}
+}
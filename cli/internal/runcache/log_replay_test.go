@@ -0,0 +1,84 @@
+package runcache
+
+import (
+	"compress/gzip"
+	"strings"
+	"testing"
+
+	"github.com/hashicorp/go-hclog"
+	"github.com/mitchellh/cli"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/logstreamer"
+	"gotest.tools/v3/assert"
+)
+
+const _testLog = "web:build: cache hit, replaying output abc123\nline 1\nline 2\nline 3\nline 4\n"
+
+func writeLog(t *testing.T, compress bool) fs.AbsolutePath {
+	logFileName := fs.AbsolutePathFromUpstream(t.TempDir()).Join("turbo-build.log")
+	f, err := logFileName.Create()
+	assert.NilError(t, err, "Create")
+	if compress {
+		gzipWriter := gzip.NewWriter(f)
+		_, err = gzipWriter.Write([]byte(_testLog))
+		assert.NilError(t, err, "Write")
+		assert.NilError(t, gzipWriter.Close(), "Close")
+	} else {
+		_, err = f.WriteString(_testLog)
+		assert.NilError(t, err, "WriteString")
+	}
+	assert.NilError(t, f.Close(), "Close")
+	return logFileName
+}
+
+func replay(t *testing.T, logFileName fs.AbsolutePath, tail int, maxBytes int) []string {
+	output := cli.NewMockUi()
+	newLogReplayer(tail, maxBytes)(hclog.NewNullLogger(), output, logFileName)
+	return strings.Split(strings.TrimSuffix(output.OutputWriter.String(), "\n"), "\n")
+}
+
+func TestLogReplayer(t *testing.T) {
+	for _, compress := range []bool{false, true} {
+		logFileName := writeLog(t, compress)
+
+		lines := replay(t, logFileName, 0, 0)
+		assert.DeepEqual(t, lines, strings.Split(strings.TrimSuffix(_testLog, "\n"), "\n"))
+
+		lines = replay(t, logFileName, 2, 0)
+		assert.Equal(t, len(lines), 4)
+		assert.Equal(t, lines[0], "web:build: cache hit, replaying output abc123")
+		assert.Assert(t, strings.Contains(lines[1], "2 earlier lines omitted"), lines[1])
+		assert.DeepEqual(t, lines[2:], []string{"line 3", "line 4"})
+
+		lines = replay(t, logFileName, 0, 12)
+		assert.Equal(t, len(lines), 4)
+		assert.DeepEqual(t, lines[1:3], []string{"line 1", "line 2"})
+		assert.Assert(t, strings.Contains(lines[3], "output truncated after 12 bytes"), lines[3])
+	}
+}
+
+func TestConvertingUi(t *testing.T) {
+	jsonLine := logstreamer.NewJSONLine("web", "build", logstreamer.StreamStdout, "line 1")
+
+	// A json log replayed as text gets the task prefix
+	output := cli.NewMockUi()
+	prefixed := &cli.PrefixedUi{Ui: output, OutputPrefix: "web:build: "}
+	replayUi := newConvertingUi(prefixed, false, "web", "build", "web:build")
+	replayUi.Output(jsonLine)
+	replayUi.Output("web:build: line 2")
+	assert.Equal(t, output.OutputWriter.String(), "web:build: line 1\nweb:build: line 2\n")
+
+	// A text log replayed as json loses its, possibly colored, prefix
+	output = cli.NewMockUi()
+	replayUi = newConvertingUi(logstreamer.NewJSONUi(output, "web", "build"), true, "web", "build", "web:build")
+	replayUi.Output("\x1b[36mweb:build: \x1b[0mline 1")
+	replayUi.Output(jsonLine)
+	lines := strings.Split(strings.TrimSuffix(output.OutputWriter.String(), "\n"), "\n")
+	assert.Equal(t, len(lines), 2)
+	for _, text := range lines {
+		line, ok := logstreamer.ParseJSONLine(text)
+		assert.Assert(t, ok, text)
+		assert.Equal(t, line.Text, "line 1")
+		assert.Equal(t, line.Stream, logstreamer.StreamStdout)
+	}
+}
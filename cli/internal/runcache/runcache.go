@@ -2,11 +2,13 @@ package runcache
 
 import (
 	"bufio"
+	"compress/gzip"
 	"context"
 	"fmt"
 	"io"
 	"os"
 	"path/filepath"
+	"strconv"
 	"strings"
 
 	"github.com/fatih/color"
@@ -14,9 +16,12 @@ import (
 	"github.com/mitchellh/cli"
 	"github.com/spf13/pflag"
 	"github.com/vercel/turborepo/cli/internal/cache"
+	"github.com/vercel/turborepo/cli/internal/chrometracing"
+	"github.com/vercel/turborepo/cli/internal/ci"
 	"github.com/vercel/turborepo/cli/internal/colorcache"
 	"github.com/vercel/turborepo/cli/internal/fs"
 	"github.com/vercel/turborepo/cli/internal/globby"
+	"github.com/vercel/turborepo/cli/internal/logstreamer"
 	"github.com/vercel/turborepo/cli/internal/nodes"
 	"github.com/vercel/turborepo/cli/internal/ui"
 	"github.com/vercel/turborepo/cli/internal/util"
@@ -25,6 +30,47 @@ import (
 // LogReplayer is a function that is responsible for replaying the contents of a given log file
 type LogReplayer = func(logger hclog.Logger, output cli.Ui, logFile fs.AbsolutePath)
 
+// LogFormat controls how task output is rendered, both to the terminal and to log files.
+// The zero value is treated as TextLogFormat.
+type LogFormat string
+
+const (
+	// TextLogFormat prefixes each line of output with the package and task
+	TextLogFormat LogFormat = "text"
+	// JSONLogFormat writes each line of output as a JSON object
+	JSONLogFormat LogFormat = "json"
+)
+
+// LogGrouping controls whether each task's output is wrapped in collapsible CI log groups
+type LogGrouping string
+
+const (
+	// AutoLogGrouping groups output when running under a CI provider that supports it
+	AutoLogGrouping LogGrouping = "auto"
+	// GitHubLogGrouping always groups output using GitHub Actions workflow commands
+	GitHubLogGrouping LogGrouping = "github"
+	// GitLabLogGrouping always groups output using GitLab collapsible sections
+	GitLabLogGrouping LogGrouping = "gitlab"
+	// NoLogGrouping never groups output
+	NoLogGrouping LogGrouping = "none"
+)
+
+var _logGroupings = []LogGrouping{AutoLogGrouping, GitHubLogGrouping, GitLabLogGrouping, NoLogGrouping}
+
+// LogOrder controls the order in which grouped task output is written
+type LogOrder string
+
+const (
+	// CompletionLogOrder writes each task's output as soon as it finishes
+	CompletionLogOrder LogOrder = "completion"
+	// DependencyLogOrder writes each task's output after the output of its dependencies,
+	// in the same order on every run
+	DependencyLogOrder LogOrder = "dependency"
+)
+
+// _defaultLogOrderMaxBytes is how much output can be held back to keep it in dependency order
+const _defaultLogOrderMaxBytes = 64 * 1024 * 1024
+
 // Opts holds the configurable options for a RunCache instance
 type Opts struct {
 	SkipReads              bool
@@ -32,6 +78,43 @@ type Opts struct {
 	TaskOutputModeOverride *util.TaskOutputMode
 	LogReplayer            LogReplayer
 	OutputWatcher          OutputWatcher
+	LogFormat              LogFormat
+	LogGrouping            LogGrouping
+	LogOrder               LogOrder
+	// LogOrderMaxBytes limits how much output is held back to keep it in dependency order
+	LogOrderMaxBytes int
+	// LogTail is how many lines of replayed logs are shown with --output-logs=hash-only-with-tail=N
+	LogTail int
+	// LogMaxBytes, if positive, limits how much of a log is replayed
+	LogMaxBytes int
+	// CompressLogs stores task logs gzip-compressed
+	CompressLogs bool
+	// CacheOverrides maps a task name or package task id to the CachePolicy to use
+	// for it, in place of its configuration in turbo.json
+	CacheOverrides map[string]CachePolicy
+	// SkipRemoteReads and SkipRemoteWrites keep every task from restoring outputs from,
+	// or saving them to, the remote cache, in addition to tasks whose remote role in
+	// turbo.json doesn't allow it
+	SkipRemoteReads  bool
+	SkipRemoteWrites bool
+}
+
+// LogGrouper returns the Grouper to use for each task's output, or nil if output
+// should not be grouped. Automatic grouping is skipped for json logs, where
+// group markers would break line-by-line parsing.
+func (o *Opts) LogGrouper() ci.Grouper {
+	switch o.LogGrouping {
+	case NoLogGrouping:
+		return nil
+	case GitHubLogGrouping:
+		return ci.GitHubGrouper
+	case GitLabLogGrouping:
+		return ci.GitLabGrouper
+	}
+	if o.LogFormat == JSONLogFormat {
+		return nil
+	}
+	return ci.GrouperFor(ci.Constant())
 }
 
 // AddFlags adds the flags relevant to the runcache package to the given FlagSet
@@ -49,11 +132,54 @@ func AddFlags(opts *Opts, flags *pflag.FlagSet) {
 		Usage: `Set type of process output logging. Use "full" to show
 all output. Use "hash-only" to show only turbo-computed
 task hashes. Use "new-only" to show only new output with
-only hashes for cached tasks. Use "none" to hide process
-output.`,
+only hashes for cached tasks. Use "hash-only-with-tail=N"
+to show new output, and hashes with the last N lines of
+logs for cached tasks. Use "none" to hide process output.`,
 		DefValue: defaultTaskOutputMode,
 		Value:    &taskOutputModeValue{opts: opts},
 	})
+	flags.AddFlag(&pflag.Flag{
+		Name: "log-format",
+		Usage: `Set the format of task output, both in the terminal
+and in log files. Use "json" to write one JSON object per
+line, with timestamp, package, task, stream and text.`,
+		DefValue: string(TextLogFormat),
+		Value:    &logFormatValue{opts: opts},
+	})
+	flags.AddFlag(&pflag.Flag{
+		Name: "log-grouping",
+		Usage: `Wrap each task's output in a collapsible group. The
+default, "auto", groups output when running in GitHub
+Actions or GitLab CI. Grouped output is written once
+the task finishes.`,
+		DefValue: string(AutoLogGrouping),
+		Value:    &logGroupingValue{opts: opts},
+	})
+	flags.AddFlag(&pflag.Flag{
+		Name: "log-order",
+		Usage: `Set the order in which task output is written. Use
+"completion" to write each task's output as it finishes.
+Use "dependency" to group each task's output and write
+it after its dependencies' output, in the same order on
+every run.`,
+		DefValue: string(CompletionLogOrder),
+		Value:    &logOrderValue{opts: opts},
+	})
+	flags.IntVar(&opts.LogOrderMaxBytes, "log-order-max-bytes", _defaultLogOrderMaxBytes, `With --log-order=dependency, the most output to hold
+back while waiting for earlier tasks. Past this, output
+is written in completion order until it catches up.`)
+	flags.IntVar(&opts.LogMaxBytes, "output-logs-max-bytes", 0, `When replaying logs for a cache hit, stop after showing
+this many bytes. Defaults to no limit.`)
+	flags.BoolVar(&opts.CompressLogs, "compress-logs", false, "Store task logs gzip-compressed.")
+	flags.BoolVar(&opts.SkipRemoteReads, "no-remote-read", false, "Don't restore task outputs from the remote cache. The local cache is still used.")
+	flags.BoolVar(&opts.SkipRemoteWrites, "no-remote-write", false, "Don't save task outputs to the remote cache. The local cache is still used.")
+	flags.AddFlag(&pflag.Flag{
+		Name: "cache-override",
+		Usage: `Override caching for a task, e.g. "build=off" or
+"web#test=read-only". Policies are "on", "off",
+"read-only" and "write-only". Can be passed multiple times.`,
+		Value: &cacheOverridesValue{opts: opts},
+	})
 	_ = flags.Bool("stream", true, "Unused")
 	if err := flags.MarkDeprecated("stream", "[WARNING] The --stream flag is unnecessary and has been deprecated. It will be removed in future versions of turbo."); err != nil {
 		// fail fast if we've misconfigured our flags
@@ -65,11 +191,17 @@ type taskOutputModeValue struct {
 	opts *Opts
 }
 
+// _tailTaskOutputPrefix starts the one output mode that takes a parameter, the number of lines
+const _tailTaskOutputPrefix = "hash-only-with-tail="
+
 func (l *taskOutputModeValue) String() string {
 	var outputMode util.TaskOutputMode
 	if l.opts.TaskOutputModeOverride != nil {
 		outputMode = *l.opts.TaskOutputModeOverride
 	}
+	if outputMode == util.TailTaskOutput {
+		return fmt.Sprintf("%v%v", _tailTaskOutputPrefix, l.opts.LogTail)
+	}
 	taskOutputMode, err := util.ToTaskOutputModeString(outputMode)
 	if err != nil {
 		panic(err)
@@ -78,6 +210,16 @@ func (l *taskOutputModeValue) String() string {
 }
 
 func (l *taskOutputModeValue) Set(value string) error {
+	if strings.HasPrefix(value, _tailTaskOutputPrefix) {
+		tail, err := strconv.Atoi(strings.TrimPrefix(value, _tailTaskOutputPrefix))
+		if err != nil || tail < 1 {
+			return fmt.Errorf("%v needs a number of lines, got %q", _tailTaskOutputPrefix, value)
+		}
+		outputMode := util.TailTaskOutput
+		l.opts.TaskOutputModeOverride = &outputMode
+		l.opts.LogTail = tail
+		return nil
+	}
 	outputMode, err := util.FromTaskOutputModeString(value)
 	if err != nil {
 		return fmt.Errorf("must be one of \"%v\"", l.Type())
@@ -97,11 +239,95 @@ func (l *taskOutputModeValue) Type() string {
 		first = false
 		builder.WriteString(string(mode))
 	}
+	builder.WriteString("|" + _tailTaskOutputPrefix + "N")
 	return builder.String()
 }
 
 var _ pflag.Value = &taskOutputModeValue{}
 
+type logFormatValue struct {
+	opts *Opts
+}
+
+func (l *logFormatValue) String() string {
+	if l.opts.LogFormat == "" {
+		return string(TextLogFormat)
+	}
+	return string(l.opts.LogFormat)
+}
+
+func (l *logFormatValue) Set(value string) error {
+	switch LogFormat(value) {
+	case TextLogFormat, JSONLogFormat:
+		l.opts.LogFormat = LogFormat(value)
+		return nil
+	}
+	return fmt.Errorf("must be one of \"%v\"", l.Type())
+}
+
+func (l *logFormatValue) Type() string {
+	return fmt.Sprintf("%v|%v", TextLogFormat, JSONLogFormat)
+}
+
+var _ pflag.Value = &logFormatValue{}
+
+type logGroupingValue struct {
+	opts *Opts
+}
+
+func (l *logGroupingValue) String() string {
+	if l.opts.LogGrouping == "" {
+		return string(AutoLogGrouping)
+	}
+	return string(l.opts.LogGrouping)
+}
+
+func (l *logGroupingValue) Set(value string) error {
+	for _, grouping := range _logGroupings {
+		if LogGrouping(value) == grouping {
+			l.opts.LogGrouping = grouping
+			return nil
+		}
+	}
+	return fmt.Errorf("must be one of \"%v\"", l.Type())
+}
+
+func (l *logGroupingValue) Type() string {
+	groupings := make([]string, len(_logGroupings))
+	for i, grouping := range _logGroupings {
+		groupings[i] = string(grouping)
+	}
+	return strings.Join(groupings, "|")
+}
+
+var _ pflag.Value = &logGroupingValue{}
+
+type logOrderValue struct {
+	opts *Opts
+}
+
+func (l *logOrderValue) String() string {
+	if l.opts.LogOrder == "" {
+		return string(CompletionLogOrder)
+	}
+	return string(l.opts.LogOrder)
+}
+
+func (l *logOrderValue) Set(value string) error {
+	switch LogOrder(value) {
+	case CompletionLogOrder, DependencyLogOrder:
+		l.opts.LogOrder = LogOrder(value)
+		return nil
+	}
+	return fmt.Errorf("must be one of \"%v\"", l.Type())
+}
+
+func (l *logOrderValue) Type() string {
+	return fmt.Sprintf("%v|%v", CompletionLogOrder, DependencyLogOrder)
+}
+
+var _ pflag.Value = &logOrderValue{}
+
 // RunCache represents the interface to the cache for a single `turbo run`
 type RunCache struct {
 	taskOutputModeOverride *util.TaskOutputMode
@@ -110,24 +336,41 @@ type RunCache struct {
 	writesDisabled         bool
 	repoRoot               fs.AbsolutePath
 	logReplayer            LogReplayer
+	tailLogReplayer        LogReplayer
 	outputWatcher          OutputWatcher
 	colorCache             *colorcache.ColorCache
+	logFormat              LogFormat
+	compressLogs           bool
+	cacheOverrides         map[string]CachePolicy
+	// localCache is a view of cache that skips the remote cache, for tasks that may not
+	// read from or write to it
+	localCache       cache.Cache
+	skipRemoteReads  bool
+	skipRemoteWrites bool
 }
 
 // New returns a new instance of RunCache, wrapping the given cache
-func New(cache cache.Cache, repoRoot fs.AbsolutePath, opts Opts, colorCache *colorcache.ColorCache) *RunCache {
+func New(turboCache cache.Cache, repoRoot fs.AbsolutePath, opts Opts, colorCache *colorcache.ColorCache) *RunCache {
 	rc := &RunCache{
 		taskOutputModeOverride: opts.TaskOutputModeOverride,
-		cache:                  cache,
+		cache:                  turboCache,
 		readsDisabled:          opts.SkipReads,
 		writesDisabled:         opts.SkipWrites,
 		repoRoot:               repoRoot,
 		logReplayer:            opts.LogReplayer,
+		tailLogReplayer:        opts.LogReplayer,
 		outputWatcher:          opts.OutputWatcher,
 		colorCache:             colorCache,
+		logFormat:              opts.LogFormat,
+		compressLogs:           opts.CompressLogs,
+		cacheOverrides:         opts.CacheOverrides,
+		localCache:             cache.LocalOnly(turboCache),
+		skipRemoteReads:        opts.SkipRemoteReads,
+		skipRemoteWrites:       opts.SkipRemoteWrites,
 	}
 	if rc.logReplayer == nil {
-		rc.logReplayer = defaultLogReplayer
+		rc.logReplayer = newLogReplayer(0, opts.LogMaxBytes)
+		rc.tailLogReplayer = newLogReplayer(opts.LogTail, opts.LogMaxBytes)
 	}
 	if rc.outputWatcher == nil {
 		rc.outputWatcher = &NoOpOutputWatcher{}
@@ -140,33 +383,50 @@ func New(cache cache.Cache, repoRoot fs.AbsolutePath, opts Opts, colorCache *col
 type TaskCache struct {
 	rc                *RunCache
 	repoRelativeGlobs []string
-	hash              string
-	pt                *nodes.PackageTask
-	taskOutputMode    util.TaskOutputMode
-	cachingDisabled   bool
-	LogFileName       fs.AbsolutePath
+	// watchGlobs are the globs that the output watcher tracks. They include
+	// every file that a task with dynamicOutputs could declare.
+	watchGlobs     []string
+	hash           string
+	pt             *nodes.PackageTask
+	taskOutputMode util.TaskOutputMode
+	readsDisabled  bool
+	writesDisabled bool
+	LogFileName    fs.AbsolutePath
+	// skipRemoteReads and skipRemoteWrites restrict the task to the local cache
+	skipRemoteReads  bool
+	skipRemoteWrites bool
 }
 
 // RestoreOutputs attempts to restore output for the corresponding task from the cache. Returns true
 // if successful.
-func (tc TaskCache) RestoreOutputs(ctx context.Context, terminal *cli.PrefixedUi, logger hclog.Logger) (bool, error) {
-	if tc.cachingDisabled || tc.rc.readsDisabled {
+func (tc TaskCache) RestoreOutputs(ctx context.Context, terminal cli.Ui, logger hclog.Logger) (bool, error) {
+	if tc.readsDisabled {
 		if tc.taskOutputMode != util.NoTaskOutput {
 			terminal.Output(fmt.Sprintf("cache bypass, force executing %s", ui.Dim(tc.hash)))
 		}
 		return false, nil
 	}
-	changedOutputGlobs, err := tc.rc.outputWatcher.GetChangedOutputs(ctx, tc.hash, tc.repoRelativeGlobs)
+	changedOutputGlobs, err := tc.rc.outputWatcher.GetChangedOutputs(ctx, tc.hash, tc.watchGlobs)
 	if err != nil {
 		logger.Warn(fmt.Sprintf("Failed to check if we can skip restoring outputs for %v: %v. Proceeding to check cache", tc.pt.TaskID, err))
 		terminal.Warn(ui.Dim(fmt.Sprintf("Failed to check if we can skip restoring outputs for %v: %v. Proceeding to check cache", tc.pt.TaskID, err)))
-		changedOutputGlobs = tc.repoRelativeGlobs
+		changedOutputGlobs = tc.watchGlobs
 	}
 	hasChangedOutputs := len(changedOutputGlobs) > 0
 	if hasChangedOutputs {
 		// Note that we currently don't use the output globs when restoring, but we could in the
 		// future to avoid doing unnecessary file I/O
-		hit, _, _, err := tc.rc.cache.Fetch(tc.rc.repoRoot.ToString(), tc.hash, changedOutputGlobs)
+		fetch := chrometracing.EventWithArgs("cache fetch "+tc.pt.TaskID, chrometracing.Args{"hash": tc.hash})
+		fetchCache := tc.rc.cache
+		if tc.skipRemoteReads {
+			fetchCache = tc.rc.localCache
+		}
+		// The artifact's log may be stored with a different --compress-logs than the log
+		// from a previous run, which shouldn't be replayed instead of it
+		_ = tc.LogFileName.Remove()
+		_ = tc.otherLogFileName().Remove()
+		hit, _, _, err := fetchCache.Fetch(tc.rc.repoRoot.ToString(), tc.hash, changedOutputGlobs)
+		fetch.DoneWithArgs(chrometracing.Args{"hit": hit})
 		if err != nil {
 			return false, err
 		} else if !hit {
@@ -175,7 +435,7 @@ func (tc TaskCache) RestoreOutputs(ctx context.Context, terminal *cli.PrefixedUi
 			}
 			return false, nil
 		}
-		if err := tc.rc.outputWatcher.NotifyOutputsWritten(ctx, tc.hash, tc.repoRelativeGlobs); err != nil {
+		if err := tc.rc.outputWatcher.NotifyOutputsWritten(ctx, tc.hash, tc.watchGlobs); err != nil {
 			// Don't fail the whole operation just because we failed to watch the outputs
 			logger.Warn(fmt.Sprintf("Failed to mark outputs as cached for %v: %v", tc.pt.TaskID, err))
 			terminal.Warn(ui.Dim(fmt.Sprintf("Failed to mark outputs as cached for %v: %v", tc.pt.TaskID, err)))
@@ -191,18 +451,46 @@ func (tc TaskCache) RestoreOutputs(ctx context.Context, terminal *cli.PrefixedUi
 	case util.HashTaskOutput:
 		terminal.Output(fmt.Sprintf("cache hit, suppressing output %s", ui.Dim(tc.hash)))
 	case util.FullTaskOutput:
-		logger.Debug("log file", "path", tc.LogFileName)
-		if tc.LogFileName.FileExists() {
-			// The task label is baked into the log file, so we need to grab the underlying Ui
-			// instance in order to not duplicate it
-			tc.rc.logReplayer(logger, terminal.Ui, tc.LogFileName)
-		}
+		tc.replayLog(logger, terminal, tc.rc.logReplayer)
+	case util.TailTaskOutput:
+		tc.replayLog(logger, terminal, tc.rc.tailLogReplayer)
 	default:
 		// NoLogs, do not output anything
 	}
 	return true, nil
 }
 
+// replayLog replays the task's log, whether or not it was stored compressed
+func (tc TaskCache) replayLog(logger hclog.Logger, terminal cli.Ui, replayer LogReplayer) {
+	logFileName := tc.LogFileName
+	if !logFileName.FileExists() {
+		logFileName = tc.otherLogFileName()
+	}
+	logger.Debug("log file", "path", logFileName)
+	if logFileName.FileExists() {
+		replayUi := newConvertingUi(terminal, tc.rc.logFormat == JSONLogFormat, tc.pt.PackageName, tc.pt.Task, tc.pt.OutputPrefix())
+		replayer(logger, replayUi, logFileName)
+	}
+}
+
+// otherLogFileName returns where the task's log is stored with the opposite of the
+// current --compress-logs setting, such as in an artifact cached by another run
+func (tc TaskCache) otherLogFileName() fs.AbsolutePath {
+	logFileName := tc.rc.repoRoot.Join(tc.pt.RepoRelativeLogFile())
+	if tc.rc.compressLogs {
+		return logFileName
+	}
+	return CompressedLogFile(logFileName)
+}
+
+// CompressedLogSuffix is added to the name of a task's log when it is stored with --compress-logs
+const CompressedLogSuffix = ".gz"
+
+// CompressedLogFile returns where a task's log is stored with --compress-logs
+func CompressedLogFile(logFileName fs.AbsolutePath) fs.AbsolutePath {
+	return fs.AbsolutePathFromUpstream(logFileName.ToString() + CompressedLogSuffix)
+}
+
 // nopWriteCloser is modeled after io.NopCloser, which is for Readers
 type nopWriteCloser struct {
 	io.Writer
@@ -213,6 +501,7 @@ func (nopWriteCloser) Close() error { return nil }
 type fileWriterCloser struct {
 	io.Writer
 	file  *os.File
+	gzip  *gzip.Writer
 	bufio *bufio.Writer
 }
 
@@ -220,58 +509,90 @@ func (fwc *fileWriterCloser) Close() error {
 	if err := fwc.bufio.Flush(); err != nil {
 		return err
 	}
+	if fwc.gzip != nil {
+		if err := fwc.gzip.Close(); err != nil {
+			return err
+		}
+	}
 	return fwc.file.Close()
 }
 
 // OutputWriter creates a sink suitable for handling the output of the command associated
-// with this task.
-func (tc TaskCache) OutputWriter() (io.WriteCloser, error) {
-	if tc.cachingDisabled || tc.rc.writesDisabled {
-		return nopWriteCloser{os.Stdout}, nil
+// with this task. Output that should be shown is written to terminal.
+func (tc TaskCache) OutputWriter(terminal io.Writer) (io.WriteCloser, error) {
+	if tc.writesDisabled {
+		return nopWriteCloser{terminal}, nil
 	}
 	// Setup log file
 	if err := tc.LogFileName.EnsureDir(); err != nil {
 		return nil, err
 	}
+	// Don't leave a log from a previous run with a different --compress-logs to be cached
+	if err := tc.otherLogFileName().Remove(); err != nil && !os.IsNotExist(err) {
+		return nil, err
+	}
 	output, err := tc.LogFileName.Create()
 	if err != nil {
 		return nil, err
 	}
-	colorPrefixer := tc.rc.colorCache.PrefixColor(tc.pt.PackageName)
-	prettyTaskPrefix := colorPrefixer(tc.pt.OutputPrefix())
-	bufWriter := bufio.NewWriter(output)
-	if _, err := bufWriter.WriteString(fmt.Sprintf("%s: cache hit, replaying output %s\n", prettyTaskPrefix, ui.Dim(tc.hash))); err != nil {
+	var header string
+	if tc.rc.logFormat == JSONLogFormat {
+		header = logstreamer.NewJSONLine(tc.pt.PackageName, tc.pt.Task, logstreamer.StreamTurbo, fmt.Sprintf("cache hit, replaying output %s", tc.hash))
+	} else {
+		colorPrefixer := tc.rc.colorCache.PrefixColor(tc.pt.PackageName)
+		prettyTaskPrefix := colorPrefixer(tc.pt.OutputPrefix())
+		header = fmt.Sprintf("%s: cache hit, replaying output %s", prettyTaskPrefix, ui.Dim(tc.hash))
+	}
+	fwc := &fileWriterCloser{
+		file: output,
+	}
+	var logWriter io.Writer = output
+	if tc.rc.compressLogs {
+		fwc.gzip = gzip.NewWriter(output)
+		logWriter = fwc.gzip
+	}
+	bufWriter := bufio.NewWriter(logWriter)
+	fwc.bufio = bufWriter
+	if _, err := bufWriter.WriteString(header + "\n"); err != nil {
 		// We've already errored, we don't care if there's a further error closing the file we just
 		// failed to write to.
 		_ = output.Close()
 		return nil, err
 	}
-	fwc := &fileWriterCloser{
-		file:  output,
-		bufio: bufWriter,
-	}
 	if tc.taskOutputMode == util.NoTaskOutput || tc.taskOutputMode == util.HashTaskOutput {
 		// only write to log file, not to stdout
 		fwc.Writer = bufWriter
 	} else {
-		fwc.Writer = io.MultiWriter(os.Stdout, bufWriter)
+		fwc.Writer = io.MultiWriter(terminal, bufWriter)
 	}
 	return fwc, nil
 }
 
 var _emptyIgnore []string
 
-// SaveOutputs is responsible for saving the outputs of task to the cache, after the task has completed
-func (tc TaskCache) SaveOutputs(ctx context.Context, logger hclog.Logger, terminal cli.Ui, duration int) error {
-	if tc.cachingDisabled || tc.rc.writesDisabled {
-		return nil
+// SaveOutputs is responsible for saving the outputs of task to the cache, after the task has completed.
+// It returns the outputs globs declared in turbo.json that matched no files.
+func (tc TaskCache) SaveOutputs(ctx context.Context, logger hclog.Logger, terminal cli.Ui, duration int) ([]string, error) {
+	if tc.writesDisabled {
+		return nil, nil
+	}
+
+	globs := append([]string{}, tc.repoRelativeGlobs...)
+	if len(tc.pt.TaskDefinition.DynamicOutputs) > 0 {
+		dynamicOutputs, err := readDynamicOutputs(tc.rc.repoRoot.Join(tc.pt.Pkg.Dir), tc.pt.TaskID, tc.pt.TaskDefinition.DynamicOutputs)
+		if err != nil {
+			return nil, err
+		}
+		for _, output := range dynamicOutputs {
+			globs = append(globs, filepath.Join(tc.pt.Pkg.Dir, output))
+		}
 	}
 
-	logger.Debug("caching output", "outputs", tc.repoRelativeGlobs)
+	logger.Debug("caching output", "outputs", globs)
 
-	filesToBeCached, err := globby.GlobFiles(tc.rc.repoRoot.ToStringDuringMigration(), tc.repoRelativeGlobs, _emptyIgnore)
+	filesToBeCached, err := globby.GlobFiles(tc.rc.repoRoot.ToStringDuringMigration(), globs, _emptyIgnore)
 	if err != nil {
-		return err
+		return nil, err
 	}
 
 	relativePaths := make([]string, len(filesToBeCached))
@@ -286,16 +607,43 @@ func (tc TaskCache) SaveOutputs(ctx context.Context, logger hclog.Logger, termin
 		relativePaths[index] = relativePath
 	}
 
-	if err = tc.rc.cache.Put(tc.pt.Pkg.Dir, tc.hash, duration, relativePaths); err != nil {
-		return err
+	if err := checkCaseConflicts(tc.pt.TaskID, relativePaths, tc.pt.HashableOutputs(), globs); err != nil {
+		return nil, err
+	}
+	emptyOutputs := findEmptyOutputs(tc.pt, relativePaths)
+
+	put := chrometracing.EventWithArgs("cache put "+tc.pt.TaskID, chrometracing.Args{"hash": tc.hash, "files": len(relativePaths)})
+	putCache := tc.rc.cache
+	if tc.skipRemoteWrites {
+		putCache = tc.rc.localCache
+	} else if tc.pt.TaskDefinition.RemoteCacheTTL > 0 {
+		putCache = cache.WithTTL(tc.rc.cache, tc.pt.TaskDefinition.RemoteCacheTTL)
+	}
+	err = putCache.Put(tc.pt.Pkg.Dir, tc.hash, duration, relativePaths)
+	put.Done()
+	if err != nil {
+		return nil, err
 	}
-	err = tc.rc.outputWatcher.NotifyOutputsWritten(ctx, tc.hash, tc.repoRelativeGlobs)
+	err = tc.rc.outputWatcher.NotifyOutputsWritten(ctx, tc.hash, tc.watchGlobs)
 	if err != nil {
 		// Don't fail the cache write because we also failed to record it, we will just do
 		// extra I/O in the future restoring files that haven't changed from cache
 		logger.Warn(fmt.Sprintf("Failed to mark outputs as cached for %v: %v", tc.pt.TaskID, err))
 		terminal.Warn(ui.Dim(fmt.Sprintf("Failed to mark outputs as cached for %v: %v", tc.pt.TaskID, err)))
 	}
+	return emptyOutputs, nil
+}
+
+// ClearOutputsManifest removes the outputs manifest left by a previous run of a task with
+// dynamicOutputs, so that outputs it no longer declares aren't cached
+func (tc TaskCache) ClearOutputsManifest() error {
+	if len(tc.pt.TaskDefinition.DynamicOutputs) == 0 {
+		return nil
+	}
+	manifest := tc.rc.repoRoot.Join(tc.pt.Pkg.Dir, filepath.FromSlash(OutputsManifest))
+	if err := manifest.Remove(); err != nil && !os.IsNotExist(err) {
+		return err
+	}
 	return nil
 }
 
@@ -308,35 +656,55 @@ func (rc *RunCache) TaskCache(pt *nodes.PackageTask, hash string) TaskCache {
 	for index, output := range hashableOutputs {
 		repoRelativeGlobs[index] = filepath.Join(pt.Pkg.Dir, output)
 	}
+	if rc.compressLogs {
+		// The compressed log isn't one of the hashable outputs, so that the hash doesn't
+		// depend on --compress-logs
+		logFileName = CompressedLogFile(logFileName)
+		repoRelativeGlobs = append(repoRelativeGlobs, pt.RepoRelativeLogFile()+CompressedLogSuffix)
+	}
+
+	// A task with dynamicOutputs declares some of its outputs in a manifest while it runs,
+	// so the manifest is cached, and the output watcher covers everything it could declare
+	watchGlobs := repoRelativeGlobs
+	if len(pt.TaskDefinition.DynamicOutputs) > 0 {
+		repoRelativeGlobs = append(repoRelativeGlobs, filepath.Join(pt.Pkg.Dir, OutputsManifest))
+		watchGlobs = append([]string{}, repoRelativeGlobs...)
+		for _, dir := range pt.TaskDefinition.DynamicOutputs {
+			watchGlobs = append(watchGlobs, filepath.Join(pt.Pkg.Dir, dir, "**"))
+		}
+	}
 
 	taskOutputMode := pt.TaskDefinition.OutputMode
 	if rc.taskOutputModeOverride != nil {
 		taskOutputMode = *rc.taskOutputModeOverride
 	}
 
+	cachingDisabled := !pt.TaskDefinition.ShouldCache
+	readsDisabled := cachingDisabled || rc.readsDisabled
+	writesDisabled := cachingDisabled || rc.writesDisabled
+	// Overrides from the command line are more specific than both turbo.json and --force / --no-cache
+	if policy, ok := cachePolicyFor(rc.cacheOverrides, pt); ok {
+		readsDisabled = policy == CachePolicyOff || policy == CachePolicyWriteOnly
+		writesDisabled = policy == CachePolicyOff || policy == CachePolicyReadOnly
+	}
+
 	return TaskCache{
 		rc:                rc,
 		repoRelativeGlobs: repoRelativeGlobs,
+		watchGlobs:        watchGlobs,
 		hash:              hash,
 		pt:                pt,
 		taskOutputMode:    taskOutputMode,
-		cachingDisabled:   !pt.TaskDefinition.ShouldCache,
+		readsDisabled:     readsDisabled,
+		writesDisabled:    writesDisabled,
 		LogFileName:       logFileName,
+		skipRemoteReads:   rc.skipRemoteReads || pt.TaskDefinition.SkipRemoteReads,
+		skipRemoteWrites:  rc.skipRemoteWrites || pt.TaskDefinition.SkipRemoteWrites,
 	}
 }
 
-// defaultLogReplayer will try to replay logs back to the given Ui instance
-func defaultLogReplayer(logger hclog.Logger, output cli.Ui, logFileName fs.AbsolutePath) {
-	logger.Debug("start replaying logs")
-	f, err := logFileName.Open()
-	if err != nil {
-		output.Warn(fmt.Sprintf("error reading logs: %v", err))
-		logger.Error(fmt.Sprintf("error reading logs: %v", err.Error()))
-	}
-	defer func() { _ = f.Close() }()
-	scan := bufio.NewScanner(f)
-	for scan.Scan() {
-		output.Output(string(scan.Bytes())) //Writing to Stdout
-	}
-	logger.Debug("finish replaying logs")
+// SkipsRemoteWrites returns true if the task saves its outputs to the cache, but its
+// remote role keeps them out of the remote cache
+func (tc TaskCache) SkipsRemoteWrites() bool {
+	return !tc.writesDisabled && tc.skipRemoteWrites
 }
@@ -75,6 +75,32 @@ func (g *git) ChangedFiles(fromCommit string, toCommit string, includeUntracked
 	return normalized, nil
 }
 
+// PreviousContent returns the contents of a file at the merge-base of the given commits,
+// which is what ChangedFiles compares against
+func (g *git) PreviousContent(fromCommit string, toCommit string, filePath string) ([]byte, error) {
+	if fromCommit == "" {
+		return nil, fmt.Errorf("no commit to compare %v against", filePath)
+	}
+	out, err := exec.Command("git", "merge-base", fromCommit, toCommit).Output()
+	if err != nil {
+		return nil, errors.Wrapf(err, "finding the merge-base of %v and %v", fromCommit, toCommit)
+	}
+	return g.Content(strings.TrimSpace(string(out)), filePath)
+}
+
+// Content returns the contents of a file at the given commit
+func (g *git) Content(commit string, filePath string) ([]byte, error) {
+	relativePath, err := filepath.Rel(g.repoRoot, filePath)
+	if err != nil {
+		return nil, errors.Wrapf(err, "unable to determine relative path for %s and %s", g.repoRoot, filePath)
+	}
+	out, err := exec.Command("git", "show", fmt.Sprintf("%v:%v", commit, filepath.ToSlash(relativePath))).Output()
+	if err != nil {
+		return nil, errors.Wrapf(err, "reading %v at %v", relativePath, commit)
+	}
+	return out, nil
+}
+
 func commitExists(commit string) (bool, error) {
 	err := exec.Command("git", "cat-file", "-t", commit).Run()
 	if err != nil {
@@ -20,6 +20,10 @@ var ErrFallback = errors.New("cannot find a .git folder. Falling back to manual
 type SCM interface {
 	// ChangedFiles returns a list of modified files since the given commit, optionally including untracked files.*/
 	ChangedFiles(fromCommit string, toCommit string, includeUntracked bool, relativeTo string) ([]string, error)
+	// PreviousContent returns the contents of a file at the commit that ChangedFiles compares against
+	PreviousContent(fromCommit string, toCommit string, filePath string) ([]byte, error)
+	// Content returns the contents of a file at the given commit
+	Content(commit string, filePath string) ([]byte, error)
 }
 
 // newGitSCM returns a new SCM instance for this repo root.
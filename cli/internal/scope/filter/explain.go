@@ -0,0 +1,162 @@
+package filter
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+
+	"github.com/pkg/errors"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// PackageSelection explains whether a package was selected by a set of filter
+// patterns, and which parts of the patterns decided it
+type PackageSelection struct {
+	Package  string   `json:"package"`
+	Selected bool     `json:"selected"`
+	Reasons  []string `json:"reasons"`
+}
+
+// ExplainPackagesFromPatterns applies the given filter patterns, the same way that
+// GetPackagesFromPatterns does, and explains the outcome for every package in the graph.
+// The result is sorted by package name.
+func (r *Resolver) ExplainPackagesFromPatterns(patterns []string) ([]PackageSelection, error) {
+	selectors := []*TargetSelector{}
+	for _, pattern := range patterns {
+		selector, err := ParseTargetSelector(pattern, r.Cwd)
+		if err != nil {
+			return nil, err
+		}
+		selectors = append(selectors, &selector)
+	}
+	selected, err := r.GetFilteredPackages(selectors)
+	if err != nil {
+		return nil, err
+	}
+
+	included := make(map[string][]string)
+	excluded := make(map[string][]string)
+	hasIncludeSelectors := false
+	for _, selector := range selectors {
+		reasons, err := r.explainSelector(selector)
+		if err != nil {
+			return nil, err
+		}
+		target := included
+		if selector.exclude {
+			target = excluded
+		} else {
+			hasIncludeSelectors = true
+		}
+		for pkg, reason := range reasons {
+			target[pkg] = append(target[pkg], reason)
+		}
+	}
+
+	packages := []string{}
+	for _, v := range r.Graph.Vertices() {
+		packages = append(packages, v.(string))
+	}
+	// The root package isn't part of the graph, but can be selected by name
+	if selected.pkgs.Includes(util.RootPkgName) {
+		packages = append(packages, util.RootPkgName)
+	}
+	var selections []PackageSelection
+	for _, pkg := range packages {
+		selection := PackageSelection{Package: pkg, Selected: len(patterns) == 0 || selected.pkgs.Includes(pkg)}
+		switch {
+		case len(patterns) == 0:
+			selection.Reasons = []string{"no filters were given, so every package is selected"}
+		case selection.Selected && hasIncludeSelectors:
+			selection.Reasons = included[pkg]
+		case selection.Selected:
+			selection.Reasons = []string{"only exclusions were given, and none of them matched"}
+		case len(excluded[pkg]) > 0:
+			selection.Reasons = excluded[pkg]
+		default:
+			selection.Reasons = []string{"not matched by any filter"}
+		}
+		sort.Strings(selection.Reasons)
+		selections = append(selections, selection)
+	}
+	sort.Slice(selections, func(i, j int) bool {
+		return selections[i].Package < selections[j].Package
+	})
+	return selections, nil
+}
+
+// describeMatch describes how a package satisfied the entry point part of a selector,
+// i.e. the name, directory and git range, without the ... for dependencies or dependents
+func describeMatch(selector *TargetSelector) string {
+	var parts []string
+	if selector.namePattern != "" {
+		parts = append(parts, fmt.Sprintf("name matches %q", selector.namePattern))
+	}
+	if selector.parentDir != "" {
+		parts = append(parts, fmt.Sprintf("directory matches %v", selector.parentDir))
+	}
+	if selector.fromRef != "" {
+		changeRange := fmt.Sprintf("%v...%v", selector.fromRef, selector.getToRef())
+		if selector.matchDependencies {
+			parts = append(parts, fmt.Sprintf("it or a dependency changed in %v", changeRange))
+		} else {
+			parts = append(parts, fmt.Sprintf("changed in %v", changeRange))
+		}
+	}
+	return strings.Join(parts, " and ")
+}
+
+// explainSelector returns a reason for each package that the given selector matches,
+// mirroring the walk in filterGraphWithSelectors
+func (r *Resolver) explainSelector(selector *TargetSelector) (map[string]string, error) {
+	verb := "selected"
+	if selector.exclude {
+		verb = "excluded"
+	}
+	reasons := make(map[string]string)
+	add := func(pkg interface{}, reason string) {
+		name := pkg.(string)
+		if _, ok := reasons[name]; !ok {
+			reasons[name] = fmt.Sprintf("%v by %q: %v", verb, selector.raw, reason)
+		}
+	}
+
+	entryPackages, err := r.filterGraphWithSelector(selector)
+	if err != nil {
+		return nil, err
+	}
+	match := describeMatch(selector)
+	for _, pkg := range entryPackages {
+		if !selector.excludeSelf || (!selector.includeDependencies && !selector.includeDependents) {
+			add(pkg, match)
+		}
+		if selector.includeDependencies {
+			dependencies, err := r.Graph.Ancestors(pkg)
+			if err != nil {
+				return nil, errors.Wrapf(err, "failed to get dependencies of package %v", pkg)
+			}
+			for dep := range dependencies {
+				add(dep, fmt.Sprintf("dependency of %v (%v)", pkg, match))
+			}
+		}
+		if selector.includeDependents {
+			dependents, err := r.Graph.Descendents(pkg)
+			if err != nil {
+				return nil, errors.Wrapf(err, "failed to get dependents of package %v", pkg)
+			}
+			for dependent := range dependents {
+				add(dependent, fmt.Sprintf("dependent of %v (%v)", pkg, match))
+				if selector.includeDependencies {
+					dependentDeps, err := r.Graph.Ancestors(dependent)
+					if err != nil {
+						return nil, errors.Wrapf(err, "failed to get dependencies of dependent %v", dependent)
+					}
+					for dependentDep := range dependentDeps {
+						add(dependentDep, fmt.Sprintf("dependency of %v, a dependent of %v (%v)", dependent, pkg, match))
+					}
+				}
+			}
+		}
+	}
+	return reasons, nil
+}
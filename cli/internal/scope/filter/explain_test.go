@@ -0,0 +1,51 @@
+package filter
+
+import (
+	"path/filepath"
+	"reflect"
+	"testing"
+
+	"github.com/pyr-sh/dag"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+func Test_ExplainPackagesFromPatterns(t *testing.T) {
+	// web depends on ui, which depends on utils. docs stands alone.
+	graph := &dag.AcyclicGraph{}
+	packageJSONs := make(map[interface{}]*fs.PackageJSON)
+	for _, name := range []string{"web", "ui", "utils", "docs"} {
+		graph.Add(name)
+		packageJSONs[name] = &fs.PackageJSON{Name: name, Dir: filepath.Join("packages", name)}
+	}
+	graph.Connect(dag.BasicEdge("web", "ui"))
+	graph.Connect(dag.BasicEdge("ui", "utils"))
+	r := &Resolver{
+		Graph:        graph,
+		PackageInfos: packageJSONs,
+		Cwd:          t.TempDir(),
+	}
+
+	selections, err := r.ExplainPackagesFromPatterns([]string{"web...", "!utils"})
+	if err != nil {
+		t.Fatalf("ExplainPackagesFromPatterns: %v", err)
+	}
+	expected := []PackageSelection{
+		{Package: "docs", Selected: false, Reasons: []string{"not matched by any filter"}},
+		{Package: "ui", Selected: true, Reasons: []string{`selected by "web...": dependency of web (name matches "web")`}},
+		{Package: "utils", Selected: false, Reasons: []string{`excluded by "!utils": name matches "utils"`}},
+		{Package: "web", Selected: true, Reasons: []string{`selected by "web...": name matches "web"`}},
+	}
+	if !reflect.DeepEqual(selections, expected) {
+		t.Errorf("ExplainPackagesFromPatterns got %v, want %v", selections, expected)
+	}
+
+	selections, err = r.ExplainPackagesFromPatterns(nil)
+	if err != nil {
+		t.Fatalf("ExplainPackagesFromPatterns: %v", err)
+	}
+	for _, selection := range selections {
+		if !selection.Selected {
+			t.Errorf("expected %v to be selected when there are no filters", selection.Package)
+		}
+	}
+}
@@ -4,6 +4,7 @@ import (
 	"fmt"
 	"os"
 	"path/filepath"
+	"reflect"
 	"strings"
 
 	"github.com/hashicorp/go-hclog"
@@ -108,19 +109,17 @@ func (l *LegacyFilter) asFilterPatterns() []string {
 	return patterns
 }
 
+// HasFilters returns whether any filters, including the legacy selectors, were given
+func (o *Opts) HasFilters() bool {
+	return len(o.allFilterPatterns()) > 0
+}
+
 // ResolvePackages translates specified flags to a set of entry point packages for
 // the selected tasks. Returns the selected packages and whether or not the selected
 // packages represents a default "all packages".
 func ResolvePackages(opts *Opts, cwd string, scm scm.SCM, ctx *context.Context, tui cli.Ui, logger hclog.Logger) (util.Set, bool, error) {
-	filterResolver := &scope_filter.Resolver{
-		Graph:                  &ctx.TopologicalGraph,
-		PackageInfos:           ctx.PackageInfos,
-		Cwd:                    cwd,
-		PackagesChangedInRange: opts.getPackageChangeFunc(scm, cwd, ctx.PackageInfos),
-	}
-	filterPatterns := opts.FilterPatterns
-	legacyFilterPatterns := opts.LegacyFilter.asFilterPatterns()
-	filterPatterns = append(filterPatterns, legacyFilterPatterns...)
+	filterResolver := opts.newResolver(cwd, scm, ctx)
+	filterPatterns := opts.allFilterPatterns()
 	isAllPackages := len(filterPatterns) == 0
 	filteredPkgs, err := filterResolver.GetPackagesFromPatterns(filterPatterns)
 	if err != nil {
@@ -137,7 +136,42 @@ func ResolvePackages(opts *Opts, cwd string, scm scm.SCM, ctx *context.Context,
 	return filteredPkgs, isAllPackages, nil
 }
 
-func (o *Opts) getPackageChangeFunc(scm scm.SCM, cwd string, packageInfos map[interface{}]*fs.PackageJSON) scope_filter.PackagesChangedInRange {
+// ExplainPackages explains, for every package, whether it is selected by the filters
+// in opts and which parts of the filters decided it
+func ExplainPackages(opts *Opts, cwd string, scm scm.SCM, ctx *context.Context) ([]scope_filter.PackageSelection, error) {
+	selections, err := opts.newResolver(cwd, scm, ctx).ExplainPackagesFromPatterns(opts.allFilterPatterns())
+	if err != nil {
+		return nil, err
+	}
+	// The root node is a placeholder in the graph, not a package
+	explained := []scope_filter.PackageSelection{}
+	for _, selection := range selections {
+		if selection.Package != ctx.RootNode {
+			explained = append(explained, selection)
+		}
+	}
+	return explained, nil
+}
+
+func (o *Opts) newResolver(cwd string, scm scm.SCM, ctx *context.Context) *scope_filter.Resolver {
+	return &scope_filter.Resolver{
+		Graph:                  &ctx.TopologicalGraph,
+		PackageInfos:           ctx.PackageInfos,
+		Cwd:                    cwd,
+		PackagesChangedInRange: o.getPackageChangeFunc(scm, cwd, ctx),
+	}
+}
+
+// allFilterPatterns returns the --filter patterns along with the legacy selectors
+// expressed as filter patterns
+func (o *Opts) allFilterPatterns() []string {
+	filterPatterns := o.FilterPatterns
+	legacyFilterPatterns := o.LegacyFilter.asFilterPatterns()
+	return append(filterPatterns, legacyFilterPatterns...)
+}
+
+func (o *Opts) getPackageChangeFunc(scm scm.SCM, cwd string, ctx *context.Context) scope_filter.PackagesChangedInRange {
+	packageInfos := ctx.PackageInfos
 	return func(fromRef string, toRef string) (util.Set, error) {
 		// We could filter changed files at the git level, since it's possible
 		// that the changes we're interested in are scoped, but we need to handle
@@ -151,24 +185,99 @@ func (o *Opts) getPackageChangeFunc(scm scm.SCM, cwd string, packageInfos map[in
 			}
 			changedFiles = scmChangedFiles
 		}
+		// A lockfile change only affects the packages whose dependencies resolve differently,
+		// so when the lockfile can be compared it isn't a change to the root package
+		lockfilePkgs, changedFiles, err := lockfileChangedPackages(o, scm, fromRef, toRef, cwd, ctx, changedFiles)
+		if err != nil {
+			return nil, err
+		}
 		if hasRepoGlobalFileChanged, err := repoGlobalFileHasChanged(o, changedFiles); err != nil {
 			return nil, err
 		} else if hasRepoGlobalFileChanged {
-			allPkgs := make(util.Set)
-			for pkg := range packageInfos {
-				allPkgs.Add(pkg)
-			}
-			return allPkgs, nil
+			return allPackages(packageInfos), nil
 		}
 		filteredChangedFiles, err := filterIgnoredFiles(o, changedFiles)
 		if err != nil {
 			return nil, err
 		}
 		changedPkgs := getChangedPackages(filteredChangedFiles, packageInfos)
+		for pkg := range lockfilePkgs {
+			changedPkgs.Add(pkg)
+		}
 		return changedPkgs, nil
 	}
 }
 
+// lockfileChangedPackages compares the lockfile at fromRef with its contents at toRef, if
+// it is one of the changed files, and returns the packages whose external dependencies,
+// including everything those depend on, resolve differently. The lockfile is taken out of
+// the changed files that are returned. If the lockfile isn't parsed for this package manager,
+// or if --global-deps matches it, the changed files are returned as they are. If it can't be
+// read or parsed at either ref, every package is returned.
+func lockfileChangedPackages(opts *Opts, scm scm.SCM, fromRef string, toRef string, cwd string, ctx *context.Context, changedFiles []string) (util.Set, []string, error) {
+	if ctx.Lockfile == nil || ctx.PackageManager == nil || fromRef == "" {
+		return nil, changedFiles, nil
+	}
+	lockfilePath := filepath.FromSlash(ctx.PackageManager.Lockfile)
+	if isGlobal, err := repoGlobalFileHasChanged(opts, []string{lockfilePath}); err != nil {
+		return nil, nil, err
+	} else if isGlobal {
+		return nil, changedFiles, nil
+	}
+	var otherFiles []string
+	for _, file := range changedFiles {
+		if file != lockfilePath {
+			otherFiles = append(otherFiles, file)
+		}
+	}
+	if len(otherFiles) == len(changedFiles) {
+		return nil, changedFiles, nil
+	}
+	notIgnored, err := filterIgnoredFiles(opts, []string{lockfilePath})
+	if err != nil {
+		return nil, nil, err
+	}
+	changedPkgs := make(util.Set)
+	if len(notIgnored) == 0 {
+		return changedPkgs, otherFiles, nil
+	}
+	// If either version of the lockfile can't be read, any package's dependencies
+	// may have changed
+	contents, err := scm.PreviousContent(fromRef, toRef, filepath.Join(cwd, lockfilePath))
+	if err != nil {
+		return allPackages(ctx.PackageInfos), otherFiles, nil
+	}
+	previous, err := fs.ParseLockfile(contents, ctx.PackageManager.Name)
+	if err != nil {
+		return allPackages(ctx.PackageInfos), otherFiles, nil
+	}
+	// Changes against HEAD include the working tree, so the lockfile on disk is the one
+	// to compare with. Other refs are compared as they were committed.
+	current := ctx.Lockfile
+	if toRef != "" && toRef != "HEAD" {
+		contents, err := scm.Content(toRef, filepath.Join(cwd, lockfilePath))
+		if err != nil {
+			return allPackages(ctx.PackageInfos), otherFiles, nil
+		}
+		current, err = fs.ParseLockfile(contents, ctx.PackageManager.Name)
+		if err != nil {
+			return allPackages(ctx.PackageInfos), otherFiles, nil
+		}
+	}
+	var resolutions map[string]string
+	if root, ok := ctx.PackageInfos[util.RootPkgName]; ok {
+		resolutions = root.Resolutions
+	}
+	for name, pkg := range ctx.PackageInfos {
+		before := previous.Subtree(pkg.UnresolvedExternalDeps, resolutions)
+		after := current.Subtree(pkg.UnresolvedExternalDeps, resolutions)
+		if !reflect.DeepEqual(before, after) {
+			changedPkgs.Add(name)
+		}
+	}
+	return changedPkgs, otherFiles, nil
+}
+
 func repoGlobalFileHasChanged(opts *Opts, changedFiles []string) (bool, error) {
 	globalDepsGlob, err := filter.Compile(opts.GlobalDepPatterns)
 	if err != nil {
@@ -230,6 +339,15 @@ func fileInPackage(changedFile string, packagePath string) bool {
 	return false
 }
 
+// allPackages returns the name of every package in the repository
+func allPackages(packageInfos map[interface{}]*fs.PackageJSON) util.Set {
+	allPkgs := make(util.Set)
+	for pkg := range packageInfos {
+		allPkgs.Add(pkg)
+	}
+	return allPkgs
+}
+
 func getChangedPackages(changedFiles []string, packageInfos map[interface{}]*fs.PackageJSON) util.Set {
 	changedPackages := make(util.Set)
 	for _, changedFile := range changedFiles {
@@ -10,18 +10,37 @@ import (
 	"github.com/pyr-sh/dag"
 	"github.com/vercel/turborepo/cli/internal/context"
 	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/packagemanager"
 	"github.com/vercel/turborepo/cli/internal/ui"
 	"github.com/vercel/turborepo/cli/internal/util"
 )
 
 type mockSCM struct {
 	changed []string
+	// previous is the content of every file before the change, if it can be read
+	previous []byte
+	// atRef is the content of every file at any other commit, if it can be read
+	atRef []byte
 }
 
 func (m *mockSCM) ChangedFiles(_fromCommit string, _toCommit string, _includeUntracked bool, _relativeTo string) ([]string, error) {
 	return m.changed, nil
 }
 
+func (m *mockSCM) PreviousContent(_fromCommit string, _toCommit string, filePath string) ([]byte, error) {
+	if m.previous == nil {
+		return nil, fmt.Errorf("%v didn't exist", filePath)
+	}
+	return m.previous, nil
+}
+
+func (m *mockSCM) Content(commit string, filePath string) ([]byte, error) {
+	if m.atRef == nil {
+		return nil, fmt.Errorf("%v didn't exist at %v", filePath, commit)
+	}
+	return m.atRef, nil
+}
+
 func TestResolvePackages(t *testing.T) {
 	tui := ui.Default()
 	logger := hclog.Default()
@@ -254,3 +273,119 @@ func TestResolvePackages(t *testing.T) {
 		})
 	}
 }
+
+func TestResolvePackagesLockfileChange(t *testing.T) {
+	tui := ui.Default()
+	logger := hclog.Default()
+	// app -> lib, with other on its own
+	graph := dag.AcyclicGraph{}
+	graph.Add("app")
+	graph.Add("lib")
+	graph.Add("other")
+	graph.Connect(dag.BasicEdge("app", "lib"))
+	packageInfos := map[interface{}]*fs.PackageJSON{
+		"app": {
+			Dir: "app",
+		},
+		"lib": {
+			Dir:                    "lib",
+			UnresolvedExternalDeps: map[string]string{"lodash": "^4.17.0"},
+		},
+		"other": {
+			Dir:                    "other",
+			UnresolvedExternalDeps: map[string]string{"chalk": "^4.0.0"},
+		},
+	}
+	previous := []byte(`"lodash@npm:^4.17.0":
+  version: 4.17.20
+"chalk@npm:^4.0.0":
+  version: 4.1.2
+  dependencies:
+    ansi-styles: ^4.1.0
+"ansi-styles@npm:^4.1.0":
+  version: 4.3.0
+`)
+	lockfile := &fs.YarnLockfile{
+		"lodash@npm:^4.17.0": {Version: "4.17.21"},
+		"chalk@npm:^4.0.0": {
+			Version:      "4.1.2",
+			Dependencies: map[string]string{"ansi-styles": "^4.1.0"},
+		},
+		"ansi-styles@npm:^4.1.0": {Version: "4.3.0"},
+	}
+
+	testCases := []struct {
+		name       string
+		changed    []string
+		previous   []byte
+		atRef      []byte
+		filter     string
+		globalDeps []string
+		expected   []string
+	}{
+		{
+			name:     "a dependency of one package changed",
+			changed:  []string{"yarn.lock"},
+			previous: previous,
+			expected: []string{"app", "lib"},
+		},
+		{
+			name:       "the lockfile is a global dependency",
+			changed:    []string{"yarn.lock"},
+			previous:   previous,
+			globalDeps: []string{"yarn.lock"},
+			expected:   []string{"app", "lib", "other"},
+		},
+		{
+			name:     "the lockfile is unchanged at the ref compared with",
+			changed:  []string{"yarn.lock"},
+			previous: previous,
+			atRef:    previous,
+			filter:   "[dummy...feature]",
+			expected: []string{},
+		},
+		{
+			name:     "other files changed along with the lockfile",
+			changed:  []string{"yarn.lock", filepath.FromSlash("other/src/index.ts")},
+			previous: previous,
+			expected: []string{"app", "lib", "other"},
+		},
+		{
+			name:     "the lockfile can't be compared",
+			changed:  []string{"yarn.lock"},
+			previous: []byte("not a lockfile"),
+			expected: []string{"app", "lib", "other"},
+		},
+	}
+	for _, tc := range testCases {
+		t.Run(tc.name, func(t *testing.T) {
+			opts := &Opts{
+				LegacyFilter:      LegacyFilter{Since: "dummy"},
+				GlobalDepPatterns: tc.globalDeps,
+			}
+			if tc.filter != "" {
+				opts = &Opts{
+					FilterPatterns:    []string{tc.filter},
+					GlobalDepPatterns: tc.globalDeps,
+				}
+			}
+			pkgs, _, err := ResolvePackages(opts, filepath.FromSlash("/dummy/repo/root"), &mockSCM{changed: tc.changed, previous: tc.previous, atRef: tc.atRef}, &context.Context{
+				PackageInfos:     packageInfos,
+				PackageNames:     []string{"app", "lib", "other"},
+				TopologicalGraph: graph,
+				Lockfile:         lockfile,
+				PackageManager:   &packagemanager.PackageManager{Name: "nodejs-berry", Lockfile: "yarn.lock"},
+			}, tui, logger)
+			if err != nil {
+				t.Fatalf("expected no error, got %v", err)
+			}
+			expected := make(util.Set)
+			for _, pkg := range tc.expected {
+				expected.Add(pkg)
+			}
+			if !reflect.DeepEqual(pkgs, expected) {
+				t.Errorf("ResolvePackages got %v, want %v", pkgs, expected)
+			}
+		})
+	}
+}
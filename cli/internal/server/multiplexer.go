@@ -0,0 +1,345 @@
+package server
+
+import (
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+	"fmt"
+	"sync"
+	"time"
+
+	"github.com/hashicorp/go-hclog"
+	"github.com/vercel/turborepo/cli/internal/daemon/connector"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/turbodprotocol"
+	codes "google.golang.org/grpc/codes"
+	"google.golang.org/grpc/metadata"
+	status "google.golang.org/grpc/status"
+)
+
+// Multiplexer implements the GRPC serverside of TurbodServer for any number
+// of repositories. Requests are routed by the repository root attached to
+// each request, and each repository gets its own Server, with isolated
+// file watching and caches, created the first time it is seen. Repositories
+// that haven't been used for _repoIdleTimeout stop being served.
+type Multiplexer struct {
+	turbodprotocol.UnimplementedTurbodServer
+	logger       hclog.Logger
+	turboVersion string
+	logFilePath  fs.AbsolutePath
+	watchIgnores []string
+	started      time.Time
+	stopEvicting context.CancelFunc
+
+	mu      sync.Mutex // protects the fields below
+	servers map[fs.AbsolutePath]*repoServer
+	closed  bool
+
+	closerMu sync.Mutex
+	closer   *closer
+}
+
+// repoServer is the Server for one repository. Starting a Server can be slow, since
+// it starts watching the repository, so it happens without holding the Multiplexer's
+// lock, and requests for the same repository wait for ready to be closed.
+type repoServer struct {
+	ready  chan struct{}
+	server *Server // set before ready is closed, nil if the server failed to start
+	err    error
+	// lastUsed is protected by the Multiplexer's lock
+	lastUsed time.Time
+}
+
+const (
+	// _repoIdleTimeout is how long a repository is served without receiving requests
+	_repoIdleTimeout = 1 * time.Hour
+	// _evictionInterval is how often idle repositories are checked for
+	_evictionInterval = 1 * time.Minute
+)
+
+// NewMultiplexer returns a new Multiplexer that isn't yet serving any repositories.
+// watchIgnores are excluded from file watching in every repository, in addition to
+// anything listed in each repository's turbo.json.
+func NewMultiplexer(logger hclog.Logger, turboVersion string, logFilePath fs.AbsolutePath, watchIgnores ...string) *Multiplexer {
+	evictCtx, stopEvicting := context.WithCancel(context.Background())
+	m := &Multiplexer{
+		logger:       logger,
+		turboVersion: turboVersion,
+		logFilePath:  logFilePath,
+		watchIgnores: watchIgnores,
+		started:      time.Now(),
+		stopEvicting: stopEvicting,
+		servers:      make(map[fs.AbsolutePath]*repoServer),
+	}
+	go m.evictIdleServers(evictCtx)
+	return m
+}
+
+// getServerName returns a short, stable identifier for a repository. It is used
+// to namespace per-repository state such as cookie directories.
+func getServerName(repoRoot fs.AbsolutePath) string {
+	pathHash := sha256.Sum256([]byte(repoRoot.ToString()))
+	return hex.EncodeToString(pathHash[:])[:16]
+}
+
+// Register registers this multiplexer to respond to GRPC requests
+func (m *Multiplexer) Register(grpcServer GRPCServer) {
+	m.closerMu.Lock()
+	m.closer = &closer{
+		stop: grpcServer.GracefulStop,
+	}
+	m.closerMu.Unlock()
+	turbodprotocol.RegisterTurbodServer(grpcServer, m)
+}
+
+func (m *Multiplexer) tryClose() bool {
+	m.closerMu.Lock()
+	defer m.closerMu.Unlock()
+	if m.closer != nil {
+		m.closer.close()
+		return true
+	}
+	return false
+}
+
+// Close shuts down every repository currently being served
+func (m *Multiplexer) Close() error {
+	m.stopEvicting()
+	m.mu.Lock()
+	m.closed = true
+	servers := m.servers
+	m.servers = make(map[fs.AbsolutePath]*repoServer)
+	m.mu.Unlock()
+	var firstErr error
+	for repoRoot, entry := range servers {
+		if err := m.closeServer(repoRoot, entry); err != nil && firstErr == nil {
+			firstErr = err
+		}
+	}
+	return firstErr
+}
+
+// closeServer closes a repository's server once it has started. The caller must
+// already have removed entry from m.servers.
+func (m *Multiplexer) closeServer(repoRoot fs.AbsolutePath, entry *repoServer) error {
+	<-entry.ready
+	if entry.server == nil {
+		return nil
+	}
+	m.logger.Info(fmt.Sprintf("no longer serving %v", repoRoot))
+	if err := entry.server.Close(); err != nil {
+		m.logger.Error(fmt.Sprintf("failed to close server for %v: %v", repoRoot, err))
+		return err
+	}
+	return nil
+}
+
+// removeServer stops serving the given repository, for instance because it was deleted.
+// It returns whether any repositories are still being served.
+func (m *Multiplexer) removeServer(repoRoot fs.AbsolutePath, entry *repoServer) bool {
+	m.mu.Lock()
+	current, ok := m.servers[repoRoot]
+	if ok && (entry == nil || current == entry) {
+		delete(m.servers, repoRoot)
+	}
+	remaining := len(m.servers)
+	m.mu.Unlock()
+	if ok && (entry == nil || current == entry) {
+		_ = m.closeServer(repoRoot, current)
+	}
+	return remaining > 0
+}
+
+// evictIdleServers periodically stops serving repositories that haven't
+// received any requests in a while.
+func (m *Multiplexer) evictIdleServers(ctx context.Context) {
+	ticker := time.NewTicker(_evictionInterval)
+	defer ticker.Stop()
+	for {
+		select {
+		case <-ctx.Done():
+			return
+		case now := <-ticker.C:
+			m.evictServersIdleSince(now.Add(-_repoIdleTimeout))
+		}
+	}
+}
+
+func (m *Multiplexer) evictServersIdleSince(cutoff time.Time) {
+	m.mu.Lock()
+	idle := make(map[fs.AbsolutePath]*repoServer)
+	for repoRoot, entry := range m.servers {
+		if entry.lastUsed.Before(cutoff) {
+			m.logger.Info(fmt.Sprintf("%v has been idle since %v", repoRoot, entry.lastUsed))
+			idle[repoRoot] = entry
+		}
+	}
+	m.mu.Unlock()
+	for repoRoot, entry := range idle {
+		m.removeServer(repoRoot, entry)
+	}
+}
+
+// repoRootFor returns the repository root identified in the request metadata
+func repoRootFor(ctx context.Context) (fs.AbsolutePath, error) {
+	md, ok := metadata.FromIncomingContext(ctx)
+	if !ok {
+		return "", status.Error(codes.InvalidArgument, "request is missing metadata")
+	}
+	values := md.Get(connector.RepoRootMetadataKey)
+	if len(values) != 1 {
+		return "", status.Errorf(codes.InvalidArgument, "expected exactly one %v, got %v", connector.RepoRootMetadataKey, len(values))
+	}
+	repoRoot, err := fs.CheckedToAbsolutePath(values[0])
+	if err != nil {
+		return "", status.Error(codes.InvalidArgument, err.Error())
+	}
+	return repoRoot, nil
+}
+
+// serverFor returns the Server for the repository identified in the request metadata,
+// starting one if necessary.
+func (m *Multiplexer) serverFor(ctx context.Context) (*Server, error) {
+	repoRoot, err := repoRootFor(ctx)
+	if err != nil {
+		return nil, err
+	}
+	m.mu.Lock()
+	if m.closed {
+		m.mu.Unlock()
+		return nil, status.Error(codes.Unavailable, "daemon is shutting down")
+	}
+	entry, ok := m.servers[repoRoot]
+	if !ok {
+		entry = &repoServer{ready: make(chan struct{})}
+		m.servers[repoRoot] = entry
+	}
+	entry.lastUsed = time.Now()
+	m.mu.Unlock()
+	if !ok {
+		m.startServer(repoRoot, entry)
+	}
+	select {
+	case <-entry.ready:
+	case <-ctx.Done():
+		return nil, status.FromContextError(ctx.Err()).Err()
+	}
+	if entry.err != nil {
+		// This isn't FailedPrecondition, which clients take to mean a version mismatch
+		return nil, status.Errorf(codes.Internal, "failed to serve %v: %v", repoRoot, entry.err)
+	}
+	return entry.server, nil
+}
+
+// startServer creates the Server for entry and marks it as ready
+func (m *Multiplexer) startServer(repoRoot fs.AbsolutePath, entry *repoServer) {
+	defer close(entry.ready)
+	m.logger.Info(fmt.Sprintf("starting to serve %v", repoRoot))
+	server, err := New(getServerName(repoRoot), m.logger.Named(repoRoot.Base()), repoRoot, m.turboVersion, m.logFilePath, m.watchIgnores...)
+	if err != nil {
+		entry.err = err
+		// Let the next request try again
+		m.mu.Lock()
+		if m.servers[repoRoot] == entry {
+			delete(m.servers, repoRoot)
+		}
+		m.mu.Unlock()
+		return
+	}
+	server.setCloser(func() { m.removeServer(repoRoot, entry) })
+	entry.server = server
+}
+
+func (m *Multiplexer) repoRoots() []string {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	roots := make([]string, 0, len(m.servers))
+	for repoRoot := range m.servers {
+		roots = append(roots, repoRoot.ToString())
+	}
+	return roots
+}
+
+// Hello implements the Hello rpc from turbo.proto
+// Beyond checking the version, this begins serving the requesting repository
+// so that file watching is warmed up before the first real request.
+func (m *Multiplexer) Hello(ctx context.Context, req *turbodprotocol.HelloRequest) (*turbodprotocol.HelloResponse, error) {
+	clientVersion := req.Version
+	if clientVersion != m.turboVersion {
+		err := status.Errorf(codes.FailedPrecondition, "version mismatch. Client %v Server %v", clientVersion, m.turboVersion)
+		return nil, err
+	}
+	if _, err := m.serverFor(ctx); err != nil {
+		return nil, err
+	}
+	return &turbodprotocol.HelloResponse{}, nil
+}
+
+// Shutdown implements the Shutdown rpc from turbo.proto
+// This stops serving the requesting repository. Once no repositories are being
+// served, the daemon itself shuts down.
+func (m *Multiplexer) Shutdown(ctx context.Context, req *turbodprotocol.ShutdownRequest) (*turbodprotocol.ShutdownResponse, error) {
+	repoRoot, err := repoRootFor(ctx)
+	if err != nil {
+		return nil, err
+	}
+	if m.removeServer(repoRoot, nil) {
+		return &turbodprotocol.ShutdownResponse{}, nil
+	}
+	if m.tryClose() {
+		return &turbodprotocol.ShutdownResponse{}, nil
+	}
+	err = status.Error(codes.NotFound, "shutdown mechanism not found")
+	return nil, err
+}
+
+// Status implements the Status rpc from turbo.proto
+func (m *Multiplexer) Status(ctx context.Context, req *turbodprotocol.StatusRequest) (*turbodprotocol.StatusResponse, error) {
+	server, err := m.serverFor(ctx)
+	if err != nil {
+		return nil, err
+	}
+	resp, err := server.Status(ctx, req)
+	if err != nil {
+		return nil, err
+	}
+	resp.DaemonStatus.UptimeMsec = uint64(time.Since(m.started).Milliseconds())
+	resp.DaemonStatus.RepoRoots = m.repoRoots()
+	return resp, nil
+}
+
+// NotifyOutputsWritten implements the NotifyOutputsWritten rpc from turbo.proto
+func (m *Multiplexer) NotifyOutputsWritten(ctx context.Context, req *turbodprotocol.NotifyOutputsWrittenRequest) (*turbodprotocol.NotifyOutputsWrittenResponse, error) {
+	server, err := m.serverFor(ctx)
+	if err != nil {
+		return nil, err
+	}
+	return server.NotifyOutputsWritten(ctx, req)
+}
+
+// GetChangedOutputs implements the GetChangedOutputs rpc from turbo.proto
+func (m *Multiplexer) GetChangedOutputs(ctx context.Context, req *turbodprotocol.GetChangedOutputsRequest) (*turbodprotocol.GetChangedOutputsResponse, error) {
+	server, err := m.serverFor(ctx)
+	if err != nil {
+		return nil, err
+	}
+	return server.GetChangedOutputs(ctx, req)
+}
+
+// GetFileHashes implements the GetFileHashes rpc from turbo.proto
+func (m *Multiplexer) GetFileHashes(ctx context.Context, req *turbodprotocol.GetFileHashesRequest) (*turbodprotocol.GetFileHashesResponse, error) {
+	server, err := m.serverFor(ctx)
+	if err != nil {
+		return nil, err
+	}
+	return server.GetFileHashes(ctx, req)
+}
+
+// QueryFiles implements the QueryFiles rpc from turbo.proto
+func (m *Multiplexer) QueryFiles(ctx context.Context, req *turbodprotocol.QueryFilesRequest) (*turbodprotocol.QueryFilesResponse, error) {
+	server, err := m.serverFor(ctx)
+	if err != nil {
+		return nil, err
+	}
+	return server.QueryFiles(ctx, req)
+}
@@ -0,0 +1,149 @@
+package server
+
+import (
+	"context"
+	"sort"
+	"testing"
+	"time"
+
+	"github.com/hashicorp/go-hclog"
+	"google.golang.org/grpc/metadata"
+	"gotest.tools/v3/assert"
+
+	"github.com/vercel/turborepo/cli/internal/daemon/connector"
+	turbofs "github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/turbodprotocol"
+)
+
+func repoContext(repoRoot turbofs.AbsolutePath) context.Context {
+	md := metadata.Pairs(connector.RepoRootMetadataKey, repoRoot.ToString())
+	return metadata.NewIncomingContext(context.Background(), md)
+}
+
+func TestMultiplexerServesMultipleRepos(t *testing.T) {
+	logger := hclog.Default()
+	repoA := turbofs.AbsolutePathFromUpstream(t.TempDir())
+	repoB := turbofs.AbsolutePathFromUpstream(t.TempDir())
+
+	m := NewMultiplexer(logger, "some-version", "/log/file/path")
+	defer func() { _ = m.Close() }()
+	m.Register(&mockGrpc{stopped: make(chan struct{})})
+
+	_, err := m.Hello(repoContext(repoA), &turbodprotocol.HelloRequest{Version: "some-version"})
+	assert.NilError(t, err, "Hello")
+	_, err = m.Hello(repoContext(repoB), &turbodprotocol.HelloRequest{Version: "some-version"})
+	assert.NilError(t, err, "Hello")
+
+	_, err = m.Hello(repoContext(repoA), &turbodprotocol.HelloRequest{Version: "other-version"})
+	assert.ErrorContains(t, err, "version mismatch")
+
+	resp, err := m.Status(repoContext(repoA), &turbodprotocol.StatusRequest{})
+	assert.NilError(t, err, "Status")
+	roots := resp.DaemonStatus.RepoRoots
+	sort.Strings(roots)
+	expected := []string{repoA.ToString(), repoB.ToString()}
+	sort.Strings(expected)
+	assert.DeepEqual(t, roots, expected)
+
+	// Each repository has its own watcher
+	m.mu.Lock()
+	serverA := m.servers[repoA]
+	serverB := m.servers[repoB]
+	m.mu.Unlock()
+	assert.Assert(t, serverA.server.watcher != serverB.server.watcher)
+
+	_, err = m.Status(context.Background(), &turbodprotocol.StatusRequest{})
+	assert.ErrorContains(t, err, "missing metadata")
+}
+
+func TestMultiplexerDeleteRepoRoot(t *testing.T) {
+	logger := hclog.Default()
+	repoA := turbofs.AbsolutePathFromUpstream(t.TempDir())
+	repoB := turbofs.AbsolutePathFromUpstream(t.TempDir())
+
+	grpcServer := &mockGrpc{stopped: make(chan struct{})}
+	m := NewMultiplexer(logger, "some-version", "/log/file/path")
+	defer func() { _ = m.Close() }()
+	m.Register(grpcServer)
+
+	_, err := m.Hello(repoContext(repoA), &turbodprotocol.HelloRequest{Version: "some-version"})
+	assert.NilError(t, err, "Hello")
+	_, err = m.Hello(repoContext(repoB), &turbodprotocol.HelloRequest{Version: "some-version"})
+	assert.NilError(t, err, "Hello")
+
+	// Deleting one repository stops serving it, but not the daemon
+	err = repoA.Remove()
+	assert.NilError(t, err, "Remove")
+
+	deadline := time.After(2 * time.Second)
+	for {
+		if len(m.repoRoots()) == 1 {
+			break
+		}
+		select {
+		case <-deadline:
+			t.Fatal("timed out waiting for deleted repository to be removed")
+		case <-time.After(10 * time.Millisecond):
+		}
+	}
+	assert.DeepEqual(t, m.repoRoots(), []string{repoB.ToString()})
+	select {
+	case <-grpcServer.stopped:
+		t.Error("expected the daemon to keep serving other repositories")
+	default:
+	}
+}
+
+func TestMultiplexerShutdown(t *testing.T) {
+	logger := hclog.Default()
+	repoA := turbofs.AbsolutePathFromUpstream(t.TempDir())
+	repoB := turbofs.AbsolutePathFromUpstream(t.TempDir())
+
+	grpcServer := &mockGrpc{stopped: make(chan struct{})}
+	m := NewMultiplexer(logger, "some-version", "/log/file/path")
+	defer func() { _ = m.Close() }()
+	m.Register(grpcServer)
+
+	_, err := m.Hello(repoContext(repoA), &turbodprotocol.HelloRequest{Version: "some-version"})
+	assert.NilError(t, err, "Hello")
+	_, err = m.Hello(repoContext(repoB), &turbodprotocol.HelloRequest{Version: "some-version"})
+	assert.NilError(t, err, "Hello")
+
+	// Shutting down from one repository only stops serving that repository
+	_, err = m.Shutdown(repoContext(repoA), &turbodprotocol.ShutdownRequest{})
+	assert.NilError(t, err, "Shutdown")
+	assert.DeepEqual(t, m.repoRoots(), []string{repoB.ToString()})
+	select {
+	case <-grpcServer.stopped:
+		t.Error("expected the daemon to keep serving other repositories")
+	default:
+	}
+
+	// The daemon stops once the last repository is shut down
+	_, err = m.Shutdown(repoContext(repoB), &turbodprotocol.ShutdownRequest{})
+	assert.NilError(t, err, "Shutdown")
+	select {
+	case <-grpcServer.stopped:
+	case <-time.After(2 * time.Second):
+		t.Error("timed out waiting for the daemon to stop")
+	}
+}
+
+func TestMultiplexerEvictsIdleRepos(t *testing.T) {
+	logger := hclog.Default()
+	repoA := turbofs.AbsolutePathFromUpstream(t.TempDir())
+	repoB := turbofs.AbsolutePathFromUpstream(t.TempDir())
+
+	m := NewMultiplexer(logger, "some-version", "/log/file/path")
+	defer func() { _ = m.Close() }()
+
+	_, err := m.Hello(repoContext(repoA), &turbodprotocol.HelloRequest{Version: "some-version"})
+	assert.NilError(t, err, "Hello")
+	time.Sleep(10 * time.Millisecond)
+	cutoff := time.Now()
+	_, err = m.Hello(repoContext(repoB), &turbodprotocol.HelloRequest{Version: "some-version"})
+	assert.NilError(t, err, "Hello")
+
+	m.evictServersIdleSince(cutoff)
+	assert.DeepEqual(t, m.repoRoots(), []string{repoB.ToString()})
+}
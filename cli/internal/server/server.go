@@ -2,11 +2,16 @@ package server
 
 import (
 	"context"
+	"fmt"
+	"reflect"
+	"runtime"
 	"sync"
 	"time"
 
 	"github.com/hashicorp/go-hclog"
 	"github.com/pkg/errors"
+	"github.com/vercel/turborepo/cli/internal/filehashcache"
+	"github.com/vercel/turborepo/cli/internal/filequery"
 	"github.com/vercel/turborepo/cli/internal/filewatcher"
 	"github.com/vercel/turborepo/cli/internal/fs"
 	"github.com/vercel/turborepo/cli/internal/globwatcher"
@@ -25,13 +30,34 @@ import (
 type Server struct {
 	turbodprotocol.UnimplementedTurbodServer
 	watcher      *filewatcher.FileWatcher
+	cookieJar    *filewatcher.CookieJar
 	globWatcher  *globwatcher.GlobWatcher
+	hashCache    *filehashcache.FileHashCache
+	stopSaving   context.CancelFunc
+	fileQuery    *filequery.FileQuery
 	turboVersion string
 	started      time.Time
 	logFilePath  fs.AbsolutePath
 	repoRoot     fs.AbsolutePath
-	closerMu     sync.Mutex
-	closer       *closer
+	// configIgnores are the watchOptions.ignore entries from turbo.json when the server started
+	configIgnores []string
+	closerMu      sync.Mutex
+	closer        *closer
+
+	errorsMu     sync.Mutex
+	recentErrors []recentError
+}
+
+// _hashCacheSaveInterval is how often file hashes are persisted while the server runs,
+// so that they survive the daemon being killed rather than shut down
+const _hashCacheSaveInterval = 1 * time.Minute
+
+// _maxRecentErrors is the number of file watching errors retained for status reporting
+const _maxRecentErrors = 10
+
+type recentError struct {
+	message string
+	at      time.Time
 }
 
 // GRPCServer is the interface that the turbo server needs to the underlying
@@ -42,9 +68,12 @@ type GRPCServer interface {
 	GracefulStop()
 }
 
+// closer wraps the action taken when a server needs to stop serving. For a
+// standalone server, this is stopping the GRPC server. For a server owned by
+// a Multiplexer, it is removing just that repository.
 type closer struct {
-	grpcServer GRPCServer
-	once       sync.Once
+	stop func()
+	once sync.Once
 }
 
 func (c *closer) close() {
@@ -54,15 +83,19 @@ func (c *closer) close() {
 	// and avoid deadlocking.
 	c.once.Do(func() {
 		go func() {
-			c.grpcServer.GracefulStop()
+			c.stop()
 		}()
 	})
 }
 
 var _defaultCookieTimeout = 500 * time.Millisecond
 
-// New returns a new instance of Server
-func New(serverName string, logger hclog.Logger, repoRoot fs.AbsolutePath, turboVersion string, logFilePath fs.AbsolutePath) (*Server, error) {
+// New returns a new instance of Server. watchIgnores are repo-relative directories,
+// or globs matching directories, that are excluded from file watching, in addition
+// to watchOptions.ignore in the repository's turbo.json.
+func New(serverName string, logger hclog.Logger, repoRoot fs.AbsolutePath, turboVersion string, logFilePath fs.AbsolutePath, watchIgnores ...string) (*Server, error) {
+	configIgnores := readWatchIgnores(logger, repoRoot)
+	watchIgnores = append(append([]string{}, watchIgnores...), configIgnores...)
 	cookieDir := fs.GetTurboDataDir().Join("cookies", serverName)
 	cookieJar, err := filewatcher.NewCookieJar(cookieDir, _defaultCookieTimeout)
 	if err != nil {
@@ -72,18 +105,27 @@ func New(serverName string, logger hclog.Logger, repoRoot fs.AbsolutePath, turbo
 	if err != nil {
 		return nil, err
 	}
-	fileWatcher := filewatcher.New(logger.Named("FileWatcher"), repoRoot, watcher)
+	fileWatcher := filewatcher.New(logger.Named("FileWatcher"), repoRoot, watcher, watchIgnores...)
 	globWatcher := globwatcher.New(logger.Named("GlobWatcher"), repoRoot, cookieJar)
+	hashCachePath := fs.GetTurboDataDir().Join("filehashes", serverName+".json")
+	hashCache := filehashcache.New(logger.Named("FileHashCache"), repoRoot, hashCachePath)
+	fileQuery := filequery.New(logger.Named("FileQuery"), repoRoot, cookieJar)
 	server := &Server{
-		watcher:      fileWatcher,
-		globWatcher:  globWatcher,
-		turboVersion: turboVersion,
-		started:      time.Now(),
-		logFilePath:  logFilePath,
-		repoRoot:     repoRoot,
+		watcher:       fileWatcher,
+		cookieJar:     cookieJar,
+		globWatcher:   globWatcher,
+		hashCache:     hashCache,
+		fileQuery:     fileQuery,
+		turboVersion:  turboVersion,
+		started:       time.Now(),
+		logFilePath:   logFilePath,
+		repoRoot:      repoRoot,
+		configIgnores: configIgnores,
 	}
 	server.watcher.AddClient(cookieJar)
 	server.watcher.AddClient(globWatcher)
+	server.watcher.AddClient(hashCache)
+	server.watcher.AddClient(fileQuery)
 	server.watcher.AddClient(server)
 	if err := server.watcher.Start(); err != nil {
 		return nil, errors.Wrapf(err, "watching %v", repoRoot)
@@ -92,9 +134,27 @@ func New(serverName string, logger hclog.Logger, repoRoot fs.AbsolutePath, turbo
 		_ = server.watcher.Close()
 		return nil, errors.Wrapf(err, "failed to watch cookie directory: %v", cookieDir)
 	}
+	saveCtx, stopSaving := context.WithCancel(context.Background())
+	server.stopSaving = stopSaving
+	go server.saveHashCache(saveCtx, logger)
 	return server, nil
 }
 
+func (s *Server) saveHashCache(ctx context.Context, logger hclog.Logger) {
+	ticker := time.NewTicker(_hashCacheSaveInterval)
+	defer ticker.Stop()
+	for {
+		select {
+		case <-ctx.Done():
+			return
+		case <-ticker.C:
+			if err := s.hashCache.Save(); err != nil {
+				logger.Warn("failed to save file hashes", "error", err)
+			}
+		}
+	}
+}
+
 func (s *Server) tryClose() bool {
 	s.closerMu.Lock()
 	defer s.closerMu.Unlock()
@@ -105,33 +165,76 @@ func (s *Server) tryClose() bool {
 	return false
 }
 
+// readWatchIgnores returns the watchOptions.ignore entries from the repository's turbo.json.
+// A missing or invalid turbo.json isn't fatal here; a run will report it.
+func readWatchIgnores(logger hclog.Logger, repoRoot fs.AbsolutePath) []string {
+	turboJSONPath := repoRoot.Join("turbo.json")
+	if !turboJSONPath.FileExists() {
+		return nil
+	}
+	turboJSON, err := fs.ReadTurboJSON(turboJSONPath)
+	if err != nil {
+		logger.Warn(fmt.Sprintf("failed to read watch options from %v: %v", turboJSONPath, err))
+		return nil
+	}
+	return turboJSON.WatchOptions.Ignore
+}
+
 // OnFileWatchEvent implements filewatcher.FileWatchClient.OnFileWatchEvent
 // In the event that the root of the monorepo is deleted, shut down the server.
+// If watchOptions.ignore in turbo.json changes, the server is also shut down, so
+// that the next request for this repository starts watching it with the new ignores.
 func (s *Server) OnFileWatchEvent(ev filewatcher.Event) {
 	if ev.EventType == filewatcher.FileDeleted && ev.Path == s.repoRoot {
 		_ = s.tryClose()
+	} else if ev.Path == s.repoRoot.Join("turbo.json") {
+		if !reflect.DeepEqual(readWatchIgnores(hclog.NewNullLogger(), s.repoRoot), s.configIgnores) {
+			_ = s.tryClose()
+		}
 	}
 }
 
 // OnFileWatchError implements filewatcher.FileWatchClient.OnFileWatchError
-func (s *Server) OnFileWatchError(err error) {}
+// Errors are retained so that they can be reported via Status.
+func (s *Server) OnFileWatchError(err error) {
+	s.errorsMu.Lock()
+	defer s.errorsMu.Unlock()
+	s.recentErrors = append(s.recentErrors, recentError{
+		message: err.Error(),
+		at:      time.Now(),
+	})
+	if len(s.recentErrors) > _maxRecentErrors {
+		s.recentErrors = s.recentErrors[len(s.recentErrors)-_maxRecentErrors:]
+	}
+}
 
 // OnFileWatchClosed implements filewatcher.FileWatchClient.OnFileWatchClosed
 func (s *Server) OnFileWatchClosed() {}
 
 // Close is used for shutting down this copy of the server
 func (s *Server) Close() error {
-	return s.watcher.Close()
+	s.stopSaving()
+	watcherErr := s.watcher.Close()
+	// Persist file hashes so that the next daemon for this repository
+	// doesn't need to start from scratch.
+	if err := s.hashCache.Save(); err != nil {
+		return err
+	}
+	return watcherErr
 }
 
 // Register registers this server to respond to GRPC requests
 func (s *Server) Register(grpcServer GRPCServer) {
+	s.setCloser(grpcServer.GracefulStop)
+	turbodprotocol.RegisterTurbodServer(grpcServer, s)
+}
+
+func (s *Server) setCloser(stop func()) {
 	s.closerMu.Lock()
 	s.closer = &closer{
-		grpcServer: grpcServer,
+		stop: stop,
 	}
 	s.closerMu.Unlock()
-	turbodprotocol.RegisterTurbodServer(grpcServer, s)
 }
 
 // NotifyOutputsWritten implements the NotifyOutputsWritten rpc from turbo.proto
@@ -154,6 +257,37 @@ func (s *Server) GetChangedOutputs(ctx context.Context, req *turbodprotocol.GetC
 	}, nil
 }
 
+// GetFileHashes implements the GetFileHashes rpc from turbo.proto
+func (s *Server) GetFileHashes(ctx context.Context, req *turbodprotocol.GetFileHashesRequest) (*turbodprotocol.GetFileHashesResponse, error) {
+	hashes, err := s.hashCache.GetFileHashes(req.Files)
+	if err != nil {
+		return nil, status.Error(codes.InvalidArgument, err.Error())
+	}
+	return &turbodprotocol.GetFileHashesResponse{
+		FileHashes: hashes,
+	}, nil
+}
+
+// QueryFiles implements the QueryFiles rpc from turbo.proto
+func (s *Server) QueryFiles(ctx context.Context, req *turbodprotocol.QueryFilesRequest) (*turbodprotocol.QueryFilesResponse, error) {
+	result, err := s.fileQuery.Query(req.Root, req.Globs, req.Since)
+	if err != nil {
+		return nil, err
+	}
+	files := make([]*turbodprotocol.QueryFile, len(result.Files))
+	for i, file := range result.Files {
+		files[i] = &turbodprotocol.QueryFile{
+			Name:   file.Name,
+			Exists: file.Exists,
+		}
+	}
+	return &turbodprotocol.QueryFilesResponse{
+		Clock:           result.Clock,
+		IsFreshInstance: result.IsFreshInstance,
+		Files:           files,
+	}, nil
+}
+
 // Hello implements the Hello rpc from turbo.proto
 func (s *Server) Hello(ctx context.Context, req *turbodprotocol.HelloRequest) (*turbodprotocol.HelloResponse, error) {
 	clientVersion := req.Version
@@ -176,10 +310,28 @@ func (s *Server) Shutdown(ctx context.Context, req *turbodprotocol.ShutdownReque
 // Status implements the Status rpc from turbo.proto
 func (s *Server) Status(ctx context.Context, req *turbodprotocol.StatusRequest) (*turbodprotocol.StatusResponse, error) {
 	uptime := uint64(time.Since(s.started).Milliseconds())
+	watchStats := s.watcher.Stats()
+	var memStats runtime.MemStats
+	runtime.ReadMemStats(&memStats)
+	s.errorsMu.Lock()
+	recentErrors := make([]*turbodprotocol.DaemonError, len(s.recentErrors))
+	for i, recent := range s.recentErrors {
+		recentErrors[i] = &turbodprotocol.DaemonError{
+			Message:       recent.message,
+			TimestampMsec: recent.at.UnixMilli(),
+		}
+	}
+	s.errorsMu.Unlock()
 	return &turbodprotocol.StatusResponse{
 		DaemonStatus: &turbodprotocol.DaemonStatus{
-			LogFile:    s.logFilePath.ToString(),
-			UptimeMsec: uptime,
+			LogFile:           s.logFilePath.ToString(),
+			UptimeMsec:        uptime,
+			WatchBacklog:      uint64(watchStats.Backlog),
+			WatchedRoots:      uint32(watchStats.WatchedRoots),
+			HeapAllocBytes:    memStats.HeapAlloc,
+			SysMemoryBytes:    memStats.Sys,
+			CookieLatencyUsec: uint64(s.cookieJar.LastLatency().Microseconds()),
+			RecentErrors:      recentErrors,
 		},
 	}, nil
 }
@@ -2,6 +2,7 @@ package server
 
 import (
 	"context"
+	"fmt"
 	"testing"
 	"time"
 
@@ -71,3 +72,28 @@ func TestShutdown(t *testing.T) {
 		t.Error("timed out waiting for graceful stop to be called")
 	}
 }
+
+func TestStatusReportsRecentErrors(t *testing.T) {
+	logger := hclog.Default()
+	repoRootRaw := t.TempDir()
+	repoRoot := turbofs.AbsolutePathFromUpstream(repoRootRaw)
+
+	s, err := New("testServer", logger, repoRoot, "some-version", "/log/file/path")
+	assert.NilError(t, err, "New")
+	defer func() { _ = s.Close() }()
+
+	for i := 0; i < _maxRecentErrors+2; i++ {
+		s.OnFileWatchError(fmt.Errorf("error %v", i))
+	}
+
+	ctx := context.Background()
+	resp, err := s.Status(ctx, &turbodprotocol.StatusRequest{})
+	assert.NilError(t, err, "Status")
+	status := resp.DaemonStatus
+	// the repo root plus the cookie directory
+	assert.Equal(t, status.WatchedRoots, uint32(2))
+	assert.Assert(t, status.SysMemoryBytes > 0)
+	assert.Equal(t, len(status.RecentErrors), _maxRecentErrors)
+	assert.Equal(t, status.RecentErrors[0].Message, "error 2")
+	assert.Equal(t, status.RecentErrors[_maxRecentErrors-1].Message, fmt.Sprintf("error %v", _maxRecentErrors+1))
+}
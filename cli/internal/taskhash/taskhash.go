@@ -14,6 +14,7 @@ import (
 	gitignore "github.com/sabhiram/go-gitignore"
 	"github.com/vercel/turborepo/cli/internal/doublestar"
 	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/globby"
 	"github.com/vercel/turborepo/cli/internal/nodes"
 	"github.com/vercel/turborepo/cli/internal/turbopath"
 	"github.com/vercel/turborepo/cli/internal/util"
@@ -33,6 +34,12 @@ type Tracker struct {
 	mu                  sync.RWMutex
 	packageInputsHashes packageFileHashes
 	packageTaskHashes   map[string]string // taskID -> hash
+	packageTaskInputs   map[string]*TaskHashInputs
+	// packageOutputs are the outputs of every task the pipeline defines, keyed by package
+	packageOutputs map[string][]string
+	// contentHashes are shared by every package-inputs hash, so that a file that is an
+	// input to many tasks is only hashed once
+	contentHashes *fs.ContentHashes
 }
 
 // NewTracker creates a tracker for package-inputs combinations and package-task combinations.
@@ -43,20 +50,40 @@ func NewTracker(rootNode string, globalHash string, pipeline fs.Pipeline, packag
 		pipeline:          pipeline,
 		packageInfos:      packageInfos,
 		packageTaskHashes: make(map[string]string),
+		packageTaskInputs: make(map[string]*TaskHashInputs),
+		contentHashes:     fs.NewContentHashes(),
 	}
 }
 
-// packageFileSpec defines a combination of a package and optional set of input globs
+// UseFileHashSource looks up the hashes of changed files in source, such as the daemon's
+// file hash cache, before reading and hashing them
+func (th *Tracker) UseFileHashSource(repoRoot fs.AbsolutePath, source fs.FileHashSource) {
+	th.contentHashes.UseSource(turbopath.AbsoluteSystemPathFromUpstream(repoRoot.ToString()), source)
+}
+
+// packageFileSpec defines a combination of a package, an optional set of input globs,
+// and the transforms applied to matching inputs before they are hashed
 type packageFileSpec struct {
-	pkg    string
-	inputs []string
+	pkg        string
+	inputs     []string
+	transforms map[string]fs.InputTransform
+	// excludedOutputs are package-relative output globs whose files aren't inputs
+	excludedOutputs []string
 }
 
-func specFromPackageTask(pt *nodes.PackageTask) packageFileSpec {
-	return packageFileSpec{
-		pkg:    pt.PackageName,
-		inputs: pt.TaskDefinition.Inputs,
+// fileSpec returns the files that make up a task's inputs. Tasks without their own inputs
+// hash every file in the package, except for the outputs of tasks in that package, so
+// that running a task doesn't change its own hash or that of its siblings.
+func (th *Tracker) fileSpec(pkg string, taskDefinition *fs.TaskDefinition) packageFileSpec {
+	spec := packageFileSpec{
+		pkg:        pkg,
+		inputs:     taskDefinition.Inputs,
+		transforms: taskDefinition.InputsTransform,
+	}
+	if len(taskDefinition.Inputs) == 0 && taskDefinition.ExcludeOutputsFromInputs {
+		spec.excludedOutputs = th.packageOutputs[pkg]
 	}
+	return spec
 }
 
 // packageFileHashKey is a hashable representation of a packageFileSpec.
@@ -64,7 +91,14 @@ type packageFileHashKey string
 
 func (pfs packageFileSpec) ToKey() packageFileHashKey {
 	sort.Strings(pfs.inputs)
-	return packageFileHashKey(fmt.Sprintf("%v#%v", pfs.pkg, strings.Join(pfs.inputs, "!")))
+	key := fmt.Sprintf("%v#%v", pfs.pkg, strings.Join(pfs.inputs, "!"))
+	if len(pfs.transforms) > 0 {
+		key = fmt.Sprintf("%v#%v", key, transformsKey(pfs.transforms))
+	}
+	if len(pfs.excludedOutputs) > 0 {
+		key = fmt.Sprintf("%v#exclude:%v", key, strings.Join(pfs.excludedOutputs, "!"))
+	}
+	return packageFileHashKey(key)
 }
 
 func safeCompileIgnoreFile(filepath string) (*gitignore.GitIgnore, error) {
@@ -75,18 +109,63 @@ func safeCompileIgnoreFile(filepath string) (*gitignore.GitIgnore, error) {
 	return gitignore.CompileIgnoreLines([]string{}...), nil
 }
 
-func (pfs *packageFileSpec) hash(pkg *fs.PackageJSON, repoRoot fs.AbsolutePath) (string, error) {
+// usesTreeHash returns true if the files that make up this spec can be the tracked files
+// in the package, so that a clean package can be hashed by its git tree object ID.
+// Inputs and input transforms require hashing file by file. Excluded outputs only do if
+// a tracked file matches them, which hash checks.
+func (pfs *packageFileSpec) usesTreeHash() bool {
+	return len(pfs.inputs) == 0 && len(pfs.transforms) == 0
+}
+
+// tracksExcludedOutputs returns true if git tracks a file in the package that matches one
+// of the excluded outputs, in which case the package's tree covers a file that isn't an input.
+func (pfs *packageFileSpec) tracksExcludedOutputs(pkg *fs.PackageJSON, repoRoot fs.AbsolutePath) (bool, error) {
+	if len(pfs.excludedOutputs) == 0 {
+		return false, nil
+	}
+	files, err := fs.GetPackageTrackedFiles(repoRoot, pkg.Dir)
+	if err != nil {
+		return false, err
+	}
+	matched, err := matchOutputs(files, pfs.excludedOutputs)
+	if err != nil {
+		return false, err
+	}
+	return len(matched) > 0, nil
+}
+
+func (pfs *packageFileSpec) hash(pkg *fs.PackageJSON, repoRoot fs.AbsolutePath, contentHashes *fs.ContentHashes) (string, error) {
+	// When every tracked file is an input and nothing in the package has changed since HEAD,
+	// git has already hashed the package for us: its tree object ID covers every file in it.
+	// This is a different hash than the file by file one below, so the same files in a clean
+	// and a modified checkout never share a cache key.
+	if pfs.usesTreeHash() {
+		treeID, clean, err := fs.GetPackageTreeHash(repoRoot, pkg.Dir)
+		if err == nil && clean {
+			tracksOutputs, err := pfs.tracksExcludedOutputs(pkg, repoRoot)
+			if err == nil && !tracksOutputs {
+				return fs.HashObject(fmt.Sprintf("tree:%v", treeID))
+			}
+		}
+	}
 	hashObject, pkgDepsErr := fs.GetPackageDeps(repoRoot, &fs.PackageDepsOptions{
 		PackagePath:   pkg.Dir,
 		InputPatterns: pfs.inputs,
+		ContentHashes: contentHashes,
 	})
 	if pkgDepsErr != nil {
-		manualHashObject, err := manuallyHashPackage(pkg, pfs.inputs, repoRoot)
+		manualHashObject, err := manuallyHashPackage(pkg, pfs.inputs, repoRoot, contentHashes)
 		if err != nil {
 			return "", err
 		}
 		hashObject = manualHashObject
 	}
+	if err := excludeOutputs(hashObject, pfs.excludedOutputs); err != nil {
+		return "", err
+	}
+	if err := applyInputTransforms(hashObject, pfs.transforms, repoRoot.Join(pkg.Dir)); err != nil {
+		return "", err
+	}
 	hashOfFiles, otherErr := fs.HashObject(hashObject)
 	if otherErr != nil {
 		return "", otherErr
@@ -94,7 +173,7 @@ func (pfs *packageFileSpec) hash(pkg *fs.PackageJSON, repoRoot fs.AbsolutePath)
 	return hashOfFiles, nil
 }
 
-func manuallyHashPackage(pkg *fs.PackageJSON, inputs []string, rootPath fs.AbsolutePath) (map[turbopath.AnchoredUnixPath]string, error) {
+func manuallyHashPackage(pkg *fs.PackageJSON, inputs []string, rootPath fs.AbsolutePath, contentHashes *fs.ContentHashes) (map[turbopath.AnchoredUnixPath]string, error) {
 	hashObject := make(map[turbopath.AnchoredUnixPath]string)
 	// Instead of implementing all gitignore properly, we hack it. We only respect .gitignore in the root and in
 	// the directory of a package.
@@ -130,7 +209,7 @@ func manuallyHashPackage(pkg *fs.PackageJSON, inputs []string, rootPath fs.Absol
 						return nil
 					}
 				}
-				hash, err := fs.GitLikeHashFile(convertedName.ToString())
+				hash, err := contentHashes.HashFile(convertedName)
 				if err != nil {
 					return fmt.Errorf("could not hash file %v. \n%w", convertedName.ToString(), err)
 				}
@@ -147,14 +226,82 @@ func manuallyHashPackage(pkg *fs.PackageJSON, inputs []string, rootPath fs.Absol
 	return hashObject, nil
 }
 
+// excludeOutputs removes the files matching any of the given package-relative output globs
+// from a map of package-relative file paths to file hashes. Globs are anchored at the
+// package, so "dist/**" doesn't match "src/dist/index.ts".
+func excludeOutputs(hashObject map[turbopath.AnchoredUnixPath]string, outputs []string) error {
+	files := make([]turbopath.AnchoredUnixPath, 0, len(hashObject))
+	for path := range hashObject {
+		files = append(files, path)
+	}
+	matched, err := matchOutputs(files, outputs)
+	if err != nil {
+		return err
+	}
+	for _, path := range matched {
+		delete(hashObject, path.ToUnixPath())
+	}
+	return nil
+}
+
+// matchOutputs returns the files that match the given output globs
+func matchOutputs(files []turbopath.AnchoredUnixPath, outputs []string) ([]turbopath.AnchoredSystemPath, error) {
+	var globs []string
+	for _, output := range outputs {
+		// Negated outputs, like !.next/cache/**, aren't cached, but what they match is
+		// still written by the build, so it stays excluded
+		if !strings.HasPrefix(output, "!") {
+			globs = append(globs, output)
+		}
+	}
+	systemFiles := make([]turbopath.AnchoredSystemPath, 0, len(files))
+	for _, path := range files {
+		systemFiles = append(systemFiles, path.ToSystemPath())
+	}
+	matched, _, err := globby.MatchFiles(systemFiles, globs, nil)
+	if err != nil {
+		return nil, fmt.Errorf("invalid outputs: %w", err)
+	}
+	return matched, nil
+}
+
 // packageFileHashes is a map from a package and optional input globs to the hash of
 // the matched files in the package.
 type packageFileHashes map[packageFileHashKey]string
 
+// outputsByPackage returns the outputs, including the log, of every task that the pipeline
+// defines for a script in each package. Tasks that aren't part of this run are included,
+// so that a task's hash doesn't depend on which other tasks were run along with it.
+func (th *Tracker) outputsByPackage() map[string][]string {
+	outputsByPackage := make(map[string][]string)
+	for key, pkg := range th.packageInfos {
+		pkgName, ok := key.(string)
+		if !ok || pkgName == th.rootNode {
+			continue
+		}
+		outputs := make(util.Set)
+		for script := range pkg.Scripts {
+			taskDefinition, ok := th.pipeline.GetTaskDefinition(util.GetTaskId(pkgName, script))
+			if !ok {
+				continue
+			}
+			outputs.Add(fmt.Sprintf(".turbo/turbo-%v.log", script))
+			for _, output := range taskDefinition.Outputs {
+				outputs.Add(output)
+			}
+		}
+		if outputs.Len() > 0 {
+			outputsByPackage[pkgName] = outputs.UnsafeListOfStrings()
+			sort.Strings(outputsByPackage[pkgName])
+		}
+	}
+	return outputsByPackage
+}
+
 // CalculateFileHashes hashes each unique package-inputs combination that is present
 // in the task graph. Must be called before calculating task hashes.
 func (th *Tracker) CalculateFileHashes(allTasks []dag.Vertex, workerCount int, repoRoot fs.AbsolutePath) error {
-	hashTasks := make(util.Set)
+	taskDefinitions := make(map[string]fs.TaskDefinition)
 	for _, v := range allTasks {
 		taskID, ok := v.(string)
 		if !ok {
@@ -171,10 +318,27 @@ func (th *Tracker) CalculateFileHashes(allTasks []dag.Vertex, workerCount int, r
 		if !ok {
 			return fmt.Errorf("missing pipeline entry %v", taskID)
 		}
-		hashTasks.Add(&packageFileSpec{
-			pkg:    pkgName,
-			inputs: taskDefinition.Inputs,
-		})
+		taskDefinitions[taskID] = taskDefinition
+	}
+	th.packageOutputs = th.outputsByPackage()
+
+	// Tasks that share a package and inputs share a file hash, so each combination is only hashed once
+	hashTasks := make(map[packageFileHashKey]*packageFileSpec)
+	for taskID, taskDefinition := range taskDefinitions {
+		pkgName, _ := util.GetPackageTaskFromId(taskID)
+		taskDefinition := taskDefinition
+		spec := th.fileSpec(pkgName, &taskDefinition)
+		hashTasks[spec.ToKey()] = &spec
+		for otherPkg, inputs := range taskDefinition.PackageInputs {
+			if _, ok := th.packageInfos[otherPkg]; !ok {
+				return fmt.Errorf("%v has inputs in package %v, which doesn't exist", taskID, otherPkg)
+			}
+			spec := &packageFileSpec{
+				pkg:    otherPkg,
+				inputs: inputs,
+			}
+			hashTasks[spec.ToKey()] = spec
+		}
 	}
 
 	hashes := make(map[packageFileHashKey]string)
@@ -187,7 +351,7 @@ func (th *Tracker) CalculateFileHashes(allTasks []dag.Vertex, workerCount int, r
 				if !ok {
 					return fmt.Errorf("cannot find package %v", ht.pkg)
 				}
-				hash, err := ht.hash(pkg, repoRoot)
+				hash, err := ht.hash(pkg, repoRoot, th.contentHashes)
 				if err != nil {
 					return err
 				}
@@ -198,8 +362,8 @@ func (th *Tracker) CalculateFileHashes(allTasks []dag.Vertex, workerCount int, r
 			return nil
 		})
 	}
-	for ht := range hashTasks {
-		hashQueue <- ht.(*packageFileSpec)
+	for _, ht := range hashTasks {
+		hashQueue <- ht
 	}
 	close(hashQueue)
 	err := hashErrs.Wait()
@@ -219,6 +383,25 @@ type taskHashInputs struct {
 	hashableEnvPairs     []string
 	globalHash           string
 	taskDependencyHashes []string
+	variant              string
+}
+
+// TaskHashInputs lists everything that contributed to a task's hash, so that users can
+// see why it changed. Environment variable values are hashed rather than shown, since
+// they may be secrets.
+type TaskHashInputs struct {
+	// Hash of the task's input files, including any inputs in other packages
+	FilesHash string `json:"filesHash"`
+	// Hash of the resolved versions of the package's external dependencies
+	ExternalDepsHash string            `json:"externalDepsHash"`
+	Outputs          []string          `json:"outputs"`
+	PassThroughArgs  []string          `json:"passThroughArgs"`
+	EnvVars          map[string]string `json:"envVars"`
+	GlobalHash       string            `json:"globalHash"`
+	// Hashes of the tasks that this task depends on
+	DependencyHashes []string `json:"dependencyHashes"`
+	// Variant is the condition of the task definition variant that applied, if any
+	Variant string `json:"variant,omitempty"`
 }
 
 func (th *Tracker) calculateDependencyHashes(dependencySet dag.Set) ([]string, error) {
@@ -249,21 +432,65 @@ func (th *Tracker) calculateDependencyHashes(dependencySet dag.Set) ([]string, e
 	return dependenciesHashList, nil
 }
 
+// withPackageInputs combines the hash of a task's own input files with the hashes of its
+// inputs in other packages. Tasks without inputs in other packages keep their existing hash.
+func (th *Tracker) withPackageInputs(hashOfFiles string, packageInputs map[string][]string) (string, error) {
+	packageHashes := []string{}
+	for pkg, inputs := range packageInputs {
+		spec := packageFileSpec{pkg: pkg, inputs: inputs}
+		hash, ok := th.packageInputsHashes[spec.ToKey()]
+		if !ok {
+			return "", fmt.Errorf("cannot find package-file hash for %v", spec.ToKey())
+		}
+		packageHashes = append(packageHashes, fmt.Sprintf("%v=%v", pkg, hash))
+	}
+	sort.Strings(packageHashes)
+	return fs.HashObject(append([]string{hashOfFiles}, packageHashes...))
+}
+
+func getHashableEnvPairs(taskDefinition *fs.TaskDefinition) []string {
+	hashableEnvPairs := []string{}
+	envVars := util.ResolveEnvVars(os.Environ(), taskDefinition.EnvVarDependencies, taskDefinition.EnvVarExclusions, taskDefinition.PinnedEnvVars)
+	for name, value := range envVars {
+		hashableEnvPairs = append(hashableEnvPairs, fmt.Sprintf("%v=%v", name, value))
+	}
+	sort.Strings(hashableEnvPairs)
+	return hashableEnvPairs
+}
+
+// EnvVarHashes returns the environment variables that are part of the hash of the given task,
+// keyed by name. Values are hashed rather than returned, since they may be secrets.
+func EnvVarHashes(pt *nodes.PackageTask) (map[string]string, error) {
+	envVars := make(map[string]string, len(pt.TaskDefinition.EnvVarDependencies))
+	for _, pair := range getHashableEnvPairs(pt.TaskDefinition) {
+		kv := strings.SplitN(pair, "=", 2)
+		valueHash, err := fs.HashObject(kv[1])
+		if err != nil {
+			return nil, err
+		}
+		envVars[kv[0]] = valueHash
+	}
+	return envVars, nil
+}
+
 // CalculateTaskHash calculates the hash for package-task combination. It is threadsafe, provided
 // that it has previously been called on its task-graph dependencies. File hashes must be calculated
 // first.
 func (th *Tracker) CalculateTaskHash(pt *nodes.PackageTask, dependencySet dag.Set, args []string) (string, error) {
-	pkgFileHashKey := specFromPackageTask(pt).ToKey()
+	pkgFileHashKey := th.fileSpec(pt.PackageName, pt.TaskDefinition).ToKey()
 	hashOfFiles, ok := th.packageInputsHashes[pkgFileHashKey]
 	if !ok {
 		return "", fmt.Errorf("cannot find package-file hash for %v", pkgFileHashKey)
 	}
 	outputs := pt.HashableOutputs()
-	hashableEnvPairs := []string{}
-	for _, envVar := range pt.TaskDefinition.EnvVarDependencies {
-		hashableEnvPairs = append(hashableEnvPairs, fmt.Sprintf("%v=%v", envVar, os.Getenv(envVar)))
+	if len(pt.TaskDefinition.PackageInputs) > 0 {
+		var err error
+		hashOfFiles, err = th.withPackageInputs(hashOfFiles, pt.TaskDefinition.PackageInputs)
+		if err != nil {
+			return "", err
+		}
 	}
-	sort.Strings(hashableEnvPairs)
+	hashableEnvPairs := getHashableEnvPairs(pt.TaskDefinition)
 	taskDependencyHashes, err := th.calculateDependencyHashes(dependencySet)
 	if err != nil {
 		return "", err
@@ -277,12 +504,36 @@ func (th *Tracker) CalculateTaskHash(pt *nodes.PackageTask, dependencySet dag.Se
 		hashableEnvPairs:     hashableEnvPairs,
 		globalHash:           th.globalHash,
 		taskDependencyHashes: taskDependencyHashes,
+		variant:              pt.TaskDefinition.Variant,
 	})
 	if err != nil {
 		return "", fmt.Errorf("failed to hash task %v: %v", pt.TaskID, hash)
 	}
+	envVars, err := EnvVarHashes(pt)
+	if err != nil {
+		return "", err
+	}
 	th.mu.Lock()
 	th.packageTaskHashes[pt.TaskID] = hash
+	th.packageTaskInputs[pt.TaskID] = &TaskHashInputs{
+		FilesHash:        hashOfFiles,
+		ExternalDepsHash: pt.Pkg.ExternalDepsHash,
+		Outputs:          outputs,
+		PassThroughArgs:  args,
+		EnvVars:          envVars,
+		GlobalHash:       th.globalHash,
+		DependencyHashes: taskDependencyHashes,
+		Variant:          pt.TaskDefinition.Variant,
+	}
 	th.mu.Unlock()
 	return hash, nil
 }
+
+// HashInputs returns everything that contributed to the hash of the given task, if it
+// has been calculated
+func (th *Tracker) HashInputs(taskID string) (*TaskHashInputs, bool) {
+	th.mu.RLock()
+	defer th.mu.RUnlock()
+	inputs, ok := th.packageTaskInputs[taskID]
+	return inputs, ok
+}
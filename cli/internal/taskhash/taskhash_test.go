@@ -2,10 +2,12 @@ package taskhash
 
 import (
 	"os"
+	"os/exec"
 	"path/filepath"
 	"strings"
 	"testing"
 
+	"github.com/pyr-sh/dag"
 	"github.com/vercel/turborepo/cli/internal/fs"
 	"github.com/vercel/turborepo/cli/internal/turbopath"
 )
@@ -87,7 +89,7 @@ func Test_manuallyHashPackage(t *testing.T) {
 	pkg := &fs.PackageJSON{
 		Dir: pkgName.ToString(),
 	}
-	hashes, err := manuallyHashPackage(pkg, []string{}, fs.AbsolutePath(repoRoot.ToString()))
+	hashes, err := manuallyHashPackage(pkg, []string{}, fs.AbsolutePath(repoRoot.ToString()), fs.NewContentHashes())
 	if err != nil {
 		t.Fatalf("failed to calculate manual hashes: %v", err)
 	}
@@ -113,7 +115,7 @@ func Test_manuallyHashPackage(t *testing.T) {
 	}
 
 	count = 0
-	justFileHashes, err := manuallyHashPackage(pkg, []string{filepath.FromSlash("**/*file")}, fs.AbsolutePath(repoRoot.ToString()))
+	justFileHashes, err := manuallyHashPackage(pkg, []string{filepath.FromSlash("**/*file")}, fs.AbsolutePath(repoRoot.ToString()), fs.NewContentHashes())
 	if err != nil {
 		t.Fatalf("failed to calculate manual hashes: %v", err)
 	}
@@ -136,3 +138,151 @@ func Test_manuallyHashPackage(t *testing.T) {
 		t.Errorf("found extra hashes in %v", hashes)
 	}
 }
+
+func Test_excludeOutputs(t *testing.T) {
+	hashObject := map[turbopath.AnchoredUnixPath]string{
+		"src/index.ts":           "hash-of-index",
+		"src/dist/helpers.ts":    "hash-of-helpers",
+		"dist/index.js":          "hash-of-build",
+		".next/cache/manifest":   "hash-of-next",
+		".turbo/turbo-build.log": "hash-of-log",
+		"package.json":           "hash-of-package-json",
+	}
+	outputs := []string{".turbo/turbo-build.log", "dist/**", ".next/**", "!.next/cache/**"}
+	if err := excludeOutputs(hashObject, outputs); err != nil {
+		t.Fatalf("excludeOutputs: %v", err)
+	}
+	expected := []turbopath.AnchoredUnixPath{"package.json", "src/dist/helpers.ts", "src/index.ts"}
+	if len(hashObject) != len(expected) {
+		t.Errorf("expected %v, got %v", expected, hashObject)
+	}
+	for _, path := range expected {
+		if _, ok := hashObject[path]; !ok {
+			t.Errorf("expected %v to still be an input", path)
+		}
+	}
+}
+
+func Test_fileSpecExcludesPackageOutputs(t *testing.T) {
+	th := NewTracker("___ROOT___", "global-hash", fs.Pipeline{}, nil)
+	th.packageOutputs = map[string][]string{"web": {".turbo/turbo-build.log", "dist/**"}}
+
+	spec := th.fileSpec("web", &fs.TaskDefinition{ExcludeOutputsFromInputs: true})
+	if strings.Join(spec.excludedOutputs, ",") != ".turbo/turbo-build.log,dist/**" {
+		t.Errorf("expected the outputs of tasks in web to be excluded, got %v", spec.excludedOutputs)
+	}
+	spec = th.fileSpec("web", &fs.TaskDefinition{ExcludeOutputsFromInputs: true, Inputs: []string{"src/**"}})
+	if len(spec.excludedOutputs) != 0 {
+		t.Errorf("expected declared inputs to be used as-is, got exclusions %v", spec.excludedOutputs)
+	}
+	spec = th.fileSpec("web", &fs.TaskDefinition{})
+	if len(spec.excludedOutputs) != 0 {
+		t.Errorf("expected excludeOutputsFromInputs: false to keep outputs, got exclusions %v", spec.excludedOutputs)
+	}
+}
+
+func Test_usesTreeHash(t *testing.T) {
+	testCases := []struct {
+		spec packageFileSpec
+		want bool
+	}{
+		{packageFileSpec{pkg: "web"}, true},
+		{packageFileSpec{pkg: "web", inputs: []string{"src/**"}}, false},
+		{packageFileSpec{pkg: "web", transforms: map[string]fs.InputTransform{"*.json": fs.InputTransformIgnore}}, false},
+		{packageFileSpec{pkg: "web", excludedOutputs: []string{"dist/**"}}, true},
+	}
+	for _, tc := range testCases {
+		if got := tc.spec.usesTreeHash(); got != tc.want {
+			t.Errorf("usesTreeHash(%v) = %v, want %v", tc.spec.ToKey(), got, tc.want)
+		}
+	}
+}
+
+func Test_fileHashIgnoresOtherTasksInRun(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	for _, file := range []string{"src/index.ts", "coverage/lcov.info", "dist/index.js"} {
+		path := repoRoot.Join("apps", "web", file)
+		if err := path.EnsureDir(); err != nil {
+			t.Fatalf("EnsureDir: %v", err)
+		}
+		if err := path.WriteFile([]byte(file), 0644); err != nil {
+			t.Fatalf("WriteFile: %v", err)
+		}
+	}
+	pipeline := fs.Pipeline{
+		"build": {Outputs: []string{"dist/**"}, ExcludeOutputsFromInputs: true},
+		"test":  {Outputs: []string{"coverage/**"}, ExcludeOutputsFromInputs: true},
+	}
+	packageInfos := map[interface{}]*fs.PackageJSON{
+		"web": {Name: "web", Dir: "apps/web", Scripts: map[string]string{"build": "tsc", "test": "jest"}},
+	}
+	buildHash := func(allTasks []dag.Vertex) string {
+		th := NewTracker("___ROOT___", "global-hash", pipeline, packageInfos)
+		if err := th.CalculateFileHashes(allTasks, 1, repoRoot); err != nil {
+			t.Fatalf("CalculateFileHashes: %v", err)
+		}
+		build := pipeline["build"]
+		return th.packageInputsHashes[th.fileSpec("web", &build).ToKey()]
+	}
+
+	alone := buildHash([]dag.Vertex{"web#build"})
+	withTest := buildHash([]dag.Vertex{"web#build", "web#test"})
+	if alone == "" || alone != withTest {
+		t.Errorf("expected web#build to hash the same with and without web#test in the run, got %q and %q", alone, withTest)
+	}
+}
+
+func Test_treeHashWithExcludedOutputs(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	for _, file := range []string{"src/index.ts", "dist/index.js"} {
+		path := repoRoot.Join("web", file)
+		if err := path.EnsureDir(); err != nil {
+			t.Fatalf("EnsureDir: %v", err)
+		}
+		if err := path.WriteFile([]byte(file), 0644); err != nil {
+			t.Fatalf("WriteFile: %v", err)
+		}
+	}
+	for _, args := range [][]string{
+		{"init", "."},
+		{"config", "--local", "user.name", "test"},
+		{"config", "--local", "user.email", "test@example.com"},
+		{"add", "."},
+		{"commit", "-m", "foo"},
+	} {
+		cmd := exec.Command("git", args...)
+		cmd.Dir = repoRoot.ToString()
+		if out, err := cmd.CombinedOutput(); err != nil {
+			t.Fatalf("git %v failed: %v %v", args[0], err, string(out))
+		}
+	}
+	pkg := &fs.PackageJSON{Name: "web", Dir: "web"}
+	treeID, clean, err := fs.GetPackageTreeHash(repoRoot, pkg.Dir)
+	if err != nil || !clean {
+		t.Fatalf("GetPackageTreeHash: %v %v", clean, err)
+	}
+	treeHash, err := fs.HashObject("tree:" + treeID)
+	if err != nil {
+		t.Fatalf("HashObject: %v", err)
+	}
+
+	// No tracked file is an output, so the tree covers exactly the inputs
+	spec := packageFileSpec{pkg: "web", excludedOutputs: []string{"coverage/**"}}
+	hash, err := spec.hash(pkg, repoRoot, fs.NewContentHashes())
+	if err != nil {
+		t.Fatalf("hash: %v", err)
+	}
+	if hash != treeHash {
+		t.Errorf("expected untracked outputs to use the tree hash %v, got %v", treeHash, hash)
+	}
+
+	// dist/index.js is tracked, but isn't an input, so files are hashed one by one
+	spec = packageFileSpec{pkg: "web", excludedOutputs: []string{"dist/**"}}
+	hash, err = spec.hash(pkg, repoRoot, fs.NewContentHashes())
+	if err != nil {
+		t.Fatalf("hash: %v", err)
+	}
+	if hash == treeHash {
+		t.Errorf("expected tracked outputs to hash files one by one, got the tree hash")
+	}
+}
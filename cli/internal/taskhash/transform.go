@@ -0,0 +1,150 @@
+package taskhash
+
+import (
+	"bytes"
+	"fmt"
+	"sort"
+
+	"github.com/vercel/turborepo/cli/internal/doublestar"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+// _transformPrecedence decides which transform applies when a file matches
+// more than one inputsTransform glob. Higher values win.
+var _transformPrecedence = map[fs.InputTransform]int{
+	fs.InputTransformStripWhitespace: 1,
+	fs.InputTransformStripComments:   2,
+	fs.InputTransformIgnore:          3,
+}
+
+// transformFor returns the transform that applies to the given package-relative path, if any
+func transformFor(transforms map[string]fs.InputTransform, path turbopath.AnchoredUnixPath) (fs.InputTransform, error) {
+	var match fs.InputTransform
+	for glob, transform := range transforms {
+		matches, err := doublestar.Match(glob, path.ToString())
+		if err != nil {
+			return "", fmt.Errorf("invalid inputsTransform glob %v: %w", glob, err)
+		}
+		if matches && _transformPrecedence[transform] > _transformPrecedence[match] {
+			match = transform
+		}
+	}
+	return match, nil
+}
+
+// applyInputTransforms updates a map of package-relative file paths to file hashes
+// according to the task's inputsTransform. Ignored files are removed, and the hashes
+// of other transformed files are replaced by a hash of their normalized contents.
+// The name of the transform is part of the new hash, so that changing a file's
+// transform invalidates the cache even if its normalized contents are unchanged.
+func applyInputTransforms(hashObject map[turbopath.AnchoredUnixPath]string, transforms map[string]fs.InputTransform, pkgDir fs.AbsolutePath) error {
+	if len(transforms) == 0 {
+		return nil
+	}
+	for path := range hashObject {
+		transform, err := transformFor(transforms, path)
+		if err != nil {
+			return err
+		}
+		switch transform {
+		case "":
+			continue
+		case fs.InputTransformIgnore:
+			delete(hashObject, path)
+			continue
+		}
+		contents, err := pkgDir.Join(path.ToSystemPath().ToString()).ReadFile()
+		if err != nil {
+			return fmt.Errorf("could not read %v to apply inputsTransform: %w", path, err)
+		}
+		normalized := normalizeInput(transform, contents)
+		hash, err := fs.HashObject(fmt.Sprintf("%v:%s", transform, normalized))
+		if err != nil {
+			return err
+		}
+		hashObject[path] = hash
+	}
+	return nil
+}
+
+// transformsKey is a stable representation of a set of inputsTransform entries
+func transformsKey(transforms map[string]fs.InputTransform) string {
+	entries := make([]string, 0, len(transforms))
+	for glob, transform := range transforms {
+		entries = append(entries, fmt.Sprintf("%v=%v", glob, transform))
+	}
+	sort.Strings(entries)
+	return fmt.Sprintf("%v", entries)
+}
+
+func normalizeInput(transform fs.InputTransform, contents []byte) []byte {
+	if transform == fs.InputTransformStripComments {
+		contents = stripComments(contents)
+	}
+	return stripWhitespace(contents)
+}
+
+// stripWhitespace drops trailing whitespace from every line, along with blank lines,
+// so that differences in line endings and formatting-only edits hash the same
+func stripWhitespace(contents []byte) []byte {
+	var out bytes.Buffer
+	for _, line := range bytes.Split(contents, []byte("\n")) {
+		line = bytes.TrimRight(line, " \t\r")
+		if len(bytes.TrimSpace(line)) == 0 {
+			continue
+		}
+		out.Write(line)
+		out.WriteByte('\n')
+	}
+	return out.Bytes()
+}
+
+// stripComments removes `//` line comments and `/* */` block comments. String literals
+// delimited by ", ' or ` are left untouched, so that e.g. a URL in a string isn't cut short.
+func stripComments(contents []byte) []byte {
+	var out bytes.Buffer
+	for i := 0; i < len(contents); i++ {
+		c := contents[i]
+		switch {
+		case c == '"' || c == '\'' || c == '`':
+			end := i + 1
+			for end < len(contents) && contents[end] != c {
+				if contents[end] == '\\' {
+					end++
+				}
+				end++
+			}
+			if end >= len(contents) {
+				end = len(contents) - 1
+			}
+			out.Write(contents[i : end+1])
+			i = end
+		case c == '/' && i+1 < len(contents) && contents[i+1] == '/':
+			for i < len(contents) && contents[i] != '\n' {
+				i++
+			}
+			if i < len(contents) {
+				out.WriteByte('\n')
+			}
+		case c == '/' && i+1 < len(contents) && contents[i+1] == '*':
+			end := bytes.Index(contents[i+2:], []byte("*/"))
+			if end == -1 {
+				return out.Bytes()
+			}
+			// Keep the line structure, so that a comment between two tokens still separates them
+			comment := contents[i : i+2+end+2]
+			newlines := bytes.Count(comment, []byte("\n"))
+			if newlines == 0 {
+				out.WriteByte(' ')
+			}
+			for n := 0; n < newlines; n++ {
+				out.WriteByte('\n')
+			}
+			i += len(comment) - 1
+		default:
+			out.WriteByte(c)
+		}
+	}
+	return out.Bytes()
+}
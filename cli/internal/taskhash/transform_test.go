@@ -0,0 +1,80 @@
+package taskhash
+
+import (
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+func Test_stripComments(t *testing.T) {
+	testCases := []struct {
+		name     string
+		input    string
+		expected string
+	}{
+		{"line comment", "const a = 1; // one\nconst b = 2;\n", "const a = 1; \nconst b = 2;\n"},
+		{"block comment", "const a /* the a */ = 1;\n", "const a   = 1;\n"},
+		{"multi-line block comment", "/**\n * docs\n */\nexport {};\n", "\n\n\nexport {};\n"},
+		{"comment markers in strings", "const url = \"https://turborepo.org/*\";\n", "const url = \"https://turborepo.org/*\";\n"},
+		{"escaped quote", "const s = 'it\\'s // fine';\n", "const s = 'it\\'s // fine';\n"},
+	}
+	for _, tc := range testCases {
+		actual := string(stripComments([]byte(tc.input)))
+		if actual != tc.expected {
+			t.Errorf("%v: expected %q, got %q", tc.name, tc.expected, actual)
+		}
+	}
+}
+
+func Test_normalizeInputIsStable(t *testing.T) {
+	original := "export const a = 1;\n\nexport const b = 2;\n"
+	reformatted := "// generated\r\nexport const a = 1;   \r\n/* b */\r\nexport const b = 2;\r\n\r\n"
+	if string(normalizeInput(fs.InputTransformStripComments, []byte(original))) != string(normalizeInput(fs.InputTransformStripComments, []byte(reformatted))) {
+		t.Errorf("expected comment and whitespace changes to normalize to the same contents")
+	}
+	if string(normalizeInput(fs.InputTransformStripWhitespace, []byte(original))) == string(normalizeInput(fs.InputTransformStripWhitespace, []byte(reformatted))) {
+		t.Errorf("expected strip-whitespace to keep comments")
+	}
+}
+
+func Test_applyInputTransforms(t *testing.T) {
+	pkgDir := fs.AbsolutePathFromUpstream(t.TempDir())
+	files := map[string]string{
+		"src/index.ts": "export const a = 1; // one\n",
+		"README.md":    "# docs\n",
+		"docs/a.md":    "more docs\n",
+	}
+	for name, contents := range files {
+		file := pkgDir.Join(name)
+		if err := file.EnsureDir(); err != nil {
+			t.Fatalf("failed to ensure directories for %v: %v", file, err)
+		}
+		if err := file.WriteFile([]byte(contents), 0644); err != nil {
+			t.Fatalf("failed to write %v: %v", file, err)
+		}
+	}
+	hashObject := map[turbopath.AnchoredUnixPath]string{
+		"src/index.ts": "git-hash-of-index",
+		"README.md":    "git-hash-of-readme",
+		"docs/a.md":    "git-hash-of-docs",
+	}
+	transforms := map[string]fs.InputTransform{
+		"**/*.md":     fs.InputTransformIgnore,
+		"src/**":      fs.InputTransformStripWhitespace,
+		"src/**/*.ts": fs.InputTransformStripComments,
+	}
+	if err := applyInputTransforms(hashObject, transforms, pkgDir); err != nil {
+		t.Fatalf("applyInputTransforms: %v", err)
+	}
+	if len(hashObject) != 1 {
+		t.Fatalf("expected markdown files to be ignored, got %v", hashObject)
+	}
+	expected, err := fs.HashObject("strip-comments:export const a = 1;\n")
+	if err != nil {
+		t.Fatalf("HashObject: %v", err)
+	}
+	if hashObject["src/index.ts"] != expected {
+		t.Errorf("expected src/index.ts to hash its contents without comments, got %v", hashObject["src/index.ts"])
+	}
+}
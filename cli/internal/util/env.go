@@ -0,0 +1,57 @@
+package util
+
+import (
+	"strings"
+)
+
+// ParsePinnedEnvVar splits an environment variable declaration of the form NAME=value,
+// returning false if the declaration doesn't pin a value
+func ParsePinnedEnvVar(declaration string) (string, string, bool) {
+	kv := strings.SplitN(declaration, "=", 2)
+	if len(kv) != 2 {
+		return "", "", false
+	}
+	return kv[0], kv[1], true
+}
+
+// ResolveEnvVars returns the values of the declared environment variables, keyed by name.
+// Names can use wildcards to match every variable in the environment with a matching name.
+// Variables that a wildcard matched are left out if they match one of the exclusions.
+// Names without wildcards are always included, with an empty value if they aren't set.
+// Pinned variables have their pinned value, whatever is in the environment.
+func ResolveEnvVars(environ []string, names []string, exclusions []string, pinned map[string]string) map[string]string {
+	values := make(map[string]string)
+	for _, e := range environ {
+		kv := strings.SplitN(e, "=", 2)
+		if len(kv) != 2 {
+			continue
+		}
+		values[kv[0]] = kv[1]
+	}
+	resolved := make(map[string]string)
+	for _, name := range names {
+		if !strings.Contains(name, TaskWildcard) {
+			resolved[name] = values[name]
+			continue
+		}
+		for envName, value := range values {
+			if !MatchWildcard(name, envName) {
+				continue
+			}
+			excluded := false
+			for _, exclusion := range exclusions {
+				if MatchWildcard(exclusion, envName) {
+					excluded = true
+					break
+				}
+			}
+			if !excluded {
+				resolved[envName] = value
+			}
+		}
+	}
+	for name, value := range pinned {
+		resolved[name] = value
+	}
+	return resolved
+}
@@ -0,0 +1,41 @@
+package util
+
+import (
+	"reflect"
+	"testing"
+)
+
+func TestResolveEnvVars(t *testing.T) {
+	environ := []string{
+		"MYAPP_URL=https://example.com",
+		"MYAPP_SECRET_TOKEN=hunter2",
+		"MYAPP_SECRET_KEY=abc",
+		"NODE_ENV=development",
+		"OTHER=1",
+	}
+	resolved := ResolveEnvVars(
+		environ,
+		[]string{"MYAPP_*", "MYAPP_SECRET_KEY", "UNSET"},
+		[]string{"MYAPP_SECRET_*"},
+		map[string]string{"NODE_ENV": "production"},
+	)
+	expected := map[string]string{
+		"MYAPP_URL":        "https://example.com",
+		"MYAPP_SECRET_KEY": "abc",
+		"UNSET":            "",
+		"NODE_ENV":         "production",
+	}
+	if !reflect.DeepEqual(resolved, expected) {
+		t.Errorf("ResolveEnvVars got %v, want %v", resolved, expected)
+	}
+}
+
+func TestParsePinnedEnvVar(t *testing.T) {
+	name, value, ok := ParsePinnedEnvVar("NODE_ENV=production")
+	if !ok || name != "NODE_ENV" || value != "production" {
+		t.Errorf("ParsePinnedEnvVar got %v, %v, %v", name, value, ok)
+	}
+	if _, _, ok := ParsePinnedEnvVar("NODE_ENV"); ok {
+		t.Error("expected NODE_ENV not to pin a value")
+	}
+}
@@ -1,5 +1,7 @@
 package util
 
+import "sync"
+
 // Semaphore is a wrapper around a channel to provide
 // utility methods to clarify that we are treating the
 // channel as a semaphore
@@ -41,3 +43,58 @@ func (s Semaphore) Release() {
 		panic("release without an acquire")
 	}
 }
+
+// PrioritySemaphore is a semaphore whose waiters can ask for priority. A released
+// slot goes to the longest-waiting priority waiter, if there is one, before any
+// other waiter.
+type PrioritySemaphore struct {
+	mu        sync.Mutex
+	available int
+	waiting   []chan struct{}
+	// priorityWaiting are the waiters that asked for priority
+	priorityWaiting []chan struct{}
+}
+
+// NewPrioritySemaphore creates a semaphore that allows up
+// to a given limit of simultaneous acquisitions
+func NewPrioritySemaphore(n int) *PrioritySemaphore {
+	if n <= 0 {
+		panic("semaphore with limit <=0")
+	}
+	return &PrioritySemaphore{available: n}
+}
+
+// Acquire is used to acquire an available slot, ahead of waiters
+// without priority if priority is true. Blocks until available.
+func (s *PrioritySemaphore) Acquire(priority bool) {
+	s.mu.Lock()
+	if s.available > 0 {
+		s.available--
+		s.mu.Unlock()
+		return
+	}
+	ready := make(chan struct{})
+	if priority {
+		s.priorityWaiting = append(s.priorityWaiting, ready)
+	} else {
+		s.waiting = append(s.waiting, ready)
+	}
+	s.mu.Unlock()
+	<-ready
+}
+
+// Release is used to return a slot, handing it to the next
+// waiter if there is one. Acquire must be called as a pre-condition.
+func (s *PrioritySemaphore) Release() {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	if len(s.priorityWaiting) > 0 {
+		close(s.priorityWaiting[0])
+		s.priorityWaiting = s.priorityWaiting[1:]
+	} else if len(s.waiting) > 0 {
+		close(s.waiting[0])
+		s.waiting = s.waiting[1:]
+	} else {
+		s.available++
+	}
+}
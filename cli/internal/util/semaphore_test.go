@@ -0,0 +1,40 @@
+package util
+
+import (
+	"testing"
+	"time"
+)
+
+func TestPrioritySemaphore(t *testing.T) {
+	sema := NewPrioritySemaphore(1)
+	sema.Acquire(false)
+
+	acquired := make(chan string, 2)
+	waitFor := func(name string, priority bool, queued int) {
+		go func() {
+			sema.Acquire(priority)
+			acquired <- name
+		}()
+		// Wait until the goroutine is queued, so that the order of waiters is known
+		for {
+			sema.mu.Lock()
+			waiting := len(sema.waiting) + len(sema.priorityWaiting)
+			sema.mu.Unlock()
+			if waiting == queued {
+				return
+			}
+			time.Sleep(time.Millisecond)
+		}
+	}
+	waitFor("first", false, 1)
+	waitFor("second", true, 2)
+
+	sema.Release()
+	if got := <-acquired; got != "second" {
+		t.Errorf("first waiter to acquire got %v, want second, which has priority", got)
+	}
+	sema.Release()
+	if got := <-acquired; got != "first" {
+		t.Errorf("second waiter to acquire got %v, want first", got)
+	}
+}
@@ -40,3 +40,18 @@ type CacheDisabledError struct {
 func (cd *CacheDisabledError) Error() string {
 	return cd.Message
 }
+
+// RemoteUnavailableError is an error used to indicate that the remote cache
+// couldn't be reached, or answered with a 5xx or 429 status, so the same
+// request may succeed later.
+type RemoteUnavailableError struct {
+	Err error
+}
+
+func (ru *RemoteUnavailableError) Error() string {
+	return ru.Err.Error()
+}
+
+func (ru *RemoteUnavailableError) Unwrap() error {
+	return ru.Err
+}
@@ -17,6 +17,9 @@ const (
 	HashTaskOutput
 	// NewTaskOutput will show all new task output and turbo-computed task hashes for cached output
 	NewTaskOutput
+	// TailTaskOutput will show all new task output, and turbo-computed task hashes with the end
+	// of the log for cached output
+	TailTaskOutput
 )
 
 const (
@@ -24,6 +27,7 @@ const (
 	noTaskOutputString   = "none"
 	hashTaskOutputString = "hash-only"
 	newTaskOutputString  = "new-only"
+	tailTaskOutputString = "hash-only-with-tail"
 )
 
 // TaskOutputModeStrings is an array containing the string representations for task output modes
@@ -61,6 +65,8 @@ func ToTaskOutputModeString(value TaskOutputMode) (string, error) {
 		return hashTaskOutputString, nil
 	case NewTaskOutput:
 		return newTaskOutputString, nil
+	case TailTaskOutput:
+		return tailTaskOutputString, nil
 	}
 
 	return "", fmt.Errorf("invalid task output mode: %v", value)
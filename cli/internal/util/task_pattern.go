@@ -0,0 +1,112 @@
+package util
+
+import (
+	"sort"
+	"strings"
+)
+
+// TaskWildcard matches any sequence of characters in a task or package name in turbo.json
+const TaskWildcard = "*"
+
+// IsTaskPattern returns true if the task name of a turbo.json pipeline key contains a
+// wildcard, e.g. "test:*" or "web#codegen-*"
+func IsTaskPattern(key string) bool {
+	task := key
+	if IsPackageTask(key) {
+		_, task = GetPackageTaskFromId(key)
+	}
+	return strings.Contains(task, TaskWildcard)
+}
+
+// MatchWildcard returns true if s matches the pattern, where each wildcard matches any
+// sequence of characters, including none. This applies to task names in pipeline keys,
+// and to package names in dependsOn.
+func MatchWildcard(pattern string, s string) bool {
+	parts := strings.Split(pattern, TaskWildcard)
+	if len(parts) == 1 {
+		return pattern == s
+	}
+	if !strings.HasPrefix(s, parts[0]) {
+		return false
+	}
+	s = s[len(parts[0]):]
+	last := parts[len(parts)-1]
+	for _, part := range parts[1 : len(parts)-1] {
+		i := strings.Index(s, part)
+		if i == -1 {
+			return false
+		}
+		s = s[i+len(part):]
+	}
+	return len(s) >= len(last) && strings.HasSuffix(s, last)
+}
+
+// IsPackagePattern returns true if the package name of a package task contains a
+// wildcard, e.g. "@acme/*#build"
+func IsPackagePattern(taskID string) bool {
+	if !IsPackageTask(taskID) {
+		return false
+	}
+	pkg, _ := GetPackageTaskFromId(taskID)
+	return strings.Contains(pkg, TaskWildcard)
+}
+
+// bestTaskPattern returns the most specific of the given patterns that matches the task
+// name: the one with the most characters besides wildcards, and then the first one
+// alphabetically
+func bestTaskPattern(patterns map[string]string, task string) (string, bool) {
+	var matches []string
+	for key, pattern := range patterns {
+		if MatchWildcard(pattern, task) {
+			matches = append(matches, key)
+		}
+	}
+	if len(matches) == 0 {
+		return "", false
+	}
+	sort.Slice(matches, func(i, j int) bool {
+		iLiteral := len(patterns[matches[i]]) - strings.Count(patterns[matches[i]], TaskWildcard)
+		jLiteral := len(patterns[matches[j]]) - strings.Count(patterns[matches[j]], TaskWildcard)
+		if iLiteral != jLiteral {
+			return iLiteral > jLiteral
+		}
+		return matches[i] < matches[j]
+	})
+	return matches[0], true
+}
+
+// ResolveTaskKey returns which of the given turbo.json pipeline keys defines the given
+// package task. In order of precedence, that is:
+//   - the package task itself, e.g. "web#test:unit"
+//   - a wildcard package task, e.g. "web#test:*"
+//   - the task name, e.g. "test:unit"
+//   - a wildcard task name, e.g. "test:*"
+func ResolveTaskKey(keys []string, taskID string) (string, bool) {
+	pkg, task := GetPackageTaskFromId(taskID)
+	packagePatterns := make(map[string]string)
+	taskPatterns := make(map[string]string)
+	hasTask := false
+	for _, key := range keys {
+		switch {
+		case key == taskID:
+			return key, true
+		case key == task:
+			hasTask = true
+		case !IsTaskPattern(key):
+			// Exact keys for other tasks don't apply
+		case IsPackageTask(key):
+			if keyPkg, keyTask := GetPackageTaskFromId(key); keyPkg == pkg {
+				packagePatterns[key] = keyTask
+			}
+		default:
+			taskPatterns[key] = key
+		}
+	}
+	if key, ok := bestTaskPattern(packagePatterns, task); ok {
+		return key, true
+	}
+	if hasTask {
+		return task, true
+	}
+	return bestTaskPattern(taskPatterns, task)
+}
@@ -0,0 +1,53 @@
+package util
+
+import "testing"
+
+func TestMatchWildcard(t *testing.T) {
+	testCases := []struct {
+		pattern string
+		task    string
+		want    bool
+	}{
+		{"test:*", "test:unit", true},
+		{"test:*", "test:", true},
+		{"test:*", "test", false},
+		{"codegen-*", "codegen-graphql", true},
+		{"*:watch", "build:watch", true},
+		{"*:watch", "build:watch:fast", false},
+		{"test:*:ci", "test:unit:ci", true},
+		{"test:*:ci", "test:ci", false},
+		{"a*a", "a", false},
+		{"a*a", "aa", true},
+		{"build", "build", true},
+		{"build", "builds", false},
+	}
+	for _, tc := range testCases {
+		if got := MatchWildcard(tc.pattern, tc.task); got != tc.want {
+			t.Errorf("MatchWildcard(%q, %q) = %v, want %v", tc.pattern, tc.task, got, tc.want)
+		}
+	}
+}
+
+func TestResolveTaskKey(t *testing.T) {
+	keys := []string{"build", "test:*", "test:unit:*", "web#test:*", "docs#test:e2e", "test:e2e"}
+	testCases := []struct {
+		taskID string
+		want   string
+		ok     bool
+	}{
+		{"web#build", "build", true},
+		{"api#test:unit", "test:*", true},
+		{"api#test:unit:ci", "test:unit:*", true},
+		{"web#test:unit", "web#test:*", true},
+		{"web#test:e2e", "web#test:*", true},
+		{"api#test:e2e", "test:e2e", true},
+		{"docs#test:e2e", "docs#test:e2e", true},
+		{"web#lint", "", false},
+	}
+	for _, tc := range testCases {
+		got, ok := ResolveTaskKey(keys, tc.taskID)
+		if got != tc.want || ok != tc.ok {
+			t.Errorf("ResolveTaskKey(%q) = %q, %v, want %q, %v", tc.taskID, got, ok, tc.want, tc.ok)
+		}
+	}
+}
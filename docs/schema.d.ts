@@ -51,6 +51,43 @@ export interface Schema {
    * @default {}
    */
   remoteCache?: RemoteCache;
+  /**
+   * Configuration options that control how the turbo daemon watches your repository.
+   * @default {}
+   */
+  watchOptions?: WatchOptions;
+  /**
+   * Configuration options that control which directories are packages.
+   * @default {}
+   */
+  workspaces?: WorkspacesOptions;
+  /**
+   * Make references to tasks that the pipeline doesn't define into errors: dependsOn
+   * entries that no pipeline key defines, and scripts of packages in scope that turbo run
+   * would otherwise skip because no pipeline key defines them for that package.
+   *
+   * @default false
+   */
+  strict?: boolean;
+  /**
+   * Commands that turbo run runs before and after the run, and before and after each
+   * task's script. They run in a shell at the repository root.
+   *
+   * @default {}
+   */
+  hooks?: Hooks;
+  /**
+   * A value mixed into the hash of every task. TURBO_CACHE_KEY_SUFFIX and
+   * --cache-key-suffix override it.
+   */
+  cacheKeySuffix?: string;
+  /**
+   * When turbo run is run from inside a package directory without any filters, run the
+   * tasks in that package only, like npm run, rather than across every package.
+   *
+   * @default false
+   */
+  inferPackageFromCwd?: boolean;
 }
 
 export interface Pipeline {
@@ -103,10 +140,23 @@ export interface Pipeline {
    * will not cause a cache miss.
    *
    * If omitted or empty, all files in the package are considered as inputs.
+   *
+   * Use $PKG(<package>)/<glob> to include files in another package, relative to
+   * that package, e.g. "$PKG(ui)/src/**".
    * @default []
    */
   inputs?: string[];
 
+  /**
+   * Normalizes input files matching each glob before they are hashed. Use "ignore" to
+   * leave matching files out of the hash, "strip-whitespace" to ignore trailing
+   * whitespace, blank lines and line endings, and "strip-comments" to also ignore
+   * `//` and `/* *\/` comments.
+   *
+   * @default {}
+   */
+  inputsTransform?: { [glob: string]: "ignore" | "strip-whitespace" | "strip-comments" };
+
   /**
    * The style of output for this task. Use "full" to display the entire output of
    * the task. Use "hash-only" to show only the computed task hashes. Use "new-only" to
@@ -116,6 +166,144 @@ export interface Pipeline {
    * @default full
    */
   outputMode?: string;
+
+  /**
+   * The number of seconds this task is given to exit after each signal when turbo
+   * stops it. turbo sends SIGINT, then SIGTERM, and then kills the task and
+   * everything it started.
+   *
+   * @default 10
+   */
+  shutdownTimeout?: number;
+
+  /**
+   * The ports that this task listens on. Use "auto" to have turbo pick a free port.
+   * Each port is exported to every task in the run as TURBO_PORT_<TASK>, e.g.
+   * TURBO_PORT_WEB_DEV for web#dev, with a _1, _2, ... suffix for additional ports.
+   *
+   * @default []
+   */
+  ports?: Array<"auto" | number>;
+
+  /**
+   * Directories, relative to the package, in which this task can declare more
+   * outputs while it runs, by writing { "outputs": [...globs] } to
+   * .turbo/outputs.json in its package.
+   *
+   * @default []
+   */
+  dynamicOutputs?: string[];
+
+  /**
+   * Names of environment variables, which may contain `*` wildcards, whose values are
+   * redacted from this task's logs. The values of environment variables in `dependsOn`
+   * and `globalDependencies` are always redacted, unless turbo runs with --no-redact.
+   *
+   * @default []
+   */
+  redact?: string[];
+
+  /**
+   * When this task doesn't declare inputs, leave the outputs of the tasks in the same
+   * package out of its inputs, so that building doesn't change the task's hash.
+   *
+   * @default true
+   */
+  excludeOutputsFromInputs?: boolean;
+
+  /**
+   * Globs, relative to the package, for files to copy into .turbo/failures/<hash>
+   * when this task fails, along with the task's log file. These files aren't cached.
+   *
+   * @default []
+   */
+  captureOnFailure?: string[];
+
+  /**
+   * Names of mutexes that this task holds while it runs. Tasks that share a mutex
+   * never run at the same time, even in different packages.
+   *
+   * @default []
+   */
+  with?: string[];
+
+  /**
+   * The longest the task may run, as a duration like "90s" or "10m". A task that
+   * is still running is stopped and fails. By default, tasks have no time limit.
+   */
+  timeout?: string;
+
+  /**
+   * Whether the task may restore its outputs from the remote cache, and save them
+   * to it. The local cache is used either way.
+   *
+   * @default { "read": true, "write": true }
+   */
+  remote?: RemoteRole;
+
+  /**
+   * A marker that the task emits once the tasks that depend on it may start, before
+   * it finishes: a file, relative to the package, that it writes, or a regular
+   * expression matching a line that it logs. Dependents that started early aren't
+   * cached if the task then fails.
+   */
+  readyWhen?: ReadyWhen;
+
+  /**
+   * Alternative definitions of the task for particular environments. The first
+   * variant whose condition holds replaces the fields it sets; the rest of the
+   * definition is unchanged. The variant that applies is part of the task's hash.
+   *
+   * @default []
+   */
+  variants?: TaskVariant[];
+}
+
+export interface TaskVariant extends Omit<Pipeline, "variants"> {
+  /**
+   * When the variant applies.
+   */
+  when: VariantCondition;
+}
+
+export interface VariantCondition {
+  /**
+   * Environment variables, and the values that they must all have for the
+   * variant to apply.
+   */
+  env: { [name: string]: string };
+}
+
+export interface RemoteRole {
+  /**
+   * Restore the task's outputs from the remote cache.
+   *
+   * @default true
+   */
+  read?: boolean;
+  /**
+   * Save the task's outputs to the remote cache.
+   *
+   * @default true
+   */
+  write?: boolean;
+  /**
+   * How long the remote cache should keep the task's artifacts, as a duration
+   * like "168h". It is sent with each upload as a hint; the remote cache decides
+   * how long it actually keeps them.
+   */
+  ttl?: string;
+}
+
+export interface ReadyWhen {
+  /**
+   * A file, relative to the package, that the task writes once its dependents may start.
+   */
+  file?: string;
+  /**
+   * A regular expression matching a line that the task logs once its dependents may start.
+   */
+  log?: string;
 }
 
 export interface RemoteCache {
@@ -129,3 +317,56 @@ export interface RemoteCache {
    */
   signature?: boolean;
 }
+
+export interface Hooks {
+  /**
+   * Runs before any task starts. If it fails, the run stops.
+   */
+  preRun?: string;
+
+  /**
+   * Runs after every task has finished, with TURBO_RUN_EXIT_CODE set.
+   */
+  postRun?: string;
+
+  /**
+   * Runs before each task's script. If it fails, the task fails.
+   */
+  preTask?: string;
+
+  /**
+   * Runs after each task's script, with TURBO_TASK_STATUS set to success or failure.
+   */
+  postTask?: string;
+
+  /**
+   * Include the hook commands in the global hash, so that changing them changes the hash
+   * of every task.
+   *
+   * @default false
+   */
+  hash?: boolean;
+}
+
+export interface WatchOptions {
+  /**
+   * A list of directories, relative to the repository root, that the turbo daemon
+   * should never watch (e.g. "bazel-out"). Globs are supported, so a leading "**"
+   * matches a directory name at any depth. The root .git and node_modules directories
+   * are always ignored.
+   *
+   * @default []
+   */
+  ignore?: string[];
+}
+
+export interface WorkspacesOptions {
+  /**
+   * A list of globs, relative to the repository root, matching workspace directories
+   * that turbo should not treat as packages (e.g. "packages/templates/*"), in addition
+   * to those the package manager skips.
+   *
+   * @default []
+   */
+  exclude?: string[];
+}
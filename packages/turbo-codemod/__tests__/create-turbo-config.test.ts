@@ -0,0 +1,74 @@
+import fs from "fs-extra";
+import os from "os";
+import path from "path";
+import { renderDiff } from "../src/diff";
+import { transformer } from "../src/transforms/create-turbo-config";
+
+describe("create-turbo-config", () => {
+  let root: string;
+
+  beforeEach(() => {
+    root = fs.mkdtempSync(path.join(os.tmpdir(), "turbo-codemod-"));
+  });
+
+  afterEach(() => {
+    fs.rmSync(root, { recursive: true });
+  });
+
+  it("returns changes without writing them", () => {
+    fs.writeJsonSync(
+      path.join(root, "package.json"),
+      { name: "my-repo", turbo: { pipeline: { build: {} } } },
+      { spaces: 2 }
+    );
+
+    const result = transformer(root);
+
+    expect(result.skipped).toEqual([]);
+    expect(result.changes.map((change) => change.path)).toEqual([
+      "turbo.json",
+      "package.json",
+    ]);
+    expect(result.changes[0].before).toBeNull();
+    expect(JSON.parse(result.changes[0].after)).toEqual({
+      pipeline: { build: {} },
+    });
+    expect(JSON.parse(result.changes[1].after)).toEqual({ name: "my-repo" });
+    expect(fs.existsSync(path.join(root, "turbo.json"))).toBe(false);
+  });
+
+  it("skips repositories that already have a turbo.json", () => {
+    fs.writeJsonSync(path.join(root, "package.json"), { name: "my-repo" });
+    fs.writeJsonSync(path.join(root, "turbo.json"), { pipeline: {} });
+
+    const result = transformer(root);
+
+    expect(result.changes).toEqual([]);
+    expect(result.skipped.map((file) => file.path)).toEqual([
+      "turbo.json",
+      "package.json",
+    ]);
+  });
+
+  it("renders changes as a diff", () => {
+    const diff = renderDiff({
+      path: "package.json",
+      before: '{\n  "name": "my-repo",\n  "turbo": {}\n}\n',
+      after: '{\n  "name": "my-repo"\n}\n',
+    });
+
+    expect(diff).toEqual(
+      [
+        "--- a/package.json",
+        "+++ b/package.json",
+        "@@ -1,4 +1,3 @@",
+        " {",
+        '-  "name": "my-repo",',
+        '-  "turbo": {}',
+        '+  "name": "my-repo"',
+        " }",
+        "",
+      ].join("\n")
+    );
+  });
+});
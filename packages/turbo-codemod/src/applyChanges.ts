@@ -0,0 +1,83 @@
+import chalk from "chalk";
+import fs from "fs-extra";
+import path from "path";
+import { renderDiff } from "./diff";
+import { Flags, TransformResult } from "./types";
+
+/**
+ * Writes the changes from a transform to disk, unless this is a dry run,
+ * and reports what happened. With `--print` the new contents of each file
+ * are shown, and with `--diff` a unified diff of each change is shown.
+ */
+export function applyChanges(
+  root: string,
+  result: TransformResult,
+  flags: Flags
+) {
+  let modifiedCount = 0;
+  let skippedCount = result.skipped.length;
+  let errorCount = 0;
+
+  for (const { path: filePath, reason } of result.skipped) {
+    skip(filePath, chalk.dim(`(${reason})`));
+  }
+  for (const change of result.changes) {
+    if (flags.diff) {
+      console.log(renderDiff(change));
+    } else if (flags.print) {
+      console.log(change.after);
+    }
+    if (flags.dry) {
+      skip(change.path, chalk.dim("(dry run)"));
+      skippedCount++;
+      continue;
+    }
+    try {
+      fs.outputFileSync(path.join(root, change.path), change.after);
+      ok(change.path, chalk.dim(change.before === null ? "(created)" : ""));
+      modifiedCount++;
+    } catch (err) {
+      console.error(err);
+      error(change.path);
+      errorCount++;
+    }
+  }
+
+  console.log("All done.");
+  console.log("Results:");
+  console.log(chalk.red(`${errorCount} errors`));
+  console.log(chalk.yellow(`${skippedCount} skipped`));
+  console.log(chalk.green(`${modifiedCount} modified`));
+}
+
+/**
+ * Runs a transform from the command line, exiting if it can't be applied.
+ */
+export function runTransformer(
+  transform: (root: string) => TransformResult,
+  files: string[],
+  flags: Flags
+) {
+  if (files.length !== 1) {
+    return;
+  }
+  const root = path.resolve(process.cwd(), files[0]);
+  let result: TransformResult;
+  try {
+    result = transform(root);
+  } catch (err: any) {
+    error(err.message);
+    process.exit(1);
+  }
+  applyChanges(root, result, flags);
+}
+
+export function skip(...args: any[]) {
+  console.log(chalk.yellow.inverse(` SKIP `), ...args);
+}
+export function error(...args: any[]) {
+  console.log(chalk.red.inverse(` ERROR `), ...args);
+}
+export function ok(...args: any[]) {
+  console.log(chalk.green.inverse(` OK `), ...args);
+}
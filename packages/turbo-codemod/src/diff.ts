@@ -0,0 +1,68 @@
+import { FileChange } from "./types";
+
+type DiffLine = { op: " " | "-" | "+"; line: string };
+
+function splitLines(contents: string | null): string[] {
+  if (!contents) {
+    return [];
+  }
+  const lines = contents.split("\n");
+  if (lines[lines.length - 1] === "") {
+    lines.pop();
+  }
+  return lines;
+}
+
+// diffLines computes a line diff from the longest common subsequence of the
+// two files. Transforms only touch small config files, so O(n*m) is fine.
+function diffLines(before: string[], after: string[]): DiffLine[] {
+  const lcs: number[][] = Array.from({ length: before.length + 1 }, () =>
+    new Array(after.length + 1).fill(0)
+  );
+  for (let i = before.length - 1; i >= 0; i--) {
+    for (let j = after.length - 1; j >= 0; j--) {
+      lcs[i][j] =
+        before[i] === after[j]
+          ? lcs[i + 1][j + 1] + 1
+          : Math.max(lcs[i + 1][j], lcs[i][j + 1]);
+    }
+  }
+  const result: DiffLine[] = [];
+  let i = 0;
+  let j = 0;
+  while (i < before.length && j < after.length) {
+    if (before[i] === after[j]) {
+      result.push({ op: " ", line: before[i] });
+      i++;
+      j++;
+    } else if (lcs[i + 1][j] >= lcs[i][j + 1]) {
+      result.push({ op: "-", line: before[i++] });
+    } else {
+      result.push({ op: "+", line: after[j++] });
+    }
+  }
+  while (i < before.length) {
+    result.push({ op: "-", line: before[i++] });
+  }
+  while (j < after.length) {
+    result.push({ op: "+", line: after[j++] });
+  }
+  return result;
+}
+
+/**
+ * Renders a change as a unified diff, with a single hunk covering the whole file.
+ */
+export function renderDiff(change: FileChange): string {
+  const before = splitLines(change.before);
+  const after = splitLines(change.after);
+  const header = [
+    change.before === null ? "--- /dev/null" : `--- a/${change.path}`,
+    `+++ b/${change.path}`,
+    `@@ -${before.length ? 1 : 0},${before.length} +${after.length ? 1 : 0},${
+      after.length
+    } @@`,
+  ];
+  const body = diffLines(before, after).map(({ op, line }) => op + line);
+  return [...header, ...body].join("\n") + "\n";
+}
@@ -20,6 +20,7 @@ const help = `
     --force             Bypass Git safety checks and forcibly run codemods
     --dry               Dry run (no changes are made to files)
     --print             Print transformed files to your terminal
+    --diff              Print a diff of each change to your terminal
     --help, -h          Show this help message
     --version, -v       Show the version of this script
 `;
@@ -61,6 +62,7 @@ async function run() {
       force: { type: "boolean", default: false },
       dry: { type: "boolean", default: false },
       print: { type: "boolean", default: false },
+      diff: { type: "boolean", default: false },
       version: { type: "boolean", default: false, alias: "v" },
     },
     description: "Codemods for updating Turborepo codebases.",
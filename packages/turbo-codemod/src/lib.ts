@@ -0,0 +1,22 @@
+import { transformer as addPackageManager } from "./transforms/add-package-manager";
+import { transformer as createTurboConfig } from "./transforms/create-turbo-config";
+import { Transformer } from "./types";
+
+export { applyChanges } from "./applyChanges";
+export { renderDiff } from "./diff";
+export type {
+  FileChange,
+  Flags,
+  SkippedFile,
+  TransformResult,
+  Transformer,
+} from "./types";
+
+/**
+ * Every available transform, keyed by the name used on the command line.
+ * Transforms return the changes they would make rather than writing files.
+ */
+export const transforms: Record<string, Transformer> = {
+  "add-package-manager": addPackageManager,
+  "create-turbo-config": createTurboConfig,
+};
@@ -2,4 +2,35 @@ export interface Flags {
   dry: boolean;
   force: boolean;
   print: boolean;
+  diff: boolean;
 }
+
+/**
+ * A change to a single file, produced by a transform. `path` is relative to the
+ * directory the transform was run in, and `before` is null for new files.
+ */
+export interface FileChange {
+  path: string;
+  before: string | null;
+  after: string;
+}
+
+/**
+ * A file that a transform looked at but chose not to change.
+ */
+export interface SkippedFile {
+  path: string;
+  reason: string;
+}
+
+/**
+ * The result of running a transform. Transforms never write to disk themselves,
+ * so callers can apply the changes, render them as a diff, or offer them as
+ * editor quick fixes.
+ */
+export interface TransformResult {
+  changes: FileChange[];
+  skipped: SkippedFile[];
+}
+
+export type Transformer = (root: string) => TransformResult;